@@ -2,11 +2,16 @@ use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
 
 use goose::config::{Config, ExtensionConfig};
+use goose::model::ReasoningEffort;
 
+use crate::commands::audit::{handle_audit_command, AuditCommand};
 use crate::commands::bench::agent_generator;
 use crate::commands::configure::handle_configure;
+use crate::commands::hints::{handle_hints_command, HintsCommand};
 use crate::commands::info::handle_info;
 use crate::commands::mcp::run_server;
+use crate::commands::mcp_serve::run_mcp_serve;
+use crate::commands::permissions::{handle_permissions_command, PermissionsCommand};
 use crate::commands::project::{handle_project_default, handle_projects_interactive};
 use crate::commands::recipe::{handle_deeplink, handle_list, handle_validate};
 // Import the new handlers from commands::schedule
@@ -15,12 +20,15 @@ use crate::commands::schedule::{
     handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
     handle_schedule_sessions,
 };
-use crate::commands::session::{handle_session_list, handle_session_remove};
+use crate::commands::session::{
+    handle_session_import, handle_session_list, handle_session_remove, ExportFormat, ImportFormat,
+};
 use crate::commands::system_prompt::handle_system_prompt_command;
 use crate::logging::setup_logging;
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
 use crate::session;
+use crate::session::run_output::{OutputFormat, RunStatus};
 use crate::session::{build_session, SessionBuilderConfig, SessionSettings};
 use goose_bench::bench_config::BenchRunConfig;
 use goose_bench::runners::bench_runner::BenchRunner;
@@ -70,6 +78,14 @@ fn extract_identifier(identifier: Identifier) -> session::Identifier {
     }
 }
 
+/// Whether interactive sessions use the full-screen interface, asked for with --tui or turned on
+/// by default with GOOSE_CLI_TUI
+fn use_tui(flag: bool) -> bool {
+    flag || Config::global()
+        .get_param::<bool>("GOOSE_CLI_TUI")
+        .unwrap_or(false)
+}
+
 fn parse_key_val(s: &str) -> Result<(String, String), String> {
     match s.split_once('=') {
         Some((key, value)) => Ok((key.to_string(), value.to_string())),
@@ -106,7 +122,7 @@ enum SessionCommand {
         #[arg(short, long, help = "Regex for removing matched sessions (optional)")]
         regex: Option<String>,
     },
-    #[command(about = "Export a session to Markdown format")]
+    #[command(about = "Export a session to Markdown, HTML, JSON or a recipe")]
     Export {
         #[command(flatten)]
         identifier: Option<Identifier>,
@@ -115,9 +131,41 @@ enum SessionCommand {
             short,
             long,
             help = "Output file path (default: stdout)",
-            long_help = "Path to save the exported Markdown. If not provided, output will be sent to stdout"
+            long_help = "Path to save the export. If not provided, output will be sent to stdout"
         )]
         output: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            value_enum,
+            default_value_t = ExportFormat::Markdown,
+            help = "Format of the export",
+            long_help = "Format of the export: markdown, a self-contained html transcript, json with a stable schema for analysis, or a recipe in YAML that reruns what the session did. The recipe is written by the configured model."
+        )]
+        format: ExportFormat,
+    },
+    #[command(about = "Import a transcript from another agent or an API log as a session")]
+    Import {
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            value_enum,
+            help = "Format of the transcript",
+            long_help = "Format of the transcript: openai for chat completions messages, anthropic for Messages API messages, or jsonl for one message per line"
+        )]
+        format: ImportFormat,
+
+        #[arg(
+            short,
+            long,
+            value_name = "NAME",
+            help = "Name for the imported session (default: a new session id)"
+        )]
+        name: Option<String>,
+
+        #[arg(value_name = "FILE", help = "Transcript file to import")]
+        file: PathBuf,
     },
 }
 
@@ -286,7 +334,55 @@ enum Command {
 
     /// Manage system prompts and behaviors
     #[command(about = "Run one of the mcp servers bundled with goose")]
-    Mcp { name: String },
+    Mcp {
+        name: String,
+
+        /// Serve over HTTP instead of stdio
+        #[arg(
+            long,
+            value_name = "ADDR",
+            help = "Serve over Streamable HTTP and SSE on this address (e.g., ':8080')",
+            long_help = "Serve the extension over HTTP instead of stdio so a remote agent can connect to it. Streamable HTTP is served at /mcp and SSE at /sse. An address like ':8080' listens on localhost only, where requests from other hosts or web origins are rejected; listening on other interfaces requires an auth token."
+        )]
+        http: Option<String>,
+
+        /// Bearer token required from HTTP clients
+        #[arg(
+            long,
+            value_name = "TOKEN",
+            help = "Require this bearer token from HTTP clients",
+            long_help = "Require 'Authorization: Bearer <TOKEN>' on every HTTP request. Falls back to the GOOSE_MCP_AUTH_TOKEN environment variable.",
+            requires = "http"
+        )]
+        auth_token: Option<String>,
+    },
+
+    /// Serve goose itself over MCP
+    #[command(
+        name = "mcp-serve",
+        about = "Serve goose itself as an MCP server",
+        long_about = "Expose goose as an MCP server so other agents and IDEs can delegate tasks to it. Offers the run_task, run_recipe and list_sessions tools, and the recipe library as prompts. Serves over stdio unless --http is given."
+    )]
+    McpServe {
+        /// Serve over HTTP instead of stdio
+        #[arg(
+            long,
+            value_name = "ADDR",
+            help = "Serve over Streamable HTTP and SSE on this address (e.g., ':8080')",
+            long_help = "Serve over HTTP instead of stdio so a remote host can connect. Streamable HTTP is served at /mcp and SSE at /sse. An address like ':8080' listens on localhost only. Requires --auth-token."
+        )]
+        http: Option<String>,
+
+        /// Bearer token required from HTTP clients
+        #[arg(
+            long,
+            value_name = "TOKEN",
+            help = "Require this bearer token from HTTP clients",
+            long_help = "Require 'Authorization: Bearer <TOKEN>' on every HTTP request. Required with --http. Falls back to the GOOSE_MCP_AUTH_TOKEN environment variable.",
+            requires = "http"
+        )]
+        auth_token: Option<String>,
+    },
 
     /// Start or resume interactive chat sessions
     #[command(
@@ -325,6 +421,14 @@ enum Command {
         )]
         debug: bool,
 
+        /// Use the full-screen terminal interface
+        #[arg(
+            long,
+            help = "Use the full-screen terminal interface",
+            long_help = "Show the session in a full-screen interface with a scrollable conversation, collapsible tool calls, a side panel with extensions, context usage and subagent tasks, and a dialog for tool permissions. Set GOOSE_CLI_TUI to true to use it by default."
+        )]
+        tui: bool,
+
         /// Maximum number of consecutive identical tool calls allowed
         #[arg(
             long = "max-tool-repetitions",
@@ -391,6 +495,24 @@ enum Command {
             long_help = "Use a specific system prompt for this session. You can specify either the prompt ID or name. Use 'goose system-prompt list' to see available prompts."
         )]
         system_prompt: Option<String>,
+
+        /// Reasoning effort for models that reason before answering
+        #[arg(
+            long = "reasoning-effort",
+            value_name = "LEVEL",
+            help = "Reasoning effort for reasoning models (low, medium, high)",
+            long_help = "Override GOOSE_REASONING_EFFORT for this session. Providers that take a thinking budget instead derive one from the effort."
+        )]
+        reasoning_effort: Option<ReasoningEffort>,
+
+        /// Token budget for extended thinking
+        #[arg(
+            long = "thinking-budget",
+            value_name = "TOKENS",
+            help = "Token budget for extended thinking",
+            long_help = "Override GOOSE_THINKING_BUDGET for this session. Providers that take a reasoning effort instead derive one from the budget."
+        )]
+        thinking_budget: Option<i32>,
     },
 
     /// Open the last project directory
@@ -624,6 +746,64 @@ enum Command {
             long_help = "Use a specific system prompt for this run. You can specify either the prompt ID or name. Use 'goose system-prompt list' to see available prompts."
         )]
         system_prompt: Option<String>,
+
+        /// Reasoning effort for models that reason before answering
+        #[arg(
+            long = "reasoning-effort",
+            value_name = "LEVEL",
+            help = "Reasoning effort for reasoning models (low, medium, high)",
+            long_help = "Override GOOSE_REASONING_EFFORT for this run. Providers that take a thinking budget instead derive one from the effort."
+        )]
+        reasoning_effort: Option<ReasoningEffort>,
+
+        /// Token budget for extended thinking
+        #[arg(
+            long = "thinking-budget",
+            value_name = "TOKENS",
+            help = "Token budget for extended thinking",
+            long_help = "Override GOOSE_THINKING_BUDGET for this run. Providers that take a reasoning effort instead derive one from the budget."
+        )]
+        thinking_budget: Option<i32>,
+
+        /// Record the provider's responses to a file
+        #[arg(
+            long = "record",
+            value_name = "FILE",
+            help = "Record the model's responses to a file for later replay",
+            long_help = "Record every response from the provider to FILE, keyed by the conversation so far. The file is written after each response, so it is kept even if the run fails. Replay it with --replay.",
+            conflicts_with = "replay"
+        )]
+        record: Option<PathBuf>,
+
+        /// Replay the provider's responses from a file
+        #[arg(
+            long = "replay",
+            value_name = "FILE",
+            help = "Replay the model's responses from a file recorded with --record",
+            long_help = "Answer from a recording made with --record instead of calling the provider, without network access or API keys. The run fails with an error naming the point where the conversation diverges from the recording."
+        )]
+        replay: Option<PathBuf>,
+
+        /// Output format for headless runs
+        #[arg(
+            long = "output-format",
+            value_name = "FORMAT",
+            value_enum,
+            default_value_t = OutputFormat::Text,
+            help = "Output format: text, json or stream-json",
+            long_help = "Print JSON records instead of rendered text. 'stream-json' prints one JSON object per line for every message, tool request and response, notification, model change and usage update, then a result record. 'json' prints a single object with the result and all events when the run ends. Implies --quiet and cannot be combined with --interactive."
+        )]
+        output_format: OutputFormat,
+
+        /// Stop once the session has used this many tokens
+        #[arg(
+            long = "token-budget",
+            value_name = "TOKENS",
+            value_parser = clap::value_parser!(i32).range(1..),
+            help = "Stop the run once the session has used this many tokens",
+            long_help = "Stop the run after the turn in which the session's accumulated input and output tokens reach TOKENS, exiting with code 4. Needs a session, so it cannot be combined with --no-session."
+        )]
+        token_budget: Option<i32>,
     },
 
     /// Recipe utilities for validation and deeplinking
@@ -643,6 +823,27 @@ enum Command {
         command: crate::commands::system_prompt::SystemPromptCommand,
     },
 
+    /// Query the audit log of tool requests
+    #[command(about = "Query and verify the audit log of tool requests")]
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommand,
+    },
+
+    /// Inspect tool permissions and argument rules
+    #[command(about = "Inspect tool permissions and argument rules")]
+    Permissions {
+        #[command(subcommand)]
+        command: PermissionsCommand,
+    },
+
+    /// Inspect the project hints given to the developer extension
+    #[command(about = "Inspect the .goosehints and AGENTS.md files goose loads")]
+    Hints {
+        #[command(subcommand)]
+        command: HintsCommand,
+    },
+
     /// Manage scheduled jobs
     #[command(about = "Manage scheduled jobs", visible_alias = "sched")]
     Schedule {
@@ -739,8 +940,16 @@ pub async fn cli() -> Result<()> {
             handle_info(verbose)?;
             return Ok(());
         }
-        Some(Command::Mcp { name }) => {
-            let _ = run_server(&name).await;
+        Some(Command::Mcp {
+            name,
+            http,
+            auth_token,
+        }) => {
+            run_server(&name, http.as_deref(), auth_token).await?;
+        }
+        Some(Command::McpServe { http, auth_token }) => {
+            run_mcp_serve(http.as_deref(), auth_token).await?;
+            return Ok(());
         }
         Some(Command::Session {
             command,
@@ -748,6 +957,7 @@ pub async fn cli() -> Result<()> {
             resume,
             history,
             debug,
+            tui,
             max_tool_repetitions,
             max_turns,
             extensions,
@@ -755,6 +965,8 @@ pub async fn cli() -> Result<()> {
             streamable_http_extensions,
             builtins,
             system_prompt,
+            reasoning_effort,
+            thinking_budget,
         }) => {
             return match command {
                 Some(SessionCommand::List {
@@ -769,7 +981,15 @@ pub async fn cli() -> Result<()> {
                     handle_session_remove(id, regex)?;
                     return Ok(());
                 }
-                Some(SessionCommand::Export { identifier, output }) => {
+                Some(SessionCommand::Import { format, name, file }) => {
+                    handle_session_import(format, file, name)?;
+                    return Ok(());
+                }
+                Some(SessionCommand::Export {
+                    identifier,
+                    output,
+                    format,
+                }) => {
                     let session_identifier = if let Some(id) = identifier {
                         extract_identifier(id)
                     } else {
@@ -783,16 +1003,26 @@ pub async fn cli() -> Result<()> {
                         }
                     };
 
-                    crate::commands::session::handle_session_export(session_identifier, output)?;
+                    crate::commands::session::handle_session_export(
+                        session_identifier,
+                        output,
+                        format,
+                    )
+                    .await?;
                     Ok(())
                 }
                 None => {
                     // Run session command by default
-                    let settings = system_prompt.map(|prompt| SessionSettings {
+                    let settings = (system_prompt.is_some()
+                        || reasoning_effort.is_some()
+                        || thinking_budget.is_some())
+                    .then(|| SessionSettings {
                         goose_provider: None,
                         goose_model: None,
                         temperature: None,
-                        system_prompt_id: Some(prompt),
+                        reasoning_effort,
+                        thinking_budget,
+                        system_prompt_id: system_prompt,
                     });
 
                     let mut session: crate::Session = build_session(SessionBuilderConfig {
@@ -817,6 +1047,8 @@ pub async fn cli() -> Result<()> {
                         sub_recipes: None,
                         final_output_response: None,
                         retry_config: None,
+                        record_file: None,
+                        replay_file: None,
                     })
                     .await;
                     setup_logging(
@@ -828,12 +1060,22 @@ pub async fn cli() -> Result<()> {
                         None,
                     )?;
 
+                    if use_tui(tui) {
+                        // The full-screen interface always shows the previous messages
+                        if let Err(e) = session.interactive_tui(None).await {
+                            eprintln!("Session ended with error: {}", e);
+                        }
+                        return Ok(());
+                    }
+
                     // Render previous messages if resuming a session and history flag is set
                     if resume && history {
                         session.render_message_history();
                     }
 
-                    let _ = session.interactive(None).await;
+                    if let Err(e) = session.interactive(None).await {
+                        eprintln!("Session ended with error: {}", e);
+                    }
                     Ok(())
                 }
             };
@@ -874,7 +1116,25 @@ pub async fn cli() -> Result<()> {
             provider,
             model,
             system_prompt,
+            reasoning_effort,
+            thinking_budget,
+            record,
+            replay,
+            output_format,
+            token_budget,
         }) => {
+            if output_format != OutputFormat::Text && interactive {
+                eprintln!("Error: JSON output cannot be used with --interactive");
+                std::process::exit(1);
+            }
+            if token_budget.is_some() && no_session {
+                eprintln!(
+                    "Error: --token-budget needs a session to count tokens, remove --no-session"
+                );
+                std::process::exit(1);
+            }
+            let quiet = quiet || output_format != OutputFormat::Text;
+
             let (input_config, recipe_info) = match (instructions, input_text, recipe) {
                 (Some(file), _, _) if file == "-" => {
                     let mut input = String::new();
@@ -946,6 +1206,14 @@ pub async fn cli() -> Result<()> {
                     settings.system_prompt_id = Some(prompt);
                 }
 
+                // CLI reasoning settings override recipe settings
+                if reasoning_effort.is_some() {
+                    settings.reasoning_effort = reasoning_effort;
+                }
+                if thinking_budget.is_some() {
+                    settings.thinking_budget = thinking_budget;
+                }
+
                 Some(settings)
             };
 
@@ -973,6 +1241,8 @@ pub async fn cli() -> Result<()> {
                     .as_ref()
                     .and_then(|r| r.final_output_response.clone()),
                 retry_config: recipe_info.as_ref().and_then(|r| r.retry_config.clone()),
+                record_file: record,
+                replay_file: replay,
             })
             .await;
 
@@ -985,10 +1255,19 @@ pub async fn cli() -> Result<()> {
                 None,
             )?;
 
+            session.set_output_format(output_format);
+            session.set_token_budget(token_budget);
+
             if interactive {
-                let _ = session.interactive(input_config.contents).await;
+                if let Err(e) = session.interactive(input_config.contents).await {
+                    eprintln!("Session ended with error: {}", e);
+                }
             } else if let Some(contents) = input_config.contents {
-                let _ = session.headless(contents).await;
+                let result = session.headless(contents).await;
+                let status = session.finish_headless(result.as_ref().err());
+                if status != RunStatus::Success {
+                    std::process::exit(status.exit_code());
+                }
             } else {
                 eprintln!("Error: no text provided for prompt in headless mode");
                 std::process::exit(1);
@@ -1080,6 +1359,18 @@ pub async fn cli() -> Result<()> {
             .await?;
             return Ok(());
         }
+        Some(Command::Audit { command }) => {
+            handle_audit_command(command)?;
+            return Ok(());
+        }
+        Some(Command::Permissions { command }) => {
+            handle_permissions_command(command)?;
+            return Ok(());
+        }
+        Some(Command::Hints { command }) => {
+            handle_hints_command(command)?;
+            return Ok(());
+        }
         Some(Command::Web { port, host, open }) => {
             crate::commands::web::handle_web(port, host, open).await?;
             return Ok(());
@@ -1112,6 +1403,8 @@ pub async fn cli() -> Result<()> {
                     sub_recipes: None,
                     final_output_response: None,
                     retry_config: None,
+                    record_file: None,
+                    replay_file: None,
                 })
                 .await;
                 setup_logging(
@@ -1122,7 +1415,12 @@ pub async fn cli() -> Result<()> {
                         .and_then(|s| s.to_str()),
                     None,
                 )?;
-                if let Err(e) = session.interactive(None).await {
+                let result = if use_tui(false) {
+                    session.interactive_tui(None).await
+                } else {
+                    session.interactive(None).await
+                };
+                if let Err(e) = result {
                     eprintln!("Session ended with error: {}", e);
                 }
                 Ok(())
@@ -0,0 +1,157 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Subcommand;
+use console::style;
+use goose::audit::{AuditLog, AuditQuery, PermissionDecision, ToolStatus};
+
+#[derive(Subcommand)]
+pub enum AuditCommand {
+    /// List recorded tool requests
+    #[command(about = "List recorded tool requests, oldest first")]
+    List {
+        /// Only entries from this session
+        #[arg(
+            long,
+            value_name = "SESSION_ID",
+            help = "Only entries from this session"
+        )]
+        session: Option<String>,
+
+        /// Only entries for this tool
+        #[arg(
+            long,
+            value_name = "TOOL",
+            help = "Only entries for this tool ('*' matches any characters)"
+        )]
+        tool: Option<String>,
+
+        /// Only entries at or after this time
+        #[arg(
+            long,
+            value_name = "DATE",
+            help = "Only entries at or after this time (RFC 3339 or YYYY-MM-DD)"
+        )]
+        since: Option<String>,
+
+        /// Only denied requests
+        #[arg(long, help = "Only show requests that were denied")]
+        denied: bool,
+
+        /// Maximum number of entries to show
+        #[arg(
+            short,
+            long,
+            value_name = "N",
+            help = "Show only the N most recent matching entries"
+        )]
+        limit: Option<usize>,
+
+        /// Output format (text, json)
+        #[arg(
+            long = "format",
+            value_name = "FORMAT",
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
+
+    /// Check that the log has not been modified
+    #[command(about = "Verify the hash chain of the audit log")]
+    Verify {},
+}
+
+fn parse_since(since: &str) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(since) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .with_context(|| format!("Invalid --since '{}': use RFC 3339 or YYYY-MM-DD", since))?;
+    Ok(date
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc())
+}
+
+pub fn handle_audit_command(command: AuditCommand) -> Result<()> {
+    let log = AuditLog::default();
+    match command {
+        AuditCommand::List {
+            session,
+            tool,
+            since,
+            denied,
+            limit,
+            format,
+        } => {
+            let query = AuditQuery {
+                session_id: session,
+                tool,
+                since: since.as_deref().map(parse_since).transpose()?,
+                decision: denied.then_some(PermissionDecision::Denied),
+                limit,
+            };
+            let entries = log.query(&query)?;
+
+            if format == "json" {
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+                return Ok(());
+            }
+
+            if entries.is_empty() {
+                println!("No audit entries found in {}", log.path().display());
+                return Ok(());
+            }
+            for entry in entries {
+                let decision = match entry.decision {
+                    PermissionDecision::Allowed => style("allowed").green(),
+                    PermissionDecision::Denied => style("denied").red(),
+                    PermissionDecision::Skipped => style("skipped").dim(),
+                };
+                let outcome = match (entry.status, entry.duration_ms) {
+                    (ToolStatus::Success, Some(ms)) => format!("ok in {}ms", ms),
+                    (ToolStatus::Error, Some(ms)) => format!("error in {}ms", ms),
+                    (ToolStatus::NotRun, _) => "not run".to_string(),
+                    (status, None) => format!("{:?}", status).to_lowercase(),
+                };
+                println!(
+                    "{:>5} {} {} {} by {} ({})",
+                    entry.seq,
+                    style(entry.timestamp.format("%Y-%m-%d %H:%M:%S")).dim(),
+                    style(&entry.tool).cyan(),
+                    decision,
+                    serde_json::to_value(entry.decided_by)?
+                        .as_str()
+                        .unwrap_or_default(),
+                    outcome
+                );
+                if let Some(session_id) = &entry.session_id {
+                    println!("      session: {}", session_id);
+                }
+                println!("      args: {}", entry.arguments);
+                if let Some(error) = &entry.error {
+                    println!("      {} {}", style("error:").red(), error);
+                }
+            }
+            Ok(())
+        }
+        AuditCommand::Verify {} => {
+            let verification = log.verify()?;
+            if verification.is_valid() {
+                println!(
+                    "{} {} entries verified in {}",
+                    style("✓").green(),
+                    verification.entries,
+                    log.path().display()
+                );
+                Ok(())
+            } else {
+                anyhow::bail!(
+                    "Audit log chain is broken at line {}: {}",
+                    verification.first_invalid_line.unwrap_or_default(),
+                    verification.error.unwrap_or_default()
+                );
+            }
+        }
+    }
+}
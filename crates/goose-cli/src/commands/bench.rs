@@ -53,6 +53,8 @@ pub async fn agent_generator(
         sub_recipes: None,
         final_output_response: None,
         retry_config: None,
+        record_file: None,
+        replay_file: None,
     })
     .await;
 
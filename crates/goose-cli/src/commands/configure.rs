@@ -1099,6 +1099,11 @@ pub fn configure_goose_router_strategy_dialog() -> Result<(), Box<dyn Error>> {
             "Vector Strategy",
             "Use vector-based similarity to select tools",
         )
+        .item(
+            "hybrid",
+            "Hybrid Strategy",
+            "Use fast keyword matching, optionally blended with embeddings, to select tools",
+        )
         .item(
             "default",
             "Default Strategy",
@@ -1116,6 +1121,15 @@ pub fn configure_goose_router_strategy_dialog() -> Result<(), Box<dyn Error>> {
                 "Set to Vector Strategy - using vector-based similarity for tool selection",
             )?;
         }
+        "hybrid" => {
+            config.set_param(
+                "GOOSE_ROUTER_TOOL_SELECTION_STRATEGY",
+                Value::String("hybrid".to_string()),
+            )?;
+            cliclack::outro(
+                "Set to Hybrid Strategy - using keyword and usage-aware ranking for tool selection",
+            )?;
+        }
         "default" => {
             config.set_param(
                 "GOOSE_ROUTER_TOOL_SELECTION_STRATEGY",
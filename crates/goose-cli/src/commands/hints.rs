@@ -0,0 +1,76 @@
+use anyhow::Result;
+use clap::Subcommand;
+use console::style;
+use goose::token_counter::TokenCounter;
+use goose_mcp::hints;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand)]
+pub enum HintsCommand {
+    /// Show the hints the developer extension adds to its instructions
+    #[command(about = "Show the merged hints for a directory and their token cost")]
+    Show {
+        /// Directory to show the hints for
+        #[arg(
+            long = "working-dir",
+            value_name = "DIR",
+            help = "Directory to show the hints for (defaults to the current directory)"
+        )]
+        working_dir: Option<PathBuf>,
+    },
+}
+
+pub fn handle_hints_command(command: HintsCommand) -> Result<()> {
+    match command {
+        HintsCommand::Show { working_dir } => handle_show(working_dir),
+    }
+}
+
+fn handle_show(working_dir: Option<PathBuf>) -> Result<()> {
+    let cwd = match working_dir {
+        Some(dir) => std::fs::canonicalize(dir)?,
+        None => std::env::current_dir()?,
+    };
+    let global_dir = hints::global_hints_dir();
+    let ignore_patterns = goose_mcp::load_ignore_patterns(&cwd);
+    let is_ignored = |path: &Path| ignore_patterns.matched(path, false).is_ignore();
+    let scope = hints::IncludeScope::new(&global_dir, &cwd, &is_ignored);
+    let files = hints::load_hints(&global_dir, &cwd, &scope);
+    if files.is_empty() {
+        println!(
+            "No hints found. Add a .goosehints or AGENTS.md file to {} or one of its parents in the repository.",
+            cwd.display()
+        );
+        return Ok(());
+    }
+
+    let counter = TokenCounter::new();
+    println!("{}", style("Hint files").bold());
+    for file in &files {
+        let scope = if file.global { "global" } else { "project" };
+        println!(
+            "  {} {} {}",
+            file.path.display(),
+            style(format!("({})", scope)).dim(),
+            style(format!("{} tokens", counter.count_tokens(&file.content))).cyan()
+        );
+        for included in &file.included {
+            println!("    {} {}", style("includes").dim(), included.display());
+        }
+    }
+
+    let merged = hints::format_hints(&files, &cwd);
+    println!();
+    println!("{}", merged);
+    println!();
+    println!(
+        "{} {}",
+        style("Total:").bold(),
+        style(format!("{} tokens", counter.count_tokens(&merged))).cyan()
+    );
+    println!(
+        "{}",
+        style("Hints in subdirectories are added when goose first works on files there.").dim()
+    );
+    Ok(())
+}
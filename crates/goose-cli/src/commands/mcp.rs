@@ -1,11 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use goose_mcp::{
     ComputerControllerRouter, DeveloperRouter, GoogleDriveRouter, MemoryRouter, TutorialRouter,
 };
 use mcp_server::router::RouterService;
-use mcp_server::{BoundedService, ByteTransport, Server};
+use mcp_server::{BoundedService, ByteTransport, HttpTransportConfig, Server};
 use tokio::io::{stdin, stdout};
 
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::Notify;
 
@@ -16,7 +17,50 @@ use nix::unistd::getpgrp;
 #[cfg(unix)]
 use nix::unistd::Pid;
 
-pub async fn run_server(name: &str) -> Result<()> {
+/// Parse a listen address, treating a bare ':port' as localhost
+fn parse_bind_addr(addr: &str) -> Result<SocketAddr> {
+    let addr = match addr.strip_prefix(':') {
+        Some(port) => format!("127.0.0.1:{port}"),
+        None => addr.to_string(),
+    };
+    addr.parse()
+        .with_context(|| format!("Invalid listen address '{addr}'"))
+}
+
+/// Build the HTTP transport config, refusing to listen beyond localhost without a token
+fn http_config(addr: &str, auth_token: Option<String>) -> Result<HttpTransportConfig> {
+    let bind_addr = parse_bind_addr(addr)?;
+    match &auth_token {
+        None if !bind_addr.ip().is_loopback() => anyhow::bail!(
+            "Refusing to listen on {bind_addr} without a bearer token. Pass --auth-token or set GOOSE_MCP_AUTH_TOKEN."
+        ),
+        None => tracing::warn!("Serving over HTTP without a bearer token"),
+        Some(_) => {}
+    }
+    Ok(HttpTransportConfig::new(bind_addr).with_auth_token(auth_token))
+}
+
+async fn serve(
+    server: Server<Box<dyn BoundedService>>,
+    http: Option<&str>,
+    auth_token: Option<String>,
+) -> Result<()> {
+    match http {
+        Some(addr) => {
+            let auth_token = auth_token.or_else(|| std::env::var("GOOSE_MCP_AUTH_TOKEN").ok());
+            let config = http_config(addr, auth_token)?;
+            tracing::info!("Server initialized and ready to handle HTTP requests");
+            Ok(server.run_http(config).await?)
+        }
+        None => {
+            let transport = ByteTransport::new(stdin(), stdout());
+            tracing::info!("Server initialized and ready to handle requests");
+            Ok(server.run(transport).await?)
+        }
+    }
+}
+
+pub async fn run_server(name: &str, http: Option<&str>, auth_token: Option<String>) -> Result<()> {
     // Initialize logging
     crate::logging::setup_logging(Some(&format!("mcp-{name}")), None)?;
 
@@ -34,6 +78,16 @@ pub async fn run_server(name: &str) -> Result<()> {
         _ => None,
     };
 
+    let server = Server::new(router.unwrap_or_else(|| panic!("Unknown server requested {}", name)));
+    serve_until_shutdown(server, http, auth_token).await
+}
+
+/// Serves until the transport closes or goose is interrupted
+pub(crate) async fn serve_until_shutdown(
+    server: Server<Box<dyn BoundedService>>,
+    http: Option<&str>,
+    auth_token: Option<String>,
+) -> Result<()> {
     // Create shutdown notification channel
     let shutdown = Arc::new(Notify::new());
     let shutdown_clone = shutdown.clone();
@@ -44,15 +98,9 @@ pub async fn run_server(name: &str) -> Result<()> {
         shutdown_clone.notify_one();
     });
 
-    // Create and run the server
-    let server = Server::new(router.unwrap_or_else(|| panic!("Unknown server requested {}", name)));
-    let transport = ByteTransport::new(stdin(), stdout());
-
-    tracing::info!("Server initialized and ready to handle requests");
-
     tokio::select! {
-        result = server.run(transport) => {
-            Ok(result?)
+        result = serve(server, http, auth_token) => {
+            result
         }
         _ = shutdown.notified() => {
             // On Unix systems, kill the entire process group
@@ -69,3 +117,22 @@ pub async fn run_server(name: &str) -> Result<()> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bare_port_binds_localhost() {
+        let addr = parse_bind_addr(":8080").unwrap();
+        assert_eq!(addr, SocketAddr::from(([127, 0, 0, 1], 8080)));
+    }
+
+    #[test]
+    fn test_public_address_requires_token() {
+        assert!(http_config("0.0.0.0:8080", None).is_err());
+        assert!(http_config("0.0.0.0:8080", Some("token".to_string())).is_ok());
+        assert!(http_config(":8080", None).is_ok());
+        assert!(http_config("[::1]:8080", None).is_ok());
+    }
+}
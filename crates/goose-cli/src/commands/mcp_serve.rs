@@ -0,0 +1,807 @@
+//! `goose mcp-serve`: goose itself as an MCP server
+//!
+//! Other MCP hosts can hand whole tasks to goose. Every tool call runs on a fresh agent with
+//! the configured provider and enabled extensions, and is saved as a regular goose session so it
+//! can be continued with a later call or resumed with `goose session --resume`.
+
+use anyhow::Result;
+use futures::StreamExt;
+use goose::agents::{Agent, AgentEvent, ExtensionConfig, SessionConfig};
+use goose::config::{Config, ExtensionConfigManager};
+use goose::message::{push_message, Message};
+use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::{Permission, PermissionConfirmation};
+use goose::providers::base::Provider;
+use goose::providers::create;
+use goose::recipe::build_recipe::{
+    build_recipe_from_template, validate_recipe_parameters, RecipeError,
+};
+use goose::recipe::{Recipe, RecipeParameterRequirement};
+use goose::session::info::{get_valid_sorted_sessions, SortOrder};
+use goose::session::{self, Identifier};
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    protocol::ServerCapabilities,
+};
+use mcp_server::router::{CapabilitiesBuilder, RouterService};
+use mcp_server::{BoundedService, Router, Server};
+use rmcp::model::{
+    Content, JsonRpcMessage, JsonRpcNotification, JsonRpcVersion2_0, Notification, Prompt,
+    PromptArgument, Resource, Tool, ToolAnnotations,
+};
+use rmcp::object;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::{future::Future, pin::Pin};
+use tokio::sync::mpsc;
+
+use crate::recipes::github_recipe::RecipeSource;
+use crate::recipes::search_recipe::{list_available_recipes, retrieve_recipe_file};
+
+use super::mcp::serve_until_shutdown;
+
+/// A recipe from the recipe library, offered as an MCP prompt
+#[derive(Clone)]
+struct RecipePrompt {
+    prompt: Prompt,
+    template: String,
+}
+
+#[derive(Clone)]
+pub struct GooseMcpRouter {
+    tools: Vec<Tool>,
+    prompts: Arc<HashMap<String, RecipePrompt>>,
+    instructions: String,
+}
+
+impl Default for GooseMcpRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GooseMcpRouter {
+    pub fn new() -> Self {
+        let run_task = Tool::new(
+            "run_task".to_string(),
+            "Hand a task to goose, an autonomous agent with its own tools and extensions (shell, file editing, browsers, and whatever else the user enabled), and wait for the result. Describe the task completely, goose does not see your conversation. Returns goose's final answer and the session id, pass the session_id to a later call to continue the same conversation with goose."
+            .to_string(),
+            object!({
+                "type": "object",
+                "required": ["instructions"],
+                "properties": {
+                    "instructions": {
+                        "type": "string",
+                        "description": "The task for goose, with all the context it needs"
+                    },
+                    "session_id": {
+                        "type": "string",
+                        "description": "Continue this goose session instead of starting a new one"
+                    },
+                    "max_turns": {
+                        "type": "integer",
+                        "description": "Maximum number of agent turns before goose stops"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Run a task with goose".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(true),
+        });
+
+        let run_recipe = Tool::new(
+            "run_recipe".to_string(),
+            "Run a goose recipe, a packaged task with its own instructions, extensions and parameters, and wait for the result. The recipe is a name from the recipe library, see the prompts of this server. Returns goose's final answer and the session id."
+            .to_string(),
+            object!({
+                "type": "object",
+                "required": ["recipe"],
+                "properties": {
+                    "recipe": {
+                        "type": "string",
+                        "description": "Name of a recipe in the recipe library"
+                    },
+                    "params": {
+                        "type": "object",
+                        "description": "Values for the recipe's parameters",
+                        "additionalProperties": {"type": "string"}
+                    },
+                    "prompt": {
+                        "type": "string",
+                        "description": "The message to start with, for recipes that have no prompt of their own"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Run a goose recipe".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(true),
+        });
+
+        let list_sessions = Tool::new(
+            "list_sessions".to_string(),
+            "List recent goose sessions, newest first, with their descriptions and working directories.".to_string(),
+            object!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of sessions to list (defaults to 10)",
+                        "default": 10
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("List goose sessions".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        let instructions = "This server is goose, an autonomous coding and automation agent. Use run_task to delegate a self-contained task to goose, and run_recipe to run one of the packaged recipes listed as prompts. Both block until goose has finished. Tool calls that need approval in goose's current mode are declined, since nobody can answer the approval prompt, so set GOOSE_MODE=auto for goose to act on its own."
+        .to_string();
+
+        Self {
+            tools: vec![run_task, run_recipe, list_sessions],
+            prompts: Arc::new(load_recipe_prompts()),
+            instructions,
+        }
+    }
+
+    async fn run_task(
+        &self,
+        arguments: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let instructions = arguments
+            .get("instructions")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("Missing 'instructions' parameter".to_string())
+            })?;
+        let session_id = arguments.get("session_id").and_then(|v| v.as_str());
+        let max_turns = arguments
+            .get("max_turns")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32);
+
+        let (agent, provider) = create_agent(None, None).await?;
+        let run = AgentRun {
+            agent,
+            provider,
+            notifier,
+        };
+        run.run(session_id, instructions.to_string(), max_turns, None)
+            .await
+    }
+
+    async fn run_recipe(
+        &self,
+        arguments: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let recipe_name = arguments
+            .get("recipe")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ToolError::InvalidParameters("Missing 'recipe' parameter".to_string())
+            })?;
+        let params: Vec<(String, String)> = arguments
+            .get("params")
+            .and_then(|v| v.as_object())
+            .map(|params| {
+                params
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let recipe = load_recipe_non_interactive(recipe_name, params)?;
+
+        let prompt = recipe
+            .prompt
+            .clone()
+            .filter(|p| !p.trim().is_empty())
+            .or_else(|| {
+                arguments
+                    .get("prompt")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            })
+            .ok_or_else(|| {
+                ToolError::InvalidParameters(format!(
+                    "Recipe '{}' has no prompt, pass the message to start with as 'prompt'",
+                    recipe_name
+                ))
+            })?;
+
+        let (agent, provider) = create_agent(Some(&recipe), recipe.extensions.clone()).await?;
+        if let Some(instructions) = &recipe.instructions {
+            agent.extend_system_prompt(instructions.clone()).await;
+        }
+        if let Some(sub_recipes) = recipe.sub_recipes.clone() {
+            agent.add_sub_recipes(sub_recipes).await;
+        }
+        if let Some(response) = recipe.response.clone() {
+            agent.add_final_output_tool(response).await;
+        }
+
+        let run = AgentRun {
+            agent,
+            provider,
+            notifier,
+        };
+        run.run(None, prompt, None, recipe.retry.clone()).await
+    }
+
+    fn list_sessions(&self, arguments: Value) -> Result<Vec<Content>, ToolError> {
+        let limit = arguments
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(10) as usize;
+
+        let sessions = get_valid_sorted_sessions(SortOrder::Descending)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to list sessions: {}", e)))?;
+
+        if sessions.is_empty() {
+            return Ok(vec![Content::text("No goose sessions found")]);
+        }
+
+        let lines: Vec<String> = sessions
+            .iter()
+            .take(limit)
+            .map(|s| {
+                let description = if s.metadata.description.is_empty() {
+                    "(no description)"
+                } else {
+                    s.metadata.description.as_str()
+                };
+                format!(
+                    "{} - {} - {} messages - {} - {}",
+                    s.id,
+                    description,
+                    s.metadata.message_count,
+                    s.metadata.working_dir.display(),
+                    s.modified
+                )
+            })
+            .collect();
+
+        Ok(vec![Content::text(lines.join("\n"))])
+    }
+}
+
+/// Whether a recipe is named the way the recipe library names them, rather than by a file path.
+/// Clients may only run recipes from the library, never arbitrary files on this host.
+fn is_library_recipe_name(recipe_name: &str) -> bool {
+    let path = Path::new(recipe_name);
+    let mut components = path.components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) && path.extension().is_none()
+        && !recipe_name.contains('\\')
+        && !recipe_name.starts_with('~')
+}
+
+/// Loads a recipe with the given parameter values, failing instead of prompting for missing ones
+fn load_recipe_non_interactive(
+    recipe_name: &str,
+    params: Vec<(String, String)>,
+) -> Result<Recipe, ToolError> {
+    if !is_library_recipe_name(recipe_name) {
+        return Err(ToolError::InvalidParameters(format!(
+            "Recipe '{}' is not a recipe name, only recipes from the recipe library can be run",
+            recipe_name
+        )));
+    }
+    let recipe_file = retrieve_recipe_file(recipe_name).map_err(|e| {
+        ToolError::InvalidParameters(format!("Recipe '{}' not found: {}", recipe_name, e))
+    })?;
+
+    build_recipe_from_template(
+        recipe_file,
+        params,
+        None::<fn(&str, &str) -> Result<String>>,
+    )
+    .map_err(|e| match e {
+        RecipeError::MissingParams { parameters } => ToolError::InvalidParameters(format!(
+            "Recipe '{}' needs values for these params: {}",
+            recipe_name,
+            parameters.join(", ")
+        )),
+        e => ToolError::ExecutionError(format!("Failed to load recipe '{}': {}", recipe_name, e)),
+    })
+}
+
+/// Prompts for the recipes in the recipe library, asking the host to run them with run_recipe
+fn load_recipe_prompts() -> HashMap<String, RecipePrompt> {
+    let recipes = match list_available_recipes() {
+        Ok(recipes) => recipes,
+        Err(e) => {
+            tracing::warn!("Failed to list recipes: {}", e);
+            return HashMap::new();
+        }
+    };
+
+    let mut prompts = HashMap::new();
+    for info in recipes {
+        if prompts.contains_key(&info.name) {
+            continue;
+        }
+
+        // Parameters are only read from local recipes, GitHub ones are fetched when they run
+        let parameters = match info.source {
+            RecipeSource::Local => retrieve_recipe_file(&info.path)
+                .ok()
+                .and_then(|file| {
+                    let dir = file.parent_dir.to_string_lossy().to_string();
+                    validate_recipe_parameters(&file.content, &dir).ok()
+                })
+                .flatten()
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        let arguments: Vec<PromptArgument> = parameters
+            .iter()
+            .map(|p| PromptArgument {
+                name: p.key.clone(),
+                description: Some(p.description.clone()),
+                required: Some(
+                    p.requirement == RecipeParameterRequirement::Required && p.default.is_none(),
+                ),
+            })
+            .collect();
+
+        let title = info.title.clone().unwrap_or_else(|| info.name.clone());
+        let description = info.description.clone().unwrap_or_default();
+        let template = recipe_prompt_template(&info.name, &title, &description, &arguments);
+
+        prompts.insert(
+            info.name.clone(),
+            RecipePrompt {
+                prompt: Prompt::new(&info.name, Some(&description), Some(arguments)),
+                template,
+            },
+        );
+    }
+    prompts
+}
+
+fn recipe_prompt_template(
+    recipe: &str,
+    title: &str,
+    description: &str,
+    arguments: &[PromptArgument],
+) -> String {
+    let params = if arguments.is_empty() {
+        "It takes no params.".to_string()
+    } else {
+        let lines: Vec<String> = arguments
+            .iter()
+            .map(|arg| format!("- {}: {{{}}}", arg.name, arg.name))
+            .collect();
+        format!(
+            "Pass these params, leaving out any whose value is still a placeholder in braces:\n{}",
+            lines.join("\n")
+        )
+    };
+
+    format!(
+        "Use the run_recipe tool to run the goose recipe \"{}\" ({}).\n{}\n\n{}\n",
+        recipe, title, description, params
+    )
+}
+
+/// An agent with a provider and the configured extensions, as `goose run` would set it up
+async fn create_agent(
+    recipe: Option<&Recipe>,
+    extensions_override: Option<Vec<ExtensionConfig>>,
+) -> Result<(Agent, Arc<dyn Provider>), ToolError> {
+    let config = Config::global();
+    let settings = recipe.and_then(|r| r.settings.as_ref());
+
+    let provider_name = settings
+        .and_then(|s| s.goose_provider.clone())
+        .or_else(|| config.get_param("GOOSE_PROVIDER").ok())
+        .ok_or_else(|| {
+            ToolError::ExecutionError(
+                "No provider configured. Run 'goose configure' first".to_string(),
+            )
+        })?;
+    let model_name = settings
+        .and_then(|s| s.goose_model.clone())
+        .or_else(|| config.get_param("GOOSE_MODEL").ok())
+        .ok_or_else(|| {
+            ToolError::ExecutionError(
+                "No model configured. Run 'goose configure' first".to_string(),
+            )
+        })?;
+
+    let model_config = goose::model::ModelConfig::new(model_name)
+        .with_temperature(settings.and_then(|s| s.temperature))
+        .with_reasoning_effort(settings.and_then(|s| s.reasoning_effort))
+        .with_thinking_budget(settings.and_then(|s| s.thinking_budget));
+    let provider = create(&provider_name, model_config).map_err(|e| {
+        ToolError::ExecutionError(format!(
+            "Failed to create provider {}: {}",
+            provider_name, e
+        ))
+    })?;
+
+    let agent = Agent::new();
+    agent
+        .update_provider(provider.clone())
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to initialize agent: {}", e)))?;
+    if let Some(prompt_id) = settings.and_then(|s| s.system_prompt_id.clone()) {
+        agent.set_system_prompt_id(prompt_id).await;
+    }
+
+    let extensions = match extensions_override {
+        Some(extensions) => extensions,
+        None => ExtensionConfigManager::get_all()
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to load extensions: {}", e)))?
+            .into_iter()
+            .filter(|ext| ext.enabled)
+            .map(|ext| ext.config)
+            .collect(),
+    };
+    for extension in extensions {
+        if let Err(e) = agent.add_extension(extension.clone()).await {
+            tracing::warn!("Continuing without extension '{}': {}", extension.name(), e);
+        }
+    }
+
+    Ok((agent, provider))
+}
+
+struct AgentRun {
+    agent: Agent,
+    provider: Arc<dyn Provider>,
+    notifier: mpsc::Sender<JsonRpcMessage>,
+}
+
+impl AgentRun {
+    /// Runs the agent to completion on a new or existing session and returns its last answer
+    async fn run(
+        &self,
+        session_id: Option<&str>,
+        prompt: String,
+        max_turns: Option<u32>,
+        retry_config: Option<goose::agents::types::RetryConfig>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let (session_id, session_file) = match session_id {
+            Some(id) => {
+                let path = session::get_path(Identifier::Name(id.to_string())).map_err(|e| {
+                    ToolError::InvalidParameters(format!("Invalid session id '{}': {}", id, e))
+                })?;
+                if !path.exists() {
+                    return Err(ToolError::InvalidParameters(format!(
+                        "No goose session '{}' exists",
+                        id
+                    )));
+                }
+                (id.to_string(), path)
+            }
+            None => new_session_file()?,
+        };
+
+        let mut messages = if session_file.exists() {
+            session::read_messages(&session_file).map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to read session {}: {}", session_id, e))
+            })?
+        } else {
+            Vec::new()
+        };
+        messages.push(Message::user().with_text(prompt));
+
+        let working_dir = std::env::current_dir().map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to get working directory: {}", e))
+        })?;
+        let session_config = SessionConfig {
+            id: Identifier::Name(session_id.clone()),
+            working_dir: working_dir.clone(),
+            schedule_id: None,
+            execution_mode: None,
+            max_turns,
+            retry_config,
+        };
+
+        let mut stream = self
+            .agent
+            .reply(&messages, Some(session_config), None)
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("goose failed to start: {}", e)))?;
+
+        let mut error = None;
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(AgentEvent::Message(message)) => {
+                    if let Some(confirmation) = message.as_tool_confirmation_request() {
+                        self.notify(format!(
+                            "Declined {}, tools that need approval cannot run through mcp-serve",
+                            confirmation.tool_name
+                        ));
+                        self.agent
+                            .handle_confirmation(
+                                confirmation.id.clone(),
+                                PermissionConfirmation {
+                                    principal_type: PrincipalType::Tool,
+                                    permission: Permission::DenyOnce,
+                                    arguments: None,
+                                },
+                            )
+                            .await;
+                        continue;
+                    }
+                    if let Some(request) = message.as_tool_request() {
+                        if let Ok(tool_call) = &request.tool_call {
+                            self.notify(format!("Running {}", tool_call.name));
+                        }
+                    }
+                    push_message(&mut messages, message);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+        drop(stream);
+
+        if let Err(e) = session::persist_messages(
+            &session_file,
+            &messages,
+            Some(self.provider.clone()),
+            Some(working_dir),
+        )
+        .await
+        {
+            tracing::error!("Failed to save session {}: {}", session_id, e);
+        }
+
+        if let Some(e) = error {
+            return Err(ToolError::ExecutionError(format!(
+                "goose stopped with an error: {}. Session: {}",
+                e, session_id
+            )));
+        }
+
+        let answer = messages
+            .iter()
+            .rev()
+            .find(|m| m.role == rmcp::model::Role::Assistant)
+            .map(|m| m.as_concat_text())
+            .filter(|text| !text.trim().is_empty())
+            .unwrap_or_else(|| "goose finished without a final answer".to_string());
+
+        Ok(vec![
+            Content::text(answer),
+            Content::text(format!("session_id: {}", session_id)),
+        ])
+    }
+
+    /// Progress for the host, as an MCP log message
+    fn notify(&self, text: String) {
+        self.notifier
+            .try_send(JsonRpcMessage::Notification(JsonRpcNotification {
+                jsonrpc: JsonRpcVersion2_0,
+                notification: Notification {
+                    method: "notifications/message".to_string(),
+                    params: object!({
+                        "level": "info",
+                        "logger": "goose",
+                        "data": text,
+                    }),
+                    extensions: Default::default(),
+                },
+            }))
+            .ok();
+    }
+}
+
+/// A session file for a new run, session ids have one second resolution so they get a suffix
+/// when several runs start at once
+fn new_session_file() -> Result<(String, PathBuf), ToolError> {
+    let base = session::generate_session_id();
+    let mut id = base.clone();
+    let mut n = 1;
+    loop {
+        let path = session::get_path(Identifier::Name(id.clone()))
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to create session: {}", e)))?;
+        if !path.exists() {
+            // Claim the name before the agent starts so concurrent runs pick another one
+            std::fs::File::create(&path).map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to create session: {}", e))
+            })?;
+            return Ok((id, path));
+        }
+        n += 1;
+        id = format!("{}_{}", base, n);
+    }
+}
+
+impl Router for GooseMcpRouter {
+    fn name(&self) -> String {
+        "goose".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new()
+            .with_tools(false)
+            .with_prompts(false)
+            .build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            match tool_name.as_str() {
+                "run_task" => this.run_task(arguments, notifier).await,
+                "run_recipe" => this.run_recipe(arguments, notifier).await,
+                "list_sessions" => this.list_sessions(arguments),
+                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            }
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        _uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        Box::pin(async move { Ok("".to_string()) })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        let mut prompts: Vec<Prompt> = self.prompts.values().map(|p| p.prompt.clone()).collect();
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt = self.prompts.get(prompt_name).map(|p| p.template.clone());
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            prompt.ok_or_else(|| PromptError::NotFound(format!("Prompt {} not found", prompt_name)))
+        })
+    }
+}
+
+pub async fn run_mcp_serve(http: Option<&str>, auth_token: Option<String>) -> Result<()> {
+    crate::logging::setup_logging(Some("mcp-serve"), None)?;
+
+    // Every tool runs goose with the user's provider and extensions, so remote callers always
+    // need a token, even on localhost
+    let auth_token = auth_token.or_else(|| std::env::var("GOOSE_MCP_AUTH_TOKEN").ok());
+    if http.is_some() && auth_token.is_none() {
+        anyhow::bail!(
+            "Serving goose over HTTP requires a bearer token. Pass --auth-token or set GOOSE_MCP_AUTH_TOKEN."
+        );
+    }
+
+    tracing::info!("Starting goose as an MCP server");
+
+    let router: Box<dyn BoundedService> = Box::new(RouterService(GooseMcpRouter::new()));
+    serve_until_shutdown(Server::new(router), http, auth_token).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipe_prompt_template() {
+        let arguments = vec![
+            PromptArgument {
+                name: "language".to_string(),
+                description: Some("Language to translate to".to_string()),
+                required: Some(true),
+            },
+            PromptArgument {
+                name: "tone".to_string(),
+                description: None,
+                required: Some(false),
+            },
+        ];
+        let template =
+            recipe_prompt_template("translate", "Translate", "Translates text", &arguments);
+
+        assert!(template.contains("run_recipe tool to run the goose recipe \"translate\""));
+        assert!(template.contains("- language: {language}"));
+        assert!(template.contains("- tone: {tone}"));
+
+        let template = recipe_prompt_template("hello", "Hello", "Says hello", &[]);
+        assert!(template.contains("It takes no params."));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tool_and_prompt() {
+        let router = GooseMcpRouter {
+            tools: vec![],
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+        };
+        let (tx, _rx) = mpsc::channel(1);
+
+        let result = router.call_tool("nope", Value::Null, tx).await;
+        assert!(matches!(result, Err(ToolError::NotFound(_))));
+
+        let result = router.get_prompt("nope").await;
+        assert!(matches!(result, Err(PromptError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_run_task_requires_instructions() {
+        let router = GooseMcpRouter {
+            tools: vec![],
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+        };
+        let (tx, _rx) = mpsc::channel(1);
+
+        let result = router
+            .call_tool("run_task", serde_json::json!({}), tx)
+            .await;
+        assert!(matches!(result, Err(ToolError::InvalidParameters(_))));
+    }
+
+    #[test]
+    fn test_only_library_recipe_names_are_accepted() {
+        assert!(is_library_recipe_name("translate"));
+        assert!(is_library_recipe_name("code-review"));
+        for name in [
+            "/etc/recipe",
+            "../recipe",
+            "./translate.yaml",
+            "translate.yaml",
+            "nested/recipe",
+            "~/recipe",
+            "..\\recipe",
+        ] {
+            assert!(!is_library_recipe_name(name), "{name} should be rejected");
+        }
+    }
+}
@@ -1,7 +1,11 @@
+pub mod audit;
 pub mod bench;
 pub mod configure;
+pub mod hints;
 pub mod info;
 pub mod mcp;
+pub mod mcp_serve;
+pub mod permissions;
 pub mod project;
 pub mod recipe;
 pub mod schedule;
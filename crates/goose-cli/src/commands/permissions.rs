@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use console::style;
+use goose::config::permission::PermissionLevel;
+use goose::config::{Config, PermissionManager};
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum PermissionsCommand {
+    /// Show which permission a tool call would get
+    #[command(about = "Check which permission a tool call would get")]
+    Test {
+        /// Prefixed tool name, e.g. developer__shell
+        #[arg(help = "Prefixed tool name, e.g. developer__shell")]
+        tool: String,
+
+        /// Tool arguments as a JSON object
+        #[arg(
+            long,
+            value_name = "JSON",
+            help = "Tool arguments as a JSON object",
+            default_value = "{}"
+        )]
+        args: String,
+
+        /// Working directory used to resolve path conditions
+        #[arg(
+            long = "working-dir",
+            value_name = "DIR",
+            help = "Working directory used to resolve path conditions (defaults to the current directory)"
+        )]
+        working_dir: Option<PathBuf>,
+    },
+
+    /// List the argument rules in permission.yaml
+    #[command(about = "List argument rules in evaluation order")]
+    Rules {},
+}
+
+fn level_label(level: &PermissionLevel) -> String {
+    let label = match level {
+        PermissionLevel::AlwaysAllow => style("always_allow").green(),
+        PermissionLevel::AskBefore => style("ask_before").yellow(),
+        PermissionLevel::NeverAllow => style("never_allow").red(),
+    };
+    label.bold().to_string()
+}
+
+pub fn handle_permissions_command(command: PermissionsCommand) -> Result<()> {
+    match command {
+        PermissionsCommand::Test {
+            tool,
+            args,
+            working_dir,
+        } => handle_test(&tool, &args, working_dir),
+        PermissionsCommand::Rules {} => handle_rules(),
+    }
+}
+
+fn handle_test(tool: &str, args: &str, working_dir: Option<PathBuf>) -> Result<()> {
+    let arguments: Value =
+        serde_json::from_str(args).context("--args must be a JSON object of tool arguments")?;
+    if !arguments.is_object() {
+        anyhow::bail!("--args must be a JSON object of tool arguments");
+    }
+    let working_dir = match working_dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+
+    let manager = PermissionManager::default();
+    let mode: String = Config::global()
+        .get_param("GOOSE_MODE")
+        .unwrap_or_else(|_| "auto".to_string());
+    // Auto and chat modes only honor never_allow rules, the same as when the agent checks a call
+    let only_denials = mode == "auto" || mode == "chat";
+
+    let rule = manager.evaluate_rules(tool, &arguments, &working_dir);
+    if let Some((index, rule)) = rule {
+        if !only_denials || rule.permission == PermissionLevel::NeverAllow {
+            println!(
+                "{} (rule #{}{})",
+                level_label(&rule.permission),
+                index + 1,
+                rule.description
+                    .as_ref()
+                    .map(|d| format!(": {}", d))
+                    .unwrap_or_default()
+            );
+            return Ok(());
+        }
+    }
+
+    if let Some(level) = manager.get_user_permission(tool) {
+        if rule.is_none() && !only_denials {
+            println!("{} (tool permission)", level_label(&level));
+            return Ok(());
+        }
+    }
+
+    if mode == "smart_approve" {
+        if let Some(level) = manager.get_smart_approve_permission(tool) {
+            println!("{} (remembered by smart approve)", level_label(&level));
+            return Ok(());
+        }
+    }
+
+    println!(
+        "No rule or tool permission applies; decided by GOOSE_MODE ({})",
+        style(mode).cyan()
+    );
+    Ok(())
+}
+
+fn handle_rules() -> Result<()> {
+    let manager = PermissionManager::default();
+    let rules = manager.get_rules();
+    if rules.is_empty() {
+        println!("No argument rules configured");
+        return Ok(());
+    }
+
+    for (index, rule) in rules.iter().enumerate() {
+        println!(
+            "{}. {} {}",
+            index + 1,
+            style(&rule.tool).cyan(),
+            level_label(&rule.permission)
+        );
+        if let Some(description) = &rule.description {
+            println!("   {}", style(description).dim());
+        }
+        for (argument, condition) in &rule.when {
+            let conditions = [
+                ("matches", &condition.matches),
+                ("not_matches", &condition.not_matches),
+                ("within", &condition.within),
+                ("outside", &condition.outside),
+            ];
+            for (name, value) in conditions {
+                if let Some(value) = value {
+                    println!("   {} {} {}", argument, name, value);
+                }
+            }
+        }
+        if let Err(e) = rule.validate() {
+            println!("   {} {}", style("invalid:").red(), e);
+        }
+    }
+    Ok(())
+}
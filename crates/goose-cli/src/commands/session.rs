@@ -1,8 +1,14 @@
 use crate::session::message_to_markdown;
 use anyhow::{Context, Result};
+use clap::ValueEnum;
 use cliclack::{confirm, multiselect, select};
+use goose::agents::Agent;
+use goose::config::{Config, ExtensionConfigManager};
+use goose::model::ModelConfig;
+use goose::session::export::{self, SessionExport};
+use goose::session::import::{self, TranscriptFormat};
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
-use goose::session::{self, Identifier};
+use goose::session::{self, Identifier, SessionMetadata};
 use goose::utils::safe_truncate;
 use regex::Regex;
 use std::fs;
@@ -166,11 +172,30 @@ pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Re
     Ok(())
 }
 
-/// Export a session to Markdown without creating a full Session object
+/// The formats a session can be exported to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Markdown, as the CLI renders the conversation
+    #[default]
+    Markdown,
+    /// A single HTML page with collapsible tool calls and embedded images
+    Html,
+    /// JSON with a stable, versioned schema
+    Json,
+    /// A recipe in YAML that reruns what the session did
+    Recipe,
+}
+
+/// Export a session without creating a full Session object
 ///
-/// This function directly reads messages from the session file and converts them to Markdown
-/// without creating an Agent or prompting about working directories.
-pub fn handle_session_export(identifier: Identifier, output_path: Option<PathBuf>) -> Result<()> {
+/// Markdown, HTML and JSON are rendered directly from the session file, without an Agent or
+/// prompting about working directories. A recipe export asks the configured model to write the
+/// recipe, so only that format needs a provider.
+pub async fn handle_session_export(
+    identifier: Identifier,
+    output_path: Option<PathBuf>,
+    format: ExportFormat,
+) -> Result<()> {
     // Get the session file path
     let session_file_path = match goose::session::get_path(identifier.clone()) {
         Ok(path) => path,
@@ -194,18 +219,141 @@ pub fn handle_session_export(identifier: Identifier, output_path: Option<PathBuf
         }
     };
 
-    // Generate the markdown content using the export functionality
-    let markdown = export_session_to_markdown(messages, &session_file_path, None);
+    let content = match format {
+        ExportFormat::Markdown => export_session_to_markdown(messages, &session_file_path, None),
+        ExportFormat::Html | ExportFormat::Json => {
+            let metadata = session::read_metadata(&session_file_path)?;
+            let session_id = session_file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            let export = SessionExport::new(session_id, &metadata, &messages);
+            if format == ExportFormat::Html {
+                export::to_html(&export)
+            } else {
+                serde_json::to_string_pretty(&export)?
+            }
+        }
+        ExportFormat::Recipe => {
+            let metadata = session::read_metadata(&session_file_path)?;
+            export_session_to_recipe(messages, &metadata).await?
+        }
+    };
 
-    // Output the markdown
     if let Some(output) = output_path {
-        fs::write(&output, markdown)
+        fs::write(&output, content)
             .with_context(|| format!("Failed to write to output file: {}", output.display()))?;
         println!("Session exported to {}", output.display());
     } else {
-        println!("{}", markdown);
+        println!("{}", content);
+    }
+
+    Ok(())
+}
+
+/// Asks the configured model to turn the session into a recipe, with the enabled extensions
+/// loaded so the recipe knows which tools the session had
+async fn export_session_to_recipe(
+    messages: Vec<goose::message::Message>,
+    metadata: &SessionMetadata,
+) -> Result<String> {
+    if messages.is_empty() {
+        return Err(anyhow::anyhow!(
+            "The session has no messages to make a recipe from"
+        ));
+    }
+
+    let config = Config::global();
+    let provider_name: String = config
+        .get_param("GOOSE_PROVIDER")
+        .context("No provider configured. Run 'goose configure' first")?;
+    let model: String = config
+        .get_param("GOOSE_MODEL")
+        .context("No model configured. Run 'goose configure' first")?;
+
+    let agent = Agent::new();
+    let provider = goose::providers::create(&provider_name, ModelConfig::new(model))?;
+    agent.update_provider(provider).await?;
+    for extension in ExtensionConfigManager::get_all()? {
+        if extension.enabled {
+            if let Err(e) = agent.add_extension(extension.config.clone()).await {
+                eprintln!(
+                    "Warning: Failed to load extension {}: {}",
+                    extension.config.name(),
+                    e
+                );
+            }
+        }
+    }
+
+    let mut recipe = agent.create_recipe(messages).await?;
+    if !metadata.description.is_empty() {
+        recipe.title = metadata.description.clone();
+    }
+    Ok(serde_yaml::to_string(&recipe)?)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// OpenAI chat completions messages
+    #[value(name = "openai")]
+    OpenAi,
+    /// Anthropic Messages API messages
+    Anthropic,
+    /// One JSON message per line
+    Jsonl,
+}
+
+impl From<ImportFormat> for TranscriptFormat {
+    fn from(format: ImportFormat) -> Self {
+        match format {
+            ImportFormat::OpenAi => TranscriptFormat::OpenAi,
+            ImportFormat::Anthropic => TranscriptFormat::Anthropic,
+            ImportFormat::Jsonl => TranscriptFormat::Jsonl,
+        }
     }
+}
 
+pub fn handle_session_import(
+    format: ImportFormat,
+    file: PathBuf,
+    name: Option<String>,
+) -> Result<()> {
+    let content = fs::read_to_string(&file)
+        .with_context(|| format!("Failed to read transcript: {}", file.display()))?;
+
+    let name = name.unwrap_or_else(session::generate_session_id);
+    let session_file = session::get_path(Identifier::Name(name.clone()))?;
+    if session_file.exists() {
+        return Err(anyhow::anyhow!(
+            "A session named '{}' already exists, choose another --name",
+            name
+        ));
+    }
+
+    let metadata = SessionMetadata {
+        description: format!(
+            "Imported from {}",
+            file.file_name()
+                .map(|f| f.to_string_lossy())
+                .unwrap_or_default()
+        ),
+        ..SessionMetadata::new(std::env::current_dir()?)
+    };
+    let imported = import::import_transcript(format.into(), &content, &session_file, metadata)?;
+
+    println!(
+        "Imported {} messages into session {}",
+        imported.messages.len(),
+        name
+    );
+    if !imported.issues.is_empty() {
+        println!("Changes made to fit the transcript to a goose conversation:");
+        for issue in &imported.issues {
+            println!("- {}", issue);
+        }
+    }
+    println!("Resume it with: goose session --resume --name {}", name);
     Ok(())
 }
 
@@ -583,6 +583,7 @@ async fn process_message_streaming(
                                         goose::permission::PermissionConfirmation {
                                             principal_type: goose::permission::permission_confirmation::PrincipalType::Tool,
                                             permission: goose::permission::Permission::AllowOnce,
+                                            arguments: None,
                                         }
                                     ).await;
                                 }
@@ -635,6 +636,7 @@ async fn process_message_streaming(
                         // Log model change
                         tracing::info!("Model changed to {} in {} mode", model, mode);
                     }
+                    Ok(_) => {}
 
                     Err(e) => {
                         error!("Error in message stream: {}", e);
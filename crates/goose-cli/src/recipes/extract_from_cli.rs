@@ -58,6 +58,8 @@ pub fn extract_recipe_info_from_cli(
             goose_provider: s.goose_provider,
             goose_model: s.goose_model,
             temperature: s.temperature,
+            reasoning_effort: s.reasoning_effort,
+            thinking_budget: s.thinking_budget,
             system_prompt_id: s.system_prompt_id,
         }),
         sub_recipes: Some(all_sub_recipes),
@@ -86,6 +88,7 @@ fn extract_recipe_name(recipe_identifier: &str) -> String {
 mod tests {
     use std::path::PathBuf;
 
+    use goose::model::ReasoningEffort;
     use tempfile::TempDir;
 
     use super::*;
@@ -114,6 +117,8 @@ mod tests {
         assert_eq!(settings.goose_provider, Some("test_provider".to_string()));
         assert_eq!(settings.goose_model, Some("test_model".to_string()));
         assert_eq!(settings.temperature, Some(0.7));
+        assert_eq!(settings.reasoning_effort, Some(ReasoningEffort::High));
+        assert_eq!(settings.thinking_budget, Some(4000));
 
         assert!(sub_recipes.is_some());
         let sub_recipes = sub_recipes.unwrap();
@@ -239,6 +244,8 @@ settings:
   goose_provider: test_provider
   goose_model: test_model
   temperature: 0.7
+  reasoning_effort: high
+  thinking_budget: 4000
 sub_recipes:
 - path: existing_sub_recipe.yaml
   name: existing_sub_recipe        
@@ -7,7 +7,10 @@ use mcp_core::protocol::{
     ListToolsResult, ReadResourceResult, ServerCapabilities, ToolsCapability,
 };
 use mcp_core::{Tool, ToolError};
-use rmcp::model::{Content, GetPromptResult, ServerNotification};
+use rmcp::model::{
+    ArgumentInfo, CompleteResult, Content, GetPromptResult, ListResourceTemplatesResult, Reference,
+    ServerNotification,
+};
 use serde_json::Value;
 use std::collections::HashMap;
 use tokio::sync::mpsc::{self, Receiver};
@@ -49,6 +52,7 @@ impl McpClientTrait for MockClient {
                 prompts: None,
                 resources: None,
                 tools: Some(ToolsCapability { list_changed: None }),
+                completions: None,
             },
             server_info: Implementation {
                 name: "MockClient".to_string(),
@@ -74,6 +78,13 @@ impl McpClientTrait for MockClient {
         ))
     }
 
+    async fn list_resource_templates(
+        &self,
+        _next_cursor: Option<String>,
+    ) -> Result<ListResourceTemplatesResult, Error> {
+        Ok(ListResourceTemplatesResult::default())
+    }
+
     async fn list_tools(&self, _: Option<String>) -> Result<ListToolsResult, Error> {
         let rmcp_tools: Vec<rmcp::model::Tool> = self
             .tools
@@ -126,6 +137,16 @@ impl McpClientTrait for MockClient {
         ))
     }
 
+    async fn complete(
+        &self,
+        _reference: Reference,
+        _argument: ArgumentInfo,
+    ) -> Result<CompleteResult, Error> {
+        Err(Error::UnexpectedResponse(
+            "Completions not supported by mock client".to_string(),
+        ))
+    }
+
     async fn subscribe(&self) -> Receiver<ServerNotification> {
         mpsc::channel(1).1
     }
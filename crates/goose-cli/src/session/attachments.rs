@@ -0,0 +1,401 @@
+//! Files and images attached to the prompts of an interactive session. `@path` mentions inline
+//! the contents of text files, images come from `/image` or the clipboard and are sent as image
+//! content, scaled down the same way the developer extension's image_processor does.
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use goose::message::MessageContent;
+use image::{ImageFormat, ImageOutputFormat};
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Mentioned files larger than this are not inlined
+const MAX_MENTION_FILE_SIZE: u64 = 100 * 1024;
+
+/// All the files mentioned in one prompt together
+const MAX_MENTIONS_SIZE: u64 = 400 * 1024;
+
+/// Same limit as the image_processor tool
+const MAX_IMAGE_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Wider images are scaled down, as the image_processor tool does
+const MAX_IMAGE_WIDTH: u32 = 768;
+
+/// The files and images a prompt mentions
+#[derive(Debug, Default)]
+pub struct ExpandedPrompt {
+    /// The contents of each text file, to send after the prompt
+    pub files: Vec<String>,
+    pub images: Vec<MessageContent>,
+    pub report: AttachmentReport,
+}
+
+/// What happened to the files a prompt mentions, for telling the user
+#[derive(Debug, Default)]
+pub struct AttachmentReport {
+    /// Paths of the files that were inlined or attached
+    pub attached: Vec<String>,
+    /// Why a mentioned file was left out
+    pub skipped: Vec<String>,
+}
+
+/// The paths mentioned with `@` in the text, in order and without duplicates. A mention starts
+/// a word, so email addresses are left alone, and can be quoted to allow spaces.
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let mut mentions = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for (index, c) in text.char_indices() {
+        let starts_word = previous.is_none_or(char::is_whitespace);
+        previous = Some(c);
+        if c != '@' || !starts_word {
+            continue;
+        }
+
+        let rest = &text[index + 1..];
+        let mention = if let Some(quoted) = rest.strip_prefix('"') {
+            quoted.split_once('"').map(|(path, _)| path)
+        } else {
+            rest.split_whitespace().next()
+        };
+        if let Some(mention) = mention.filter(|m| !m.is_empty()) {
+            if !mentions.iter().any(|m| m == mention) {
+                mentions.push(mention.to_string());
+            }
+        }
+    }
+    mentions
+}
+
+/// Reads the text files and images mentioned in the prompt. Mentions that are not files are
+/// ignored, the user may have meant something else.
+pub fn expand_mentions(
+    text: &str,
+    base_dir: &Path,
+    is_ignored: impl Fn(&Path) -> bool,
+) -> ExpandedPrompt {
+    let mut expanded = ExpandedPrompt::default();
+    let mut seen = HashSet::new();
+    let mut total_size = 0;
+
+    for mention in parse_mentions(text) {
+        let Some((display, path)) = resolve_mention(&mention, base_dir) else {
+            continue;
+        };
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        if is_ignored(&path) {
+            expanded
+                .report
+                .skipped
+                .push(format!("{} is restricted by .gooseignore", display));
+            continue;
+        }
+
+        // Not even an image is read when it is this large
+        let too_large = std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_IMAGE_FILE_SIZE);
+        if too_large {
+            expanded
+                .report
+                .skipped
+                .push(format!("{} is too large to include", display));
+            continue;
+        }
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                expanded
+                    .report
+                    .skipped
+                    .push(format!("{} could not be read: {}", display, e));
+                continue;
+            }
+        };
+        if image::guess_format(&bytes).is_ok() {
+            match image_content(bytes) {
+                Ok(image) => {
+                    expanded.images.push(image);
+                    expanded.report.attached.push(display);
+                }
+                Err(e) => expanded.report.skipped.push(format!("{}: {}", display, e)),
+            }
+            continue;
+        }
+
+        let size = bytes.len() as u64;
+        if size > MAX_MENTION_FILE_SIZE {
+            expanded.report.skipped.push(format!(
+                "{} is too large to include ({} KB, the limit is {} KB)",
+                display,
+                size / 1024,
+                MAX_MENTION_FILE_SIZE / 1024
+            ));
+            continue;
+        }
+        if total_size + size > MAX_MENTIONS_SIZE {
+            expanded.report.skipped.push(format!(
+                "{} would take the files in this message over {} KB",
+                display,
+                MAX_MENTIONS_SIZE / 1024
+            ));
+            continue;
+        }
+        let Ok(contents) = String::from_utf8(bytes) else {
+            expanded
+                .report
+                .skipped
+                .push(format!("{} is not a text file", display));
+            continue;
+        };
+
+        total_size += size;
+        expanded.files.push(format!(
+            "<file path=\"{}\">\n{}{}</file>",
+            display,
+            contents,
+            if contents.ends_with('\n') { "" } else { "\n" }
+        ));
+        expanded.report.attached.push(display);
+    }
+    expanded
+}
+
+/// The path a mention refers to, relative to the base directory, when it is a file. Punctuation
+/// that ends the sentence the mention is in is not part of the path.
+fn resolve_mention(mention: &str, base_dir: &Path) -> Option<(String, PathBuf)> {
+    let mut candidate = mention;
+    loop {
+        let path = base_dir.join(expand_home(candidate));
+        if path.is_file() {
+            return Some((candidate.to_string(), path));
+        }
+        let trimmed = candidate.trim_end_matches([',', '.', ';', ':', '!', '?', ')', ']', '\'']);
+        if trimmed == candidate || trimmed.is_empty() {
+            return None;
+        }
+        candidate = trimmed;
+    }
+}
+
+/// Paths starting with `~/` are in the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Reads the image file at a path the user typed, relative to `base_dir`, to attach to the next
+/// message. Files matched by `is_ignored` are refused, the same as for mentions.
+pub fn load_image(
+    path: &str,
+    base_dir: &Path,
+    is_ignored: impl Fn(&Path) -> bool,
+) -> Result<MessageContent> {
+    let path = base_dir.join(expand_home(path));
+    if is_ignored(&path) {
+        bail!("{} is restricted by .gooseignore", path.display());
+    }
+    let size = std::fs::metadata(&path)
+        .with_context(|| format!("Could not read {}", path.display()))?
+        .len();
+    if size > MAX_IMAGE_FILE_SIZE {
+        bail!(
+            "{} is too large ({:.2}MB). Maximum size is 10MB.",
+            path.display(),
+            size as f64 / (1024.0 * 1024.0)
+        );
+    }
+    let bytes =
+        std::fs::read(&path).with_context(|| format!("Could not read {}", path.display()))?;
+    image_content(bytes)
+}
+
+/// Reads the image on the system clipboard with the platform's own tools
+pub fn paste_image() -> Result<MessageContent> {
+    let bytes = read_clipboard_image()?;
+    if bytes.is_empty() {
+        bail!("There is no image on the clipboard");
+    }
+    image_content(bytes)
+}
+
+/// Image content for the bytes of an image file, the format is sniffed from the bytes rather
+/// than trusted from the file name. Images in a format every provider accepts and that are small
+/// enough are sent as they are, anything else is sent as PNG.
+fn image_content(bytes: Vec<u8>) -> Result<MessageContent> {
+    let format = image::guess_format(&bytes).map_err(|_| anyhow!("Not a supported image"))?;
+    let image = image::load_from_memory_with_format(&bytes, format)
+        .map_err(|e| anyhow!("Failed to decode the image: {}", e))?;
+
+    let mime_type = match format {
+        ImageFormat::Png => Some("image/png"),
+        ImageFormat::Jpeg => Some("image/jpeg"),
+        ImageFormat::Gif => Some("image/gif"),
+        ImageFormat::WebP => Some("image/webp"),
+        _ => None,
+    };
+    if let Some(mime_type) = mime_type.filter(|_| image.width() <= MAX_IMAGE_WIDTH) {
+        let data = base64::prelude::BASE64_STANDARD.encode(&bytes);
+        return Ok(MessageContent::image(data, mime_type));
+    }
+
+    let image = if image.width() > MAX_IMAGE_WIDTH {
+        let scale = MAX_IMAGE_WIDTH as f32 / image.width() as f32;
+        let height = (image.height() as f32 * scale) as u32;
+        image.resize(
+            MAX_IMAGE_WIDTH,
+            height.max(1),
+            image::imageops::FilterType::Lanczos3,
+        )
+    } else {
+        image
+    };
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+        .map_err(|e| anyhow!("Failed to encode the image: {}", e))?;
+    let data = base64::prelude::BASE64_STANDARD.encode(png);
+    Ok(MessageContent::image(data, "image/png"))
+}
+
+#[cfg(target_os = "macos")]
+fn read_clipboard_image() -> Result<Vec<u8>> {
+    // AppleScript prints the PNG data as «data PNGf89504E47...»
+    let output = Command::new("osascript")
+        .args(["-e", "the clipboard as «class PNGf»"])
+        .output()
+        .context("Failed to run osascript")?;
+    if !output.status.success() {
+        bail!("There is no image on the clipboard");
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let hex = text
+        .trim()
+        .strip_prefix("«data PNGf")
+        .and_then(|rest| rest.strip_suffix('»'))
+        .ok_or_else(|| anyhow!("There is no image on the clipboard"))?;
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| anyhow!("Unexpected clipboard data"))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn read_clipboard_image() -> Result<Vec<u8>> {
+    let script = "Add-Type -AssemblyName System.Windows.Forms; \
+        $image = [System.Windows.Forms.Clipboard]::GetImage(); \
+        if ($image) { $stream = New-Object System.IO.MemoryStream; \
+        $image.Save($stream, [System.Drawing.Imaging.ImageFormat]::Png); \
+        [Convert]::ToBase64String($stream.ToArray()) }";
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-STA", "-Command", script])
+        .output()
+        .context("Failed to run powershell")?;
+    let encoded = String::from_utf8_lossy(&output.stdout);
+    base64::prelude::BASE64_STANDARD
+        .decode(encoded.trim())
+        .map_err(|_| anyhow!("There is no image on the clipboard"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn read_clipboard_image() -> Result<Vec<u8>> {
+    let (program, args): (&str, &[&str]) = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        ("wl-paste", &["--type", "image/png"])
+    } else {
+        (
+            "xclip",
+            &["-selection", "clipboard", "-target", "image/png", "-out"],
+        )
+    };
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Pasting images needs {} to be installed", program))?;
+    if !output.status.success() {
+        bail!("There is no image on the clipboard");
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        image::RgbImage::new(width, height).save(path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_mentions() {
+        assert_eq!(
+            parse_mentions("Compare @src/main.rs and @\"my notes.md\", then mail bob@example.com"),
+            vec!["src/main.rs", "my notes.md"]
+        );
+        assert_eq!(parse_mentions("@a.txt @a.txt @"), vec!["a.txt"]);
+    }
+
+    #[test]
+    fn test_expand_mentions() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "remember the milk").unwrap();
+        std::fs::write(dir.path().join(".env"), "TOKEN=secret").unwrap();
+        std::fs::write(
+            dir.path().join("big.log"),
+            "x".repeat(MAX_MENTION_FILE_SIZE as usize + 1),
+        )
+        .unwrap();
+        write_png(&dir.path().join("shot.png"), 4, 4);
+
+        let expanded = expand_mentions(
+            "Look at @notes.txt, @.env, @big.log, @shot.png and @missing.txt",
+            dir.path(),
+            |path| path.ends_with(".env"),
+        );
+
+        assert_eq!(
+            expanded.files,
+            vec!["<file path=\"notes.txt\">\nremember the milk\n</file>"]
+        );
+        assert_eq!(expanded.report.attached, vec!["notes.txt", "shot.png"]);
+        assert_eq!(expanded.images.len(), 1);
+        assert_eq!(expanded.report.skipped.len(), 2);
+    }
+
+    #[test]
+    fn test_wide_images_are_scaled_down() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("wide.png");
+        write_png(&path, MAX_IMAGE_WIDTH * 2, 100);
+
+        let MessageContent::Image(image) = load_image("wide.png", dir.path(), |_| false).unwrap()
+        else {
+            panic!("Expected an image");
+        };
+        assert_eq!(image.mime_type, "image/png");
+        let bytes = base64::prelude::BASE64_STANDARD
+            .decode(&image.data)
+            .unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (MAX_IMAGE_WIDTH, 50));
+    }
+
+    #[test]
+    fn test_ignored_images_are_refused() {
+        let dir = TempDir::new().unwrap();
+        write_png(&dir.path().join("secret.png"), 4, 4);
+
+        let error = load_image("secret.png", dir.path(), |path| {
+            path.ends_with("secret.png")
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("restricted by .gooseignore"));
+    }
+}
@@ -3,12 +3,16 @@ use goose::agents::extension::ExtensionError;
 use goose::agents::types::RetryConfig;
 use goose::agents::Agent;
 use goose::config::{Config, ExtensionConfig, ExtensionConfigManager};
+use goose::model::ReasoningEffort;
+use goose::providers::base::Provider;
 use goose::providers::create;
+use goose::providers::testprovider::TestProvider;
 use goose::recipe::{Response, SubRecipe};
 use goose::session;
 use goose::session::Identifier;
 use mcp_client::transport::Error as McpClientError;
 use rustyline::EditMode;
+use std::path::PathBuf;
 use std::process;
 use std::sync::Arc;
 
@@ -63,6 +67,10 @@ pub struct SessionBuilderConfig {
     pub final_output_response: Option<Response>,
     /// Retry configuration for automated validation and recovery
     pub retry_config: Option<RetryConfig>,
+    /// Record the provider's responses to this file
+    pub record_file: Option<PathBuf>,
+    /// Replay the provider's responses from this file instead of calling the provider
+    pub replay_file: Option<PathBuf>,
 }
 
 /// Offers to help debug an extension failure by creating a minimal debugging session
@@ -172,6 +180,8 @@ pub struct SessionSettings {
     pub goose_model: Option<String>,
     pub goose_provider: Option<String>,
     pub temperature: Option<f32>,
+    pub reasoning_effort: Option<ReasoningEffort>,
+    pub thinking_budget: Option<i32>,
     pub system_prompt_id: Option<String>,
 }
 
@@ -202,9 +212,19 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         .expect("No model configured. Run 'goose configure' first");
 
     let temperature = session_config.settings.as_ref().and_then(|s| s.temperature);
-
-    let model_config =
-        goose::model::ModelConfig::new(model_name.clone()).with_temperature(temperature);
+    let reasoning_effort = session_config
+        .settings
+        .as_ref()
+        .and_then(|s| s.reasoning_effort);
+    let thinking_budget = session_config
+        .settings
+        .as_ref()
+        .and_then(|s| s.thinking_budget);
+
+    let model_config = goose::model::ModelConfig::new(model_name.clone())
+        .with_temperature(temperature)
+        .with_reasoning_effort(reasoning_effort)
+        .with_thinking_budget(thinking_budget);
 
     // Create the agent
     let agent: Agent = Agent::new();
@@ -217,18 +237,47 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         agent.add_final_output_tool(final_output_response).await;
     }
 
-    let new_provider = match create(&provider_name, model_config) {
-        Ok(provider) => provider,
-        Err(e) => {
+    let new_provider: Arc<dyn Provider> = if let Some(replay_file) = &session_config.replay_file {
+        // Replaying needs neither the provider's credentials nor network access
+        if !replay_file.exists() {
             output::render_error(&format!(
-                "Error {}.\n\
-                Please check your system keychain and run 'goose configure' again.\n\
-                If your system is unable to use the keyring, please try setting secret key(s) via environment variables.\n\
-                For more info, see: https://block.github.io/goose/docs/troubleshooting/#keychainkeyring-errors",
-                e
+                "Recording {} does not exist",
+                replay_file.display()
             ));
             process::exit(1);
         }
+        match TestProvider::new_replaying(replay_file.to_string_lossy()) {
+            Ok(provider) => Arc::new(provider.with_model_config(model_config)),
+            Err(e) => {
+                output::render_error(&format!(
+                    "Failed to load recording {}: {}",
+                    replay_file.display(),
+                    e
+                ));
+                process::exit(1);
+            }
+        }
+    } else {
+        let provider = match create(&provider_name, model_config) {
+            Ok(provider) => provider,
+            Err(e) => {
+                output::render_error(&format!(
+                    "Error {}.\n\
+                    Please check your system keychain and run 'goose configure' again.\n\
+                    If your system is unable to use the keyring, please try setting secret key(s) via environment variables.\n\
+                    For more info, see: https://block.github.io/goose/docs/troubleshooting/#keychainkeyring-errors",
+                    e
+                ));
+                process::exit(1);
+            }
+        };
+        match &session_config.record_file {
+            Some(record_file) => Arc::new(
+                TestProvider::new_recording(provider, record_file.to_string_lossy())
+                    .with_autosave(),
+            ),
+            None => provider,
+        }
     };
     // Keep a reference to the provider for display_session_info
     let provider_for_display = Arc::clone(&new_provider);
@@ -616,6 +665,8 @@ mod tests {
             sub_recipes: None,
             final_output_response: None,
             retry_config: None,
+            record_file: None,
+            replay_file: None,
         };
 
         assert_eq!(config.extensions.len(), 1);
@@ -130,11 +130,28 @@ impl GooseCompleter {
             "/prompt",
             "/mode",
             "/recipe",
+            "/history",
+            "/undo",
+            "/edit",
+            "/drop",
+            "/image",
+            "/commands",
         ];
 
+        // The user's own commands come after the built-in ones
+        let cache = self.completion_cache.read().unwrap();
+        let mut custom_commands: Vec<String> = cache
+            .custom_commands
+            .keys()
+            .map(|name| format!("/{}", name))
+            .collect();
+        custom_commands.sort();
+
         // Find commands that match the prefix
         let matching_commands: Vec<Pair> = commands
             .iter()
+            .copied()
+            .chain(custom_commands.iter().map(String::as_str))
             .filter(|cmd| cmd.starts_with(line))
             .map(|cmd| Pair {
                 display: cmd.to_string(),
@@ -150,6 +167,37 @@ impl GooseCompleter {
         Ok((line.len(), vec![]))
     }
 
+    /// Complete the `name=` arguments of a custom command, None when the line does not call one
+    fn complete_custom_command_arguments(&self, line: &str) -> Option<(usize, Vec<Pair>)> {
+        let (command, rest) = line.strip_prefix('/')?.split_once(' ')?;
+        let cache = self.completion_cache.read().unwrap();
+        let arguments = cache.custom_commands.get(command)?;
+
+        // Only the word being typed is completed, and only until it has a value
+        let partial = if line.ends_with(' ') {
+            ""
+        } else {
+            rest.split_whitespace().last().unwrap_or_default()
+        };
+        if partial.contains('=') {
+            return Some((line.len(), vec![]));
+        }
+        let given: Vec<&str> = rest
+            .split_whitespace()
+            .filter_map(|word| word.split_once('=').map(|(name, _)| name))
+            .collect();
+
+        let candidates = arguments
+            .iter()
+            .filter(|name| name.starts_with(partial) && !given.contains(&name.as_str()))
+            .map(|name| Pair {
+                display: format!("{}=", name),
+                replacement: format!("{}=", name),
+            })
+            .collect();
+        Some((line.len() - partial.len(), candidates))
+    }
+
     /// Complete argument keys for a specific prompt
     fn complete_argument_keys(&self, line: &str) -> Result<(usize, Vec<Pair>)> {
         let parts: Vec<&str> = line[8..].split_whitespace().collect();
@@ -189,6 +237,49 @@ impl GooseCompleter {
                         }
                     }
 
+                    // If the last part is `key=partial`, suggest values the extension offered
+                    if let Some((key, partial)) = last_part.split_once('=') {
+                        if !line.ends_with(' ') {
+                            // Ask the extension about what has been typed, falling back to
+                            // filtering the values it offered up front
+                            let looked_up = match (&cache.argument_lookup, &info.extension) {
+                                (Some(lookup), Some(extension)) if !partial.is_empty() => {
+                                    lookup(extension, prompt_name, key, partial)
+                                }
+                                _ => Vec::new(),
+                            };
+                            let cached = info
+                                .extension
+                                .as_ref()
+                                .and_then(|extension| {
+                                    cache
+                                        .argument_values
+                                        .get(&(extension.clone(), prompt_name.to_string()))
+                                })
+                                .and_then(|values| values.get(key))
+                                .into_iter()
+                                .flatten()
+                                .filter(|value| value.starts_with(partial));
+                            let values: Vec<&String> = if looked_up.is_empty() {
+                                cached.collect()
+                            } else {
+                                looked_up.iter().collect()
+                            };
+                            let matching_values: Vec<Pair> = values
+                                .into_iter()
+                                .filter(|value| *value != partial)
+                                .map(|value| Pair {
+                                    display: value.clone(),
+                                    replacement: value.clone(),
+                                })
+                                .collect();
+
+                            if !matching_values.is_empty() {
+                                return Ok((line.len() - partial.len(), matching_values));
+                            }
+                        }
+                    }
+
                     // If the last part doesn't contain '=', it might be a partial argument name
                     if !last_part.contains('=') {
                         // Find arguments that match the prefix
@@ -338,6 +429,10 @@ impl Completer for GooseCompleter {
             if line.starts_with("/mode") {
                 return self.complete_mode_flags(line);
             }
+
+            if let Some(completion) = self.complete_custom_command_arguments(line) {
+                return Ok(completion);
+            }
         }
 
         // Default: no completions
@@ -401,6 +496,7 @@ mod tests {
 
     use super::*;
     use crate::session::output;
+    use std::collections::HashMap;
     use std::sync::{Arc, RwLock};
 
     // Helper function to create a test completion cache
@@ -465,6 +561,14 @@ mod tests {
             .prompt_info
             .insert("other_prompt".to_string(), other_prompt_info);
 
+        cache.argument_values.insert(
+            ("extension1".to_string(), "test_prompt1".to_string()),
+            HashMap::from([(
+                "optional_arg".to_string(),
+                vec!["python".to_string(), "rust".to_string(), "ruby".to_string()],
+            )]),
+        );
+
         Arc::new(RwLock::new(cache))
     }
 
@@ -496,6 +600,36 @@ mod tests {
         assert_eq!(candidates.len(), 0);
     }
 
+    #[test]
+    fn test_complete_custom_commands() {
+        let cache = create_test_cache();
+        cache.write().unwrap().custom_commands.insert(
+            "review".to_string(),
+            vec!["path".to_string(), "focus".to_string()],
+        );
+        let completer = GooseCompleter::new(cache);
+
+        let (pos, candidates) = completer.complete_slash_commands("/rev").unwrap();
+        assert_eq!(pos, 0);
+        assert_eq!(candidates[0].replacement, "/review ");
+
+        let (pos, candidates) = completer
+            .complete_custom_command_arguments("/review ")
+            .unwrap();
+        assert_eq!(pos, 8);
+        assert_eq!(candidates.len(), 2);
+
+        let (pos, candidates) = completer
+            .complete_custom_command_arguments("/review path=src/main.rs f")
+            .unwrap();
+        assert_eq!(pos, 25);
+        assert_eq!(candidates[0].replacement, "focus=");
+
+        assert!(completer
+            .complete_custom_command_arguments("/mode auto")
+            .is_none());
+    }
+
     #[test]
     fn test_complete_prompt_names() {
         let cache = create_test_cache();
@@ -604,4 +738,73 @@ mod tests {
             .unwrap();
         assert_eq!(candidates.len(), 0);
     }
+
+    #[test]
+    fn test_complete_argument_values() {
+        let cache = create_test_cache();
+        let completer = GooseCompleter::new(cache);
+
+        // Test with a partial value for an argument that has completions
+        let (pos, candidates) = completer
+            .complete_argument_keys("/prompt test_prompt1 required_arg=x optional_arg=ru")
+            .unwrap();
+        assert_eq!(
+            pos,
+            "/prompt test_prompt1 required_arg=x optional_arg=".len()
+        );
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].display, "rust");
+        assert_eq!(candidates[1].display, "ruby");
+
+        // Test with an empty value shows every completion
+        let (_pos, candidates) = completer
+            .complete_argument_keys("/prompt test_prompt1 required_arg=x optional_arg=")
+            .unwrap();
+        assert_eq!(candidates.len(), 3);
+
+        // Values another extension offered for a prompt of the same name are not used
+        let cache = create_test_cache();
+        cache.write().unwrap().argument_values.insert(
+            ("extension2".to_string(), "test_prompt1".to_string()),
+            HashMap::from([("optional_arg".to_string(), vec!["ruby".to_string()])]),
+        );
+        cache
+            .write()
+            .unwrap()
+            .argument_values
+            .remove(&("extension1".to_string(), "test_prompt1".to_string()));
+        let completer = GooseCompleter::new(cache);
+        let (_pos, candidates) = completer
+            .complete_argument_keys("/prompt test_prompt1 required_arg=x optional_arg=ru")
+            .unwrap();
+        assert!(candidates.iter().all(|c| c.display != "ruby"));
+    }
+
+    #[test]
+    fn test_complete_argument_values_asks_extension_for_prefix() {
+        let cache = create_test_cache();
+        let lookup: crate::session::ArgumentLookup = Arc::new(
+            |extension: &str, prompt: &str, argument: &str, partial: &str| {
+                assert_eq!(
+                    (extension, prompt, argument),
+                    ("extension1", "test_prompt1", "optional_arg")
+                );
+                vec![format!("{partial}lang")]
+            },
+        );
+        cache.write().unwrap().argument_lookup = Some(lookup);
+        let completer = GooseCompleter::new(cache);
+
+        let (_pos, candidates) = completer
+            .complete_argument_keys("/prompt test_prompt1 optional_arg=go")
+            .unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].display, "golang");
+
+        // Nothing typed yet uses the values fetched up front
+        let (_pos, candidates) = completer
+            .complete_argument_keys("/prompt test_prompt1 optional_arg=")
+            .unwrap();
+        assert_eq!(candidates.len(), 3);
+    }
 }
@@ -0,0 +1,378 @@
+//! Slash commands users define themselves. Each markdown or YAML file in
+//! `~/.config/goose/commands/` or the project's `.goose/commands/` is a command named after the
+//! file, holding a prompt template that is rendered with the arguments it was called with.
+//!
+//! A markdown command keeps its description and arguments in YAML frontmatter and the template
+//! in the body. A YAML command has the template under `prompt`.
+use anyhow::{anyhow, bail, Context, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Commands the CLI handles itself, files with these names are ignored
+const BUILTIN_COMMANDS: [&str; 21] = [
+    "exit",
+    "quit",
+    "help",
+    "?",
+    "t",
+    "extension",
+    "builtin",
+    "prompts",
+    "prompt",
+    "mode",
+    "plan",
+    "endplan",
+    "clear",
+    "recipe",
+    "summarize",
+    "history",
+    "undo",
+    "edit",
+    "drop",
+    "image",
+    "commands",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CommandHeader {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    arguments: Vec<CommandArgument>,
+}
+
+#[derive(Debug, Deserialize)]
+struct YamlCommand {
+    #[serde(flatten)]
+    header: CommandHeader,
+    prompt: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CustomCommand {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<CommandArgument>,
+    pub template: String,
+    /// The file the command was loaded from
+    pub path: PathBuf,
+}
+
+/// The commands found on disk, and why any files were left out
+#[derive(Debug, Default)]
+pub struct CustomCommands {
+    pub commands: BTreeMap<String, CustomCommand>,
+    pub errors: Vec<String>,
+}
+
+impl CustomCommand {
+    /// Reads a command from a `.md`, `.yaml` or `.yml` file, None for other files
+    fn from_file(path: &Path) -> Option<Result<Self>> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        if !["md", "yaml", "yml"].contains(&extension.as_str()) {
+            return None;
+        }
+        let name = path.file_stem()?.to_string_lossy().to_string();
+        Some(Self::parse(name, path, &extension))
+    }
+
+    fn parse(name: String, path: &Path, extension: &str) -> Result<Self> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!("a command name can only have letters, digits, '-' and '_'");
+        }
+        // /plan and /recipe take whatever follows them as their argument
+        if BUILTIN_COMMANDS.contains(&name.as_str())
+            || name.starts_with("plan")
+            || name.starts_with("recipe")
+        {
+            bail!("/{} is a built-in command", name);
+        }
+
+        let text = std::fs::read_to_string(path)?;
+        let (header, template) = if extension == "md" {
+            match split_frontmatter(&text) {
+                Some((frontmatter, body)) => (
+                    serde_yaml::from_str(frontmatter).context("invalid frontmatter")?,
+                    body.to_string(),
+                ),
+                None => (CommandHeader::default(), text),
+            }
+        } else {
+            let command: YamlCommand = serde_yaml::from_str(&text)?;
+            (command.header, command.prompt)
+        };
+
+        Ok(Self {
+            name,
+            description: header.description,
+            arguments: header.arguments,
+            template,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// How to call the command, e.g. `/review <path> [focus]`
+    pub fn usage(&self) -> String {
+        let mut usage = format!("/{}", self.name);
+        for argument in &self.arguments {
+            if argument.required {
+                usage.push_str(&format!(" <{}>", argument.name));
+            } else {
+                usage.push_str(&format!(" [{}]", argument.name));
+            }
+        }
+        usage
+    }
+
+    /// Renders the prompt for what followed the command name. Arguments are given as
+    /// `name=value` or in the order they are declared, and everything typed is also available to
+    /// the template as `args`.
+    pub fn render(&self, input: &str) -> Result<String> {
+        let input = input.trim();
+        let words =
+            shlex::split(input).ok_or_else(|| anyhow!("The arguments have an unclosed quote"))?;
+
+        let mut values: HashMap<String, String> = HashMap::new();
+        let mut positional = Vec::new();
+        for word in words {
+            match word.split_once('=') {
+                Some((name, value)) if self.arguments.iter().any(|a| a.name == name) => {
+                    values.insert(name.to_string(), value.to_string());
+                }
+                _ => positional.push(word),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        for argument in &self.arguments {
+            if values.contains_key(&argument.name) {
+                continue;
+            }
+            if let Some(value) = positional.next().or_else(|| argument.default.clone()) {
+                values.insert(argument.name.clone(), value);
+            } else if argument.required {
+                bail!("Missing {}, usage: {}", argument.name, self.usage());
+            }
+        }
+        values.insert("args".to_string(), input.to_string());
+
+        let prompt = goose::prompt_template::render_inline_once(&self.template, &values)
+            .with_context(|| format!("Failed to render {}", self.path.display()))?;
+        if prompt.is_empty() {
+            bail!("/{} rendered an empty prompt", self.name);
+        }
+        Ok(prompt)
+    }
+}
+
+/// Splits `---` delimited YAML frontmatter from the rest of a markdown file
+fn split_frontmatter(text: &str) -> Option<(&str, &str)> {
+    let rest = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// The directories commands are loaded from, later ones override earlier ones
+fn command_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(strategy) = choose_app_strategy(crate::APP_STRATEGY.clone()) {
+        dirs.push(strategy.config_dir().join("commands"));
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        dirs.push(cwd.join(".goose").join("commands"));
+    }
+    dirs
+}
+
+/// Loads the user's and the project's commands, a project command replaces a user command with
+/// the same name
+pub fn load_commands() -> CustomCommands {
+    load_commands_from(&command_dirs())
+}
+
+fn load_commands_from(dirs: &[PathBuf]) -> CustomCommands {
+    let mut loaded = CustomCommands::default();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match CustomCommand::from_file(&path) {
+                Some(Ok(command)) => {
+                    loaded.commands.insert(command.name.clone(), command);
+                }
+                Some(Err(e)) => loaded
+                    .errors
+                    .push(format!("Skipped {}: {:#}", path.display(), e)),
+                None => {}
+            }
+        }
+    }
+    loaded
+}
+
+impl CustomCommands {
+    /// Renders the prompt when the input calls a custom command, None when it does not
+    pub fn expand(&self, input: &str) -> Option<Result<String>> {
+        let input = input.strip_prefix('/')?;
+        let (name, arguments) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let command = self.commands.get(name)?;
+        Some(command.render(arguments))
+    }
+
+    /// The argument names of each command, for completion
+    pub fn argument_names(&self) -> HashMap<String, Vec<String>> {
+        self.commands
+            .values()
+            .map(|command| {
+                let arguments = command.arguments.iter().map(|a| a.name.clone()).collect();
+                (command.name.clone(), arguments)
+            })
+            .collect()
+    }
+
+    /// A line per command with how to call it and what it does, for help
+    pub fn usage_lines(&self) -> Vec<String> {
+        self.commands
+            .values()
+            .map(|command| match &command.description {
+                Some(description) => format!("{} - {}", command.usage(), description),
+                None => command.usage(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const REVIEW: &str = "---
+description: Review a file
+arguments:
+  - name: path
+    required: true
+  - name: focus
+    default: correctness
+---
+Review @{{ path }} for {{ focus }}.";
+
+    fn command_dir(files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for (name, content) in files {
+            std::fs::write(dir.path().join(name), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_render_arguments() {
+        let dir = command_dir(&[("review.md", REVIEW)]);
+        let commands = load_commands_from(&[dir.path().to_path_buf()]);
+        let review = &commands.commands["review"];
+
+        assert_eq!(review.usage(), "/review <path> [focus]");
+        assert_eq!(
+            review.render("src/main.rs").unwrap(),
+            "Review @src/main.rs for correctness."
+        );
+        assert_eq!(
+            review.render("focus=speed 'my file.rs'").unwrap(),
+            "Review @my file.rs for speed."
+        );
+        assert!(review.render("").is_err());
+    }
+
+    #[test]
+    fn test_load_yaml_and_markdown_without_frontmatter() {
+        let dir = command_dir(&[
+            ("explain.md", "Explain {{ args }} simply."),
+            (
+                "standup.yaml",
+                "description: Standup notes\nprompt: Summarize today's work",
+            ),
+            ("notes.txt", "not a command"),
+        ]);
+        let commands = load_commands_from(&[dir.path().to_path_buf()]);
+
+        assert_eq!(
+            commands.commands.keys().collect::<Vec<_>>(),
+            vec!["explain", "standup"]
+        );
+        assert_eq!(
+            commands.commands["explain"]
+                .render("the borrow checker")
+                .unwrap(),
+            "Explain the borrow checker simply."
+        );
+        assert_eq!(
+            commands.commands["standup"].description.as_deref(),
+            Some("Standup notes")
+        );
+    }
+
+    #[test]
+    fn test_project_commands_override_and_builtins_are_skipped() {
+        let user = command_dir(&[("explain.md", "user"), ("clear.md", "clear")]);
+        let project = command_dir(&[("explain.md", "project"), ("plans.md", "plans")]);
+        let commands =
+            load_commands_from(&[user.path().to_path_buf(), project.path().to_path_buf()]);
+
+        assert_eq!(commands.commands.len(), 1);
+        assert_eq!(commands.commands["explain"].template, "project");
+        assert_eq!(commands.errors.len(), 2);
+    }
+
+    #[test]
+    fn test_expand_uses_the_loaded_commands() {
+        let dir = command_dir(&[("explain.md", "Explain {{ args }}.")]);
+        let commands = load_commands_from(&[dir.path().to_path_buf()]);
+        std::fs::write(dir.path().join("explain.md"), "Changed").unwrap();
+
+        assert_eq!(
+            commands.expand("/explain lifetimes").unwrap().unwrap(),
+            "Explain lifetimes."
+        );
+        assert!(commands.expand("/unknown").is_none());
+        assert!(commands.expand("explain").is_none());
+        assert_eq!(
+            load_commands_from(&[dir.path().to_path_buf()])
+                .expand("/explain")
+                .unwrap()
+                .unwrap(),
+            "Changed"
+        );
+    }
+}
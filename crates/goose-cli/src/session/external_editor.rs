@@ -0,0 +1,31 @@
+//! Lets the user change some text in their own editor, as `git commit` does.
+use std::io::{Error, ErrorKind, Result};
+use std::process::Command;
+
+/// Opens the text in $VISUAL or $EDITOR, falling back to vi, and returns what the user saved.
+/// The suffix is kept on the temporary file so the editor can highlight it.
+pub fn edit_text(text: &str, suffix: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Editors like `code --wait` come with arguments
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "$EDITOR is empty"))?;
+
+    let file = tempfile::Builder::new()
+        .prefix("goose-edit-")
+        .suffix(suffix)
+        .tempfile()?;
+    std::fs::write(file.path(), text)?;
+
+    let status = Command::new(program)
+        .args(parts)
+        .arg(file.path())
+        .status()?;
+    if !status.success() {
+        return Err(Error::other(format!("{} exited with {}", program, status)));
+    }
+    std::fs::read_to_string(file.path())
+}
@@ -0,0 +1,205 @@
+//! Edits the user can make to the conversation of an interactive session: taking back the last
+//! turn, rewriting the last prompt and dropping tool output that only adds noise. A turn is a
+//! prompt the user typed and everything that followed it.
+use goose::message::{Message, MessageContent};
+use rmcp::model::{Content, Role};
+
+/// What the model sees in place of tool output the user dropped
+pub const DROPPED_TOOL_OUTPUT: &str = "[This tool output was removed from the conversation]";
+
+/// How long the preview of a message in the history listing gets
+const PREVIEW_LENGTH: usize = 80;
+
+/// Whether the message is a prompt the user typed, as opposed to tool results sent back
+fn starts_turn(message: &Message) -> bool {
+    message.role == Role::User
+        && !message.is_tool_response()
+        && message.content.iter().any(|c| c.as_text().is_some())
+}
+
+/// The index of the message each turn starts at, in order
+pub fn turn_starts(messages: &[Message]) -> Vec<usize> {
+    messages
+        .iter()
+        .enumerate()
+        .filter(|(_, message)| starts_turn(message))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Removes the last turn and everything after it, returning the prompt that started it
+pub fn undo_last_turn(messages: &mut Vec<Message>) -> Option<Message> {
+    let start = *turn_starts(messages).last()?;
+    messages.split_off(start).into_iter().next()
+}
+
+/// The text the user typed in a prompt, without the files it mentioned
+pub fn typed_text(prompt: &Message) -> &str {
+    prompt
+        .content
+        .iter()
+        .find_map(|c| c.as_text())
+        .unwrap_or_default()
+}
+
+/// Replaces the output of the tool results in the message at the index with a short note. The
+/// results stay so every tool request still has a response.
+pub fn drop_tool_output(messages: &mut [Message], index: usize) -> Result<(), String> {
+    let message = messages
+        .get_mut(index)
+        .ok_or_else(|| format!("There is no message {}", index + 1))?;
+    if !message.is_tool_response() {
+        return Err(format!(
+            "Message {} is not tool output, use /undo to remove a turn",
+            index + 1
+        ));
+    }
+
+    let mut dropped = false;
+    for content in message.content.iter_mut() {
+        if let MessageContent::ToolResponse(response) = content {
+            let already_dropped = response.tool_result.as_ref().is_ok_and(|contents| {
+                contents.len() == 1
+                    && contents[0].as_text().map(|t| t.text.as_str()) == Some(DROPPED_TOOL_OUTPUT)
+            });
+            if !already_dropped {
+                response.tool_result = Ok(vec![Content::text(DROPPED_TOOL_OUTPUT)]);
+                dropped = true;
+            }
+        }
+    }
+    if dropped {
+        Ok(())
+    } else {
+        Err(format!(
+            "The output of message {} was already removed",
+            index + 1
+        ))
+    }
+}
+
+/// A one line summary of the message at the index, for listing the history
+pub fn describe(messages: &[Message], index: usize) -> String {
+    let message = &messages[index];
+    let mut parts = Vec::new();
+
+    let text = message.as_concat_text();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !text.is_empty() {
+        parts.push(preview(&text));
+    }
+    for content in &message.content {
+        match content {
+            MessageContent::ToolRequest(request) => parts.push(format!(
+                "calls {}",
+                request
+                    .tool_call
+                    .as_ref()
+                    .map(|call| call.name.as_str())
+                    .unwrap_or("an invalid tool")
+            )),
+            MessageContent::ToolResponse(response) => {
+                let name = tool_name(&messages[..index], &response.id).unwrap_or("tool");
+                let size: usize = match &response.tool_result {
+                    Ok(contents) => contents
+                        .iter()
+                        .filter_map(|c| c.as_text())
+                        .map(|t| t.text.len())
+                        .sum(),
+                    Err(e) => e.to_string().len(),
+                };
+                parts.push(format!("{} output, {}", name, format_size(size)));
+            }
+            MessageContent::Image(_) => parts.push("image".to_string()),
+            _ => {}
+        }
+    }
+
+    let speaker = match (&message.role, message.is_tool_response()) {
+        (Role::User, true) => "tool",
+        (Role::User, false) => "you",
+        (Role::Assistant, _) => "goose",
+    };
+    format!("{}: {}", speaker, parts.join(", "))
+}
+
+/// The name of the tool that was called with the id, looking back from the end
+fn tool_name<'a>(messages: &'a [Message], id: &str) -> Option<&'a str> {
+    messages
+        .iter()
+        .rev()
+        .flat_map(|message| message.content.iter())
+        .filter_map(|content| content.as_tool_request())
+        .find(|request| request.id == id)
+        .and_then(|request| request.tool_call.as_ref().ok())
+        .map(|call| call.name.as_str())
+}
+
+fn preview(text: &str) -> String {
+    match text.char_indices().nth(PREVIEW_LENGTH) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message::user().with_text("List the files"),
+            Message::assistant().with_tool_request(
+                "1",
+                Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+            ),
+            Message::user().with_tool_response("1", Ok(vec![Content::text("a.rs\nb.rs")])),
+            Message::assistant().with_text("There are two files"),
+            Message::user().with_text("Read a.rs"),
+            Message::assistant().with_text("It is empty"),
+        ]
+    }
+
+    #[test]
+    fn test_turns_start_at_typed_prompts() {
+        assert_eq!(turn_starts(&conversation()), vec![0, 4]);
+    }
+
+    #[test]
+    fn test_undo_last_turn() {
+        let mut messages = conversation();
+
+        let prompt = undo_last_turn(&mut messages).unwrap();
+        assert_eq!(prompt.as_concat_text(), "Read a.rs");
+        assert_eq!(messages.len(), 4);
+
+        undo_last_turn(&mut messages).unwrap();
+        assert!(messages.is_empty());
+        assert!(undo_last_turn(&mut messages).is_none());
+    }
+
+    #[test]
+    fn test_drop_tool_output() {
+        let mut messages = conversation();
+        assert_eq!(describe(&messages, 2), "tool: developer__shell output, 9 B");
+
+        drop_tool_output(&mut messages, 2).unwrap();
+        assert_eq!(
+            messages[2].content[0].as_tool_response_text().as_deref(),
+            Some(DROPPED_TOOL_OUTPUT)
+        );
+        assert!(drop_tool_output(&mut messages, 2).is_err());
+        assert!(drop_tool_output(&mut messages, 0).is_err());
+        assert!(drop_tool_output(&mut messages, 10).is_err());
+    }
+}
@@ -21,6 +21,12 @@ pub enum InputResult {
     Clear,
     Recipe(Option<String>),
     Summarize,
+    Undo,
+    Edit,
+    History,
+    Drop(usize),
+    Image(Option<String>),
+    ReloadCommands,
 }
 
 #[derive(Debug)]
@@ -96,6 +102,12 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
     const CMD_SUMMARIZE: &str = "/summarize";
+    const CMD_UNDO: &str = "/undo";
+    const CMD_EDIT: &str = "/edit";
+    const CMD_HISTORY: &str = "/history";
+    const CMD_DROP: &str = "/drop";
+    const CMD_IMAGE: &str = "/image";
+    const CMD_COMMANDS: &str = "/commands";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -156,10 +168,50 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
         s if s == CMD_SUMMARIZE => Some(InputResult::Summarize),
+        s if s == CMD_UNDO => Some(InputResult::Undo),
+        s if s == CMD_EDIT => Some(InputResult::Edit),
+        s if s == CMD_HISTORY => Some(InputResult::History),
+        s if s == CMD_DROP || s.starts_with("/drop ") => {
+            parse_drop_command(s[CMD_DROP.len()..].trim())
+        }
+        s if s == CMD_IMAGE || s.starts_with("/image ") => {
+            Some(parse_image_command(s[CMD_IMAGE.len()..].trim()))
+        }
+        s if s == CMD_COMMANDS => Some(InputResult::ReloadCommands),
         _ => None,
     }
 }
 
+fn parse_image_command(s: &str) -> InputResult {
+    if s.is_empty() {
+        // No path pastes the image on the clipboard
+        return InputResult::Image(None);
+    }
+    // Terminals quote or escape the paths of files dropped on them
+    let path = match shlex::split(s).as_deref() {
+        Some([path]) => path.to_string(),
+        _ => s.to_string(),
+    };
+    InputResult::Image(Some(path))
+}
+
+fn parse_drop_command(s: &str) -> Option<InputResult> {
+    // Messages are numbered from 1 in /history
+    match s.parse::<usize>() {
+        Ok(number) if number > 0 => Some(InputResult::Drop(number - 1)),
+        _ => {
+            println!(
+                "{}",
+                console::style(
+                    "Usage: /drop <n>, where n is the number of a tool output message in /history"
+                )
+                .red()
+            );
+            Some(InputResult::Retry)
+        }
+    }
+}
+
 fn parse_recipe_command(s: &str) -> Option<InputResult> {
     const CMD_RECIPE: &str = "/recipe";
 
@@ -266,6 +318,12 @@ fn print_help() {
 /recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
                        If no filepath is provided, it will be saved to ./recipe.yaml.
 /summarize - Summarize the current conversation to reduce context length while preserving key information.
+/image [path] - Attach an image to your next message, or the image on the clipboard when no path is given
+/history - List the turns of the conversation with the number of each message
+/undo - Remove your last message and everything goose did after it
+/edit - Edit your last message in $EDITOR and get a new reply to it
+/drop <n> - Remove the tool output in message n of /history to free up context
+/commands - Reload your custom commands from disk and list them
 /? or /help - Display this help message
 /clear - Clears the current chat history
 
@@ -511,4 +569,66 @@ mod tests {
         let result = handle_slash_command("  /summarize  ");
         assert!(matches!(result, Some(InputResult::Summarize)));
     }
+
+    #[test]
+    fn test_history_commands() {
+        assert!(matches!(
+            handle_slash_command("/undo"),
+            Some(InputResult::Undo)
+        ));
+        assert!(matches!(
+            handle_slash_command("/edit"),
+            Some(InputResult::Edit)
+        ));
+        assert!(matches!(
+            handle_slash_command("/history"),
+            Some(InputResult::History)
+        ));
+
+        // Numbers in /history start at 1
+        assert!(matches!(
+            handle_slash_command("/drop 3"),
+            Some(InputResult::Drop(2))
+        ));
+        assert!(matches!(
+            handle_slash_command("/drop 0"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/drop"),
+            Some(InputResult::Retry)
+        ));
+        assert!(handle_slash_command("/dropped").is_none());
+    }
+
+    #[test]
+    fn test_commands_command() {
+        assert!(matches!(
+            handle_slash_command("/commands"),
+            Some(InputResult::ReloadCommands)
+        ));
+        assert!(handle_slash_command("/commandsx").is_none());
+    }
+
+    #[test]
+    fn test_image_command() {
+        assert!(matches!(
+            handle_slash_command("/image"),
+            Some(InputResult::Image(None))
+        ));
+        if let Some(InputResult::Image(Some(path))) =
+            handle_slash_command("/image '/tmp/Screen Shot.png'")
+        {
+            assert_eq!(path, "/tmp/Screen Shot.png");
+        } else {
+            panic!("Expected Image with a path");
+        }
+        if let Some(InputResult::Image(Some(path))) =
+            handle_slash_command(r"/image /tmp/Screen\ Shot.png")
+        {
+            assert_eq!(path, "/tmp/Screen Shot.png");
+        } else {
+            panic!("Expected Image with a path");
+        }
+    }
 }
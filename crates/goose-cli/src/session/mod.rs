@@ -1,11 +1,18 @@
+mod attachments;
 mod builder;
 mod completion;
+mod custom_commands;
 mod export;
+mod external_editor;
+mod history;
 mod input;
 mod output;
 mod prompt;
+pub mod run_output;
 mod task_execution_display;
 mod thinking;
+mod tool_confirmation;
+mod tui;
 
 use crate::session::task_execution_display::{
     format_task_execution_notification, TASK_EXECUTION_NOTIFICATION_TYPE,
@@ -40,6 +47,7 @@ use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
 
 use rand::{distributions::Alphanumeric, Rng};
+use run_output::{OutputFormat, RunEvent, RunOutput, RunStatus, TokenUsage};
 use rustyline::EditMode;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -66,12 +74,29 @@ pub struct Session {
     max_turns: Option<u32>,
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
+    run_output: RunOutput,
+    // Images from /image waiting to be sent with the next message
+    pending_images: Vec<MessageContent>,
+    // Set while the full-screen interface owns the terminal, so nothing is printed over it
+    tui_active: bool,
+    // The user's slash commands, read when an interactive session starts and on /commands
+    custom_commands: custom_commands::CustomCommands,
 }
 
+/// Asks an extension for argument completions: extension, prompt, argument and the partial value
+type ArgumentLookup = Arc<dyn Fn(&str, &str, &str, &str) -> Vec<String> + Send + Sync>;
+
 // Cache structure for completion data
 struct CompletionCache {
     prompts: HashMap<String, Vec<String>>,
     prompt_info: HashMap<String, output::PromptInfo>,
+    // Values suggested by extensions for each prompt argument, keyed by extension and prompt,
+    // then argument
+    argument_values: HashMap<(String, String), HashMap<String, Vec<String>>>,
+    // Fetches completions for a typed prefix, since extensions may filter better than a prefix match
+    argument_lookup: Option<ArgumentLookup>,
+    // The user's own slash commands and the names of their arguments
+    custom_commands: HashMap<String, Vec<String>>,
     last_updated: Instant,
 }
 
@@ -80,11 +105,40 @@ impl CompletionCache {
         Self {
             prompts: HashMap::new(),
             prompt_info: HashMap::new(),
+            argument_values: HashMap::new(),
+            argument_lookup: None,
+            custom_commands: HashMap::new(),
             last_updated: Instant::now(),
         }
     }
 }
 
+/// Look up completions from the completer's blocking context, giving up after a short wait so a
+/// slow extension never freezes the prompt
+fn argument_lookup(
+    extension_manager: Arc<tokio::sync::RwLock<goose::agents::extension_manager::ExtensionManager>>,
+) -> ArgumentLookup {
+    Arc::new(
+        move |extension: &str, prompt: &str, argument: &str, value: &str| {
+            let lookup = async {
+                extension_manager
+                    .read()
+                    .await
+                    .complete_prompt_argument(extension, prompt, argument, value)
+                    .await
+                    .unwrap_or_default()
+            };
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(async {
+                    tokio::time::timeout(std::time::Duration::from_secs(2), lookup)
+                        .await
+                        .unwrap_or_default()
+                })
+            })
+        },
+    )
+}
+
 pub enum PlannerResponseType {
     Plan,
     ClarifyingQuestions,
@@ -152,6 +206,10 @@ impl Session {
             max_turns,
             edit_mode,
             retry_config,
+            run_output: RunOutput::default(),
+            pending_images: Vec::new(),
+            tui_active: false,
+            custom_commands: custom_commands::CustomCommands::default(),
         }
     }
 
@@ -160,11 +218,14 @@ impl Session {
         messages: &mut Vec<Message>,
         agent: &Agent,
         message_suffix: &str,
+        render: bool,
     ) -> Result<()> {
         // Summarize messages to fit within context length
         let (summarized_messages, _) = agent.summarize_context(messages).await?;
-        let msg = format!("Context maxed out\n{}\n{}", "-".repeat(50), message_suffix);
-        output::render_text(&msg, Some(Color::Yellow), true);
+        if render {
+            let msg = format!("Context maxed out\n{}\n{}", "-".repeat(50), message_suffix);
+            output::render_text(&msg, Some(Color::Yellow), true);
+        }
         *messages = summarized_messages;
 
         Ok(())
@@ -409,8 +470,106 @@ impl Session {
         Ok(())
     }
 
+    /// Sends a message the user typed in an interactive session and shows the reply
+    async fn send_interactive_message(&mut self, message: Message) -> Result<()> {
+        self.record_prompt(message).await?;
+
+        output::show_thinking();
+        self.process_agent_response(true).await?;
+        output::hide_thinking();
+        Ok(())
+    }
+
+    /// Adds a prompt the user typed to the conversation and saves it, before asking for a reply
+    async fn record_prompt(&mut self, message: Message) -> Result<()> {
+        let content = history::typed_text(&message).to_string();
+        self.push_message(message);
+
+        // Track the current directory and last instruction in projects.json
+        let session_id = self
+            .session_file
+            .as_ref()
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string());
+
+        if let Err(e) =
+            crate::project_tracker::update_project_tracker(Some(&content), session_id.as_deref())
+        {
+            eprintln!(
+                "Warning: Failed to update project tracker with instruction: {}",
+                e
+            );
+        }
+
+        let provider = self.agent.provider().await?;
+
+        // Persist messages with provider for automatic description generation
+        if let Some(session_file) = &self.session_file {
+            let working_dir = Some(std::env::current_dir().unwrap_or_default());
+
+            session::persist_messages_with_schedule_id(
+                session_file,
+                &self.messages,
+                Some(provider),
+                self.scheduled_job_id.clone(),
+                working_dir,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Builds the message for a prompt the user typed, with the files it mentions attached. Also
+    /// returns what happened to the mentioned files. Images added with /image are attached by
+    /// the caller, since only a message that is sent to goose uses them up.
+    fn prepare_prompt(&self, content: &str) -> (Message, attachments::AttachmentReport) {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let ignore_patterns = goose_mcp::load_ignore_patterns(&cwd);
+        let expanded = attachments::expand_mentions(content, &cwd, |path| {
+            ignore_patterns.matched(path, false).is_ignore()
+        });
+
+        let mut message = Message::user().with_text(content);
+        for file in expanded.files {
+            message = message.with_text(file);
+        }
+        message.content.extend(expanded.images);
+        (message, expanded.report)
+    }
+
+    /// Reads an image file the user attached with /image, refusing files restricted by
+    /// .gooseignore the same as mentions
+    fn load_image(path: &str) -> Result<MessageContent> {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let ignore_patterns = goose_mcp::load_ignore_patterns(&cwd);
+        attachments::load_image(path, &cwd, |path| {
+            ignore_patterns.matched(path, false).is_ignore()
+        })
+    }
+
+    /// Writes the messages back after the user edited the history, so resuming sees the edits
+    fn persist_history(&self) -> Result<()> {
+        if let Some(session_file) = self.session_file.as_ref().filter(|f| f.exists()) {
+            session::replace_messages(session_file, &self.messages)?;
+        }
+        Ok(())
+    }
+
     /// Start an interactive session, optionally with an initial message
     pub async fn interactive(&mut self, prompt: Option<String>) -> Result<()> {
+        let session_config = self.session_config();
+        self.agent
+            .run_session_start_hooks(session_config.as_ref())
+            .await?;
+        let result = self.run_interactive(prompt).await;
+        self.agent
+            .run_session_end_hooks(session_config.as_ref())
+            .await;
+        result
+    }
+
+    async fn run_interactive(&mut self, prompt: Option<String>) -> Result<()> {
         // Process initial message if provided
         if let Some(prompt) = prompt {
             let msg = Message::user().with_text(&prompt);
@@ -418,6 +577,7 @@ impl Session {
         }
 
         // Initialize the completion cache
+        self.custom_commands = custom_commands::load_commands();
         self.update_completion_cache().await?;
 
         // Create a new editor with our custom completer
@@ -470,56 +630,41 @@ impl Session {
             };
 
         output::display_greeting();
+        for error in &self.custom_commands.errors {
+            println!("{}", console::style(error).yellow());
+        }
         loop {
             // Display context usage before each prompt
             self.display_context_usage().await?;
 
             match input::get_input(&mut editor)? {
                 InputResult::Message(content) => {
+                    // A custom command is replaced by the prompt it renders
+                    let content = match self.custom_commands.expand(&content) {
+                        Some(Ok(prompt)) => prompt,
+                        Some(Err(e)) => {
+                            save_history(&mut editor);
+                            output::render_error(&format!("{:#}", e));
+                            continue;
+                        }
+                        None => content,
+                    };
                     match self.run_mode {
                         RunMode::Normal => {
                             save_history(&mut editor);
-
-                            self.push_message(Message::user().with_text(&content));
-
-                            // Track the current directory and last instruction in projects.json
-                            let session_id = self
-                                .session_file
-                                .as_ref()
-                                .and_then(|p| p.file_stem())
-                                .and_then(|s| s.to_str())
-                                .map(|s| s.to_string());
-
-                            if let Err(e) = crate::project_tracker::update_project_tracker(
-                                Some(&content),
-                                session_id.as_deref(),
-                            ) {
-                                eprintln!("Warning: Failed to update project tracker with instruction: {}", e);
-                            }
-
-                            let provider = self.agent.provider().await?;
-
-                            // Persist messages with provider for automatic description generation
-                            if let Some(session_file) = &self.session_file {
-                                let working_dir = Some(std::env::current_dir().unwrap_or_default());
-
-                                session::persist_messages_with_schedule_id(
-                                    session_file,
-                                    &self.messages,
-                                    Some(provider),
-                                    self.scheduled_job_id.clone(),
-                                    working_dir,
-                                )
-                                .await?;
-                            }
-
-                            output::show_thinking();
-                            self.process_agent_response(true).await?;
-                            output::hide_thinking();
+                            let (mut message, report) = self.prepare_prompt(&content);
+                            output::render_attachment_report(&report);
+                            message.content.extend(self.pending_images.drain(..));
+                            self.send_interactive_message(message).await?;
                         }
                         RunMode::Plan => {
                             let mut plan_messages = self.messages.clone();
-                            plan_messages.push(Message::user().with_text(&content));
+                            let (mut message, report) = self.prepare_prompt(&content);
+                            output::render_attachment_report(&report);
+                            // The planner sees the images, they are sent along when acting on
+                            // the plan
+                            message.content.extend(self.pending_images.iter().cloned());
+                            plan_messages.push(message);
                             let reasoner = get_reasoner()?;
                             self.plan_with_reasoner_model(plan_messages, reasoner)
                                 .await?;
@@ -587,6 +732,20 @@ impl Session {
                     continue;
                 }
                 input::InputResult::Retry => continue,
+                input::InputResult::ReloadCommands => {
+                    save_history(&mut editor);
+                    self.reload_custom_commands();
+                    for error in &self.custom_commands.errors {
+                        println!("{}", console::style(error).yellow());
+                    }
+                    let usage = self.custom_commands.usage_lines();
+                    if usage.is_empty() {
+                        println!("No custom commands, add them to ~/.config/goose/commands/ or .goose/commands/");
+                    } else {
+                        println!("Custom commands:\n{}", usage.join("\n"));
+                    }
+                    continue;
+                }
                 input::InputResult::ListPrompts(extension) => {
                     save_history(&mut editor);
 
@@ -688,6 +847,116 @@ impl Session {
 
                     continue;
                 }
+                InputResult::Image(path) => {
+                    save_history(&mut editor);
+
+                    let image = match &path {
+                        Some(path) => Self::load_image(path),
+                        None => attachments::paste_image(),
+                    };
+                    match image {
+                        Ok(image) => {
+                            self.pending_images.push(image);
+                            println!(
+                                "{}",
+                                console::style(format!(
+                                    "Attached {}, it will be sent with your next message.",
+                                    path.as_deref().unwrap_or("the image on the clipboard")
+                                ))
+                                .green()
+                            );
+                        }
+                        Err(e) => output::render_error(&e.to_string()),
+                    }
+                    continue;
+                }
+                InputResult::History => {
+                    save_history(&mut editor);
+                    output::render_history(&self.messages);
+                    continue;
+                }
+                InputResult::Undo => {
+                    save_history(&mut editor);
+
+                    if history::undo_last_turn(&mut self.messages).is_none() {
+                        output::render_error("There is no message to undo.");
+                        continue;
+                    }
+                    self.persist_history()?;
+                    println!(
+                        "{}",
+                        console::style("Removed your last message and everything after it.")
+                            .yellow()
+                    );
+                    continue;
+                }
+                InputResult::Edit => {
+                    save_history(&mut editor);
+
+                    let Some(start) = history::turn_starts(&self.messages).last().copied() else {
+                        output::render_error("There is no message to edit.");
+                        continue;
+                    };
+                    let prompt = self.messages[start].clone();
+                    let edited =
+                        match external_editor::edit_text(history::typed_text(&prompt), ".md") {
+                            Ok(text) => text.trim().to_string(),
+                            Err(e) => {
+                                output::render_error(&format!("Could not edit the message: {}", e));
+                                continue;
+                            }
+                        };
+                    if edited.is_empty() {
+                        println!(
+                            "{}",
+                            console::style("The message is empty, nothing was changed.").yellow()
+                        );
+                        continue;
+                    }
+
+                    // The reply to the old message goes, goose answers the edited one instead
+                    self.messages.truncate(start);
+                    self.persist_history()?;
+                    output::render_message(&Message::user().with_text(&edited), self.debug);
+                    // Mentions are read again, images attached to the old message are kept
+                    let (mut message, report) = self.prepare_prompt(&edited);
+                    output::render_attachment_report(&report);
+                    message.content.extend(
+                        prompt
+                            .content
+                            .into_iter()
+                            .filter(|c| matches!(c, MessageContent::Image(_))),
+                    );
+                    if !self.pending_images.is_empty() {
+                        println!(
+                            "{}",
+                            console::style(
+                                "Images added with /image are kept for your next message."
+                            )
+                            .dim()
+                        );
+                    }
+                    self.send_interactive_message(message).await?;
+                }
+                InputResult::Drop(index) => {
+                    save_history(&mut editor);
+
+                    match history::drop_tool_output(&mut self.messages, index) {
+                        Ok(()) => {
+                            self.persist_history()?;
+                            println!(
+                                "{}",
+                                console::style(format!(
+                                    "Removed the tool output of message {}.",
+                                    index + 1
+                                ))
+                                .green()
+                            );
+                        }
+                        Err(e) => output::render_error(&e),
+                    }
+                    continue;
+                }
                 InputResult::Summarize => {
                     save_history(&mut editor);
 
@@ -810,7 +1079,9 @@ impl Session {
                     // clear the messages before acting on the plan
                     self.messages.clear();
                     // add the plan response as a user message
-                    let plan_message = Message::user().with_text(plan_response.as_concat_text());
+                    let mut plan_message =
+                        Message::user().with_text(plan_response.as_concat_text());
+                    plan_message.content.extend(self.pending_images.drain(..));
                     self.push_message(plan_message);
                     // act on the plan
                     output::show_thinking();
@@ -841,15 +1112,62 @@ impl Session {
 
     /// Process a single message and exit
     pub async fn headless(&mut self, prompt: String) -> Result<()> {
+        let session_config = self.session_config();
+        self.agent
+            .run_session_start_hooks(session_config.as_ref())
+            .await?;
         let message = Message::user().with_text(&prompt);
-        self.process_message(message).await
+        let result = self.process_message(message).await;
+        self.agent
+            .run_session_end_hooks(session_config.as_ref())
+            .await;
+        result
     }
 
-    async fn process_agent_response(&mut self, interactive: bool) -> Result<()> {
-        let cancel_token = CancellationToken::new();
-        let cancel_token_clone = cancel_token.clone();
+    /// Replaces the rendering of the agent's output with JSON records in the given format
+    pub fn set_output_format(&mut self, format: OutputFormat) {
+        self.run_output.set_format(format);
+    }
+
+    /// Stops the agent once the session has used this many tokens
+    pub fn set_token_budget(&mut self, token_budget: Option<i32>) {
+        self.run_output.set_token_budget(token_budget);
+    }
 
-        let session_config = self.session_file.as_ref().map(|s| {
+    /// Ends a headless run, writing the result record, and returns how the run went
+    pub fn finish_headless(&mut self, error: Option<&anyhow::Error>) -> RunStatus {
+        if let Some(e) = error {
+            if !self.run_output.is_machine_readable() {
+                eprintln!("Error: {}", e);
+            }
+            self.run_output.emit(RunEvent::Error {
+                error: e.to_string(),
+            });
+            self.run_output.record_status(RunStatus::Error);
+        }
+        let session_id = self
+            .session_file
+            .as_ref()
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+            .map(String::from);
+        let result = self
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == rmcp::model::Role::Assistant)
+            .map(|m| m.as_concat_text())
+            .filter(|text| !text.trim().is_empty());
+        let usage = self
+            .get_metadata()
+            .ok()
+            .as_ref()
+            .and_then(TokenUsage::from_metadata);
+        self.run_output.finish(session_id, result, usage)
+    }
+
+    fn session_config(&self) -> Option<SessionConfig> {
+        self.session_file.as_ref().map(|s| {
             let session_id = session::Identifier::Path(s.clone());
             SessionConfig {
                 id: session_id.clone(),
@@ -859,13 +1177,21 @@ impl Session {
                 max_turns: self.max_turns,
                 retry_config: self.retry_config.clone(),
             }
-        });
+        })
+    }
+
+    async fn process_agent_response(&mut self, interactive: bool) -> Result<()> {
+        let cancel_token = CancellationToken::new();
+        let cancel_token_clone = cancel_token.clone();
+
+        let session_config = self.session_config();
         let mut stream = self
             .agent
             .reply(&self.messages, session_config.clone(), Some(cancel_token))
             .await?;
 
         let mut progress_bars = output::McpSpinners::new();
+        let machine_readable = self.run_output.is_machine_readable();
 
         use futures::StreamExt;
         loop {
@@ -877,23 +1203,25 @@ impl Session {
                             if let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first() {
                                 output::hide_thinking();
 
-                                // Format the confirmation prompt
-                                let prompt = "Goose would like to call the above tool, do you allow?".to_string();
+                                // A program reading the output cannot answer the prompt
+                                if machine_readable {
+                                    self.agent.handle_confirmation(confirmation.id.clone(), PermissionConfirmation {
+                                        principal_type: PrincipalType::Tool,
+                                        permission: Permission::DenyOnce,
+                                        arguments: None,
+                                    }).await;
+                                    continue;
+                                }
 
-                                // Get confirmation from user
-                                let permission_result = cliclack::select(prompt)
-                                    .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
-                                    .item(Permission::AlwaysAllow, "Always Allow", "Always allow the tool call")
-                                    .item(Permission::DenyOnce, "Deny", "Deny the tool call")
-                                    .item(Permission::Cancel, "Cancel", "Cancel the AI response and tool call")
-                                    .interact();
+                                // Get confirmation from user, file changes can be reviewed as a diff
+                                let permission_result = tool_confirmation::confirm_tool_call(confirmation);
 
-                                let permission = match permission_result {
+                                let (permission, arguments) = match permission_result {
                                     Ok(p) => p, // If Ok, use the selected permission
                                     Err(e) => {
                                         // Check if the error is an interruption (Ctrl+C/Cmd+C, Escape)
                                         if e.kind() == std::io::ErrorKind::Interrupted {
-                                            Permission::Cancel // If interrupted, set permission to Cancel
+                                            (Permission::Cancel, None) // If interrupted, set permission to Cancel
                                         } else {
                                             return Err(e.into()); // Otherwise, convert and propagate the original error
                                         }
@@ -927,6 +1255,7 @@ impl Session {
                                     self.agent.handle_confirmation(confirmation.id.clone(), PermissionConfirmation {
                                         principal_type: PrincipalType::Tool,
                                         permission,
+                                        arguments,
                                     },).await;
                                 }
                             } else if let Some(MessageContent::ContextLengthExceeded(_)) = message.content.first() {
@@ -965,7 +1294,9 @@ impl Session {
                                         } else {
                                             format!("Session cleared.\n{}", "-".repeat(50))
                                         };
-                                        output::render_text(&msg, Some(Color::Yellow), true);
+                                        if !machine_readable {
+                                            output::render_text(&msg, Some(Color::Yellow), true);
+                                        }
                                         break;  // exit the loop to hand back control to the user
                                     }
                                     "truncate" => {
@@ -976,8 +1307,10 @@ impl Session {
                                         } else {
                                             format!("Context maxed out\n{}\nGoose tried its best to truncate messages for you.", "-".repeat(50))
                                         };
-                                        output::render_text("", Some(Color::Yellow), true);
-                                        output::render_text(&msg, Some(Color::Yellow), true);
+                                        if !machine_readable {
+                                            output::render_text("", Some(Color::Yellow), true);
+                                            output::render_text(&msg, Some(Color::Yellow), true);
+                                        }
                                         self.messages = truncated_messages;
                                     }
                                     "summarize" => {
@@ -989,7 +1322,7 @@ impl Session {
                                         } else {
                                             "Goose automatically summarized messages to continue processing."
                                         };
-                                        Self::summarize_context_messages(&mut self.messages, &self.agent, message_suffix).await?;
+                                        Self::summarize_context_messages(&mut self.messages, &self.agent, message_suffix, !machine_readable).await?;
                                     }
                                     _ => {
                                         unreachable!()
@@ -1023,11 +1356,38 @@ impl Session {
                                     .await?;
                                 }
 
-                                if interactive {output::hide_thinking()};
-                                let _ = progress_bars.hide();
-                                output::render_message(&message, self.debug);
+                                if machine_readable {
+                                    self.run_output.emit(RunEvent::from_message(message.clone()));
+                                } else {
+                                    if interactive {output::hide_thinking()};
+                                    let _ = progress_bars.hide();
+                                    output::render_message(&message, self.debug);
+                                }
+                            }
+                        }
+                        Some(Ok(AgentEvent::Usage(usage))) => {
+                            if !self.run_output.track_usage(&usage.usage, self.session_file.as_deref()) {
+                                if !machine_readable {
+                                    output::render_error("Stopped, the session used up its token budget");
+                                }
+                                cancel_token_clone.cancel();
+                                drop(stream);
+                                break;
                             }
                         }
+                        Some(Ok(AgentEvent::MaxTurnsReached { .. })) => {
+                            self.run_output.record_status(RunStatus::MaxTurns);
+                        }
+                        Some(Ok(AgentEvent::ToolDenied { id, tool_name })) => {
+                            self.run_output.record_status(RunStatus::ToolDenied);
+                            self.run_output.emit(RunEvent::ToolDenied { id, tool_name });
+                        }
+                        Some(Ok(AgentEvent::McpNotification((request_id, notification)))) if machine_readable => {
+                            self.run_output.emit(RunEvent::Notification { request_id, notification });
+                        }
+                        Some(Ok(AgentEvent::ModelChange { model, mode })) if machine_readable => {
+                            self.run_output.emit(RunEvent::ModelChange { model, mode });
+                        }
                         Some(Ok(AgentEvent::McpNotification((_id, message)))) => {
                             match &message {
                                 ServerNotification::LoggingMessageNotification(notification) => {
@@ -1149,11 +1509,16 @@ impl Session {
 
                         Some(Err(e)) => {
                             eprintln!("Error: {}", e);
+                            self.run_output.emit(RunEvent::Error { error: e.to_string() });
+                            self.run_output.record_status(RunStatus::ProviderError);
                             cancel_token_clone.cancel();
                             drop(stream);
                             if let Err(e) = self.handle_interrupted_messages(false).await {
                                 eprintln!("Error handling interruption: {}", e);
                             }
+                            if machine_readable {
+                                break;
+                            }
                             output::render_error(
                                 "The error above was an exception we were not able to handle.\n\
                                 These errors are often related to connection or authentication\n\
@@ -1166,6 +1531,7 @@ impl Session {
                     }
                 }
                 _ = tokio::signal::ctrl_c() => {
+                    self.run_output.record_status(RunStatus::Cancelled);
                     cancel_token_clone.cancel();
                     drop(stream);
                     if let Err(e) = self.handle_interrupted_messages(true).await {
@@ -1175,7 +1541,9 @@ impl Session {
                 }
             }
         }
-        println!();
+        if !machine_readable {
+            println!();
+        }
 
         Ok(())
     }
@@ -1253,7 +1621,7 @@ impl Session {
                 .await?;
             }
 
-            output::render_message(&Message::assistant().with_text(&prompt), self.debug);
+            self.render_cli_message(Message::assistant().with_text(&prompt));
         } else {
             // An interruption occurred outside of a tool request-response.
             if let Some(last_msg) = self.messages.last() {
@@ -1277,19 +1645,13 @@ impl Session {
                                 .await?;
                             }
 
-                            output::render_message(
-                                &Message::assistant().with_text(prompt),
-                                self.debug,
-                            );
+                            self.render_cli_message(Message::assistant().with_text(prompt));
                         }
                         Some(_) => {
                             // A real users message
                             self.messages.pop();
                             let prompt = "Interrupted before the model replied and removed the last message.";
-                            output::render_message(
-                                &Message::assistant().with_text(prompt),
-                                self.debug,
-                            );
+                            self.render_cli_message(Message::assistant().with_text(prompt));
                         }
                         None => panic!("No content in last message"),
                     }
@@ -1299,6 +1661,19 @@ impl Session {
         Ok(())
     }
 
+    /// Shows a message the CLI wrote itself, or records it when the output is machine-readable
+    fn render_cli_message(&mut self, message: Message) {
+        if self.tui_active {
+            // The full-screen interface shows the conversation itself
+            return;
+        }
+        if self.run_output.is_machine_readable() {
+            self.run_output.emit(RunEvent::from_message(message));
+        } else {
+            output::render_message(&message, self.debug);
+        }
+    }
+
     pub fn session_file(&self) -> Option<PathBuf> {
         self.session_file.clone()
     }
@@ -1309,10 +1684,42 @@ impl Session {
         // Get fresh data
         let prompts = self.agent.list_extension_prompts().await;
 
+        // Ask extensions for argument completions up front since the completer is synchronous
+        let agent = &self.agent;
+        let requests = prompts.iter().flat_map(|(extension, prompt_list)| {
+            prompt_list.iter().flat_map(move |prompt| {
+                prompt
+                    .arguments
+                    .iter()
+                    .flatten()
+                    .map(move |argument| async move {
+                        let values = agent
+                            .complete_prompt_argument(extension, &prompt.name, &argument.name, "")
+                            .await
+                            .unwrap_or_default();
+                        (extension, &prompt.name, &argument.name, values)
+                    })
+            })
+        });
+        let mut argument_values: HashMap<(String, String), HashMap<String, Vec<String>>> =
+            HashMap::new();
+        for (extension, prompt, argument, values) in futures::future::join_all(requests).await {
+            if !values.is_empty() {
+                argument_values
+                    .entry((extension.clone(), prompt.clone()))
+                    .or_default()
+                    .insert(argument.clone(), values);
+            }
+        }
+        let argument_lookup = argument_lookup(self.agent.extension_manager.clone());
+
         // Update the cache with write lock
         let mut cache = self.completion_cache.write().unwrap();
         cache.prompts.clear();
         cache.prompt_info.clear();
+        cache.argument_values = argument_values;
+        cache.argument_lookup = Some(argument_lookup);
+        cache.custom_commands = self.custom_commands.argument_names();
 
         for (extension, prompt_list) in prompts {
             let names: Vec<String> = prompt_list.iter().map(|p| p.name.clone()).collect();
@@ -1335,12 +1742,20 @@ impl Session {
         Ok(())
     }
 
+    /// Reads the custom commands from disk again and makes them available to completion
+    fn reload_custom_commands(&mut self) {
+        self.custom_commands = custom_commands::load_commands();
+        self.completion_cache.write().unwrap().custom_commands =
+            self.custom_commands.argument_names();
+    }
+
     /// Invalidate the completion cache
     /// This should be called when extensions are added or removed
     async fn invalidate_completion_cache(&self) {
         let mut cache = self.completion_cache.write().unwrap();
         cache.prompts.clear();
         cache.prompt_info.clear();
+        cache.argument_values.clear();
         cache.last_updated = Instant::now();
     }
 
@@ -1427,6 +1842,10 @@ impl Session {
                     )
                     .await;
                 }
+
+                if let Some(stats) = metadata.tool_cache {
+                    output::display_tool_cache_stats(stats);
+                }
             }
             Err(_) => {
                 output::display_context_usage(0, context_limit);
@@ -1,5 +1,8 @@
+use super::attachments::AttachmentReport;
 use bat::WrappingMode;
 use console::{style, Color};
+use goose::agents::edit_preview::{EditPreview, ProposedEdit};
+use goose::agents::ToolCacheStats;
 use goose::config::Config;
 use goose::message::{Message, MessageContent, ToolRequest, ToolResponse};
 use goose::providers::pricing::get_model_pricing;
@@ -319,6 +322,40 @@ pub fn render_prompt_info(info: &PromptInfo) {
     println!();
 }
 
+pub fn render_attachment_report(report: &AttachmentReport) {
+    for reason in &report.skipped {
+        println!("{}", style(format!("Not attached: {}", reason)).yellow());
+    }
+    if !report.attached.is_empty() {
+        println!(
+            "{}",
+            style(format!("Attached {}", report.attached.join(", "))).dim()
+        );
+    }
+}
+
+/// Lists the messages numbered from 1, grouped by the turn they belong to
+pub fn render_history(messages: &[Message]) {
+    if messages.is_empty() {
+        println!("{}", style("The conversation is empty.").dim());
+        return;
+    }
+
+    let turn_starts = super::history::turn_starts(messages);
+    println!();
+    for index in 0..messages.len() {
+        if let Some(turn) = turn_starts.iter().position(|start| *start == index) {
+            println!(" {}", style(format!("Turn {}", turn + 1)).green().bold());
+        }
+        println!(
+            "  {} {}",
+            style(format!("[{}]", index + 1)).cyan(),
+            super::history::describe(messages, index)
+        );
+    }
+    println!();
+}
+
 pub fn render_extension_success(name: &str) {
     println!();
     println!(
@@ -377,6 +414,15 @@ fn render_text_editor_request(call: &ToolCall, debug: bool) {
         );
     }
 
+    // File changes are shown as a diff against the file on disk
+    if let Some(preview) =
+        ProposedEdit::from_tool_call(&call.name, &call.arguments).and_then(|edit| edit.preview())
+    {
+        render_edit_preview(&preview);
+        println!();
+        return;
+    }
+
     // Print other arguments normally, excluding path
     if let Some(args) = call.arguments.as_object() {
         let mut other_args = serde_json::Map::new();
@@ -390,6 +436,27 @@ fn render_text_editor_request(call: &ToolCall, debug: bool) {
     println!();
 }
 
+/// Prints a unified diff with the hunks numbered, so they can be picked by number
+pub fn render_edit_preview(preview: &EditPreview) {
+    if preview.new_file {
+        println!("{}", style("new file").dim());
+    }
+    for (index, hunk) in preview.hunks.iter().enumerate() {
+        println!(
+            "{} {}",
+            style(&hunk.header).cyan(),
+            style(format!("hunk {}", index + 1)).dim()
+        );
+        for line in &hunk.lines {
+            match line.chars().next() {
+                Some('+') => println!("{}", style(line).green()),
+                Some('-') => println!("{}", style(line).red()),
+                _ => println!("{}", style(line).dim()),
+            }
+        }
+    }
+}
+
 fn render_shell_request(call: &ToolCall, debug: bool) {
     print_tool_header(call);
 
@@ -711,6 +778,19 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     );
 }
 
+/// Display how many tool calls the tool result cache answered this session
+pub fn display_tool_cache_stats(stats: ToolCacheStats) {
+    if stats.hits + stats.misses == 0 {
+        return;
+    }
+    println!(
+        "Tool cache: {} hits, {} misses ({:.0}% hit rate)",
+        stats.hits,
+        stats.misses,
+        stats.hit_rate() * 100.0
+    );
+}
+
 fn normalize_model_name(model: &str) -> String {
     let mut result = model.to_string();
 
@@ -734,7 +814,8 @@ fn normalize_model_name(model: &str) -> String {
     result
 }
 
-async fn estimate_cost_usd(
+/// The cost of the tokens in USD, if price data is available for the model
+pub async fn estimate_cost_usd(
     provider: &str,
     model: &str,
     input_tokens: usize,
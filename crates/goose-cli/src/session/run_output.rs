@@ -0,0 +1,364 @@
+//! Machine-readable output for headless `goose run`, one JSON record per agent event and a final
+//! result record, so wrappers do not have to scrape the rendered terminal output.
+use clap::ValueEnum;
+use goose::message::{Message, MessageContent};
+use goose::providers::base::Usage;
+use goose::session::{self, SessionMetadata};
+use rmcp::model::ServerNotification;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Rendered markdown for people
+    #[default]
+    Text,
+    /// A single JSON object with the result and every event, printed when the run ends
+    Json,
+    /// One JSON object per line as events happen, ending with the result
+    StreamJson,
+}
+
+/// How a headless run ended. The exit codes are part of the CLI's interface, do not renumber them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Success,
+    Error,
+    MaxTurns,
+    BudgetExceeded,
+    ToolDenied,
+    ProviderError,
+    Cancelled,
+}
+
+impl RunStatus {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            RunStatus::Success => 0,
+            RunStatus::Error => 1,
+            // 2 is what clap exits with for usage errors
+            RunStatus::MaxTurns => 3,
+            RunStatus::BudgetExceeded => 4,
+            RunStatus::ToolDenied => 5,
+            RunStatus::ProviderError => 6,
+            RunStatus::Cancelled => 130,
+        }
+    }
+
+    /// When several things went wrong the one that ended the run wins over a denied tool call
+    /// the agent worked around
+    fn precedence(self) -> u8 {
+        match self {
+            RunStatus::Success => 0,
+            RunStatus::ToolDenied => 1,
+            RunStatus::MaxTurns => 2,
+            RunStatus::BudgetExceeded => 3,
+            RunStatus::ProviderError => 4,
+            RunStatus::Error => 5,
+            RunStatus::Cancelled => 6,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct TokenUsage {
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub total_tokens: i32,
+}
+
+impl TokenUsage {
+    /// The tokens used over the whole session, as the agent accumulates them in the metadata
+    pub fn from_metadata(metadata: &SessionMetadata) -> Option<Self> {
+        let total_tokens = metadata
+            .accumulated_total_tokens
+            .or(metadata.total_tokens)?;
+        Some(Self {
+            input_tokens: metadata
+                .accumulated_input_tokens
+                .or(metadata.input_tokens)
+                .unwrap_or_default(),
+            output_tokens: metadata
+                .accumulated_output_tokens
+                .or(metadata.output_tokens)
+                .unwrap_or_default(),
+            total_tokens,
+        })
+    }
+
+    fn add(self, usage: &Usage) -> Self {
+        Self {
+            input_tokens: self.input_tokens + usage.input_tokens.unwrap_or_default(),
+            output_tokens: self.output_tokens + usage.output_tokens.unwrap_or_default(),
+            total_tokens: self.total_tokens + usage.total_tokens.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunEvent {
+    Message {
+        message: Message,
+    },
+    ToolRequest {
+        message: Message,
+    },
+    ToolResponse {
+        message: Message,
+    },
+    /// A tool call that was not run because permission for it was denied
+    ToolDenied {
+        id: String,
+        tool_name: String,
+    },
+    Notification {
+        request_id: String,
+        notification: ServerNotification,
+    },
+    ModelChange {
+        model: String,
+        mode: String,
+    },
+    Usage(TokenUsage),
+    Error {
+        error: String,
+    },
+    Result {
+        status: RunStatus,
+        exit_code: i32,
+        session_id: Option<String>,
+        result: Option<String>,
+        usage: Option<TokenUsage>,
+        duration_ms: u128,
+    },
+}
+
+impl RunEvent {
+    pub fn from_message(message: Message) -> Self {
+        let is_tool_response = message
+            .content
+            .iter()
+            .any(|c| matches!(c, MessageContent::ToolResponse(_)));
+        if message.is_tool_call() {
+            RunEvent::ToolRequest { message }
+        } else if is_tool_response {
+            RunEvent::ToolResponse { message }
+        } else {
+            RunEvent::Message { message }
+        }
+    }
+}
+
+/// Writes the records of a run in the chosen format and keeps track of how the run is going
+pub struct RunOutput {
+    format: OutputFormat,
+    events: Vec<Value>,
+    status: RunStatus,
+    usage: Option<TokenUsage>,
+    token_budget: Option<i32>,
+    started: Instant,
+}
+
+impl Default for RunOutput {
+    fn default() -> Self {
+        Self::new(OutputFormat::Text)
+    }
+}
+
+impl RunOutput {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            events: Vec::new(),
+            status: RunStatus::Success,
+            usage: None,
+            token_budget: None,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn set_format(&mut self, format: OutputFormat) {
+        self.format = format;
+    }
+
+    pub fn set_token_budget(&mut self, token_budget: Option<i32>) {
+        self.token_budget = token_budget;
+    }
+
+    /// Whether the terminal rendering is replaced by JSON records
+    pub fn is_machine_readable(&self) -> bool {
+        self.format != OutputFormat::Text
+    }
+
+    pub fn status(&self) -> RunStatus {
+        self.status
+    }
+
+    pub fn record_status(&mut self, status: RunStatus) {
+        if status.precedence() > self.status.precedence() {
+            self.status = status;
+        }
+    }
+
+    /// Follows the tokens the run uses as the agent reports them. Returns false once the session
+    /// has used up its token budget and the agent should be stopped.
+    pub fn track_usage(&mut self, usage: &Usage, session_file: Option<&Path>) -> bool {
+        // The session totals also count earlier runs of a resumed session, without a session
+        // the run adds up what it is told
+        let total = session_file
+            .and_then(|path| session::read_metadata(path).ok())
+            .as_ref()
+            .and_then(TokenUsage::from_metadata)
+            .unwrap_or_else(|| self.usage.unwrap_or_default().add(usage));
+        self.record_usage(Some(total));
+        match self.token_budget {
+            Some(budget) if total.total_tokens >= budget => {
+                self.record_status(RunStatus::BudgetExceeded);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Emits a usage record when the token counts moved since the last one
+    pub fn record_usage(&mut self, usage: Option<TokenUsage>) {
+        if let Some(usage) = usage {
+            if self.usage != Some(usage) {
+                self.usage = Some(usage);
+                self.emit(RunEvent::Usage(usage));
+            }
+        }
+    }
+
+    pub fn emit(&mut self, event: RunEvent) {
+        if !self.is_machine_readable() {
+            return;
+        }
+        let value = serde_json::to_value(&event).unwrap_or_else(|e| {
+            serde_json::json!({"type": "error", "error": format!("Failed to serialize event: {}", e)})
+        });
+        match self.format {
+            OutputFormat::StreamJson => {
+                let mut stdout = std::io::stdout().lock();
+                let _ = writeln!(stdout, "{}", value);
+                let _ = stdout.flush();
+            }
+            _ => self.events.push(value),
+        }
+    }
+
+    /// Emits the result record, or for `json` the whole document, and returns the final status
+    pub fn finish(
+        &mut self,
+        session_id: Option<String>,
+        result: Option<String>,
+        usage: Option<TokenUsage>,
+    ) -> RunStatus {
+        let usage = usage.or(self.usage);
+        let result = RunEvent::Result {
+            status: self.status,
+            exit_code: self.status.exit_code(),
+            session_id,
+            result,
+            usage,
+            duration_ms: self.started.elapsed().as_millis(),
+        };
+        match self.format {
+            OutputFormat::Text => {}
+            OutputFormat::StreamJson => self.emit(result),
+            OutputFormat::Json => {
+                let mut document = serde_json::to_value(&result).unwrap_or_default();
+                if let Value::Object(fields) = &mut document {
+                    fields.insert(
+                        "events".to_string(),
+                        Value::Array(std::mem::take(&mut self.events)),
+                    );
+                }
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&document).unwrap_or_default()
+                );
+            }
+        }
+        self.status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    #[test]
+    fn test_event_type_follows_message_content() {
+        let text = RunEvent::from_message(Message::assistant().with_text("done"));
+        let request = RunEvent::from_message(
+            Message::assistant()
+                .with_text("Let me look")
+                .with_tool_request("1", Ok(ToolCall::new("developer__shell", json!({})))),
+        );
+
+        assert_eq!(serde_json::to_value(&text).unwrap()["type"], "message");
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["type"],
+            "tool_request"
+        );
+        let usage = serde_json::to_value(RunEvent::Usage(TokenUsage {
+            input_tokens: 1,
+            output_tokens: 2,
+            total_tokens: 3,
+        }))
+        .unwrap();
+        assert_eq!(
+            usage,
+            json!({"type": "usage", "input_tokens": 1, "output_tokens": 2, "total_tokens": 3})
+        );
+    }
+
+    #[test]
+    fn test_status_keeps_the_one_that_ended_the_run() {
+        let mut output = RunOutput::new(OutputFormat::Json);
+        output.record_status(RunStatus::ToolDenied);
+        output.record_status(RunStatus::MaxTurns);
+        output.record_status(RunStatus::ToolDenied);
+        assert_eq!(output.status(), RunStatus::MaxTurns);
+        assert_eq!(output.status().exit_code(), 3);
+    }
+
+    #[test]
+    fn test_token_budget_is_checked_on_every_usage_report() {
+        let mut output = RunOutput::new(OutputFormat::StreamJson);
+        output.set_token_budget(Some(100));
+        let usage = Usage {
+            input_tokens: Some(40),
+            output_tokens: Some(20),
+            total_tokens: Some(60),
+        };
+        assert!(output.track_usage(&usage, None));
+        assert_eq!(output.status(), RunStatus::Success);
+
+        assert!(!output.track_usage(&usage, None));
+        assert_eq!(output.status(), RunStatus::BudgetExceeded);
+        assert_eq!(output.usage.map(|u| u.total_tokens), Some(120));
+    }
+
+    #[test]
+    fn test_usage_is_only_emitted_when_it_changes() {
+        let mut output = RunOutput::new(OutputFormat::Json);
+        let usage = TokenUsage {
+            input_tokens: 10,
+            output_tokens: 5,
+            total_tokens: 15,
+        };
+        output.record_usage(Some(usage));
+        output.record_usage(Some(usage));
+        output.record_usage(None);
+        assert_eq!(output.events.len(), 1);
+    }
+}
@@ -0,0 +1,144 @@
+//! Asks the user whether a tool call may run. File changes from the text editor can also be
+//! applied in part or edited first, those are sent back to the agent as the arguments to use.
+use super::external_editor;
+use goose::agents::edit_preview::ProposedEdit;
+use goose::message::ToolConfirmationRequest;
+use goose::permission::Permission;
+use serde_json::Value;
+use std::io::{ErrorKind, Result};
+
+const PROMPT: &str = "Goose would like to call the above tool, do you allow?";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Review {
+    Allow,
+    AlwaysAllow,
+    ChooseHunks,
+    Edit,
+    Deny,
+    Cancel,
+}
+
+/// The permission the user gave, and the arguments to run the tool with instead when they changed
+/// the proposed edit
+pub fn confirm_tool_call(
+    confirmation: &ToolConfirmationRequest,
+) -> Result<(Permission, Option<Value>)> {
+    let edit = confirmation.edit_preview.as_ref().and_then(|_| {
+        ProposedEdit::from_tool_call(&confirmation.tool_name, &confirmation.arguments)
+    });
+    match edit {
+        Some(edit) => review_edit(&edit),
+        None => {
+            let permission = cliclack::select(PROMPT)
+                .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
+                .item(
+                    Permission::AlwaysAllow,
+                    "Always Allow",
+                    "Always allow the tool call",
+                )
+                .item(Permission::DenyOnce, "Deny", "Deny the tool call")
+                .item(
+                    Permission::Cancel,
+                    "Cancel",
+                    "Cancel the AI response and tool call",
+                )
+                .interact()?;
+            Ok((permission, None))
+        }
+    }
+}
+
+fn review_edit(edit: &ProposedEdit) -> Result<(Permission, Option<Value>)> {
+    // Read again rather than trusting the preview, the file may have changed since
+    let hunks = edit.preview().map(|p| p.hunks).unwrap_or_default();
+
+    loop {
+        let mut select = cliclack::select(PROMPT)
+            .item(Review::Allow, "Allow", "Apply the change")
+            .item(
+                Review::AlwaysAllow,
+                "Always Allow",
+                "Always allow the tool call",
+            );
+        if hunks.len() > 1 {
+            select = select.item(
+                Review::ChooseHunks,
+                "Choose hunks",
+                "Apply only some of the changes",
+            );
+        }
+        select = select
+            .item(
+                Review::Edit,
+                "Edit",
+                "Edit the change in $EDITOR, then apply it",
+            )
+            .item(Review::Deny, "Deny", "Deny the tool call")
+            .item(
+                Review::Cancel,
+                "Cancel",
+                "Cancel the AI response and tool call",
+            );
+
+        match select.interact()? {
+            Review::Allow => return Ok((Permission::AllowOnce, None)),
+            Review::AlwaysAllow => return Ok((Permission::AlwaysAllow, None)),
+            Review::Deny => return Ok((Permission::DenyOnce, None)),
+            Review::Cancel => return Ok((Permission::Cancel, None)),
+            Review::ChooseHunks => {
+                let accepted = cliclack::multiselect(
+                    "Which hunks should be applied? (use \"space\" to toggle and \"enter\" to submit)",
+                )
+                .required(false)
+                .items(
+                    &hunks
+                        .iter()
+                        .enumerate()
+                        .map(|(index, hunk)| (index, format!("hunk {}", index + 1), &hunk.header))
+                        .collect::<Vec<_>>(),
+                )
+                .initial_values((0..hunks.len()).collect())
+                .interact()?;
+
+                return Ok(if accepted.is_empty() {
+                    (Permission::DenyOnce, None)
+                } else if accepted.len() == hunks.len() {
+                    (Permission::AllowOnce, None)
+                } else {
+                    let file_text = edit.apply_hunks(&accepted);
+                    (
+                        Permission::AllowOnce,
+                        Some(edit.write_arguments(&file_text)),
+                    )
+                });
+            }
+            Review::Edit => match edit_in_editor(edit) {
+                Ok(file_text) if file_text == edit.proposed => {
+                    return Ok((Permission::AllowOnce, None))
+                }
+                Ok(file_text) => {
+                    return Ok((
+                        Permission::AllowOnce,
+                        Some(edit.write_arguments(&file_text)),
+                    ))
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => return Err(e),
+                Err(e) => {
+                    let _ = cliclack::log::error(format!("Could not edit the change: {}", e));
+                }
+            },
+        }
+    }
+}
+
+/// Opens the proposed file content in the user's editor and returns what they saved
+fn edit_in_editor(edit: &ProposedEdit) -> Result<String> {
+    // Keep the extension so the editor highlights the file
+    let suffix = edit
+        .path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    external_editor::edit_text(&edit.proposed, &suffix)
+}
@@ -0,0 +1,543 @@
+//! The state of the full-screen interface: the conversation as it is shown, the side panel and
+//! the prompt being typed. Drawing lives in `ui` so the key handling here can be tested alone.
+use crate::session::history;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use goose::agents::subagent_execution_tool::lib::TaskStatus;
+use goose::agents::subagent_execution_tool::notification_events::{
+    TaskExecutionNotificationEvent, TaskExecutionStats, TaskInfo,
+};
+use goose::message::{Message, MessageContent, ToolConfirmationRequest};
+use goose::permission::Permission;
+use rmcp::model::{Role, ServerNotification};
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// How many lines PageUp and PageDown move the conversation
+const PAGE_SIZE: usize = 10;
+
+pub struct ToolOutput {
+    pub text: String,
+    pub is_error: bool,
+}
+
+pub struct ToolEntry {
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+    /// None until the tool has answered
+    pub output: Option<ToolOutput>,
+    pub expanded: bool,
+}
+
+pub enum Entry {
+    User(String),
+    Assistant(String),
+    Tool(ToolEntry),
+    /// Something the CLI tells the user, which the model never sees
+    Notice {
+        text: String,
+        error: bool,
+    },
+}
+
+#[derive(Default)]
+pub struct SidePanel {
+    pub provider: String,
+    pub model: String,
+    pub mode: String,
+    pub extensions: Vec<String>,
+    pub total_tokens: usize,
+    pub context_limit: usize,
+    /// Only set when GOOSE_CLI_SHOW_COST is on and price data is available
+    pub cost: Option<f64>,
+    pub task_stats: Option<TaskExecutionStats>,
+    pub tasks: Vec<TaskInfo>,
+}
+
+/// What the session should do after a key press
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    None,
+    Submit(String),
+    Interrupt,
+    Quit,
+    Confirm(Permission),
+}
+
+#[derive(Default)]
+pub struct App {
+    pub entries: Vec<Entry>,
+    pub input: String,
+    /// How many lines the conversation is scrolled up from the bottom
+    pub scroll: usize,
+    /// The index in `entries` of the tool call the user selected
+    pub selected: Option<usize>,
+    /// Set when the selection moved, so the next draw scrolls it into view
+    pub reveal_selected: bool,
+    pub side: SidePanel,
+    /// Tool calls waiting for the user's permission, the first one is shown
+    pub confirmations: VecDeque<ToolConfirmationRequest>,
+    /// True while the agent is replying
+    pub busy: bool,
+    pub spinner: usize,
+    /// The latest progress the extensions reported while the agent is replying
+    pub status: Option<String>,
+    /// The id of the message the last text came from, streamed text is added to it
+    last_message_id: Option<String>,
+}
+
+impl App {
+    /// Shows the conversation from the start, as after resuming a session or editing its history
+    pub fn reload(&mut self, messages: &[Message]) {
+        self.entries.clear();
+        self.selected = None;
+        self.last_message_id = None;
+        for message in messages {
+            self.push_message(message);
+        }
+    }
+
+    pub fn push_message(&mut self, message: &Message) {
+        if message.role == Role::User && !message.is_tool_response() {
+            let mut text = history::typed_text(message).to_string();
+            let attachments = message.content.iter().skip(1).filter(|content| {
+                matches!(content, MessageContent::Text(_) | MessageContent::Image(_))
+            });
+            for content in attachments {
+                match content {
+                    MessageContent::Image(_) => text.push_str("\n[image]"),
+                    _ => text.push_str("\n[file]"),
+                }
+            }
+            self.entries.push(Entry::User(text));
+            self.last_message_id = None;
+            return;
+        }
+
+        let same_message = message.id.is_some() && message.id == self.last_message_id;
+        for content in &message.content {
+            match content {
+                MessageContent::Text(text) => match self.entries.last_mut() {
+                    Some(Entry::Assistant(last)) if same_message => last.push_str(&text.text),
+                    _ => self.entries.push(Entry::Assistant(text.text.clone())),
+                },
+                MessageContent::ToolRequest(request) => {
+                    let (name, arguments) = match &request.tool_call {
+                        Ok(call) => (call.name.clone(), call.arguments.clone()),
+                        Err(e) => (format!("invalid tool call: {}", e), Value::Null),
+                    };
+                    self.entries.push(Entry::Tool(ToolEntry {
+                        id: request.id.clone(),
+                        name,
+                        arguments,
+                        output: None,
+                        expanded: false,
+                    }));
+                }
+                MessageContent::ToolResponse(response) => {
+                    let output = match &response.tool_result {
+                        Ok(contents) => ToolOutput {
+                            text: contents
+                                .iter()
+                                .filter_map(|c| c.as_text())
+                                .map(|t| t.text.as_str())
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                            is_error: false,
+                        },
+                        Err(e) => ToolOutput {
+                            text: e.to_string(),
+                            is_error: true,
+                        },
+                    };
+                    let tool = self.entries.iter_mut().rev().find_map(|entry| match entry {
+                        Entry::Tool(tool) if tool.id == response.id => Some(tool),
+                        _ => None,
+                    });
+                    if let Some(tool) = tool {
+                        tool.output = Some(output);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.last_message_id = message.id.clone();
+    }
+
+    pub fn notice(&mut self, text: impl Into<String>, error: bool) {
+        self.entries.push(Entry::Notice {
+            text: text.into(),
+            error,
+        });
+        self.last_message_id = None;
+    }
+
+    /// Moves the spinner, called on a timer while the agent is replying
+    pub fn tick(&mut self) {
+        self.spinner = self.spinner.wrapping_add(1);
+    }
+
+    /// Keeps the side panel and status line up to date with what the extensions report
+    pub fn handle_notification(&mut self, notification: &ServerNotification) {
+        match notification {
+            ServerNotification::LoggingMessageNotification(notification) => {
+                let data = &notification.params.data;
+                if let Ok(event) =
+                    serde_json::from_value::<TaskExecutionNotificationEvent>(data.clone())
+                {
+                    self.handle_task_event(event);
+                    return;
+                }
+                let text = match data {
+                    Value::String(s) => s.clone(),
+                    Value::Object(o) => match o.get("message").or_else(|| o.get("output")) {
+                        Some(Value::String(s)) => s.clone(),
+                        _ => data.to_string(),
+                    },
+                    v => v.to_string(),
+                };
+                self.status = Some(text);
+            }
+            ServerNotification::ProgressNotification(notification) => {
+                let params = &notification.params;
+                let progress = match params.total {
+                    Some(total) => format!("{}/{}", params.progress, total),
+                    None => params.progress.to_string(),
+                };
+                self.status = Some(match &params.message {
+                    Some(message) => format!("{} ({})", message, progress),
+                    None => progress,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_task_event(&mut self, event: TaskExecutionNotificationEvent) {
+        match event {
+            TaskExecutionNotificationEvent::LineOutput { output, .. } => {
+                self.status = Some(output);
+            }
+            TaskExecutionNotificationEvent::TasksUpdate { stats, tasks } => {
+                self.side.task_stats = Some(stats);
+                self.side.tasks = tasks;
+            }
+            TaskExecutionNotificationEvent::TasksComplete {
+                stats,
+                failed_tasks,
+            } => {
+                let mut text = format!(
+                    "Tasks finished: {} of {} completed",
+                    stats.completed, stats.total
+                );
+                for task in &failed_tasks {
+                    text.push_str(&format!(
+                        "\n{} failed: {}",
+                        task.name,
+                        task.error.as_deref().unwrap_or("unknown error")
+                    ));
+                }
+                self.notice(text, !failed_tasks.is_empty());
+                self.side.task_stats = None;
+                self.side.tasks.clear();
+            }
+        }
+    }
+
+    /// The tasks the side panel lists, running ones first
+    pub fn visible_tasks(&self) -> impl Iterator<Item = &TaskInfo> {
+        let running = self
+            .side
+            .tasks
+            .iter()
+            .filter(|task| matches!(task.status, TaskStatus::Running));
+        let others = self
+            .side
+            .tasks
+            .iter()
+            .filter(|task| !matches!(task.status, TaskStatus::Running));
+        running.chain(others)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        if !self.confirmations.is_empty() {
+            let permission = match key.code {
+                KeyCode::Char('y') => Permission::AllowOnce,
+                KeyCode::Char('a') => Permission::AlwaysAllow,
+                KeyCode::Char('n') => Permission::DenyOnce,
+                KeyCode::Char('c') if ctrl => Permission::Cancel,
+                KeyCode::Esc => Permission::Cancel,
+                _ => return Action::None,
+            };
+            return Action::Confirm(permission);
+        }
+
+        match key.code {
+            KeyCode::Char('c') if ctrl => {
+                if self.busy {
+                    Action::Interrupt
+                } else if !self.input.is_empty() {
+                    self.input.clear();
+                    Action::None
+                } else {
+                    Action::Quit
+                }
+            }
+            KeyCode::Char('d') if ctrl && self.input.is_empty() && !self.busy => Action::Quit,
+            KeyCode::Char('o') if ctrl => {
+                self.toggle_tool();
+                Action::None
+            }
+            KeyCode::Char('j') if ctrl => {
+                self.input.push('\n');
+                Action::None
+            }
+            KeyCode::Enter
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::ALT | KeyModifiers::SHIFT) =>
+            {
+                self.input.push('\n');
+                Action::None
+            }
+            KeyCode::Enter => {
+                if self.busy || self.input.trim().is_empty() {
+                    return Action::None;
+                }
+                self.scroll = 0;
+                Action::Submit(std::mem::take(&mut self.input).trim().to_string())
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.input.push(c);
+                Action::None
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                Action::None
+            }
+            KeyCode::Up => self.scroll_by(1),
+            KeyCode::Down => self.scroll_by(-1),
+            KeyCode::PageUp => self.scroll_by(PAGE_SIZE as isize),
+            KeyCode::PageDown => self.scroll_by(-(PAGE_SIZE as isize)),
+            KeyCode::Home => {
+                // The drawing clamps this to the top of the conversation
+                self.scroll = usize::MAX;
+                Action::None
+            }
+            KeyCode::End => {
+                self.scroll = 0;
+                Action::None
+            }
+            KeyCode::Tab => {
+                self.select_tool(true);
+                Action::None
+            }
+            KeyCode::BackTab => {
+                self.select_tool(false);
+                Action::None
+            }
+            KeyCode::Esc => {
+                self.selected = None;
+                Action::None
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn scroll_by(&mut self, lines: isize) -> Action {
+        self.scroll = self.scroll.saturating_add_signed(lines);
+        Action::None
+    }
+
+    /// Selects the next or previous tool call, starting from the most recent one
+    fn select_tool(&mut self, forward: bool) {
+        let tools: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| matches!(entry, Entry::Tool(_)))
+            .map(|(index, _)| index)
+            .collect();
+        if tools.is_empty() {
+            return;
+        }
+        let position = self
+            .selected
+            .and_then(|selected| tools.iter().position(|&index| index == selected));
+        let next = match (position, forward) {
+            (None, _) => tools.len() - 1,
+            (Some(position), true) => (position + 1) % tools.len(),
+            (Some(position), false) => (position + tools.len() - 1) % tools.len(),
+        };
+        self.selected = Some(tools[next]);
+        self.reveal_selected = true;
+    }
+
+    /// Expands or collapses the selected tool call, or the most recent one when none is selected
+    fn toggle_tool(&mut self) {
+        let index = self.selected.or_else(|| {
+            self.entries
+                .iter()
+                .rposition(|entry| matches!(entry, Entry::Tool(_)))
+        });
+        if let Some(Entry::Tool(tool)) = index.and_then(|index| self.entries.get_mut(index)) {
+            tool.expanded = !tool.expanded;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use rmcp::model::Content;
+    use serde_json::json;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_tool_output_joins_its_call() {
+        let mut app = App::default();
+        app.reload(&[
+            Message::user().with_text("List the files"),
+            Message::assistant().with_tool_request(
+                "1",
+                Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+            ),
+            Message::user().with_tool_response("1", Ok(vec![Content::text("a.rs")])),
+            Message::assistant().with_text("There is one file"),
+        ]);
+
+        assert_eq!(app.entries.len(), 3);
+        let Entry::Tool(tool) = &app.entries[1] else {
+            panic!("expected a tool call");
+        };
+        assert_eq!(tool.name, "developer__shell");
+        assert_eq!(tool.output.as_ref().unwrap().text, "a.rs");
+    }
+
+    #[test]
+    fn test_streamed_text_is_joined() {
+        let mut app = App::default();
+        let mut chunk = Message::assistant().with_text("Hello");
+        chunk.id = Some("reply".to_string());
+        app.push_message(&chunk);
+        let mut chunk = Message::assistant().with_text(", world");
+        chunk.id = Some("reply".to_string());
+        app.push_message(&chunk);
+        app.push_message(&Message::assistant().with_text("Another message"));
+
+        assert_eq!(app.entries.len(), 2);
+        assert!(matches!(&app.entries[0], Entry::Assistant(text) if text == "Hello, world"));
+    }
+
+    #[test]
+    fn test_typing_and_submitting() {
+        let mut app = App::default();
+        type_text(&mut app, "first");
+        app.handle_key(ctrl('j'));
+        type_text(&mut app, "second");
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            Action::Submit("first\nsecond".to_string())
+        );
+        assert!(app.input.is_empty());
+
+        // Nothing is sent while the agent is replying, Ctrl+C interrupts it instead
+        app.busy = true;
+        type_text(&mut app, "third");
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::None);
+        assert_eq!(app.handle_key(ctrl('c')), Action::Interrupt);
+
+        app.busy = false;
+        assert_eq!(app.handle_key(ctrl('c')), Action::None);
+        assert_eq!(app.handle_key(ctrl('c')), Action::Quit);
+    }
+
+    #[test]
+    fn test_confirmation_keys() {
+        let mut app = App::default();
+        app.confirmations.push_back(ToolConfirmationRequest {
+            id: "1".to_string(),
+            tool_name: "developer__shell".to_string(),
+            arguments: json!({"command": "rm -rf target"}),
+            prompt: None,
+            edit_preview: None,
+        });
+
+        assert_eq!(app.handle_key(key(KeyCode::Char('x'))), Action::None);
+        assert_eq!(app.handle_key(key(KeyCode::Char('c'))), Action::None);
+        assert_eq!(
+            app.handle_key(ctrl('c')),
+            Action::Confirm(Permission::Cancel)
+        );
+        assert_eq!(
+            app.handle_key(key(KeyCode::Char('a'))),
+            Action::Confirm(Permission::AlwaysAllow)
+        );
+        assert_eq!(
+            app.handle_key(key(KeyCode::Esc)),
+            Action::Confirm(Permission::Cancel)
+        );
+        assert!(app.input.is_empty());
+    }
+
+    #[test]
+    fn test_selecting_and_expanding_tool_calls() {
+        let mut app = App::default();
+        app.reload(&[
+            Message::user().with_text("Look around"),
+            Message::assistant()
+                .with_tool_request("1", Ok(ToolCall::new("developer__shell", json!({}))))
+                .with_tool_request("2", Ok(ToolCall::new("developer__shell", json!({})))),
+        ]);
+
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.selected, Some(2));
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.selected, Some(1));
+        app.handle_key(ctrl('o'));
+        assert!(matches!(&app.entries[1], Entry::Tool(tool) if tool.expanded));
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.selected, None);
+    }
+
+    #[test]
+    fn test_task_updates_fill_the_side_panel() {
+        let mut app = App::default();
+        let update = json!({
+            "type": "task_execution",
+            "subtype": "tasks_update",
+            "stats": {"total": 2, "pending": 1, "running": 1, "completed": 0, "failed": 0},
+            "tasks": [],
+        });
+        app.handle_task_event(serde_json::from_value(update).unwrap());
+        assert_eq!(app.side.task_stats.as_ref().unwrap().running, 1);
+
+        let complete = json!({
+            "type": "task_execution",
+            "subtype": "tasks_complete",
+            "stats": {"total": 2, "completed": 2, "failed": 0, "success_rate": 1.0},
+            "failed_tasks": [],
+        });
+        app.handle_task_event(serde_json::from_value(complete).unwrap());
+        assert!(app.side.task_stats.is_none());
+        assert!(
+            matches!(&app.entries[0], Entry::Notice { text, error: false } if text.contains("2 of 2"))
+        );
+    }
+}
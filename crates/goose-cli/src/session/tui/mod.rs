@@ -0,0 +1,464 @@
+//! An optional full-screen interface for interactive sessions, turned on with `--tui` or
+//! GOOSE_CLI_TUI. It keeps the conversation in a scrollable pane with collapsible tool calls,
+//! shows extensions, context usage and subagent tasks in a side panel and asks for tool
+//! permissions in a dialog, instead of printing everything line by line.
+mod app;
+mod ui;
+
+use super::{attachments, custom_commands, history, output, Session};
+use anyhow::Result;
+use app::{Action, App};
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use futures::StreamExt;
+use goose::agents::AgentEvent;
+use goose::config::Config;
+use goose::message::{push_message, Message, MessageContent};
+use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::{Permission, PermissionConfirmation};
+use goose::providers::pricing::initialize_pricing_cache;
+use goose::session;
+use mcp_core::handler::ToolError;
+use ratatui::DefaultTerminal;
+use serde_json::Value;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+const HELP: &str = "Enter sends your message, Ctrl+J or Alt+Enter starts a new line.
+Up/Down and PageUp/PageDown scroll, Home and End jump to the start and the end.
+Tab and Shift+Tab select tool calls, Ctrl+O expands or collapses one, Esc clears the selection.
+Ctrl+C interrupts goose, clears the prompt or quits.
+
+Commands: /exit, /clear, /undo, /mode <name>, /image [path], /commands, /help
+Mention files with @path to attach them to your message.";
+
+/// Commands of the line-by-line interface that need the terminal to themselves
+const LINEAR_ONLY_COMMANDS: [&str; 12] = [
+    "/t",
+    "/extension",
+    "/builtin",
+    "/prompts",
+    "/prompt",
+    "/plan",
+    "/endplan",
+    "/recipe",
+    "/summarize",
+    "/edit",
+    "/history",
+    "/drop",
+];
+
+/// What the session does after the user sends a line
+enum Flow {
+    Continue,
+    Exit,
+}
+
+impl Session {
+    /// Start an interactive session in the full-screen interface, optionally with an initial
+    /// message
+    pub async fn interactive_tui(&mut self, prompt: Option<String>) -> Result<()> {
+        if Config::global()
+            .get_param::<bool>("GOOSE_CLI_SHOW_COST")
+            .unwrap_or(false)
+        {
+            if let Err(e) = initialize_pricing_cache().await {
+                tracing::warn!("Failed to initialize pricing cache: {e}");
+            }
+        }
+
+        let session_config = self.session_config();
+        self.agent
+            .run_session_start_hooks(session_config.as_ref())
+            .await?;
+
+        self.custom_commands = custom_commands::load_commands();
+        let mut app = App::default();
+        app.reload(&self.messages);
+        for error in &self.custom_commands.errors {
+            app.notice(error.clone(), true);
+        }
+        self.refresh_side_panel(&mut app).await;
+
+        let mut terminal = ratatui::init();
+        self.tui_active = true;
+        let result = self.run_tui(&mut terminal, &mut app, prompt).await;
+        self.tui_active = false;
+        ratatui::restore();
+        self.agent
+            .run_session_end_hooks(session_config.as_ref())
+            .await;
+        result
+    }
+
+    async fn run_tui(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        app: &mut App,
+        prompt: Option<String>,
+    ) -> Result<()> {
+        let mut events = EventStream::new();
+        if let Some(prompt) = prompt {
+            let (message, _) = self.prepare_prompt(&prompt);
+            self.tui_turn(terminal, app, &mut events, message).await?;
+        }
+
+        loop {
+            terminal.draw(|frame| ui::draw(frame, app))?;
+            let Some(event) = events.next().await else {
+                break;
+            };
+            let Event::Key(key) = event? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match app.handle_key(key) {
+                Action::Submit(text) => {
+                    if let Flow::Exit = self
+                        .handle_tui_input(terminal, app, &mut events, text)
+                        .await?
+                    {
+                        break;
+                    }
+                }
+                Action::Quit => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the commands the full-screen interface knows and sends anything else to the agent
+    async fn handle_tui_input(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        app: &mut App,
+        events: &mut EventStream,
+        text: String,
+    ) -> Result<Flow> {
+        let (command, argument) = match text.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (text.as_str(), ""),
+        };
+
+        match command {
+            "/exit" | "/quit" | "exit" | "quit" if argument.is_empty() => return Ok(Flow::Exit),
+            "/help" | "/?" => {
+                let mut help = HELP.to_string();
+                let usage = self.custom_commands.usage_lines();
+                if !usage.is_empty() {
+                    help.push_str(&format!("\n\nCustom commands:\n{}", usage.join("\n")));
+                }
+                app.notice(help, false);
+            }
+            "/commands" => {
+                self.reload_custom_commands();
+                for error in &self.custom_commands.errors {
+                    app.notice(error.clone(), true);
+                }
+                let usage = self.custom_commands.usage_lines();
+                if usage.is_empty() {
+                    app.notice("No custom commands", false);
+                } else {
+                    app.notice(format!("Custom commands:\n{}", usage.join("\n")), false);
+                }
+            }
+            "/clear" => {
+                self.messages.clear();
+                if let Some(file) = self.session_file.as_ref().filter(|f| f.exists()) {
+                    std::fs::remove_file(file)?;
+                    std::fs::File::create(file)?;
+                }
+                app.reload(&self.messages);
+                app.notice("Chat context cleared.", false);
+            }
+            "/undo" => match history::undo_last_turn(&mut self.messages) {
+                Some(prompt) => {
+                    self.persist_history()?;
+                    app.reload(&self.messages);
+                    app.notice(
+                        "Removed your last message and everything after it, it is back in the prompt to edit.",
+                        false,
+                    );
+                    app.input = history::typed_text(&prompt).to_string();
+                }
+                None => app.notice("There is no message to undo.", true),
+            },
+            "/mode" => {
+                let mode = argument.to_lowercase();
+                if !["auto", "approve", "chat", "smart_approve"].contains(&mode.as_str()) {
+                    app.notice(
+                        format!(
+                            "Invalid mode '{}'. Mode must be one of: auto, approve, chat",
+                            mode
+                        ),
+                        true,
+                    );
+                } else {
+                    Config::global().set_param("GOOSE_MODE", Value::String(mode.clone()))?;
+                    app.side.mode = mode.clone();
+                    app.notice(format!("Goose mode set to '{}'", mode), false);
+                }
+            }
+            "/image" => {
+                let image = if argument.is_empty() {
+                    attachments::paste_image()
+                } else {
+                    Self::load_image(argument)
+                };
+                match image {
+                    Ok(image) => {
+                        self.pending_images.push(image);
+                        app.notice(
+                            "Attached the image, it will be sent with your next message.",
+                            false,
+                        );
+                    }
+                    Err(e) => app.notice(format!("Could not attach the image: {}", e), true),
+                }
+            }
+            command if LINEAR_ONLY_COMMANDS.contains(&command) => app.notice(
+                format!(
+                    "{} is not available in the full-screen interface, run the session without --tui to use it.",
+                    command
+                ),
+                true,
+            ),
+            _ => {
+                // A custom command is replaced by the prompt it renders
+                let text = match self.custom_commands.expand(&text) {
+                    Some(Ok(prompt)) => prompt,
+                    Some(Err(e)) => {
+                        app.notice(format!("{:#}", e), true);
+                        return Ok(Flow::Continue);
+                    }
+                    None => text.clone(),
+                };
+                let (mut message, report) = self.prepare_prompt(&text);
+                for reason in &report.skipped {
+                    app.notice(format!("Not attached: {}", reason), true);
+                }
+                message.content.extend(self.pending_images.drain(..));
+                self.tui_turn(terminal, app, events, message).await?;
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Sends a prompt and shows the reply as it streams in, while the user can still scroll,
+    /// answer permission requests and interrupt
+    async fn tui_turn(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        app: &mut App,
+        events: &mut EventStream,
+        message: Message,
+    ) -> Result<()> {
+        app.push_message(&message);
+        self.record_prompt(message).await?;
+
+        app.busy = true;
+        let result = self.stream_tui_reply(terminal, app, events).await;
+        app.busy = false;
+        app.status = None;
+        app.confirmations.clear();
+        self.refresh_side_panel(app).await;
+        result
+    }
+
+    async fn stream_tui_reply(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        app: &mut App,
+        events: &mut EventStream,
+    ) -> Result<()> {
+        let cancel_token = CancellationToken::new();
+        let session_config = self.session_config();
+        let mut stream = self
+            .agent
+            .reply(
+                &self.messages,
+                session_config.clone(),
+                Some(cancel_token.clone()),
+            )
+            .await?;
+        let mut spinner = tokio::time::interval(Duration::from_millis(100));
+
+        // Only fields of self can be borrowed mutably while the stream borrows the agent
+        loop {
+            terminal.draw(|frame| ui::draw(frame, app))?;
+            tokio::select! {
+                result = stream.next() => match result {
+                    Some(Ok(AgentEvent::Message(message))) => match message.content.first() {
+                        Some(MessageContent::ToolConfirmationRequest(confirmation)) => {
+                            app.confirmations.push_back(confirmation.clone());
+                        }
+                        Some(MessageContent::ContextLengthExceeded(_)) => {
+                            app.status = Some("Summarizing the conversation to fit the context".to_string());
+                            terminal.draw(|frame| ui::draw(frame, app))?;
+                            Self::summarize_context_messages(&mut self.messages, &self.agent, "", false).await?;
+                            app.reload(&self.messages);
+                            app.notice("The context filled up, goose summarized the conversation to continue.", false);
+                            stream = self
+                                .agent
+                                .reply(
+                                    &self.messages,
+                                    session_config.clone(),
+                                    Some(cancel_token.clone()),
+                                )
+                                .await?;
+                        }
+                        _ => {
+                            push_message(&mut self.messages, message.clone());
+                            if let Some(session_file) = &self.session_file {
+                                session::persist_messages_with_schedule_id(
+                                    session_file,
+                                    &self.messages,
+                                    None,
+                                    self.scheduled_job_id.clone(),
+                                    std::env::current_dir().ok(),
+                                )
+                                .await?;
+                            }
+                            app.push_message(&message);
+                        }
+                    },
+                    Some(Ok(AgentEvent::McpNotification((_id, notification)))) => {
+                        app.handle_notification(&notification);
+                    }
+                    Some(Ok(AgentEvent::ModelChange { model, .. })) => {
+                        app.side.model = model;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        cancel_token.cancel();
+                        drop(stream);
+                        if let Err(e) = self.handle_interrupted_messages(false).await {
+                            tracing::error!("Error handling interruption: {}", e);
+                        }
+                        app.reload(&self.messages);
+                        app.notice(
+                            format!(
+                                "Error: {}\nThe conversation was rolled back to your last message, depending on the error you may be able to continue.",
+                                e
+                            ),
+                            true,
+                        );
+                        break;
+                    }
+                    None => break,
+                },
+                event = events.next() => {
+                    let Some(event) = event else {
+                        break;
+                    };
+                    let Event::Key(key) = event? else {
+                        continue;
+                    };
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match app.handle_key(key) {
+                        Action::Confirm(Permission::Cancel) => {
+                            let Some(confirmation) = app.confirmations.pop_front() else {
+                                continue;
+                            };
+                            let mut response = Message::user();
+                            response.content.push(MessageContent::tool_response(
+                                confirmation.id,
+                                Err(ToolError::ExecutionError("Tool call cancelled by user".to_string())),
+                            ));
+                            push_message(&mut self.messages, response);
+                            if let Some(session_file) = &self.session_file {
+                                session::persist_messages_with_schedule_id(
+                                    session_file,
+                                    &self.messages,
+                                    None,
+                                    self.scheduled_job_id.clone(),
+                                    std::env::current_dir().ok(),
+                                )
+                                .await?;
+                            }
+                            cancel_token.cancel();
+                            drop(stream);
+                            app.reload(&self.messages);
+                            app.notice("Tool call cancelled.", false);
+                            break;
+                        }
+                        Action::Confirm(permission) => {
+                            let Some(confirmation) = app.confirmations.pop_front() else {
+                                continue;
+                            };
+                            self.agent
+                                .handle_confirmation(
+                                    confirmation.id,
+                                    PermissionConfirmation {
+                                        principal_type: PrincipalType::Tool,
+                                        permission,
+                                        arguments: None,
+                                    },
+                                )
+                                .await;
+                        }
+                        Action::Interrupt => {
+                            cancel_token.cancel();
+                            drop(stream);
+                            if let Err(e) = self.handle_interrupted_messages(true).await {
+                                tracing::error!("Error handling interruption: {}", e);
+                            }
+                            app.reload(&self.messages);
+                            app.notice("Interrupted.", false);
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                _ = spinner.tick() => app.tick(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Brings the side panel up to date, as `display_context_usage` does before each prompt of
+    /// the line-by-line interface
+    async fn refresh_side_panel(&self, app: &mut App) {
+        let config = Config::global();
+        let side = &mut app.side;
+        side.provider = config
+            .get_param::<String>("GOOSE_PROVIDER")
+            .unwrap_or_else(|_| "unknown".to_string());
+        side.mode = config
+            .get_param::<String>("GOOSE_MODE")
+            .unwrap_or_else(|_| "auto".to_string());
+        side.extensions = self.agent.list_extensions().await;
+
+        let Ok(provider) = self.agent.provider().await else {
+            return;
+        };
+        let model_config = provider.get_model_config();
+        side.model = model_config.model_name.clone();
+        side.context_limit = model_config.context_limit();
+
+        let Some(metadata) = self
+            .session_file
+            .as_ref()
+            .and_then(|file| session::read_metadata(file).ok())
+        else {
+            return;
+        };
+        side.total_tokens = metadata.total_tokens.unwrap_or(0) as usize;
+        if config
+            .get_param::<bool>("GOOSE_CLI_SHOW_COST")
+            .unwrap_or(false)
+        {
+            side.cost = output::estimate_cost_usd(
+                &side.provider,
+                &side.model,
+                metadata.input_tokens.unwrap_or(0) as usize,
+                metadata.output_tokens.unwrap_or(0) as usize,
+            )
+            .await;
+        }
+    }
+}
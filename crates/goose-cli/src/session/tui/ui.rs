@@ -0,0 +1,357 @@
+//! Draws the full-screen interface: the conversation on the left with the prompt under it, the
+//! side panel on the right and the permission dialog over both when a tool call needs approval.
+use super::app::{App, Entry, ToolEntry};
+use goose::agents::subagent_execution_tool::lib::TaskStatus;
+use goose::message::ToolConfirmationRequest;
+use goose::utils::safe_truncate;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+use serde_json::Value;
+
+const SIDE_PANEL_WIDTH: u16 = 34;
+/// The prompt grows with what is typed up to this many lines
+const MAX_INPUT_LINES: usize = 8;
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const KEY_HINTS: &str =
+    " Enter send · Ctrl+J newline · Tab tool calls · Ctrl+O expand · Ctrl+C quit ";
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, side] =
+        Layout::horizontal([Constraint::Min(40), Constraint::Length(SIDE_PANEL_WIDTH)])
+            .areas(frame.area());
+    let input_lines = app.input.split('\n').count().min(MAX_INPUT_LINES) as u16;
+    let [conversation, input] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(input_lines + 2)]).areas(main);
+
+    draw_conversation(frame, app, conversation);
+    draw_input(frame, app, input);
+    draw_side_panel(frame, app, side);
+    if let Some(confirmation) = app.confirmations.front() {
+        draw_confirmation(frame, confirmation);
+    }
+}
+
+fn draw_conversation(frame: &mut Frame, app: &mut App, area: Rect) {
+    let block = Block::bordered().title(" goose ");
+    let inner = block.inner(area);
+
+    let mut lines = Vec::new();
+    let mut selected_line = None;
+    for (index, entry) in app.entries.iter().enumerate() {
+        let selected = app.selected == Some(index);
+        if selected {
+            selected_line = Some(wrapped(lines.clone()).line_count(inner.width));
+        }
+        lines.extend(entry_lines(entry, selected));
+        lines.push(Line::default());
+    }
+
+    let paragraph = wrapped(lines);
+    let max_scroll = paragraph
+        .line_count(inner.width)
+        .saturating_sub(inner.height as usize);
+    if std::mem::take(&mut app.reveal_selected) {
+        if let Some(line) = selected_line {
+            app.scroll = max_scroll.saturating_sub(line);
+        }
+    }
+    app.scroll = app.scroll.min(max_scroll);
+    let top = (max_scroll - app.scroll) as u16;
+    frame.render_widget(paragraph.block(block).scroll((top, 0)), area);
+}
+
+fn wrapped(lines: Vec<Line<'static>>) -> Paragraph<'static> {
+    Paragraph::new(lines).wrap(Wrap { trim: false })
+}
+
+fn entry_lines(entry: &Entry, selected: bool) -> Vec<Line<'static>> {
+    match entry {
+        Entry::User(text) => text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let prefix = if index == 0 { "> " } else { "  " };
+                Line::from(vec![
+                    Span::from(prefix).cyan().bold(),
+                    Span::from(line.to_string()).bold(),
+                ])
+            })
+            .collect(),
+        Entry::Assistant(text) => text
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect(),
+        Entry::Tool(tool) => tool_lines(tool, selected),
+        Entry::Notice { text, error } => {
+            let color = if *error { Color::Red } else { Color::Yellow };
+            text.lines()
+                .map(|line| Line::from(line.to_string()).fg(color).italic())
+                .collect()
+        }
+    }
+}
+
+fn tool_lines(tool: &ToolEntry, selected: bool) -> Vec<Line<'static>> {
+    let marker = if tool.expanded { "▾ " } else { "▸ " };
+    let state = match &tool.output {
+        None => Span::from("  running").dim(),
+        Some(output) if output.is_error => Span::from("  failed").red(),
+        Some(_) => Span::default(),
+    };
+    let mut header = Line::from(vec![
+        Span::from(marker),
+        Span::from(tool.name.clone()).yellow().bold(),
+        Span::from(format!("  {}", summarize_arguments(&tool.arguments))).dim(),
+        state,
+    ]);
+    if selected {
+        header = header.reversed();
+    }
+    let mut lines = vec![header];
+
+    if !tool.expanded {
+        if let Some(output) = &tool.output {
+            let count = output.text.lines().count();
+            let summary = match count {
+                0 => "  no output".to_string(),
+                1 => "  1 line of output".to_string(),
+                n => format!("  {} lines of output", n),
+            };
+            lines.push(Line::from(summary).dim());
+        }
+        return lines;
+    }
+
+    let arguments = serde_json::to_string_pretty(&tool.arguments).unwrap_or_default();
+    lines.extend(
+        arguments
+            .lines()
+            .map(|line| Line::from(format!("  {}", line)).dim()),
+    );
+    if let Some(output) = &tool.output {
+        let style = if output.is_error {
+            Style::new().red()
+        } else {
+            Style::new()
+        };
+        lines.push(Line::default());
+        lines.extend(
+            output
+                .text
+                .lines()
+                .map(|line| Line::styled(format!("  {}", line), style)),
+        );
+    }
+    lines
+}
+
+/// The first argument of a tool call on one line, which is usually the command or the path
+fn summarize_arguments(arguments: &Value) -> String {
+    let Some((key, value)) = arguments.as_object().and_then(|o| o.iter().next()) else {
+        return String::new();
+    };
+    let value = match value {
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    };
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("{}: {}", key, safe_truncate(&value, 60))
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let title = if app.busy {
+        let status = app.status.as_deref().unwrap_or("goose is working on it");
+        let status = status.lines().next().unwrap_or_default();
+        format!(
+            " {} {} · Ctrl+C to interrupt ",
+            SPINNER[app.spinner % SPINNER.len()],
+            safe_truncate(status, area.width.saturating_sub(30) as usize)
+        )
+    } else {
+        KEY_HINTS.to_string()
+    };
+    let block = Block::bordered().title(Line::from(title).dim());
+    let inner = block.inner(area);
+
+    let lines: Vec<&str> = app.input.split('\n').collect();
+    let top = lines.len().saturating_sub(MAX_INPUT_LINES);
+    let paragraph = Paragraph::new(app.input.clone())
+        .block(block)
+        .scroll((top as u16, 0));
+    frame.render_widget(paragraph, area);
+
+    if app.confirmations.is_empty() {
+        let last = lines.last().copied().unwrap_or_default();
+        let column = Line::from(last).width() as u16;
+        let row = (lines.len() - 1 - top) as u16;
+        frame.set_cursor_position((
+            (inner.x + column).min(inner.right().saturating_sub(1)),
+            inner.y + row,
+        ));
+    }
+}
+
+fn draw_side_panel(frame: &mut Frame, app: &App, area: Rect) {
+    let side = &app.side;
+    let mut lines = vec![
+        Line::from("Model").bold(),
+        Line::from(format!("{}/{}", side.provider, side.model)),
+        Line::from(format!("mode: {}", side.mode)).dim(),
+        Line::default(),
+        Line::from("Context").bold(),
+    ];
+
+    let used = if side.context_limit > 0 {
+        (side.total_tokens as f64 / side.context_limit as f64).min(1.0)
+    } else {
+        0.0
+    };
+    let bar_width = SIDE_PANEL_WIDTH.saturating_sub(10) as usize;
+    let filled = (used * bar_width as f64).round() as usize;
+    let color = if used > 0.85 {
+        Color::Red
+    } else if used > 0.6 {
+        Color::Yellow
+    } else {
+        Color::Green
+    };
+    lines.push(Line::from(vec![
+        Span::from("█".repeat(filled)).fg(color),
+        Span::from("░".repeat(bar_width - filled)).dim(),
+        Span::from(format!(" {:.0}%", used * 100.0)),
+    ]));
+    lines.push(
+        Line::from(format!(
+            "{}/{} tokens",
+            side.total_tokens, side.context_limit
+        ))
+        .dim(),
+    );
+    if let Some(cost) = side.cost {
+        lines.push(Line::from(format!("${:.4} USD", cost)).dim());
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::from("Extensions").bold());
+    if side.extensions.is_empty() {
+        lines.push(Line::from("none").dim());
+    }
+    for extension in &side.extensions {
+        lines.push(Line::from(format!("• {}", extension)));
+    }
+
+    if let Some(stats) = &side.task_stats {
+        lines.push(Line::default());
+        lines.push(Line::from("Tasks").bold());
+        lines.push(
+            Line::from(format!(
+                "{} running, {} waiting, {} done, {} failed",
+                stats.running, stats.pending, stats.completed, stats.failed
+            ))
+            .dim(),
+        );
+        for task in app.visible_tasks() {
+            let (icon, color) = match task.status {
+                TaskStatus::Pending => ("○", Color::DarkGray),
+                TaskStatus::Running => ("◐", Color::Yellow),
+                TaskStatus::Completed => ("●", Color::Green),
+                TaskStatus::Failed => ("✗", Color::Red),
+            };
+            let name = if task.task_name.is_empty() {
+                &task.id
+            } else {
+                &task.task_name
+            };
+            lines.push(Line::from(vec![
+                Span::from(format!("{} ", icon)).fg(color),
+                Span::from(safe_truncate(name, SIDE_PANEL_WIDTH as usize - 6)),
+            ]));
+        }
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::bordered())
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_confirmation(frame: &mut Frame, confirmation: &ToolConfirmationRequest) {
+    let [area] = Layout::horizontal([Constraint::Percentage(70)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let mut lines = vec![Line::from(vec![
+        Span::from("goose would like to call "),
+        Span::from(confirmation.tool_name.clone()).yellow().bold(),
+    ])];
+    if let Some(prompt) = &confirmation.prompt {
+        lines.push(Line::from(prompt.clone()));
+    }
+    lines.push(Line::default());
+
+    match &confirmation.edit_preview {
+        Some(preview) => {
+            lines.push(Line::from(preview.path.clone()).bold());
+            for line in preview.diff.lines() {
+                let style = if line.starts_with("@@") {
+                    Style::new().cyan()
+                } else if line.starts_with('+') {
+                    Style::new().green()
+                } else if line.starts_with('-') {
+                    Style::new().red()
+                } else {
+                    Style::new()
+                };
+                lines.push(Line::styled(line.to_string(), style));
+            }
+        }
+        None => {
+            let arguments =
+                serde_json::to_string_pretty(&confirmation.arguments).unwrap_or_default();
+            lines.extend(arguments.lines().map(|line| Line::from(line.to_string())));
+        }
+    }
+
+    let keys = Line::from(vec![
+        Span::from(" y ").reversed(),
+        Span::from(" allow  "),
+        Span::from(" a ").reversed(),
+        Span::from(" always allow  "),
+        Span::from(" n ").reversed(),
+        Span::from(" deny  "),
+        Span::from(" c ").reversed(),
+        Span::from(" cancel "),
+    ]);
+    let block = Block::bordered()
+        .title(" Allow this tool call? ")
+        .title_bottom(keys)
+        .border_style(Style::new().yellow());
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_summarize_arguments() {
+        assert_eq!(
+            summarize_arguments(&json!({"command": "cargo   test\n--workspace"})),
+            "command: cargo test --workspace"
+        );
+        assert_eq!(summarize_arguments(&json!({})), "");
+    }
+}
@@ -0,0 +1,330 @@
+//! Finds the hints the developer extension adds to its instructions. Hints come from the global
+//! `.goosehints` and from `.goosehints` and `AGENTS.md` files in every directory from the root of
+//! the git repository down to the working directory. Hints in directories below the working
+//! directory are added later, when the agent first works on files there.
+//!
+//! A line `@include <path>` in a hints file is replaced by the file at that path, relative to the
+//! file that includes it. Only files under the project root or the global config directory that
+//! .gooseignore does not restrict can be included.
+use etcetera::{choose_app_strategy, AppStrategy};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// The files hints are read from in each directory, in this order
+pub const HINT_FILE_NAMES: [&str; 2] = [".goosehints", "AGENTS.md"];
+
+/// How deep included files can include other files
+const MAX_INCLUDE_DEPTH: usize = 5;
+
+const INCLUDE_DIRECTIVE: &str = "@include ";
+
+#[derive(Debug, Clone)]
+pub struct HintFile {
+    pub path: PathBuf,
+    /// True for the hints in the goose config directory, which apply to every project
+    pub global: bool,
+    /// The hints with the included files in place
+    pub content: String,
+    /// The files the hints included, directly or not
+    pub included: Vec<PathBuf>,
+}
+
+/// Where `@include` lines can read files from
+pub struct IncludeScope<'a> {
+    roots: Vec<PathBuf>,
+    is_ignored: &'a dyn Fn(&Path) -> bool,
+}
+
+impl<'a> IncludeScope<'a> {
+    /// Allows files under the global config directory and the project root of the working
+    /// directory, except those `is_ignored` rejects
+    pub fn new(global_dir: &Path, cwd: &Path, is_ignored: &'a dyn Fn(&Path) -> bool) -> Self {
+        let roots = [global_dir.to_path_buf(), project_root(cwd)]
+            .into_iter()
+            .map(|dir| dir.canonicalize().unwrap_or(dir))
+            .collect();
+        Self { roots, is_ignored }
+    }
+
+    fn allows(&self, path: &Path, canonical: &Path) -> bool {
+        self.roots.iter().any(|root| canonical.starts_with(root))
+            && !(self.is_ignored)(path)
+            && !(self.is_ignored)(canonical)
+    }
+}
+
+/// The directory of the global `.goosehints`
+///
+/// - macOS/Linux: ~/.config/goose/
+/// - Windows:     ~\AppData\Roaming\Block\goose\config\
+pub fn global_hints_dir() -> PathBuf {
+    // keep previous behavior of expanding ~/.config in case this fails
+    choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.config_dir())
+        .unwrap_or_else(|_| PathBuf::from(shellexpand::tilde("~/.config/goose").to_string()))
+}
+
+/// The root of the git repository the directory is in, or the directory itself outside of one
+pub fn project_root(cwd: &Path) -> PathBuf {
+    cwd.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(cwd)
+        .to_path_buf()
+}
+
+/// The hints that apply to the working directory: the global hints, then the hints of each
+/// directory from the project root down to the working directory
+pub fn load_hints(global_dir: &Path, cwd: &Path, scope: &IncludeScope) -> Vec<HintFile> {
+    let mut files = Vec::new();
+    if global_dir.is_dir() {
+        files.extend(read_hint_file(&global_dir.join(".goosehints"), true, scope));
+    }
+
+    let root = project_root(cwd);
+    let mut dirs: Vec<&Path> = cwd
+        .ancestors()
+        .take_while(|dir| dir.starts_with(&root))
+        .collect();
+    dirs.reverse();
+    for dir in dirs {
+        // The global directory can also be the working directory
+        if dir != global_dir {
+            files.extend(hint_files_in(dir, scope));
+        }
+    }
+    files
+}
+
+/// The hints kept in one directory
+pub fn hint_files_in(dir: &Path, scope: &IncludeScope) -> Vec<HintFile> {
+    HINT_FILE_NAMES
+        .iter()
+        .filter_map(|name| read_hint_file(&dir.join(name), false, scope))
+        .collect()
+}
+
+fn read_hint_file(path: &Path, global: bool, scope: &IncludeScope) -> Option<HintFile> {
+    if !path.is_file() {
+        return None;
+    }
+    let text = std::fs::read_to_string(path).ok()?;
+    let mut visited = HashSet::from([path.canonicalize().unwrap_or_else(|_| path.to_path_buf())]);
+    let mut included = Vec::new();
+    let content = expand_includes(&text, path, 0, scope, &mut visited, &mut included);
+    Some(HintFile {
+        path: path.to_path_buf(),
+        global,
+        content,
+        included,
+    })
+}
+
+/// Replaces each `@include` line with the file it names, leaving out files that were already
+/// included on the way here so a cycle cannot loop, and files outside the scope
+fn expand_includes(
+    text: &str,
+    path: &Path,
+    depth: usize,
+    scope: &IncludeScope,
+    visited: &mut HashSet<PathBuf>,
+    included: &mut Vec<PathBuf>,
+) -> String {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut expanded = String::new();
+    for line in text.lines() {
+        let Some(target) = line.trim().strip_prefix(INCLUDE_DIRECTIVE) else {
+            expanded.push_str(line);
+            expanded.push('\n');
+            continue;
+        };
+
+        let target = dir.join(shellexpand::tilde(target.trim()).as_ref());
+        let canonical = target.canonicalize().unwrap_or_else(|_| target.clone());
+        if depth >= MAX_INCLUDE_DEPTH || visited.contains(&canonical) {
+            tracing::warn!(
+                "Not including {} from {}, includes are nested too deep or loop",
+                target.display(),
+                path.display()
+            );
+            continue;
+        }
+        if !scope.allows(&target, &canonical) {
+            tracing::warn!(
+                "Not including {} from {}, it is outside the project and the goose config directory or restricted by .gooseignore",
+                target.display(),
+                path.display()
+            );
+            continue;
+        }
+        match std::fs::read_to_string(&target) {
+            Ok(text) => {
+                visited.insert(canonical.clone());
+                included.push(target.clone());
+                expanded.push_str(&expand_includes(
+                    &text,
+                    &target,
+                    depth + 1,
+                    scope,
+                    visited,
+                    included,
+                ));
+                expanded.push('\n');
+                visited.remove(&canonical);
+            }
+            Err(e) => tracing::warn!(
+                "Failed to include {} from {}: {}",
+                target.display(),
+                path.display(),
+                e
+            ),
+        }
+    }
+    expanded.trim_end().to_string()
+}
+
+/// The hints as they appear in the developer extension's instructions
+pub fn format_hints(files: &[HintFile], cwd: &Path) -> String {
+    let mut sections = Vec::new();
+
+    let global: Vec<&HintFile> = files.iter().filter(|f| f.global).collect();
+    if !global.is_empty() {
+        let mut section = "### Global Hints\nThe developer extension includes some global hints that apply to all projects & directories.\n".to_string();
+        section.push_str(&join_contents(&global, cwd));
+        sections.push(section);
+    }
+
+    let project: Vec<&HintFile> = files.iter().filter(|f| !f.global).collect();
+    if !project.is_empty() {
+        let mut section = "### Project Hints\nThe developer extension includes some hints for working on the project in this directory.\n".to_string();
+        section.push_str(&join_contents(&project, cwd));
+        sections.push(section);
+    }
+
+    sections.join("\n\n")
+}
+
+/// The hints of a directory below the working directory, sent along with the first tool result
+/// for a file there
+pub fn format_directory_hints(files: &[HintFile], cwd: &Path) -> String {
+    let files: Vec<&HintFile> = files.iter().collect();
+    format!(
+        "### Directory Hints\nThese hints apply to files in the directories they come from.\n{}",
+        join_contents(&files, cwd)
+    )
+}
+
+/// Each file's content under its path relative to the project root. A lone file of the working
+/// directory needs no path.
+fn join_contents(files: &[&HintFile], cwd: &Path) -> String {
+    if let [file] = files {
+        if file.global || file.path.parent() == Some(cwd) {
+            return file.content.clone();
+        }
+    }
+    let root = project_root(cwd);
+    files
+        .iter()
+        .map(|file| {
+            let path = file.path.strip_prefix(&root).unwrap_or(&file.path);
+            format!("#### {}\n{}", path.display(), file.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn no_ignores(_: &Path) -> bool {
+        false
+    }
+
+    #[test]
+    fn test_hints_from_project_root_to_cwd() {
+        let root = TempDir::new().unwrap();
+        let global = TempDir::new().unwrap();
+        let cwd = root.path().join("crates/app");
+        fs::create_dir_all(root.path().join(".git")).unwrap();
+        fs::create_dir_all(&cwd).unwrap();
+        fs::write(global.path().join(".goosehints"), "global").unwrap();
+        fs::write(root.path().join("AGENTS.md"), "root agents").unwrap();
+        fs::write(root.path().join("crates/.goosehints"), "crates").unwrap();
+        fs::write(cwd.join(".goosehints"), "app").unwrap();
+
+        let scope = IncludeScope::new(global.path(), &cwd, &no_ignores);
+        let files = load_hints(global.path(), &cwd, &scope);
+        let contents: Vec<&str> = files.iter().map(|f| f.content.as_str()).collect();
+        assert_eq!(contents, vec!["global", "root agents", "crates", "app"]);
+        assert!(files[0].global);
+
+        let formatted = format_hints(&files, &cwd);
+        assert!(formatted.starts_with("### Global Hints"));
+        assert!(formatted.contains("### Project Hints"));
+        assert!(formatted.contains("#### AGENTS.md\nroot agents"));
+        assert!(formatted.contains("#### crates/app/.goosehints\napp"));
+    }
+
+    #[test]
+    fn test_hints_outside_git_come_from_cwd_only() {
+        let parent = TempDir::new().unwrap();
+        let cwd = parent.path().join("project");
+        fs::create_dir_all(&cwd).unwrap();
+        fs::write(parent.path().join(".goosehints"), "parent").unwrap();
+        fs::write(cwd.join(".goosehints"), "project").unwrap();
+
+        let scope = IncludeScope::new(Path::new("/nonexistent"), &cwd, &no_ignores);
+        let files = load_hints(Path::new("/nonexistent"), &cwd, &scope);
+        assert_eq!(files.len(), 1);
+        assert_eq!(format_hints(&files, &cwd), "### Project Hints\nThe developer extension includes some hints for working on the project in this directory.\nproject");
+    }
+
+    #[test]
+    fn test_includes() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(
+            dir.path().join(".goosehints"),
+            "Start\n@include docs/style.md\n@include missing.md\nEnd",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("docs/style.md"),
+            "Use tabs\n@include ../.goosehints",
+        )
+        .unwrap();
+
+        let scope = IncludeScope::new(Path::new("/nonexistent"), dir.path(), &no_ignores);
+        let files = hint_files_in(dir.path(), &scope);
+        assert_eq!(files[0].content, "Start\nUse tabs\nEnd");
+        assert_eq!(files[0].included, vec![dir.path().join("docs/style.md")]);
+    }
+
+    #[test]
+    fn test_includes_stay_in_scope() {
+        let outside = TempDir::new().unwrap();
+        let global = TempDir::new().unwrap();
+        let root = TempDir::new().unwrap();
+        fs::create_dir_all(root.path().join(".git")).unwrap();
+        fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        fs::write(global.path().join("shared.md"), "shared").unwrap();
+        fs::write(root.path().join(".env"), "API_KEY=1").unwrap();
+        fs::write(
+            root.path().join(".goosehints"),
+            format!(
+                "Start\n@include {}\n@include {}\n@include .env\nEnd",
+                outside.path().join("secret.txt").display(),
+                global.path().join("shared.md").display(),
+            ),
+        )
+        .unwrap();
+
+        let is_ignored = |path: &Path| path.ends_with(".env");
+        let scope = IncludeScope::new(global.path(), root.path(), &is_ignored);
+        let files = hint_files_in(root.path(), &scope);
+        assert_eq!(files[0].content, "Start\nshared\nEnd");
+        assert_eq!(files[0].included, vec![global.path().join("shared.md")]);
+    }
+}
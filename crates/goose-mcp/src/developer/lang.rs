@@ -1,5 +1,39 @@
 use std::path::Path;
 
+/// Markdown language identifiers known to `get_language_identifier`
+pub const LANGUAGE_IDENTIFIERS: &[&str] = &[
+    "bash",
+    "batch",
+    "c",
+    "cpp",
+    "css",
+    "dockerfile",
+    "go",
+    "haskell",
+    "html",
+    "java",
+    "javascript",
+    "json",
+    "kotlin",
+    "markdown",
+    "matlab",
+    "perl",
+    "php",
+    "powershell",
+    "python",
+    "r",
+    "ruby",
+    "rust",
+    "scala",
+    "scheme",
+    "sql",
+    "swift",
+    "toml",
+    "typescript",
+    "vbscript",
+    "yaml",
+];
+
 /// Get the markdown language identifier for a file extension
 pub fn get_language_identifier(path: &Path) -> &'static str {
     match path.extension().and_then(|ext| ext.to_str()) {
@@ -1,4 +1,5 @@
 mod editor_models;
+pub mod hints;
 mod lang;
 mod shell;
 
@@ -8,7 +9,7 @@ use etcetera::{choose_app_strategy, AppStrategy};
 use indoc::formatdoc;
 use serde_json::Value;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     future::Future,
     io::Cursor,
     path::{Path, PathBuf},
@@ -23,7 +24,7 @@ use url::Url;
 
 use include_dir::{include_dir, Dir};
 use mcp_core::{
-    handler::{PromptError, ResourceError, ToolError},
+    handler::{CompletionError, PromptError, ResourceError, ToolError},
     protocol::ServerCapabilities,
 };
 
@@ -31,8 +32,9 @@ use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
 
 use rmcp::model::{
-    Content, JsonRpcMessage, JsonRpcNotification, JsonRpcVersion2_0, Notification, Prompt,
-    PromptArgument, PromptTemplate, Resource, Role, Tool, ToolAnnotations,
+    AnnotateAble, ArgumentInfo, Content, JsonRpcMessage, JsonRpcNotification, JsonRpcVersion2_0,
+    Notification, Prompt, PromptArgument, PromptTemplate, RawResourceTemplate, Reference, Resource,
+    ResourceTemplate, Role, Tool, ToolAnnotations,
 };
 use rmcp::object;
 
@@ -45,6 +47,8 @@ use xcap::{Monitor, Window};
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 
+const FILE_RESOURCE_TEMPLATE: &str = "file:///{path}";
+
 // Embeds the prompts directory to the build
 static PROMPTS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/developer/prompts");
 
@@ -98,6 +102,8 @@ pub struct DeveloperRouter {
     file_history: Arc<Mutex<HashMap<PathBuf, Vec<String>>>>,
     ignore_patterns: Arc<Gitignore>,
     editor_model: Option<EditorModel>,
+    /// Directories below cwd whose hints were already sent to the agent
+    seen_hint_dirs: Arc<Mutex<HashSet<PathBuf>>>,
 }
 
 impl Default for DeveloperRouter {
@@ -106,6 +112,61 @@ impl Default for DeveloperRouter {
     }
 }
 
+/// The patterns of files the developer extension must not touch: the global and local
+/// .gooseignore files, the local .gitignore when there is no local .gooseignore, and some
+/// sensible defaults when neither exists
+pub fn load_ignore_patterns(cwd: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(cwd);
+    let mut has_ignore_file = false;
+    // Initialize ignore patterns
+    // - macOS/Linux: ~/.config/goose/
+    // - Windows:     ~\AppData\Roaming\Block\goose\config\
+    let global_ignore_path = choose_app_strategy(crate::APP_STRATEGY.clone())
+        .map(|strategy| strategy.in_config_dir(".gooseignore"))
+        .unwrap_or_else(|_| {
+            PathBuf::from(shellexpand::tilde("~/.config/goose/.gooseignore").to_string())
+        });
+
+    // Create the directory if it doesn't exist
+    let _ = std::fs::create_dir_all(global_ignore_path.parent().unwrap());
+
+    // Read global ignores if they exist
+    if global_ignore_path.is_file() {
+        let _ = builder.add(global_ignore_path);
+        has_ignore_file = true;
+    }
+
+    // Check for local ignores in current directory
+    let local_ignore_path = cwd.join(".gooseignore");
+
+    // Read local ignores if they exist
+    if local_ignore_path.is_file() {
+        let _ = builder.add(local_ignore_path);
+        has_ignore_file = true;
+    } else {
+        // If no .gooseignore exists, check for .gitignore as fallback
+        let gitignore_path = cwd.join(".gitignore");
+        if gitignore_path.is_file() {
+            tracing::debug!(
+                "No .gooseignore found, using .gitignore as fallback for ignore patterns"
+            );
+            let _ = builder.add(gitignore_path);
+            has_ignore_file = true;
+        }
+    }
+
+    // Only use default patterns if no .gooseignore files were found
+    // AND no .gitignore was used as fallback
+    if !has_ignore_file {
+        // Add some sensible defaults
+        let _ = builder.add_line(None, "**/.env");
+        let _ = builder.add_line(None, "**/.env.*");
+        let _ = builder.add_line(None, "**/secrets.*");
+    }
+
+    builder.build().expect("Failed to build ignore patterns")
+}
+
 impl DeveloperRouter {
     pub fn new() -> Self {
         // TODO consider rust native search tools, we could use
@@ -461,41 +522,18 @@ impl DeveloperRouter {
             },
         };
 
-        // choose_app_strategy().config_dir()
-        // - macOS/Linux: ~/.config/goose/
-        // - Windows:     ~\AppData\Roaming\Block\goose\config\
-        // keep previous behavior of expanding ~/.config in case this fails
-        let global_hints_path = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .map(|strategy| strategy.in_config_dir(".goosehints"))
-            .unwrap_or_else(|_| {
-                PathBuf::from(shellexpand::tilde("~/.config/goose/.goosehints").to_string())
-            });
+        let global_hints_dir = hints::global_hints_dir();
 
         // Create the directory if it doesn't exist
-        let _ = std::fs::create_dir_all(global_hints_path.parent().unwrap());
+        let _ = std::fs::create_dir_all(&global_hints_dir);
 
-        // Check for local hints in current directory
-        let local_hints_path = cwd.join(".goosehints");
+        let ignore_patterns = load_ignore_patterns(&cwd);
 
-        // Read global hints if they exist
-        let mut hints = String::new();
-        if global_hints_path.is_file() {
-            if let Ok(global_hints) = std::fs::read_to_string(&global_hints_path) {
-                hints.push_str("\n### Global Hints\nThe developer extension includes some global hints that apply to all projects & directories.\n");
-                hints.push_str(&global_hints);
-            }
-        }
-
-        // Read local hints if they exist
-        if local_hints_path.is_file() {
-            if let Ok(local_hints) = std::fs::read_to_string(&local_hints_path) {
-                if !hints.is_empty() {
-                    hints.push_str("\n\n");
-                }
-                hints.push_str("### Project Hints\nThe developer extension includes some hints for working on the project in this directory.\n");
-                hints.push_str(&local_hints);
-            }
-        }
+        // Global hints, then the hints of each directory from the project root down to cwd
+        let is_ignored = |path: &Path| ignore_patterns.matched(path, false).is_ignore();
+        let scope = hints::IncludeScope::new(&global_hints_dir, &cwd, &is_ignored);
+        let hint_files = hints::load_hints(&global_hints_dir, &cwd, &scope);
+        let hints = hints::format_hints(&hint_files, &cwd);
 
         // Return base instructions directly when no hints are found
         let instructions = if hints.is_empty() {
@@ -504,56 +542,6 @@ impl DeveloperRouter {
             format!("{base_instructions}\n{hints}")
         };
 
-        let mut builder = GitignoreBuilder::new(cwd.clone());
-        let mut has_ignore_file = false;
-        // Initialize ignore patterns
-        // - macOS/Linux: ~/.config/goose/
-        // - Windows:     ~\AppData\Roaming\Block\goose\config\
-        let global_ignore_path = choose_app_strategy(crate::APP_STRATEGY.clone())
-            .map(|strategy| strategy.in_config_dir(".gooseignore"))
-            .unwrap_or_else(|_| {
-                PathBuf::from(shellexpand::tilde("~/.config/goose/.gooseignore").to_string())
-            });
-
-        // Create the directory if it doesn't exist
-        let _ = std::fs::create_dir_all(global_ignore_path.parent().unwrap());
-
-        // Read global ignores if they exist
-        if global_ignore_path.is_file() {
-            let _ = builder.add(global_ignore_path);
-            has_ignore_file = true;
-        }
-
-        // Check for local ignores in current directory
-        let local_ignore_path = cwd.join(".gooseignore");
-
-        // Read local ignores if they exist
-        if local_ignore_path.is_file() {
-            let _ = builder.add(local_ignore_path);
-            has_ignore_file = true;
-        } else {
-            // If no .gooseignore exists, check for .gitignore as fallback
-            let gitignore_path = cwd.join(".gitignore");
-            if gitignore_path.is_file() {
-                tracing::debug!(
-                    "No .gooseignore found, using .gitignore as fallback for ignore patterns"
-                );
-                let _ = builder.add(gitignore_path);
-                has_ignore_file = true;
-            }
-        }
-
-        // Only use default patterns if no .gooseignore files were found
-        // AND no .gitignore was used as fallback
-        if !has_ignore_file {
-            // Add some sensible defaults
-            let _ = builder.add_line(None, "**/.env");
-            let _ = builder.add_line(None, "**/.env.*");
-            let _ = builder.add_line(None, "**/secrets.*");
-        }
-
-        let ignore_patterns = builder.build().expect("Failed to build ignore patterns");
-
         Self {
             tools: vec![
                 bash_tool,
@@ -569,6 +557,7 @@ impl DeveloperRouter {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model,
+            seen_hint_dirs: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
@@ -577,6 +566,35 @@ impl DeveloperRouter {
         self.ignore_patterns.matched(path, false).is_ignore()
     }
 
+    // Hints from the directories between cwd and the path that the agent has not seen yet
+    fn unseen_hints(&self, path: &Path) -> Option<String> {
+        let cwd = std::env::current_dir().ok()?;
+        let dir = if path.is_dir() { path } else { path.parent()? };
+        let relative = dir.strip_prefix(&cwd).ok()?;
+
+        let is_ignored = |path: &Path| self.is_ignored(path);
+        let scope = hints::IncludeScope::new(&hints::global_hints_dir(), &cwd, &is_ignored);
+        let mut seen = self.seen_hint_dirs.lock().unwrap();
+        let mut files = Vec::new();
+        let mut current = cwd.clone();
+        for component in relative.components() {
+            current.push(component);
+            if seen.insert(current.clone()) {
+                files.extend(
+                    hints::hint_files_in(&current, &scope)
+                        .into_iter()
+                        .filter(|file| !self.is_ignored(&file.path)),
+                );
+            }
+        }
+
+        if files.is_empty() {
+            None
+        } else {
+            Some(hints::format_directory_hints(&files, &cwd))
+        }
+    }
+
     // Helper method to resolve a path relative to cwd with platform-specific handling
     fn resolve_path(&self, path_str: &str) -> Result<PathBuf, ToolError> {
         let cwd = std::env::current_dir().expect("should have a current working dir");
@@ -595,6 +613,45 @@ impl DeveloperRouter {
         }
     }
 
+    // Suggest files and directories under the directory of a partially typed path
+    fn complete_path(&self, partial: &str) -> Vec<String> {
+        let cwd = std::env::current_dir().expect("should have a current working dir");
+        let expanded = expand_path(partial);
+
+        let (dir_part, prefix) = match expanded.rfind(['/', '\\']) {
+            Some(idx) => (&expanded[..=idx], &expanded[idx + 1..]),
+            None => ("", expanded.as_str()),
+        };
+        let dir = if dir_part.is_empty() {
+            cwd
+        } else {
+            cwd.join(dir_part)
+        };
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Vec::new();
+        };
+
+        let mut values: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.file_name().to_string_lossy().starts_with(prefix)
+                    && !self.is_ignored(&entry.path())
+            })
+            .map(|entry| {
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                format!(
+                    "{}{}{}",
+                    dir_part,
+                    entry.file_name().to_string_lossy(),
+                    if is_dir { "/" } else { "" }
+                )
+            })
+            .collect();
+        values.sort();
+        values
+    }
+
     // Shell command execution with platform-specific handling
     async fn bash(
         &self,
@@ -1554,6 +1611,36 @@ impl DeveloperRouter {
     }
 }
 
+/// The files and directories a tool call works on, whose directory hints it should load: the
+/// path of a text editor call, the search directory of a glob and the existing paths named in a
+/// shell or grep command
+fn hint_paths(tool_name: &str, arguments: &Value) -> Vec<PathBuf> {
+    let Ok(cwd) = std::env::current_dir() else {
+        return Vec::new();
+    };
+    let arg = |name: &str| arguments.get(name).and_then(|v| v.as_str());
+    match tool_name {
+        "text_editor" => arg("path")
+            .map(|p| vec![PathBuf::from(expand_path(p))])
+            .unwrap_or_default(),
+        "glob" => arg("path")
+            .map(|p| vec![cwd.join(expand_path(p))])
+            .unwrap_or_default(),
+        "shell" | "grep" => arg("command")
+            .map(|command| {
+                command
+                    .split_whitespace()
+                    .skip(1)
+                    .filter(|part| !part.starts_with('-'))
+                    .map(|part| cwd.join(part.trim_matches(['\'', '"'])))
+                    .filter(|path| path.exists())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
 impl Router for DeveloperRouter {
     fn name(&self) -> String {
         "developer".to_string()
@@ -1567,6 +1654,8 @@ impl Router for DeveloperRouter {
         CapabilitiesBuilder::new()
             .with_tools(false)
             .with_prompts(false)
+            .with_resources(false, false)
+            .with_completions()
             .build()
     }
 
@@ -1583,7 +1672,8 @@ impl Router for DeveloperRouter {
         let this = self.clone();
         let tool_name = tool_name.to_string();
         Box::pin(async move {
-            match tool_name.as_str() {
+            let hint_paths = hint_paths(&tool_name, &arguments);
+            let mut result = match tool_name.as_str() {
                 "shell" => this.bash(arguments, notifier).await,
                 "glob" => this.glob(arguments).await,
                 "grep" => this.bash(arguments, notifier).await,
@@ -1592,7 +1682,15 @@ impl Router for DeveloperRouter {
                 "screen_capture" => this.screen_capture(arguments).await,
                 "image_processor" => this.image_processor(arguments).await,
                 _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+            };
+            if let Ok(content) = &mut result {
+                for path in hint_paths {
+                    if let Some(hints) = this.unseen_hints(&path) {
+                        content.push(Content::text(hints).with_audience(vec![Role::Assistant]));
+                    }
+                }
             }
+            result
         })
     }
 
@@ -1603,9 +1701,86 @@ impl Router for DeveloperRouter {
 
     fn read_resource(
         &self,
-        _uri: &str,
+        uri: &str,
     ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
-        Box::pin(async move { Ok("".to_string()) })
+        const MAX_FILE_SIZE: u64 = 400 * 1024; // 400KB in bytes
+
+        let path = Url::parse(uri).ok().and_then(|url| url.to_file_path().ok());
+        // Check the resolved path too so a symlink cannot expose an ignored file
+        let ignored = path.as_deref().is_some_and(|p| {
+            self.is_ignored(p) || p.canonicalize().is_ok_and(|real| self.is_ignored(&real))
+        });
+        let uri = uri.to_string();
+
+        Box::pin(async move {
+            let path = path
+                .filter(|p| p.is_file())
+                .ok_or_else(|| ResourceError::NotFound(format!("Resource {} not found", uri)))?;
+
+            if ignored {
+                return Err(ResourceError::ExecutionError(format!(
+                    "Access to '{}' is restricted by .gooseignore",
+                    path.display()
+                )));
+            }
+
+            let file_size = std::fs::metadata(&path)
+                .map_err(|e| ResourceError::ExecutionError(e.to_string()))?
+                .len();
+            if file_size > MAX_FILE_SIZE {
+                return Err(ResourceError::ExecutionError(format!(
+                    "File '{}' is too large ({:.2}KB). Maximum size is 400KB.",
+                    path.display(),
+                    file_size as f64 / 1024.0
+                )));
+            }
+
+            std::fs::read_to_string(&path).map_err(|e| ResourceError::ExecutionError(e.to_string()))
+        })
+    }
+
+    fn list_resource_templates(&self) -> Vec<ResourceTemplate> {
+        vec![RawResourceTemplate {
+            uri_template: FILE_RESOURCE_TEMPLATE.to_string(),
+            name: "files".to_string(),
+            description: Some("Text files on the local filesystem by absolute path".to_string()),
+            mime_type: Some("text/plain".to_string()),
+        }
+        .no_annotation()]
+    }
+
+    fn complete(
+        &self,
+        reference: Reference,
+        argument: ArgumentInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, CompletionError>> + Send + 'static>> {
+        let values = match (&reference, argument.name.as_str()) {
+            (Reference::Resource(r), "path") if r.uri == FILE_RESOURCE_TEMPLATE => {
+                // The template already supplies the leading slash of the absolute path
+                self.complete_path(&format!("/{}", argument.value))
+                    .into_iter()
+                    .map(|p| p.trim_start_matches('/').to_string())
+                    .collect()
+            }
+            (Reference::Resource(r), _) if r.uri != FILE_RESOURCE_TEMPLATE => {
+                let uri = r.uri.clone();
+                return Box::pin(async move {
+                    Err(CompletionError::NotFound(format!(
+                        "Resource template {} not found",
+                        uri
+                    )))
+                });
+            }
+            (Reference::Prompt(_), "source_code") => self.complete_path(&argument.value),
+            (Reference::Prompt(_), "language") => lang::LANGUAGE_IDENTIFIERS
+                .iter()
+                .filter(|l| l.starts_with(&argument.value))
+                .map(|l| l.to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Box::pin(async move { Ok(values) })
     }
 
     fn list_prompts(&self) -> Vec<Prompt> {
@@ -1649,6 +1824,7 @@ impl Clone for DeveloperRouter {
             file_history: Arc::clone(&self.file_history),
             ignore_patterns: Arc::clone(&self.ignore_patterns),
             editor_model: create_editor_model(), // Recreate the editor model since it's not Clone
+            seen_hint_dirs: Arc::clone(&self.seen_hint_dirs),
         }
     }
 }
@@ -1721,6 +1897,75 @@ mod tests {
         assert!(!instructions.contains("Project Hints"));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_directory_hints_sent_with_first_file() {
+        let dir = TempDir::new().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        fs::create_dir_all(cwd.join("sub")).unwrap();
+        fs::write(cwd.join("sub/AGENTS.md"), "Sub hint content").unwrap();
+        fs::write(cwd.join("sub/a.txt"), "a").unwrap();
+        fs::write(cwd.join("sub/b.txt"), "b").unwrap();
+
+        let router = DeveloperRouter::new();
+        assert!(!router.instructions().contains("Sub hint content"));
+
+        let view = |file: &str| {
+            router.call_tool(
+                "text_editor",
+                json!({"command": "view", "path": cwd.join(file).to_str().unwrap()}),
+                dummy_sender(),
+            )
+        };
+        let first = view("sub/a.txt").await.unwrap();
+        assert!(first.iter().any(|content| content
+            .as_text()
+            .is_some_and(|text| text.text.contains("Sub hint content"))));
+
+        let second = view("sub/b.txt").await.unwrap();
+        assert!(!second.iter().any(|content| content
+            .as_text()
+            .is_some_and(|text| text.text.contains("Sub hint content"))));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_directory_hints_sent_with_search_tools() {
+        let dir = TempDir::new().unwrap();
+        std::env::set_current_dir(dir.path()).unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        for sub in ["grep", "glob"] {
+            fs::create_dir_all(cwd.join(sub)).unwrap();
+            fs::write(cwd.join(sub).join("AGENTS.md"), format!("{sub} hint")).unwrap();
+        }
+
+        let router = DeveloperRouter::new();
+        let has_hint = |contents: &[Content], hint: &str| {
+            contents.iter().any(|content| {
+                content
+                    .as_text()
+                    .is_some_and(|text| text.text.contains(hint))
+            })
+        };
+
+        let grep = router
+            .call_tool("grep", json!({"command": "ls grep"}), dummy_sender())
+            .await
+            .unwrap();
+        assert!(has_hint(&grep, "grep hint"));
+
+        let glob = router
+            .call_tool(
+                "glob",
+                json!({"pattern": "*.md", "path": "glob"}),
+                dummy_sender(),
+            )
+            .await
+            .unwrap();
+        assert!(has_hint(&glob, "glob hint"));
+    }
+
     static DEV_ROUTER: OnceCell<DeveloperRouter> = OnceCell::const_new();
 
     async fn get_router() -> &'static DeveloperRouter {
@@ -2081,6 +2326,7 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            seen_hint_dirs: Arc::new(Mutex::new(HashSet::new())),
         };
 
         // Test basic file matching
@@ -2132,6 +2378,7 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            seen_hint_dirs: Arc::new(Mutex::new(HashSet::new())),
         };
 
         // Try to write to an ignored file
@@ -2174,6 +2421,79 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_file_resource_template_and_completion() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let mut builder = GitignoreBuilder::new(temp_dir.path());
+        builder.add_line(None, "secret.txt").unwrap();
+        let ignore_patterns = builder.build().unwrap();
+
+        let router = DeveloperRouter {
+            tools: vec![],
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(ignore_patterns),
+            editor_model: None,
+            seen_hint_dirs: Arc::new(Mutex::new(HashSet::new())),
+        };
+
+        fs::write(temp_dir.path().join("notes.txt"), "hello notes").unwrap();
+        fs::write(temp_dir.path().join("secret.txt"), "hunter2").unwrap();
+        fs::create_dir(temp_dir.path().join("nested")).unwrap();
+
+        let notes_uri = Url::from_file_path(temp_dir.path().join("notes.txt")).unwrap();
+        let content = router.read_resource(notes_uri.as_str()).await.unwrap();
+        assert_eq!(content, "hello notes");
+
+        let secret_uri = Url::from_file_path(temp_dir.path().join("secret.txt")).unwrap();
+        assert!(router.read_resource(secret_uri.as_str()).await.is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(
+                temp_dir.path().join("secret.txt"),
+                temp_dir.path().join("link.txt"),
+            )
+            .unwrap();
+            let link_uri = Url::from_file_path(temp_dir.path().join("link.txt")).unwrap();
+            assert!(router.read_resource(link_uri.as_str()).await.is_err());
+        }
+
+        let values = router
+            .complete(
+                Reference::Prompt(rmcp::model::PromptReference {
+                    name: "unit_test".to_string(),
+                }),
+                ArgumentInfo {
+                    name: "source_code".to_string(),
+                    value: String::new(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(values, vec!["nested/", "notes.txt"]);
+
+        let values = router
+            .complete(
+                Reference::Prompt(rmcp::model::PromptReference {
+                    name: "unit_test".to_string(),
+                }),
+                ArgumentInfo {
+                    name: "language".to_string(),
+                    value: "ru".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(values, vec!["ruby", "rust"]);
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_bash_respects_ignore_patterns() {
@@ -2192,6 +2512,7 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            seen_hint_dirs: Arc::new(Mutex::new(HashSet::new())),
         };
 
         // Create an ignored file
@@ -14,7 +14,7 @@ mod memory;
 mod tutorial;
 
 pub use computercontroller::ComputerControllerRouter;
-pub use developer::DeveloperRouter;
+pub use developer::{hints, load_ignore_patterns, DeveloperRouter};
 pub use google_drive::GoogleDriveRouter;
 pub use memory::MemoryRouter;
 pub use tutorial::TutorialRouter;
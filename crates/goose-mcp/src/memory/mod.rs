@@ -2,13 +2,16 @@ use async_trait::async_trait;
 use etcetera::{choose_app_strategy, AppStrategy};
 use indoc::formatdoc;
 use mcp_core::{
-    handler::{PromptError, ResourceError, ToolError},
+    handler::{CompletionError, PromptError, ResourceError, ToolError},
     protocol::ServerCapabilities,
     tool::ToolCall,
 };
-use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::router::{match_uri_template, CapabilitiesBuilder};
 use mcp_server::Router;
-use rmcp::model::{Content, JsonRpcMessage, Prompt, Resource, Tool, ToolAnnotations};
+use rmcp::model::{
+    AnnotateAble, ArgumentInfo, Content, JsonRpcMessage, Prompt, RawResourceTemplate, Reference,
+    Resource, ResourceTemplate, Tool, ToolAnnotations,
+};
 use rmcp::object;
 use serde_json::Value;
 use std::{
@@ -21,6 +24,9 @@ use std::{
 };
 use tokio::sync::mpsc;
 
+const LOCAL_MEMORY_TEMPLATE: &str = "memory://local/{category}";
+const GLOBAL_MEMORY_TEMPLATE: &str = "memory://global/{category}";
+
 // MemoryRouter implementation
 #[derive(Clone)]
 pub struct MemoryRouter {
@@ -439,6 +445,43 @@ impl MemoryRouter {
         Ok(())
     }
 
+    pub fn list_categories(&self, is_global: bool) -> io::Result<Vec<String>> {
+        let base_dir = if is_global {
+            &self.global_memory_dir
+        } else {
+            &self.local_memory_dir
+        };
+        let mut categories = Vec::new();
+        if base_dir.exists() {
+            for entry in fs::read_dir(base_dir)? {
+                let entry = entry?;
+                if entry.file_type()?.is_file() {
+                    if let Some(category) = entry.file_name().to_string_lossy().strip_suffix(".txt")
+                    {
+                        categories.push(category.to_string());
+                    }
+                }
+            }
+        }
+        categories.sort();
+        Ok(categories)
+    }
+
+    /// Resolve a `memory://local/{category}` or `memory://global/{category}` URI
+    fn parse_memory_uri(uri: &str) -> Option<(String, bool)> {
+        [
+            (LOCAL_MEMORY_TEMPLATE, false),
+            (GLOBAL_MEMORY_TEMPLATE, true),
+        ]
+        .into_iter()
+        .find_map(|(template, is_global)| {
+            match_uri_template(template, uri)
+                .and_then(|mut vars| vars.remove("category"))
+                .filter(|category| !category.contains(['/', '\\']) && category != "..")
+                .map(|category| (category, is_global))
+        })
+    }
+
     pub fn clear_all_global_or_local_memories(&self, is_global: bool) -> io::Result<()> {
         let base_dir = if is_global {
             &self.global_memory_dir
@@ -511,7 +554,11 @@ impl Router for MemoryRouter {
     }
 
     fn capabilities(&self) -> ServerCapabilities {
-        CapabilitiesBuilder::new().with_tools(false).build()
+        CapabilitiesBuilder::new()
+            .with_tools(false)
+            .with_resources(false, false)
+            .with_completions()
+            .build()
     }
 
     fn list_tools(&self) -> Vec<Tool> {
@@ -545,10 +592,80 @@ impl Router for MemoryRouter {
 
     fn read_resource(
         &self,
-        _uri: &str,
+        uri: &str,
     ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
-        Box::pin(async move { Ok("".to_string()) })
+        let this = self.clone();
+        let uri = uri.to_string();
+        Box::pin(async move {
+            let (category, is_global) = Self::parse_memory_uri(&uri)
+                .ok_or_else(|| ResourceError::NotFound(format!("Resource {} not found", uri)))?;
+
+            let memory_file_path = this.get_memory_file(&category, is_global);
+            if !memory_file_path.exists() {
+                return Err(ResourceError::NotFound(format!(
+                    "Memory category {} not found",
+                    category
+                )));
+            }
+
+            fs::read_to_string(memory_file_path)
+                .map_err(|e| ResourceError::ExecutionError(e.to_string()))
+        })
     }
+
+    fn list_resource_templates(&self) -> Vec<ResourceTemplate> {
+        vec![
+            RawResourceTemplate {
+                uri_template: LOCAL_MEMORY_TEMPLATE.to_string(),
+                name: "local_memories".to_string(),
+                description: Some(
+                    "Memories stored for the current project by category".to_string(),
+                ),
+                mime_type: Some("text/plain".to_string()),
+            }
+            .no_annotation(),
+            RawResourceTemplate {
+                uri_template: GLOBAL_MEMORY_TEMPLATE.to_string(),
+                name: "global_memories".to_string(),
+                description: Some("Memories shared across all projects by category".to_string()),
+                mime_type: Some("text/plain".to_string()),
+            }
+            .no_annotation(),
+        ]
+    }
+
+    fn complete(
+        &self,
+        reference: Reference,
+        argument: ArgumentInfo,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, CompletionError>> + Send + 'static>> {
+        let this = self.clone();
+        Box::pin(async move {
+            let is_global = match &reference {
+                Reference::Resource(r) if r.uri == LOCAL_MEMORY_TEMPLATE => false,
+                Reference::Resource(r) if r.uri == GLOBAL_MEMORY_TEMPLATE => true,
+                _ => {
+                    return Err(CompletionError::NotFound(format!(
+                        "No completions for {:?}",
+                        reference
+                    )))
+                }
+            };
+
+            if argument.name != "category" {
+                return Ok(Vec::new());
+            }
+
+            let categories = this
+                .list_categories(is_global)
+                .map_err(|e| CompletionError::InternalError(e.to_string()))?;
+            Ok(categories
+                .into_iter()
+                .filter(|c| c.starts_with(&argument.value))
+                .collect())
+        })
+    }
+
     fn list_prompts(&self) -> Vec<Prompt> {
         vec![]
     }
@@ -740,6 +857,55 @@ mod tests {
         assert!(router.local_memory_dir.join("category.txt").exists());
     }
 
+    #[tokio::test]
+    async fn test_memory_resource_template_read_and_complete() {
+        let temp_dir = tempdir().unwrap();
+        let memory_base = temp_dir.path().join("template_test");
+
+        let router = MemoryRouter {
+            tools: vec![],
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+        };
+
+        router
+            .remember("context", "preferences", "likes tabs", &[], false)
+            .unwrap();
+        router
+            .remember("context", "projects", "goose", &[], false)
+            .unwrap();
+
+        let content = router
+            .read_resource("memory://local/preferences")
+            .await
+            .unwrap();
+        assert!(content.contains("likes tabs"));
+
+        assert!(router
+            .read_resource("memory://global/preferences")
+            .await
+            .is_err());
+        assert!(router
+            .read_resource("memory://local/../secrets")
+            .await
+            .is_err());
+
+        let values = router
+            .complete(
+                Reference::Resource(rmcp::model::ResourceReference {
+                    uri: LOCAL_MEMORY_TEMPLATE.to_string(),
+                }),
+                ArgumentInfo {
+                    name: "category".to_string(),
+                    value: "pr".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(values, vec!["preferences", "projects"]);
+    }
+
     #[test]
     fn test_remove_specific_memory() {
         let temp_dir = tempdir().unwrap();
@@ -309,10 +309,14 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         super::routes::config_management::upsert_permissions,
         super::routes::agent::get_tools,
         super::routes::agent::add_sub_recipes,
+        super::routes::agent::explain_tool_selection,
         super::routes::reply::confirm_permission,
         super::routes::context::manage_context,
         super::routes::session::list_sessions,
         super::routes::session::get_session_history,
+        super::routes::session::export_session,
+        super::routes::audit::list_audit_entries,
+        super::routes::audit::verify_audit_log,
         super::routes::schedule::create_schedule,
         super::routes::schedule::list_schedules,
         super::routes::schedule::delete_schedule,
@@ -350,6 +354,17 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         super::routes::context::ContextManageResponse,
         super::routes::session::SessionListResponse,
         super::routes::session::SessionHistoryResponse,
+        super::routes::session::SessionExportFormat,
+        goose::session::export::SessionExport,
+        goose::session::export::ExportedUsage,
+        goose::session::export::ExportedMessage,
+        goose::session::export::ExportedContent,
+        super::routes::audit::AuditListResponse,
+        goose::audit::AuditEntry,
+        goose::audit::AuditVerification,
+        goose::audit::PermissionDecision,
+        goose::audit::DecisionSource,
+        goose::audit::ToolStatus,
         Message,
         MessageContent,
         ContentSchema,
@@ -360,6 +375,8 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         ToolResponse,
         ToolRequest,
         ToolConfirmationRequest,
+        goose::agents::edit_preview::EditPreview,
+        goose::agents::edit_preview::DiffHunk,
         ThinkingContent,
         RedactedThinkingContent,
         FrontendToolRequest,
@@ -380,6 +397,7 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         ModelInfo,
         SessionInfo,
         SessionMetadata,
+        goose::agents::ToolCacheStats,
         super::routes::schedule::CreateScheduleRequest,
         super::routes::schedule::UpdateScheduleRequest,
         super::routes::schedule::KillJobResponse,
@@ -399,6 +417,7 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         goose::recipe::Recipe,
         goose::recipe::Author,
         goose::recipe::Settings,
+        goose::model::ReasoningEffort,
         goose::recipe::RecipeParameter,
         goose::recipe::RecipeParameterInputType,
         goose::recipe::RecipeParameterRequirement,
@@ -408,6 +427,9 @@ derive_utoipa!(ResourceContents as ResourceContentsSchema);
         goose::agents::types::SuccessCheck,
         super::routes::agent::AddSubRecipesRequest,
         super::routes::agent::AddSubRecipesResponse,
+        super::routes::agent::ExplainToolSelectionRequest,
+        goose::agents::ToolSelectionExplanation,
+        goose::agents::ToolSelectionScore,
         super::routes::system_prompt::SystemPromptsResponse,
         super::routes::system_prompt::SystemPromptResponse,
         super::routes::system_prompt::CreateSystemPromptRequest,
@@ -6,17 +6,24 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use etcetera::{choose_app_strategy, AppStrategy};
 use goose::config::PermissionManager;
-use goose::model::ModelConfig;
+use goose::config::APP_STRATEGY;
+use goose::model::{ModelConfig, ReasoningEffort};
+use goose::providers::base::Provider;
 use goose::providers::create;
+use goose::providers::testprovider::TestProvider;
 use goose::recipe::Response;
 use goose::{
-    agents::{extension::ToolInfo, extension_manager::get_parameter_names},
+    agents::{
+        extension::ToolInfo, extension_manager::get_parameter_names, ToolSelectionExplanation,
+    },
     config::permission::PermissionLevel,
 };
 use goose::{config::Config, recipe::SubRecipe};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
 #[derive(Serialize)]
@@ -71,6 +78,33 @@ struct ProviderList {
 struct UpdateProviderRequest {
     provider: String,
     model: Option<String>,
+    reasoning_effort: Option<ReasoningEffort>,
+    thinking_budget: Option<i32>,
+    /// Record the provider's responses to this file in the recordings directory
+    record_file: Option<String>,
+    /// Replay the provider's responses from this file in the recordings directory instead of
+    /// calling the provider
+    replay_file: Option<String>,
+}
+
+/// Resolve a record or replay file name inside the recordings directory, which is
+/// `GOOSE_RECORDINGS_DIR` if configured. Only bare file names are accepted, so clients
+/// cannot read or write anywhere else on the host.
+fn recording_path(name: &str) -> Result<PathBuf, StatusCode> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => {}
+        _ => return Err(StatusCode::BAD_REQUEST),
+    }
+
+    let dir = match Config::global().get_param::<String>("GOOSE_RECORDINGS_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => choose_app_strategy(APP_STRATEGY.clone())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .data_dir()
+            .join("recordings"),
+    };
+    Ok(dir.join(name))
 }
 
 #[derive(Deserialize)]
@@ -83,6 +117,15 @@ pub struct GetToolsQuery {
     extension_name: Option<String>,
 }
 
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct ExplainToolSelectionRequest {
+    /// The search query, as the model would pass it to the router search tool
+    query: String,
+    /// Number of tools to rank (defaults to 5)
+    k: Option<usize>,
+    extension_name: Option<String>,
+}
+
 #[derive(Serialize)]
 struct ErrorResponse {
     error: String,
@@ -226,6 +269,8 @@ async fn get_tools(
     path = "/agent/update_provider",
     responses(
         (status = 200, description = "Update provider completed", body = String),
+        (status = 400, description = "Invalid provider or recording file"),
+        (status = 404, description = "Replay file not found"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -255,8 +300,41 @@ async fn update_agent_provider(
             .get_param("GOOSE_MODEL")
             .expect("Did not find a model on payload or in env to update provider with")
     });
-    let model_config = ModelConfig::new(model);
-    let new_provider = create(&payload.provider, model_config).unwrap();
+    let model_config = ModelConfig::new(model)
+        .with_reasoning_effort(payload.reasoning_effort)
+        .with_thinking_budget(payload.thinking_budget);
+    let new_provider: Arc<dyn Provider> = match (payload.record_file, payload.replay_file) {
+        (Some(_), Some(_)) => return Err(StatusCode::BAD_REQUEST),
+        (None, Some(replay_file)) => {
+            let replay_path = recording_path(&replay_file)?;
+            if !replay_path.exists() {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            let provider = TestProvider::new_replaying(replay_path.to_string_lossy())
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            Arc::new(provider.with_model_config(model_config))
+        }
+        (record_file, None) => {
+            let provider = create(&payload.provider, model_config).map_err(|e| {
+                tracing::error!("Failed to create provider {}: {}", payload.provider, e);
+                StatusCode::BAD_REQUEST
+            })?;
+            match record_file {
+                Some(record_file) => {
+                    let record_path = recording_path(&record_file)?;
+                    if let Some(parent) = record_path.parent() {
+                        std::fs::create_dir_all(parent)
+                            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+                    }
+                    Arc::new(
+                        TestProvider::new_recording(provider, record_path.to_string_lossy())
+                            .with_autosave(),
+                    )
+                }
+                None => provider,
+            }
+        }
+    };
     agent
         .update_provider(new_provider)
         .await
@@ -305,6 +383,45 @@ async fn update_router_tool_selector(
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/agent/router/explain",
+    request_body = ExplainToolSelectionRequest,
+    responses(
+        (status = 200, description = "Per-tool scores behind the router's selection", body = ToolSelectionExplanation),
+        (status = 401, description = "Unauthorized - invalid secret key"),
+        (status = 404, description = "The tool selection strategy cannot explain its selections"),
+        (status = 424, description = "Agent not initialized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn explain_tool_selection(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<ExplainToolSelectionRequest>,
+) -> Result<Json<ToolSelectionExplanation>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    let explanation = agent
+        .explain_tool_selection(
+            &payload.query,
+            payload.k.unwrap_or(5),
+            payload.extension_name.as_deref(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to explain tool selection: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    explanation.map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
 #[utoipa::path(
     post,
     path = "/agent/session_config",
@@ -354,7 +471,24 @@ pub fn routes(state: Arc<AppState>) -> Router {
             "/agent/update_router_tool_selector",
             post(update_router_tool_selector),
         )
+        .route("/agent/router/explain", post(explain_tool_selection))
         .route("/agent/session_config", post(update_session_config))
         .route("/agent/add_sub_recipes", post(add_sub_recipes))
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_path_rejects_paths_outside_recordings_dir() {
+        for name in ["", "/etc/passwd", "../secrets.json", "nested/run.json", "."] {
+            assert_eq!(
+                recording_path(name).unwrap_err(),
+                StatusCode::BAD_REQUEST,
+                "{name} should be rejected"
+            );
+        }
+    }
+}
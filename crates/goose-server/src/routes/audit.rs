@@ -0,0 +1,109 @@
+use super::utils::verify_secret_key;
+use std::sync::Arc;
+
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use goose::audit::{AuditEntry, AuditLog, AuditQuery, AuditVerification, PermissionDecision};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use utoipa::ToSchema;
+
+// Query parameters for the audit endpoint
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct AuditQueryParams {
+    /// Only entries from this session
+    session_id: Option<String>,
+    /// Only entries for this tool; `*` matches any characters
+    tool: Option<String>,
+    /// Only entries at or after this RFC 3339 timestamp
+    #[param(value_type = Option<String>)]
+    since: Option<DateTime<Utc>>,
+    /// Only entries with this permission decision
+    decision: Option<PermissionDecision>,
+    /// Return at most this many of the most recent matching entries
+    limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AuditListResponse {
+    /// Matching audit entries, oldest first
+    entries: Vec<AuditEntry>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit",
+    params(AuditQueryParams),
+    responses(
+        (status = 200, description = "Audit entries retrieved successfully", body = AuditListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Audit"
+)]
+// List entries from the tool audit log
+async fn list_audit_entries(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<AuditQueryParams>,
+) -> Result<Json<AuditListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let query = AuditQuery {
+        session_id: params.session_id,
+        tool: params.tool,
+        since: params.since,
+        decision: params.decision,
+        limit: params.limit,
+    };
+    let entries = AuditLog::default().query(&query).map_err(|e| {
+        error!("Failed to read audit log: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(AuditListResponse { entries }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit/verify",
+    responses(
+        (status = 200, description = "Hash chain checked; see first_invalid_line for the result", body = AuditVerification),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Audit"
+)]
+// Verify the hash chain of the tool audit log
+async fn verify_audit_log(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<AuditVerification>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let verification = AuditLog::default().verify().map_err(|e| {
+        error!("Failed to verify audit log: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(verification))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/audit", get(list_audit_entries))
+        .route("/audit/verify", get(verify_audit_log))
+        .with_state(state)
+}
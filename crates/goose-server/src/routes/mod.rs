@@ -1,10 +1,12 @@
 // Export route modules
 pub mod agent;
 pub mod audio;
+pub mod audit;
 pub mod config_management;
 pub mod context;
 pub mod extension;
 pub mod health;
+pub mod openai;
 pub mod project;
 pub mod recipe;
 pub mod reply;
@@ -21,8 +23,10 @@ pub fn configure(state: Arc<crate::state::AppState>) -> Router {
     Router::new()
         .merge(health::routes())
         .merge(reply::routes(state.clone()))
+        .merge(openai::routes(state.clone()))
         .merge(agent::routes(state.clone()))
         .merge(audio::routes(state.clone()))
+        .merge(audit::routes(state.clone()))
         .merge(context::routes(state.clone()))
         .merge(extension::routes(state.clone()))
         .merge(config_management::routes(state.clone()))
@@ -0,0 +1,1123 @@
+//! OpenAI-compatible `/v1/chat/completions` and `/v1/models`, so OpenAI SDK clients can talk to
+//! a goose agent as if it were a model. Tools run here on the server, clients only see the text
+//! goose writes along the way and its final answer.
+use super::reply::SseResponse;
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use futures::StreamExt;
+use goose::agents::{Agent, AgentEvent, SessionConfig};
+use goose::config::{Config, ExtensionConfigManager};
+use goose::message::{push_message, Message};
+use goose::model::ModelConfig;
+use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::{Permission, PermissionConfirmation};
+use goose::providers::{create, providers};
+use goose::recipe::build_recipe::{build_recipe_from_template, RecipeError};
+use goose::recipe::read_recipe_file_content::read_recipe_file;
+use goose::recipe::Recipe;
+use goose::session::{self, Identifier};
+use rmcp::model::Role;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+
+/// The model id that talks to the agent goosed was started with
+const SHARED_AGENT_MODEL: &str = "goose";
+const RECIPE_MODEL_PREFIX: &str = "recipe:";
+const RECIPE_FILE_EXTENSIONS: &[&str] = &["yaml", "json"];
+const GOOSE_RECIPE_PATH_ENV_VAR: &str = "GOOSE_RECIPE_PATH";
+/// Names the session of a completion in responses, and the session to continue in requests
+const SESSION_HEADER: &str = "x-goose-session-id";
+/// The directory tools run in for a completion
+const WORKING_DIR_HEADER: &str = "x-goose-working-dir";
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionRequest {
+    #[serde(default)]
+    model: Option<String>,
+    messages: Vec<ChatCompletionMessage>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    temperature: Option<f32>,
+    /// Completions for the same user continue one session
+    #[serde(default)]
+    user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionMessage {
+    role: String,
+    #[serde(default)]
+    content: Option<ChatCompletionContent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ChatCompletionContent {
+    Text(String),
+    Parts(Vec<ChatCompletionContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ChatCompletionContentPart {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: ImageUrl,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageUrl {
+    url: String,
+}
+
+/// An error in the shape OpenAI clients expect, `{"error": {"message", "type", "code"}}`
+#[derive(Debug)]
+struct OpenAiError {
+    status: StatusCode,
+    message: String,
+    code: Option<&'static str>,
+}
+
+impl OpenAiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            code: None,
+        }
+    }
+
+    fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    fn error_type(&self) -> &'static str {
+        match self.status {
+            StatusCode::UNAUTHORIZED => "authentication_error",
+            s if s.is_client_error() => "invalid_request_error",
+            _ => "server_error",
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "message": self.message,
+                "type": self.error_type(),
+                "param": null,
+                "code": self.code,
+            }
+        })
+    }
+}
+
+impl IntoResponse for OpenAiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.to_json())).into_response()
+    }
+}
+
+/// Accepts the goosed secret either as `X-Secret-Key` or as the bearer token OpenAI SDKs send
+/// for their api key
+fn verify_openai_key(headers: &HeaderMap, state: &AppState) -> Result<(), OpenAiError> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if bearer == Some(state.secret_key.as_str()) || verify_secret_key(headers, state).is_ok() {
+        Ok(())
+    } else {
+        Err(OpenAiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Invalid api key, use the goosed secret key",
+        )
+        .with_code("invalid_api_key"))
+    }
+}
+
+/// What the `model` of a request selects
+#[derive(Debug, PartialEq)]
+enum ModelSelection {
+    /// The agent goosed was started with, with whatever extensions the app set up
+    SharedAgent,
+    /// A new agent set up from a recipe in the recipe library
+    Recipe(String),
+    /// A new agent with the enabled extensions on a provider and model
+    Provider { provider: String, model: String },
+}
+
+impl ModelSelection {
+    fn parse(model: &str) -> Result<Self, OpenAiError> {
+        let model = model.trim();
+        if model.is_empty() || model == SHARED_AGENT_MODEL {
+            return Ok(ModelSelection::SharedAgent);
+        }
+        if let Some(name) = model.strip_prefix(RECIPE_MODEL_PREFIX) {
+            // Recipes are looked up by name only, clients do not get to point at arbitrary files
+            if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+                return Err(OpenAiError::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid recipe name '{}'", name),
+                ));
+            }
+            return Ok(ModelSelection::Recipe(name.to_string()));
+        }
+        // Model names can contain slashes themselves (openrouter/anthropic/claude-...), the
+        // provider is everything before the first one
+        match model.split_once('/') {
+            Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+                Ok(ModelSelection::Provider {
+                    provider: provider.to_string(),
+                    model: model.to_string(),
+                })
+            }
+            _ => Err(OpenAiError::new(
+                StatusCode::NOT_FOUND,
+                format!(
+                    "The model '{}' does not exist, use '{}', '{}<name>' or '<provider>/<model>'",
+                    model, SHARED_AGENT_MODEL, RECIPE_MODEL_PREFIX
+                ),
+            )
+            .with_code("model_not_found")),
+        }
+    }
+}
+
+/// Directories the recipe library is read from, the working directory and `GOOSE_RECIPE_PATH`
+fn recipe_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(".")];
+    if let Ok(recipe_path_env) = std::env::var(GOOSE_RECIPE_PATH_ENV_VAR) {
+        let path_separator = if cfg!(windows) { ';' } else { ':' };
+        dirs.extend(recipe_path_env.split(path_separator).map(PathBuf::from));
+    }
+    dirs
+}
+
+fn recipe_names_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| RECIPE_FILE_EXTENSIONS.contains(&ext))
+        })
+        .filter_map(|path| path.file_stem().and_then(|s| s.to_str()).map(String::from))
+        .collect()
+}
+
+fn load_recipe(name: &str) -> Result<Recipe, OpenAiError> {
+    let recipe_file = recipe_dirs()
+        .iter()
+        .flat_map(|dir| {
+            RECIPE_FILE_EXTENSIONS
+                .iter()
+                .map(move |ext| dir.join(format!("{}.{}", name, ext)))
+        })
+        .find_map(|path| read_recipe_file(path).ok())
+        .ok_or_else(|| {
+            OpenAiError::new(
+                StatusCode::NOT_FOUND,
+                format!("No recipe named '{}' in the recipe library", name),
+            )
+            .with_code("model_not_found")
+        })?;
+
+    build_recipe_from_template(
+        recipe_file,
+        Vec::new(),
+        None::<fn(&str, &str) -> anyhow::Result<String>>,
+    )
+    .map_err(|e| match e {
+        RecipeError::MissingParams { parameters } => OpenAiError::new(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Recipe '{}' needs values for these params and cannot be used as a model: {}",
+                name,
+                parameters.join(", ")
+            ),
+        ),
+        e => OpenAiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load recipe '{}': {}", name, e),
+        ),
+    })
+}
+
+/// A new agent for a request, set up the way `goose run` would set up a session
+async fn create_agent(
+    provider_name: Option<String>,
+    model_name: Option<String>,
+    temperature: Option<f32>,
+    recipe: Option<&Recipe>,
+) -> Result<Agent, OpenAiError> {
+    let config = Config::global();
+    let settings = recipe.and_then(|r| r.settings.as_ref());
+    let provider_name = provider_name
+        .or_else(|| settings.and_then(|s| s.goose_provider.clone()))
+        .or_else(|| config.get_param("GOOSE_PROVIDER").ok())
+        .ok_or_else(|| {
+            OpenAiError::new(StatusCode::INTERNAL_SERVER_ERROR, "No provider configured")
+        })?;
+    let model_name = model_name
+        .or_else(|| settings.and_then(|s| s.goose_model.clone()))
+        .or_else(|| config.get_param("GOOSE_MODEL").ok())
+        .ok_or_else(|| {
+            OpenAiError::new(StatusCode::INTERNAL_SERVER_ERROR, "No model configured")
+        })?;
+
+    let model_config = ModelConfig::new(model_name)
+        .with_temperature(temperature.or_else(|| settings.and_then(|s| s.temperature)))
+        .with_reasoning_effort(settings.and_then(|s| s.reasoning_effort))
+        .with_thinking_budget(settings.and_then(|s| s.thinking_budget));
+    let provider = create(&provider_name, model_config).map_err(|e| {
+        OpenAiError::new(
+            StatusCode::BAD_REQUEST,
+            format!("Failed to create provider {}: {}", provider_name, e),
+        )
+    })?;
+
+    let agent = Agent::new();
+    agent.update_provider(provider).await.map_err(|e| {
+        OpenAiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to initialize agent: {}", e),
+        )
+    })?;
+
+    let extensions = match recipe.and_then(|r| r.extensions.clone()) {
+        Some(extensions) => extensions,
+        None => ExtensionConfigManager::get_all()
+            .map_err(|e| {
+                OpenAiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to load extensions: {}", e),
+                )
+            })?
+            .into_iter()
+            .filter(|ext| ext.enabled)
+            .map(|ext| ext.config)
+            .collect(),
+    };
+    for extension in extensions {
+        if let Err(e) = agent.add_extension(extension.clone()).await {
+            tracing::warn!("Continuing without extension '{}': {}", extension.name(), e);
+        }
+    }
+
+    if let Some(recipe) = recipe {
+        if let Some(prompt_id) = settings.and_then(|s| s.system_prompt_id.clone()) {
+            agent.set_system_prompt_id(prompt_id).await;
+        }
+        if let Some(instructions) = recipe.instructions.as_ref().or(recipe.prompt.as_ref()) {
+            agent.extend_system_prompt(instructions.clone()).await;
+        }
+        if let Some(sub_recipes) = recipe.sub_recipes.clone() {
+            agent.add_sub_recipes(sub_recipes).await;
+        }
+        if let Some(response) = recipe.response.clone() {
+            agent.add_final_output_tool(response).await;
+        }
+    }
+
+    Ok(agent)
+}
+
+/// Turns the OpenAI conversation into goose messages and the system messages that came with it.
+/// Tool calls and tool results from the client are dropped, goose runs its own tools.
+fn to_goose_messages(
+    messages: Vec<ChatCompletionMessage>,
+) -> Result<(Vec<String>, Vec<Message>), OpenAiError> {
+    let mut system = Vec::new();
+    let mut converted: Vec<Message> = Vec::new();
+
+    for message in messages {
+        let mut goose_message = match message.role.as_str() {
+            "system" | "developer" => {
+                if let Some(content) = message.content {
+                    system.push(content_text(content));
+                }
+                continue;
+            }
+            "user" => Message::user(),
+            "assistant" => Message::assistant(),
+            "tool" | "function" => continue,
+            other => {
+                return Err(OpenAiError::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unsupported message role '{}'", other),
+                ))
+            }
+        };
+
+        let parts = match message.content {
+            None => Vec::new(),
+            Some(ChatCompletionContent::Text(text)) => {
+                vec![ChatCompletionContentPart::Text { text }]
+            }
+            Some(ChatCompletionContent::Parts(parts)) => parts,
+        };
+        for part in parts {
+            goose_message = match part {
+                ChatCompletionContentPart::Text { text } => goose_message.with_text(text),
+                ChatCompletionContentPart::ImageUrl { image_url } => {
+                    let (mime_type, data) = parse_data_url(&image_url.url)?;
+                    goose_message.with_image(data, mime_type)
+                }
+                ChatCompletionContentPart::Unsupported => goose_message,
+            };
+        }
+        if goose_message.content.is_empty() {
+            continue;
+        }
+
+        // Providers want alternating roles, so consecutive messages of one role are merged
+        match converted.last_mut() {
+            Some(last) if last.role == goose_message.role => {
+                last.content.extend(goose_message.content)
+            }
+            _ => converted.push(goose_message),
+        }
+    }
+
+    match converted.last() {
+        Some(last) if last.role == Role::User => Ok((system, converted)),
+        _ => Err(OpenAiError::new(
+            StatusCode::BAD_REQUEST,
+            "The conversation must end with a user message",
+        )),
+    }
+}
+
+fn content_text(content: ChatCompletionContent) -> String {
+    match content {
+        ChatCompletionContent::Text(text) => text,
+        ChatCompletionContent::Parts(parts) => parts
+            .into_iter()
+            .filter_map(|part| match part {
+                ChatCompletionContentPart::Text { text } => Some(text),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Images have to be inlined as base64 data urls, goosed does not download remote images
+fn parse_data_url(url: &str) -> Result<(String, String), OpenAiError> {
+    url.strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .map(|(mime_type, data)| (mime_type.to_string(), data.to_string()))
+        .ok_or_else(|| {
+            OpenAiError::new(
+                StatusCode::BAD_REQUEST,
+                "Only base64 data urls are supported for images",
+            )
+        })
+}
+
+/// A session file for a completion, session ids have one second resolution so they get a suffix
+/// when several completions start at once
+fn new_session() -> anyhow::Result<(String, PathBuf)> {
+    let base = session::generate_session_id();
+    let mut id = base.clone();
+    let mut n = 1;
+    loop {
+        let path = session::get_path(Identifier::Name(id.clone()))?;
+        if !path.exists() {
+            // Claim the name before the agent starts so concurrent completions pick another one
+            std::fs::File::create(&path)?;
+            return Ok((id, path));
+        }
+        n += 1;
+        id = format!("{}_{}", base, n);
+    }
+}
+
+/// Whether a client supplied session id is a plain session name
+fn is_valid_session_key(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The session of an OpenAI `user`, with characters that cannot appear in session names replaced
+fn user_session_id(user: &str) -> String {
+    let user: String = user
+        .chars()
+        .take(64)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("openai_{}", user)
+}
+
+/// The session a completion is saved to. A request naming a session in the session header, as
+/// returned by an earlier completion, or giving a `user`, continues that session. Other requests
+/// start a new one.
+fn completion_session(
+    headers: &HeaderMap,
+    user: Option<&str>,
+) -> Result<(String, PathBuf), OpenAiError> {
+    let requested = match headers.get(SESSION_HEADER) {
+        Some(value) => {
+            let id = value
+                .to_str()
+                .ok()
+                .filter(|id| is_valid_session_key(id))
+                .ok_or_else(|| {
+                    OpenAiError::new(
+                        StatusCode::BAD_REQUEST,
+                        format!("Invalid {} header", SESSION_HEADER),
+                    )
+                })?;
+            Some(id.to_string())
+        }
+        None => user.map(user_session_id),
+    };
+
+    let session = match requested {
+        Some(id) => session::get_path(Identifier::Name(id.clone())).map(|path| (id, path)),
+        None => new_session(),
+    };
+    session.map_err(|e| {
+        OpenAiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create session: {}", e),
+        )
+    })
+}
+
+/// The directory a completion runs in. A request can name one in the working dir header, otherwise
+/// a continued session keeps the directory it was saved with and a new one uses the directory
+/// goosed was started in, the same one the desktop app starts it in.
+fn completion_working_dir(
+    headers: &HeaderMap,
+    session_path: &Path,
+) -> Result<PathBuf, OpenAiError> {
+    if let Some(value) = headers.get(WORKING_DIR_HEADER) {
+        return value
+            .to_str()
+            .ok()
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute() && dir.is_dir())
+            .ok_or_else(|| {
+                OpenAiError::new(
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "Invalid {} header, it must be an absolute path to a directory",
+                        WORKING_DIR_HEADER
+                    ),
+                )
+            });
+    }
+    // Session files without metadata, like the one a new session just claimed, read as the
+    // default metadata whose working dir is the current one
+    session::read_metadata(session_path)
+        .map(|metadata| metadata.working_dir)
+        .map_err(|e| {
+            OpenAiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to read session: {}", e),
+            )
+        })
+}
+
+enum CompletionEvent {
+    Delta(String),
+    Error(String),
+}
+
+/// Runs the agent on the conversation and sends the assistant text as it comes in. The channel
+/// closes when the agent is done.
+async fn run_completion(
+    agent: Arc<Agent>,
+    messages: Vec<Message>,
+    session_id: String,
+    session_path: PathBuf,
+    working_dir: PathBuf,
+    retry_config: Option<goose::agents::types::RetryConfig>,
+    tx: mpsc::Sender<CompletionEvent>,
+) {
+    let cancel_token = CancellationToken::new();
+    let session_config = SessionConfig {
+        id: Identifier::Name(session_id.clone()),
+        working_dir: working_dir.clone(),
+        schedule_id: None,
+        execution_mode: None,
+        max_turns: None,
+        retry_config,
+    };
+
+    let mut stream = match agent
+        .reply(&messages, Some(session_config), Some(cancel_token.clone()))
+        .await
+    {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::error!("Failed to start reply stream: {:?}", e);
+            let _ = tx.send(CompletionEvent::Error(e.to_string())).await;
+            return;
+        }
+    };
+
+    let mut all_messages = messages.clone();
+    let saved_message_count = all_messages.len();
+    let mut last_text_id: Option<Option<String>> = None;
+
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(AgentEvent::Message(message)) => {
+                // Nobody is around to approve tools for an OpenAI client
+                if let Some(confirmation) = message.as_tool_confirmation_request() {
+                    tracing::info!(
+                        "Declined {}, tools that need approval cannot run through /v1/chat/completions",
+                        confirmation.tool_name
+                    );
+                    agent
+                        .handle_confirmation(
+                            confirmation.id.clone(),
+                            PermissionConfirmation {
+                                principal_type: PrincipalType::Tool,
+                                permission: Permission::DenyOnce,
+                                arguments: None,
+                            },
+                        )
+                        .await;
+                    continue;
+                }
+
+                let text = message.as_concat_text();
+                if message.role == Role::Assistant && !text.is_empty() {
+                    // Streamed parts of one message share an id, text from a later turn starts
+                    // a new paragraph
+                    let new_message = message.id.is_none()
+                        || last_text_id.as_ref().is_some_and(|id| *id != message.id);
+                    let delta = if new_message && last_text_id.is_some() {
+                        format!("\n\n{}", text)
+                    } else {
+                        text
+                    };
+                    last_text_id = Some(message.id.clone());
+                    if tx.send(CompletionEvent::Delta(delta)).await.is_err() {
+                        cancel_token.cancel();
+                        break;
+                    }
+                }
+                push_message(&mut all_messages, message);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("Error processing message: {}", e);
+                let _ = tx.send(CompletionEvent::Error(e.to_string())).await;
+                break;
+            }
+        }
+    }
+    drop(stream);
+
+    if all_messages.len() > saved_message_count {
+        if let Ok(provider) = agent.provider().await {
+            tokio::spawn(async move {
+                if let Err(e) = session::persist_messages(
+                    &session_path,
+                    &all_messages,
+                    Some(provider),
+                    Some(working_dir),
+                )
+                .await
+                {
+                    tracing::error!("Failed to store session history: {:?}", e);
+                }
+            });
+        }
+    }
+}
+
+fn completion_chunk(
+    id: &str,
+    created: i64,
+    model: &str,
+    delta: Value,
+    finish: Option<&str>,
+) -> String {
+    let chunk = json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "delta": delta,
+            "finish_reason": finish,
+        }],
+    });
+    format!("data: {}\n\n", chunk)
+}
+
+async fn chat_completions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ChatCompletionRequest>,
+) -> Result<Response, OpenAiError> {
+    verify_openai_key(&headers, &state)?;
+
+    let model = request
+        .model
+        .clone()
+        .unwrap_or_else(|| SHARED_AGENT_MODEL.to_string());
+    let selection = ModelSelection::parse(&model)?;
+    let (system, mut messages) = to_goose_messages(request.messages)?;
+
+    let (agent, retry_config) = match selection {
+        ModelSelection::SharedAgent => {
+            let agent = state.get_agent().await.map_err(|_| {
+                OpenAiError::new(StatusCode::FAILED_DEPENDENCY, "Agent not initialized")
+            })?;
+            // The system prompt of the shared agent belongs to the app, so system messages
+            // become part of the conversation instead
+            if !system.is_empty() {
+                let first = &mut messages[0];
+                if first.role == Role::User {
+                    first
+                        .content
+                        .insert(0, goose::message::MessageContent::text(system.join("\n\n")));
+                } else {
+                    messages.insert(0, Message::user().with_text(system.join("\n\n")));
+                }
+            }
+            (agent, None)
+        }
+        ModelSelection::Recipe(name) => {
+            let recipe = load_recipe(&name)?;
+            let agent = create_agent(None, None, request.temperature, Some(&recipe)).await?;
+            for instructions in system {
+                agent.extend_system_prompt(instructions).await;
+            }
+            (Arc::new(agent), recipe.retry.clone())
+        }
+        ModelSelection::Provider { provider, model } => {
+            let agent =
+                create_agent(Some(provider), Some(model), request.temperature, None).await?;
+            for instructions in system {
+                agent.extend_system_prompt(instructions).await;
+            }
+            (Arc::new(agent), None)
+        }
+    };
+
+    let (session_id, session_path) = completion_session(&headers, request.user.as_deref())?;
+    let working_dir = completion_working_dir(&headers, &session_path)?;
+    let completion_id = format!("chatcmpl-{}", session_id);
+    let created = chrono::Utc::now().timestamp();
+
+    let (tx, mut rx) = mpsc::channel(100);
+    tokio::spawn(run_completion(
+        agent,
+        messages,
+        session_id.clone(),
+        session_path,
+        working_dir,
+        retry_config,
+        tx,
+    ));
+    let session_header = [(SESSION_HEADER, session_id)];
+
+    if !request.stream {
+        let mut content = String::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                CompletionEvent::Delta(text) => content.push_str(&text),
+                CompletionEvent::Error(error) => {
+                    return Err(OpenAiError::new(StatusCode::INTERNAL_SERVER_ERROR, error))
+                }
+            }
+        }
+        let response = json!({
+            "id": completion_id,
+            "object": "chat.completion",
+            "created": created,
+            "model": model,
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": content},
+                "finish_reason": "stop",
+            }],
+        });
+        return Ok((session_header, Json(response)).into_response());
+    }
+
+    let (sse_tx, sse_rx) = mpsc::channel(100);
+    tokio::spawn(async move {
+        let first = completion_chunk(
+            &completion_id,
+            created,
+            &model,
+            json!({"role": "assistant", "content": ""}),
+            None,
+        );
+        if sse_tx.send(first).await.is_err() {
+            return;
+        }
+        while let Some(event) = rx.recv().await {
+            let line = match event {
+                CompletionEvent::Delta(text) => completion_chunk(
+                    &completion_id,
+                    created,
+                    &model,
+                    json!({"content": text}),
+                    None,
+                ),
+                CompletionEvent::Error(error) => format!(
+                    "data: {}\n\n",
+                    OpenAiError::new(StatusCode::INTERNAL_SERVER_ERROR, error).to_json()
+                ),
+            };
+            if sse_tx.send(line).await.is_err() {
+                // Dropping the receiver stops the agent
+                return;
+            }
+        }
+        let last = completion_chunk(&completion_id, created, &model, json!({}), Some("stop"));
+        let _ = sse_tx.send(last).await;
+        let _ = sse_tx.send("data: [DONE]\n\n".to_string()).await;
+    });
+
+    Ok((
+        session_header,
+        SseResponse::new(ReceiverStream::new(sse_rx)),
+    )
+        .into_response())
+}
+
+async fn list_models(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, OpenAiError> {
+    verify_openai_key(&headers, &state)?;
+
+    let model = |id: String, owned_by: &str| {
+        json!({
+            "id": id,
+            "object": "model",
+            "created": 0,
+            "owned_by": owned_by,
+        })
+    };
+    let mut models = vec![model(SHARED_AGENT_MODEL.to_string(), "goose")];
+
+    let mut recipe_names: Vec<String> = recipe_dirs()
+        .iter()
+        .flat_map(|dir| recipe_names_in(dir))
+        .collect();
+    recipe_names.sort();
+    recipe_names.dedup();
+    models.extend(
+        recipe_names
+            .into_iter()
+            .map(|name| model(format!("{}{}", RECIPE_MODEL_PREFIX, name), "goose")),
+    );
+
+    // Any provider/model pair works, the list only has the models of the configured provider
+    let config = Config::global();
+    if let Ok(provider) = config.get_param::<String>("GOOSE_PROVIDER") {
+        let mut model_names: Vec<String> = providers()
+            .into_iter()
+            .filter(|metadata| metadata.name == provider)
+            .flat_map(|metadata| metadata.known_models.into_iter().map(|m| m.name))
+            .collect();
+        if let Ok(configured) = config.get_param::<String>("GOOSE_MODEL") {
+            model_names.insert(0, configured);
+        }
+        let mut seen = std::collections::HashSet::new();
+        models.extend(
+            model_names
+                .into_iter()
+                .filter(|name| seen.insert(name.clone()))
+                .map(|name| model(format!("{}/{}", provider, name), &provider)),
+        );
+    }
+
+    Ok(Json(json!({ "object": "list", "data": models })))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .route("/v1/models", get(list_models))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use goose::providers::{
+        base::{Provider, ProviderUsage, Usage},
+        errors::ProviderError,
+    };
+    use tower::ServiceExt;
+
+    #[derive(Clone)]
+    struct MockProvider {
+        model_config: ModelConfig,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> goose::providers::base::ProviderMetadata {
+            goose::providers::base::ProviderMetadata::empty()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[rmcp::model::Tool],
+        ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+            Ok((
+                Message::assistant().with_text("Mock response"),
+                ProviderUsage::new("mock".to_string(), Usage::default()),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+    }
+
+    async fn test_app() -> Router {
+        let agent = Agent::new();
+        let _ = agent
+            .update_provider(Arc::new(MockProvider {
+                model_config: ModelConfig::new("test-model".to_string()),
+            }))
+            .await;
+        routes(AppState::new(Arc::new(agent), "test-secret".to_string()).await)
+    }
+
+    fn parse_messages(value: Value) -> Vec<ChatCompletionMessage> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_model_selection() {
+        assert_eq!(
+            ModelSelection::parse("goose").unwrap(),
+            ModelSelection::SharedAgent
+        );
+        assert_eq!(
+            ModelSelection::parse("recipe:release-notes").unwrap(),
+            ModelSelection::Recipe("release-notes".to_string())
+        );
+        assert_eq!(
+            ModelSelection::parse("openrouter/anthropic/claude-3.5-sonnet").unwrap(),
+            ModelSelection::Provider {
+                provider: "openrouter".to_string(),
+                model: "anthropic/claude-3.5-sonnet".to_string(),
+            }
+        );
+        assert!(ModelSelection::parse("recipe:../secrets").is_err());
+        assert_eq!(
+            ModelSelection::parse("gpt-4o").unwrap_err().status,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn test_to_goose_messages() {
+        let (system, messages) = to_goose_messages(parse_messages(json!([
+            {"role": "system", "content": "Be brief"},
+            {"role": "user", "content": "Hello"},
+            {"role": "assistant", "content": null, "tool_calls": [{"id": "1"}]},
+            {"role": "tool", "tool_call_id": "1", "content": "done"},
+            {"role": "user", "content": [
+                {"type": "text", "text": "What is in this picture?"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,aGk="}}
+            ]}
+        ])))
+        .unwrap();
+
+        assert_eq!(system, vec!["Be brief".to_string()]);
+        // The tool call round trip is dropped, leaving two user messages that get merged
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content.len(), 3);
+        assert_eq!(
+            messages[0].as_concat_text(),
+            "Hello\nWhat is in this picture?"
+        );
+    }
+
+    #[test]
+    fn test_to_goose_messages_rejects_bad_input() {
+        let ends_with_assistant = parse_messages(json!([
+            {"role": "user", "content": "Hello"},
+            {"role": "assistant", "content": "Hi"}
+        ]));
+        assert!(to_goose_messages(ends_with_assistant).is_err());
+
+        let remote_image = parse_messages(json!([
+            {"role": "user", "content": [
+                {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+            ]}
+        ]));
+        assert!(to_goose_messages(remote_image).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_requires_secret_key() {
+        let app = test_app().await;
+        let request = Request::builder()
+            .uri("/v1/models")
+            .header(header::AUTHORIZATION, "Bearer wrong")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    fn completion_request(body: Value, session_id: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder()
+            .uri("/v1/chat/completions")
+            .method("POST")
+            .header("content-type", "application/json")
+            .header(header::AUTHORIZATION, "Bearer test-secret");
+        if let Some(session_id) = session_id {
+            request = request.header(SESSION_HEADER, session_id);
+        }
+        request.body(Body::from(body.to_string())).unwrap()
+    }
+
+    /// Waits for the history of a completion to be written, which happens after the response
+    async fn wait_for_session(session_id: &str, message_count: usize) -> PathBuf {
+        let path = session::get_path(Identifier::Name(session_id.to_string())).unwrap();
+        for _ in 0..50 {
+            if session::read_messages(&path).is_ok_and(|m| m.len() >= message_count) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        path
+    }
+
+    #[test]
+    fn test_session_keys() {
+        assert!(is_valid_session_key("20250701_120000"));
+        assert!(!is_valid_session_key(""));
+        assert!(!is_valid_session_key("../sessions"));
+        assert!(!is_valid_session_key("a b"));
+        assert_eq!(user_session_id("me@example.com"), "openai_me_example_com");
+        assert_eq!(user_session_id("../x"), "openai____x");
+    }
+
+    #[tokio::test]
+    async fn test_chat_completion() {
+        // Sessions are written to the data directory, keep them out of the real one
+        let data_dir = tempfile::tempdir().unwrap();
+        std::env::set_var("XDG_DATA_HOME", data_dir.path());
+
+        let working_dir = tempfile::tempdir().unwrap();
+
+        let app = test_app().await;
+        let body = json!({
+            "model": "goose",
+            "messages": [{"role": "user", "content": "test message"}]
+        });
+
+        let mut request = completion_request(body.clone(), None);
+        request.headers_mut().insert(
+            WORKING_DIR_HEADER,
+            working_dir.path().to_str().unwrap().parse().unwrap(),
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let session_id = response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let completion: Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(completion["object"], "chat.completion");
+        assert_eq!(
+            completion["choices"][0]["message"]["content"],
+            "Mock response"
+        );
+        let session_path = wait_for_session(&session_id, 2).await;
+        if cfg!(target_os = "linux") {
+            assert!(session_path.starts_with(data_dir.path()));
+        }
+
+        // Sending the session id back continues the same session
+        let follow_up = json!({
+            "model": "goose",
+            "messages": [
+                {"role": "user", "content": "test message"},
+                {"role": "assistant", "content": "Mock response"},
+                {"role": "user", "content": "another message"}
+            ]
+        });
+        let response = app
+            .clone()
+            .oneshot(completion_request(follow_up, Some(&session_id)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[SESSION_HEADER], session_id.as_str());
+        let session_path = wait_for_session(&session_id, 4).await;
+        assert_eq!(session::read_messages(&session_path).unwrap().len(), 4);
+        // and keeps running in the directory it started in
+        assert_eq!(
+            session::read_metadata(&session_path).unwrap().working_dir,
+            working_dir.path()
+        );
+
+        let mut request = completion_request(body.clone(), None);
+        request
+            .headers_mut()
+            .insert(WORKING_DIR_HEADER, "relative/dir".parse().unwrap());
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(completion_request(body, Some("../escape")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        std::env::remove_var("XDG_DATA_HOME");
+    }
+}
@@ -50,7 +50,7 @@ pub struct SseResponse {
 }
 
 impl SseResponse {
-    fn new(rx: ReceiverStream<String>) -> Self {
+    pub fn new(rx: ReceiverStream<String>) -> Self {
         Self { rx }
     }
 }
@@ -159,6 +159,18 @@ async fn reply_handler(
             retry_config: None,
         };
 
+        if let Err(e) = state.start_session(&session_id, &session_config).await {
+            tracing::error!("Failed to start session {}: {}", session_id, e);
+            let _ = stream_event(
+                MessageEvent::Error {
+                    error: e.to_string(),
+                },
+                &task_tx,
+            )
+            .await;
+            return;
+        }
+
         let mut stream = match agent
             .reply(&messages, Some(session_config), Some(task_cancel.clone()))
             .await
@@ -240,6 +252,8 @@ async fn reply_handler(
                                             ).await;
                                         }
                                     }
+                                    // The app follows usage and denials through the messages
+                                    Ok(Some(Ok(_))) => {}
 
                                     Ok(Some(Err(e))) => {
                                         tracing::error!("Error processing message: {}", e);
@@ -300,6 +314,12 @@ pub struct PermissionConfirmationRequest {
     #[serde(default = "default_principal_type")]
     principal_type: PrincipalType,
     action: String,
+    /// Arguments to run the tool with instead of the requested ones, such as a `write` of a
+    /// proposed file change that was only accepted in part. Only accepted for text editor
+    /// changes that came with a preview, on the same file.
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    arguments: Option<Value>,
 }
 
 fn default_principal_type() -> PrincipalType {
@@ -312,6 +332,7 @@ fn default_principal_type() -> PrincipalType {
     request_body = PermissionConfirmationRequest,
     responses(
         (status = 200, description = "Permission action is confirmed", body = Value),
+        (status = 400, description = "Arguments given with a denial, or not a JSON object"),
         (status = 401, description = "Unauthorized - invalid secret key"),
         (status = 500, description = "Internal server error")
     )
@@ -335,12 +356,20 @@ pub async fn confirm_permission(
         _ => Permission::DenyOnce,
     };
 
+    // The agent checks that the arguments only change a previewed file edit
+    if let Some(arguments) = &request.arguments {
+        if permission == Permission::DenyOnce || !arguments.is_object() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
     agent
         .handle_confirmation(
             request.id.clone(),
             PermissionConfirmation {
                 principal_type: request.principal_type,
                 permission,
+                arguments: request.arguments,
             },
         )
         .await;
@@ -5,16 +5,18 @@ use std::sync::Arc;
 
 use crate::state::AppState;
 use axum::{
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
 use goose::message::Message;
 use goose::session;
+use goose::session::export::{self, SessionExport};
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
 use goose::session::SessionMetadata;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 use utoipa::ToSchema;
 
@@ -132,6 +134,97 @@ async fn get_session_history(
     }))
 }
 
+#[derive(Deserialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionExportFormat {
+    /// A single HTML page with collapsible tool calls and embedded images
+    Html,
+    /// JSON with a stable, versioned schema
+    Json,
+    /// A recipe that reruns what the session did, written by the agent's model
+    Recipe,
+}
+
+// Query parameters for the session export endpoint
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct SessionExportQuery {
+    /// Format of the export
+    format: SessionExportFormat,
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/export",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        SessionExportQuery
+    ),
+    responses(
+        (status = 200, description = "Session exported successfully", content(
+            (SessionExport = "application/json"),
+            (goose::recipe::Recipe = "application/json"),
+            (String = "text/html")
+        )),
+        (status = 400, description = "Invalid session identifier, or an empty session for a recipe"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 412, description = "Recipe export needs an agent"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Export a session as an HTML transcript, JSON or a recipe
+async fn export_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Query(query): Query<SessionExportQuery>,
+) -> Result<Response, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let metadata = session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    let messages = session::read_messages(&session_path).map_err(|e| {
+        error!("Failed to read session messages: {:?}", e);
+        StatusCode::NOT_FOUND
+    })?;
+
+    match query.format {
+        SessionExportFormat::Html => {
+            let export = SessionExport::new(&session_id, &metadata, &messages);
+            Ok((
+                [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                export::to_html(&export),
+            )
+                .into_response())
+        }
+        SessionExportFormat::Json => {
+            Ok(Json(SessionExport::new(&session_id, &metadata, &messages)).into_response())
+        }
+        SessionExportFormat::Recipe => {
+            if messages.is_empty() {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            let agent = state
+                .get_agent()
+                .await
+                .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+            let mut recipe = agent.create_recipe(messages).await.map_err(|e| {
+                error!("Failed to create recipe from session {}: {}", session_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            if !metadata.description.is_empty() {
+                recipe.title = metadata.description;
+            }
+            Ok(Json(recipe).into_response())
+        }
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/sessions/insights",
@@ -305,6 +398,7 @@ pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/sessions", get(list_sessions))
         .route("/sessions/{session_id}", get(get_session_history))
+        .route("/sessions/{session_id}/export", get(export_session))
         .route("/sessions/insights", get(get_session_insights))
         .route("/sessions/activity-heatmap", get(get_activity_heatmap))
         .with_state(state)
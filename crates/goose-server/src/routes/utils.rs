@@ -1,6 +1,7 @@
 use crate::state::AppState;
 use goose::config::Config;
 use goose::providers::base::{ConfigKey, ProviderMetadata};
+use goose::providers::custom::find_custom_provider;
 use http::{HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::env;
@@ -111,6 +112,11 @@ pub fn check_provider_configured(metadata: &ProviderMetadata) -> bool {
 
     // Special case: Zero-config providers (no config keys)
     if metadata.config_keys.is_empty() {
+        // Custom providers are configured by their declaration in the config file
+        if find_custom_provider(&metadata.name).is_some() {
+            return true;
+        }
+
         // Check if the provider has been explicitly configured via the UI
         let configured_marker = format!("{}_configured", metadata.name);
         return config.get_param::<bool>(&configured_marker).is_ok();
@@ -1,5 +1,6 @@
-use goose::agents::Agent;
+use goose::agents::{Agent, SessionConfig};
 use goose::scheduler_trait::SchedulerTrait;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -10,6 +11,8 @@ pub struct AppState {
     agent: Option<AgentRef>,
     pub secret_key: String,
     pub scheduler: Arc<Mutex<Option<Arc<dyn SchedulerTrait>>>>,
+    /// Sessions whose session_start hooks ran, by session id
+    started_sessions: Arc<Mutex<HashMap<String, SessionConfig>>>,
 }
 
 impl AppState {
@@ -18,6 +21,7 @@ impl AppState {
             agent: Some(agent.clone()),
             secret_key,
             scheduler: Arc::new(Mutex::new(None)),
+            started_sessions: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -39,4 +43,40 @@ impl AppState {
             .clone()
             .ok_or_else(|| anyhow::anyhow!("Scheduler not initialized"))
     }
+
+    /// Run the session_start hooks the first time the server replies in a session. A hook that
+    /// blocks keeps the session from starting, and the hooks run again on its next reply.
+    pub async fn start_session(
+        &self,
+        session_id: &str,
+        session: &SessionConfig,
+    ) -> Result<(), anyhow::Error> {
+        let mut started = self.started_sessions.lock().await;
+        if started.contains_key(session_id) {
+            return Ok(());
+        }
+        self.get_agent()
+            .await?
+            .run_session_start_hooks(Some(session))
+            .await?;
+        started.insert(session_id.to_string(), session.clone());
+        Ok(())
+    }
+
+    /// Run the session_end hooks of every session started since the server came up
+    pub async fn end_sessions(&self) {
+        let sessions: Vec<SessionConfig> = self
+            .started_sessions
+            .lock()
+            .await
+            .drain()
+            .map(|(_, session)| session)
+            .collect();
+        let Ok(agent) = self.get_agent().await else {
+            return;
+        };
+        for session in sessions {
+            agent.run_session_end_hooks(Some(&session)).await;
+        }
+    }
 }
@@ -10,7 +10,10 @@ use uuid::Uuid;
 
 use crate::agents::extension::{ExtensionConfig, ExtensionError, ExtensionResult, ToolInfo};
 use crate::agents::extension_manager::{get_parameter_names, ExtensionManager};
-use crate::agents::final_output_tool::{FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_OUTPUT_TOOL_NAME};
+use crate::agents::final_output_tool::{
+    FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_OUTPUT_TOOL_NAME, STRUCTURED_OUTPUT_REQUEST_MESSAGE,
+};
+use crate::agents::hybrid_tool_selector::ToolSelectionExplanation;
 use crate::agents::platform_tools::{
     PLATFORM_LIST_RESOURCES_TOOL_NAME, PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME,
     PLATFORM_MANAGE_SCHEDULE_TOOL_NAME, PLATFORM_READ_RESOURCE_TOOL_NAME,
@@ -24,21 +27,28 @@ use crate::agents::retry::{RetryManager, RetryResult};
 use crate::agents::router_tool_selector::{
     create_tool_selector, RouterToolSelectionStrategy, RouterToolSelector,
 };
-use crate::agents::router_tools::{ROUTER_LLM_SEARCH_TOOL_NAME, ROUTER_VECTOR_SEARCH_TOOL_NAME};
+use crate::agents::router_tools::{
+    ROUTER_HYBRID_SEARCH_TOOL_NAME, ROUTER_LLM_SEARCH_TOOL_NAME, ROUTER_VECTOR_SEARCH_TOOL_NAME,
+};
 use crate::agents::sub_recipe_manager::SubRecipeManager;
 use crate::agents::subagent_execution_tool::subagent_execute_task_tool::{
     self, SUBAGENT_EXECUTE_TASK_TOOL_NAME,
 };
 use crate::agents::subagent_execution_tool::tasks_manager::TasksManager;
+use crate::agents::tool_result_cache::{ToolCacheStats, ToolResultCache};
 use crate::agents::tool_router_index_manager::ToolRouterIndexManager;
 use crate::agents::tool_vectordb::generate_table_id;
 use crate::agents::types::SessionConfig;
 use crate::agents::types::{FrontendTool, ToolResultReceiver};
+use crate::audit::{
+    AuditLog, AuditRecord, DecisionSource, PendingAudit, PermissionDecision, ToolStatus,
+};
 use crate::config::{Config, ExtensionConfigManager, PermissionManager};
-use crate::message::{push_message, Message};
+use crate::hooks::{HookEvent, HookInput, HookOutcome, Hooks};
+use crate::message::{push_message, Message, ToolRequest};
 use crate::permission::permission_judge::check_tool_permissions;
 use crate::permission::PermissionConfirmation;
-use crate::providers::base::Provider;
+use crate::providers::base::{Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
 use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
 use crate::scheduler_trait::SchedulerTrait;
@@ -50,7 +60,7 @@ use rmcp::model::{Content, GetPromptResult, Prompt, ServerNotification, Tool};
 use serde_json::Value;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, warn};
 
 use super::final_output_tool::FinalOutputTool;
 use super::platform_tools;
@@ -61,6 +71,10 @@ use crate::conversation_fixer::{debug_conversation_fix, ConversationFixer};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
 
+/// What the agent says when it stops because it ran out of turns
+const MAX_TURNS_REACHED_MESSAGE: &str =
+    "I've reached the maximum number of actions I can do without user input. Would you like me to continue?";
+
 /// The main goose Agent
 pub struct Agent {
     pub(super) provider: Mutex<Option<Arc<dyn Provider>>>,
@@ -76,6 +90,11 @@ pub struct Agent {
     pub(super) tool_result_tx: mpsc::Sender<(String, ToolResult<Vec<Content>>)>,
     pub(super) tool_result_rx: ToolResultReceiver,
     pub(super) tool_monitor: Arc<Mutex<Option<ToolMonitor>>>,
+    pub(super) tool_result_cache: Arc<Mutex<Option<ToolResultCache>>>,
+    pub(super) audit_log: Mutex<Option<Arc<AuditLog>>>,
+    pub(super) hooks: Mutex<Arc<Hooks>>,
+    /// The session the agent is replying in, for hooks
+    pub(super) hook_session_id: Mutex<Option<String>>,
     pub(super) router_tool_selector: Mutex<Option<Arc<Box<dyn RouterToolSelector>>>>,
     pub(super) scheduler_service: Mutex<Option<Arc<dyn SchedulerTrait>>>,
     pub(super) retry_manager: RetryManager,
@@ -85,7 +104,21 @@ pub struct Agent {
 pub enum AgentEvent {
     Message(Message),
     McpNotification((String, ServerNotification)),
-    ModelChange { model: String, mode: String },
+    ModelChange {
+        model: String,
+        mode: String,
+    },
+    /// What a provider call used, after it was added to the session's totals
+    Usage(ProviderUsage),
+    /// The reply stopped because it took the most turns it was allowed
+    MaxTurnsReached {
+        max_turns: u32,
+    },
+    /// A tool call was not run because permission for it was denied
+    ToolDenied {
+        id: String,
+        tool_name: String,
+    },
 }
 
 impl Default for Agent {
@@ -128,6 +161,17 @@ where
     })
 }
 
+/// Identify a session in audit entries and hook input by its name, or the file stem of its path
+fn audit_session_id(session: &SessionConfig) -> String {
+    match &session.id {
+        crate::session::Identifier::Name(name) => name.clone(),
+        crate::session::Identifier::Path(path) => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string()),
+    }
+}
+
 impl Agent {
     pub fn new() -> Self {
         // Create channels with buffer size 32 (adjust if needed)
@@ -136,6 +180,15 @@ impl Agent {
 
         let tool_monitor = Arc::new(Mutex::new(None));
         let retry_manager = RetryManager::with_tool_monitor(tool_monitor.clone());
+        // Caching tool results is opt-in
+        let tool_result_cache = Config::global()
+            .get_param::<bool>("GOOSE_TOOL_CACHE")
+            .unwrap_or(false)
+            .then(ToolResultCache::new);
+        let audit_log = Config::global()
+            .get_param::<bool>("GOOSE_AUDIT_LOG")
+            .unwrap_or(false)
+            .then(|| Arc::new(AuditLog::default()));
 
         Self {
             provider: Mutex::new(None),
@@ -151,6 +204,10 @@ impl Agent {
             tool_result_tx: tool_tx,
             tool_result_rx: Arc::new(Mutex::new(tool_rx)),
             tool_monitor,
+            tool_result_cache: Arc::new(Mutex::new(tool_result_cache)),
+            audit_log: Mutex::new(audit_log),
+            hooks: Mutex::new(Arc::new(Hooks::from_config())),
+            hook_session_id: Mutex::new(None),
             router_tool_selector: Mutex::new(None),
             scheduler_service: Mutex::new(None),
             retry_manager,
@@ -173,6 +230,100 @@ impl Agent {
         }
     }
 
+    pub async fn configure_tool_result_cache(&self, enabled: bool) {
+        let mut cache = self.tool_result_cache.lock().await;
+        *cache = enabled.then(ToolResultCache::new);
+    }
+
+    /// Get the tool result cache hit and miss counts, if caching is enabled
+    pub async fn get_tool_cache_stats(&self) -> Option<ToolCacheStats> {
+        let cache = self.tool_result_cache.lock().await;
+        cache.as_ref().map(|cache| cache.stats())
+    }
+
+    async fn take_unreported_tool_cache_stats(&self) -> Option<ToolCacheStats> {
+        let mut cache = self.tool_result_cache.lock().await;
+        cache.as_mut().map(|cache| cache.take_unreported_stats())
+    }
+
+    /// Set the audit log that tool requests are recorded to, or disable auditing with `None`
+    pub async fn configure_audit_log(&self, audit_log: Option<AuditLog>) {
+        *self.audit_log.lock().await = audit_log.map(Arc::new);
+    }
+
+    /// Replace the lifecycle hooks read from the config
+    pub async fn configure_hooks(&self, hooks: Hooks) {
+        *self.hooks.lock().await = Arc::new(hooks);
+    }
+
+    /// Run the hooks for an event
+    pub(super) async fn run_hooks(&self, input: HookInput) -> HookOutcome {
+        let hooks = self.hooks.lock().await.clone();
+        hooks.run(input, &self.extension_manager).await
+    }
+
+    fn session_hook_input(event: HookEvent, session: Option<&SessionConfig>) -> HookInput {
+        let input = HookInput::new(event, session.map(audit_session_id));
+        match session {
+            Some(session) => input.with_cwd(session.working_dir.clone()),
+            None => input,
+        }
+    }
+
+    /// Run the session_start hooks. Context they return is added to the system prompt, and a
+    /// hook that blocks keeps the session from starting.
+    pub async fn run_session_start_hooks(&self, session: Option<&SessionConfig>) -> Result<()> {
+        let outcome = self
+            .run_hooks(Self::session_hook_input(HookEvent::SessionStart, session))
+            .await;
+        if let Some(reason) = outcome.blocked {
+            return Err(anyhow!(
+                "A session_start hook blocked the session: {}",
+                reason
+            ));
+        }
+        if let Some(context) = outcome.context() {
+            self.extend_system_prompt(context).await;
+        }
+        Ok(())
+    }
+
+    /// Run the session_end hooks, which can only observe the session ending
+    pub async fn run_session_end_hooks(&self, session: Option<&SessionConfig>) {
+        self.run_hooks(Self::session_hook_input(HookEvent::SessionEnd, session))
+            .await;
+    }
+
+    /// Write the audit entry for a tool request that was denied or skipped
+    pub(super) async fn audit_not_run(
+        &self,
+        request: &ToolRequest,
+        session_id: Option<String>,
+        decision: PermissionDecision,
+        decided_by: DecisionSource,
+    ) {
+        let Some(audit_log) = self.audit_log.lock().await.clone() else {
+            return;
+        };
+        let Ok(tool_call) = &request.tool_call else {
+            return;
+        };
+        let record = AuditRecord {
+            session_id,
+            request_id: request.id.clone(),
+            tool: tool_call.name.clone(),
+            arguments: tool_call.arguments.clone(),
+            decision,
+            decided_by,
+            status: ToolStatus::NotRun,
+            duration_ms: None,
+            error: None,
+        };
+        if let Err(e) = audit_log.append_async(record).await {
+            error!("Failed to write audit log entry: {}", e);
+        }
+    }
+
     /// Reset the retry attempts counter to 0
     pub async fn reset_retry_attempts(&self) {
         self.retry_manager.reset_attempts().await;
@@ -264,18 +415,258 @@ impl Agent {
         self.extend_system_prompt(final_output_system_prompt).await;
     }
 
+    /// Ask the provider for the final output natively constrained to the recipe's schema.
+    ///
+    /// Leaves the final output unset when none is pending, the provider has no native
+    /// structured output, or its response does not validate; the final output tool then
+    /// remains the way to collect it.
+    async fn collect_structured_output(
+        &self,
+        system_prompt: &str,
+        messages: &[Message],
+        session: &Option<SessionConfig>,
+    ) {
+        // The lock is released while the provider is called, so the final output tool
+        // stays usable until the response arrives
+        let schema = {
+            let final_output_tool = self.final_output_tool.lock().await;
+            match final_output_tool.as_ref() {
+                Some(tool) if tool.final_output.is_none() => tool.schema().clone(),
+                _ => return,
+            }
+        };
+        let Ok(provider) = self.provider().await else {
+            return;
+        };
+        if !provider.supports_structured_output() {
+            return;
+        }
+
+        let mut conversation = messages.to_vec();
+        conversation.push(Message::user().with_text(STRUCTURED_OUTPUT_REQUEST_MESSAGE));
+
+        match provider
+            .complete_structured(system_prompt, &conversation, &schema)
+            .await
+        {
+            Ok((message, usage)) => {
+                if let Some(session_config) = session {
+                    if let Err(e) = Self::update_session_metrics(
+                        session_config,
+                        &usage,
+                        conversation.len(),
+                        None,
+                    )
+                    .await
+                    {
+                        warn!("Failed to record structured output usage: {}", e);
+                    }
+                }
+                let mut final_output_tool = self.final_output_tool.lock().await;
+                let Some(final_output_tool) = final_output_tool.as_mut() else {
+                    return;
+                };
+                if final_output_tool.final_output.is_some() {
+                    return;
+                }
+                if let Err(e) = final_output_tool
+                    .collect_structured_output(&message.as_concat_text())
+                    .await
+                {
+                    warn!(
+                        "Structured output was rejected, falling back to the final output tool: {}",
+                        e
+                    );
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Structured output request failed, falling back to the final output tool: {}",
+                    e
+                );
+            }
+        }
+    }
+
     pub async fn add_sub_recipes(&self, sub_recipes: Vec<SubRecipe>) {
         let mut sub_recipe_manager = self.sub_recipe_manager.lock().await;
         sub_recipe_manager.add_sub_recipe_tools(sub_recipes);
     }
 
-    /// Dispatch a single tool call to the appropriate client
-    #[instrument(skip(self, tool_call, request_id), fields(input, output))]
+    /// Dispatch a single tool call to the appropriate client, recording it to the audit log
+    /// if one is configured
     pub async fn dispatch_tool_call(
         &self,
         tool_call: mcp_core::tool::ToolCall,
         request_id: String,
         cancellation_token: Option<CancellationToken>,
+    ) -> (String, Result<ToolCallResult, ToolError>) {
+        self.dispatch_approved_tool_call(
+            tool_call,
+            request_id,
+            cancellation_token,
+            None,
+            DecisionSource::Caller,
+        )
+        .await
+    }
+
+    /// Dispatch a tool call that passed the permission check between its before_tool_call and
+    /// after_tool_call hooks, which can block it, change its arguments or add to its result. The
+    /// audit entry records the arguments the hooks left, and a block as a hook's decision.
+    pub(super) async fn dispatch_approved_tool_call(
+        &self,
+        mut tool_call: mcp_core::tool::ToolCall,
+        request_id: String,
+        cancellation_token: Option<CancellationToken>,
+        session_id: Option<String>,
+        decided_by: DecisionSource,
+    ) -> (String, Result<ToolCallResult, ToolError>) {
+        let hooks = self.hooks.lock().await.clone();
+        let hook_session_id = self.hook_session_id.lock().await.clone();
+        let audit_log = self.audit_log.lock().await.clone();
+
+        if hooks.has(HookEvent::BeforeToolCall, Some(&tool_call.name)) {
+            let input = HookInput::new(HookEvent::BeforeToolCall, hook_session_id.clone())
+                .with_tool(&tool_call.name, &tool_call.arguments);
+            let outcome = hooks.run(input, &self.extension_manager).await;
+            if let Some(reason) = outcome.blocked {
+                let error = format!("A before_tool_call hook blocked this call: {}", reason);
+                if let Some(audit_log) = audit_log {
+                    let record = AuditRecord {
+                        session_id,
+                        request_id: request_id.clone(),
+                        tool: tool_call.name.clone(),
+                        arguments: tool_call.arguments.clone(),
+                        decision: PermissionDecision::Denied,
+                        decided_by: DecisionSource::Hook,
+                        status: ToolStatus::NotRun,
+                        duration_ms: None,
+                        error: Some(error.clone()),
+                    };
+                    if let Err(e) = audit_log.append_async(record).await {
+                        error!("Failed to write audit log entry: {}", e);
+                    }
+                }
+                return (request_id, Err(ToolError::ExecutionError(error)));
+            }
+            if let Some(arguments) = outcome.arguments {
+                tool_call.arguments = arguments;
+            }
+        }
+
+        let Some(audit_log) = audit_log else {
+            return self
+                .dispatch_tool_call_with_after_hooks(
+                    tool_call,
+                    request_id,
+                    cancellation_token,
+                    hooks,
+                    hook_session_id,
+                )
+                .await;
+        };
+
+        let record = AuditRecord {
+            session_id,
+            request_id: request_id.clone(),
+            tool: tool_call.name.clone(),
+            arguments: tool_call.arguments.clone(),
+            decision: PermissionDecision::Allowed,
+            decided_by,
+            status: ToolStatus::Success,
+            duration_ms: None,
+            error: None,
+        };
+        let pending = PendingAudit::new(audit_log, record);
+
+        let (request_id, result) = self
+            .dispatch_tool_call_with_after_hooks(
+                tool_call,
+                request_id,
+                cancellation_token,
+                hooks,
+                hook_session_id,
+            )
+            .await;
+
+        match result {
+            Ok(call_result) => {
+                let inner = call_result.result;
+                // Dropping the result before it finishes still writes the entry
+                let audited = async move {
+                    let output = inner.await;
+                    pending
+                        .finish(output.as_ref().map(|_| ()).map_err(|e| e.to_string()))
+                        .await;
+                    output
+                }
+                .boxed();
+                (
+                    request_id,
+                    Ok(ToolCallResult {
+                        result: Box::new(audited),
+                        notification_stream: call_result.notification_stream,
+                    }),
+                )
+            }
+            Err(e) => {
+                pending.finish(Err(e.to_string())).await;
+                (request_id, Err(e))
+            }
+        }
+    }
+
+    /// Dispatch a tool call and run its after_tool_call hooks on the result
+    async fn dispatch_tool_call_with_after_hooks(
+        &self,
+        tool_call: mcp_core::tool::ToolCall,
+        request_id: String,
+        cancellation_token: Option<CancellationToken>,
+        hooks: Arc<Hooks>,
+        hook_session_id: Option<String>,
+    ) -> (String, Result<ToolCallResult, ToolError>) {
+        if !hooks.has(HookEvent::AfterToolCall, Some(&tool_call.name)) {
+            return self
+                .dispatch_tool_call_inner(tool_call, request_id, cancellation_token)
+                .await;
+        }
+
+        let input = HookInput::new(HookEvent::AfterToolCall, hook_session_id)
+            .with_tool(&tool_call.name, &tool_call.arguments);
+        let (request_id, result) = self
+            .dispatch_tool_call_inner(tool_call, request_id, cancellation_token)
+            .await;
+        match result {
+            Ok(call_result) => {
+                let inner = call_result.result;
+                let extension_manager = self.extension_manager.clone();
+                let hooked = async move {
+                    let output = inner.await;
+                    let outcome = hooks
+                        .run(input.with_output(&output), &extension_manager)
+                        .await;
+                    outcome.apply_to_output(output)
+                }
+                .boxed();
+                (
+                    request_id,
+                    Ok(ToolCallResult {
+                        result: Box::new(hooked),
+                        notification_stream: call_result.notification_stream,
+                    }),
+                )
+            }
+            Err(e) => (request_id, Err(e)),
+        }
+    }
+
+    #[instrument(skip(self, tool_call, request_id), fields(input, output))]
+    async fn dispatch_tool_call_inner(
+        &self,
+        tool_call: mcp_core::tool::ToolCall,
+        request_id: String,
+        cancellation_token: Option<CancellationToken>,
     ) -> (String, Result<ToolCallResult, ToolError>) {
         // Check if this tool call should be allowed based on repetition monitoring
         if let Some(monitor) = self.tool_monitor.lock().await.as_mut() {
@@ -291,6 +682,24 @@ impl Agent {
             }
         }
 
+        // Serve read-only, idempotent tools from the result cache, and drop cached results
+        // whenever a call may have changed what they observed
+        let mut cache_entry = None;
+        if let Some(cache) = self.tool_result_cache.lock().await.as_mut() {
+            if cache.is_cacheable(&tool_call.name) {
+                if let Some(result) = cache.get(&tool_call.name, &tool_call.arguments) {
+                    return (request_id, Ok(ToolCallResult::from(Ok(result))));
+                }
+                cache_entry = Some((
+                    tool_call.name.clone(),
+                    tool_call.arguments.clone(),
+                    cache.generation(),
+                ));
+            } else if cache.invalidates(&tool_call.name, &tool_call.arguments) {
+                cache.invalidate();
+            }
+        }
+
         if tool_call.name == PLATFORM_MANAGE_SCHEDULE_TOOL_NAME {
             let result = self
                 .handle_schedule_management(tool_call.arguments, request_id.clone())
@@ -377,6 +786,7 @@ impl Agent {
             )))
         } else if tool_call.name == ROUTER_VECTOR_SEARCH_TOOL_NAME
             || tool_call.name == ROUTER_LLM_SEARCH_TOOL_NAME
+            || tool_call.name == ROUTER_HYBRID_SEARCH_TOOL_NAME
         {
             let selector = self.router_tool_selector.lock().await.clone();
             let mut selected_tools = match selector.as_ref() {
@@ -425,15 +835,34 @@ impl Agent {
             })
         };
 
+        let processed = result
+            .result
+            .map(super::large_response_handler::process_tool_response);
+        let result_future: Box<dyn Future<Output = ToolResult<Vec<Content>>> + Send + Unpin> =
+            match cache_entry {
+                Some((name, arguments, generation)) => {
+                    let cache = Arc::clone(&self.tool_result_cache);
+                    Box::new(
+                        async move {
+                            let output = processed.await;
+                            if let Ok(content) = &output {
+                                if let Some(cache) = cache.lock().await.as_mut() {
+                                    cache.insert(&name, &arguments, content.clone(), generation);
+                                }
+                            }
+                            output
+                        }
+                        .boxed(),
+                    )
+                }
+                None => Box::new(processed),
+            };
+
         (
             request_id,
             Ok(ToolCallResult {
                 notification_stream: result.notification_stream,
-                result: Box::new(
-                    result
-                        .result
-                        .map(super::large_response_handler::process_tool_response),
-                ),
+                result: result_future,
             }),
         )
     }
@@ -660,6 +1089,9 @@ impl Agent {
             Some(RouterToolSelectionStrategy::Llm) => {
                 prefixed_tools.push(router_tools::llm_search_tool());
             }
+            Some(RouterToolSelectionStrategy::Hybrid) => {
+                prefixed_tools.push(router_tools::hybrid_search_tool());
+            }
             None => {}
         }
 
@@ -745,7 +1177,13 @@ impl Agent {
         let initial_messages = messages.clone();
         let reply_span = tracing::Span::current();
         self.reset_retry_attempts().await;
+        // Files may have changed between user turns, so cached reads only live within one reply
+        if let Some(cache) = self.tool_result_cache.lock().await.as_mut() {
+            cache.invalidate();
+        }
         let config = Config::global();
+        let hook_session_id = session.as_ref().map(audit_session_id);
+        *self.hook_session_id.lock().await = hook_session_id.clone();
 
         let (mut tools, mut toolshim_tools, mut system_prompt) =
             self.prepare_tools_and_prompt().await?;
@@ -786,15 +1224,32 @@ impl Agent {
 
                 turns_taken += 1;
                 if turns_taken > max_turns {
+                    yield AgentEvent::Message(Message::assistant().with_text(MAX_TURNS_REACHED_MESSAGE));
+                    yield AgentEvent::MaxTurnsReached { max_turns };
+                    break;
+                }
+
+                let latest_message = messages.last().map(|m| m.as_concat_text()).unwrap_or_default();
+                let hook_outcome = self
+                    .run_hooks(
+                        HookInput::new(HookEvent::BeforeProviderCall, hook_session_id.clone())
+                            .with_messages(latest_message, messages.len()),
+                    )
+                    .await;
+                if let Some(reason) = hook_outcome.blocked {
                     yield AgentEvent::Message(Message::assistant().with_text(
-                        "I've reached the maximum number of actions I can do without user input. Would you like me to continue?"
+                        format!("A before_provider_call hook stopped this reply: {}", reason)
                     ));
                     break;
                 }
+                let call_system_prompt = match hook_outcome.context() {
+                    Some(context) => format!("{}\n\n{}", system_prompt, context),
+                    None => system_prompt.clone(),
+                };
 
                 let mut stream = Self::stream_response_from_provider(
                     self.provider().await?,
-                    &system_prompt,
+                    &call_system_prompt,
                     &messages,
                     &tools,
                     &toolshim_tools,
@@ -834,11 +1289,13 @@ impl Agent {
                             }
 
                             // Record usage for the session
-                            if let Some(ref session_config) = &session {
-                                if let Some(ref usage) = usage {
-                                    Self::update_session_metrics(session_config, usage, messages.len())
+                            if let Some(usage) = usage {
+                                if let Some(ref session_config) = &session {
+                                    let tool_cache_stats = self.take_unreported_tool_cache_stats().await;
+                                    Self::update_session_metrics(session_config, &usage, messages.len(), tool_cache_stats)
                                         .await?;
                                 }
+                                yield AgentEvent::Usage(usage);
                             }
 
                             if let Some(response) = response {
@@ -892,9 +1349,17 @@ impl Agent {
                                 }
 
                                 let mode = goose_mode.clone();
+                                let audit_session_id = session.as_ref().map(audit_session_id);
                                 if mode.as_str() == "chat" {
                                     // Skip all tool calls in chat mode
                                     for request in remaining_requests {
+                                        self.audit_not_run(
+                                            &request,
+                                            audit_session_id.clone(),
+                                            PermissionDecision::Skipped,
+                                            DecisionSource::Mode,
+                                        )
+                                        .await;
                                         let mut response = message_tool_response.lock().await;
                                         *response = response.clone().with_tool_response(
                                             request.id.clone(),
@@ -903,10 +1368,17 @@ impl Agent {
                                     }
                                 } else {
                                     let mut permission_manager = PermissionManager::default();
+                                    // Rules about the working directory mean the session's, which
+                                    // is not the process's own in goosed
+                                    let working_dir = session
+                                        .as_ref()
+                                        .map(|session| session.working_dir.clone())
+                                        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
                                     let (permission_check_result, enable_extension_request_ids) =
                                         check_tool_permissions(
                                             &remaining_requests,
                                             &mode,
+                                            &working_dir,
                                             tools_with_readonly_annotation.clone(),
                                             tools_without_annotation.clone(),
                                             &mut permission_manager,
@@ -919,8 +1391,19 @@ impl Agent {
                                     // Handle pre-approved and read-only tools
                                     for request in &permission_check_result.approved {
                                         if let Ok(tool_call) = request.tool_call.clone() {
+                                            let decided_by = permission_check_result
+                                                .decided_by
+                                                .get(&request.id)
+                                                .copied()
+                                                .unwrap_or(DecisionSource::Mode);
                                             let (req_id, tool_result) = self
-                                                .dispatch_tool_call(tool_call, request.id.clone(), cancel_token.clone())
+                                                .dispatch_approved_tool_call(
+                                                    tool_call,
+                                                    request.id.clone(),
+                                                    cancel_token.clone(),
+                                                    audit_session_id.clone(),
+                                                    decided_by,
+                                                )
                                                 .await;
 
                                             tool_futures.push((
@@ -942,11 +1425,30 @@ impl Agent {
                                     }
 
                                     for request in &permission_check_result.denied {
-                                        let mut response = message_tool_response.lock().await;
-                                        *response = response.clone().with_tool_response(
-                                            request.id.clone(),
-                                            Ok(vec![Content::text(DECLINED_RESPONSE)]),
-                                        );
+                                        self.audit_not_run(
+                                            request,
+                                            audit_session_id.clone(),
+                                            PermissionDecision::Denied,
+                                            permission_check_result
+                                                .decided_by
+                                                .get(&request.id)
+                                                .copied()
+                                                .unwrap_or(DecisionSource::Mode),
+                                        )
+                                        .await;
+                                        {
+                                            let mut response = message_tool_response.lock().await;
+                                            *response = response.clone().with_tool_response(
+                                                request.id.clone(),
+                                                Ok(vec![Content::text(DECLINED_RESPONSE)]),
+                                            );
+                                        }
+                                        if let Ok(tool_call) = &request.tool_call {
+                                            yield AgentEvent::ToolDenied {
+                                                id: request.id.clone(),
+                                                tool_name: tool_call.name.clone(),
+                                            };
+                                        }
                                     }
 
                                     let tool_futures_arc = Arc::new(Mutex::new(tool_futures));
@@ -958,10 +1460,11 @@ impl Agent {
                                         &mut permission_manager,
                                         message_tool_response.clone(),
                                         cancel_token.clone(),
+                                        audit_session_id.clone(),
                                     );
 
-                                    while let Some(msg) = tool_approval_stream.try_next().await? {
-                                        yield AgentEvent::Message(msg);
+                                    while let Some(event) = tool_approval_stream.try_next().await? {
+                                        yield event;
                                     }
 
                                     tool_futures = {
@@ -1034,6 +1537,9 @@ impl Agent {
                     (tools, toolshim_tools, system_prompt) = self.prepare_tools_and_prompt().await?;
                 }
                 if !added_message {
+                    let conversation: Vec<Message> = messages.iter().chain(messages_to_add.iter()).cloned().collect();
+                    self.collect_structured_output(&system_prompt, &conversation, &session).await;
+
                     if let Some(final_output_tool) = self.final_output_tool.lock().await.as_ref() {
                         if final_output_tool.final_output.is_none() {
                             tracing::warn!("Final output tool has not been called yet. Continuing agent loop.");
@@ -1115,11 +1621,8 @@ impl Agent {
             .get_param("GOOSE_ROUTER_TOOL_SELECTION_STRATEGY")
             .unwrap_or_else(|_| "default".to_string());
 
-        let strategy = match router_tool_selection_strategy.to_lowercase().as_str() {
-            "vector" => Some(RouterToolSelectionStrategy::Vector),
-            "llm" => Some(RouterToolSelectionStrategy::Llm),
-            _ => None,
-        };
+        let strategy =
+            RouterToolSelectionStrategy::from_config_value(&router_tool_selection_strategy);
 
         let selector = match strategy {
             Some(RouterToolSelectionStrategy::Vector) => {
@@ -1129,7 +1632,7 @@ impl Agent {
                     .map_err(|e| anyhow!("Failed to create tool selector: {}", e))?;
                 Arc::new(selector)
             }
-            Some(RouterToolSelectionStrategy::Llm) => {
+            Some(RouterToolSelectionStrategy::Llm) | Some(RouterToolSelectionStrategy::Hybrid) => {
                 let selector = create_tool_selector(strategy, provider.clone(), None)
                     .await
                     .map_err(|e| anyhow!("Failed to create tool selector: {}", e))?;
@@ -1167,6 +1670,28 @@ impl Agent {
         Ok(())
     }
 
+    /// Explains how the tool router would rank tools for a search, for debugging selections.
+    /// Returns `None` when the router is off or its strategy cannot explain its choices.
+    pub async fn explain_tool_selection(
+        &self,
+        query: &str,
+        k: usize,
+        extension_name: Option<&str>,
+    ) -> Result<Option<ToolSelectionExplanation>> {
+        let selector = self.router_tool_selector.lock().await.clone();
+        let Some(selector) = selector else {
+            return Ok(None);
+        };
+        selector
+            .explain_selection(serde_json::json!({
+                "query": query,
+                "k": k,
+                "extension_name": extension_name,
+            }))
+            .await
+            .map_err(|e| anyhow!("Failed to explain tool selection: {}", e))
+    }
+
     /// Override the system prompt with a custom template
     pub async fn override_system_prompt(&self, template: String) {
         let mut prompt_manager = self.prompt_manager.lock().await;
@@ -1204,6 +1729,21 @@ impl Agent {
         Err(anyhow!("Prompt '{}' not found", name))
     }
 
+    /// Suggest values for an argument of an extension prompt given what has been typed so far
+    pub async fn complete_prompt_argument(
+        &self,
+        extension_name: &str,
+        prompt_name: &str,
+        argument_name: &str,
+        value: &str,
+    ) -> Result<Vec<String>> {
+        self.extension_manager
+            .read()
+            .await
+            .complete_prompt_argument(extension_name, prompt_name, argument_name, value)
+            .await
+    }
+
     pub async fn get_plan_prompt(&self) -> Result<String> {
         let extension_manager = self.extension_manager.read().await;
         let tools = extension_manager.get_prefixed_tools(None).await?;
@@ -1358,6 +1898,8 @@ impl Agent {
             goose_provider: Some(provider_name.clone()),
             goose_model: Some(model_name.clone()),
             temperature: Some(model_config.temperature.unwrap_or(0.0)),
+            reasoning_effort: model_config.reasoning_effort,
+            thinking_budget: model_config.thinking_budget,
             system_prompt_id: None,
         };
 
@@ -1416,4 +1958,122 @@ mod tests {
         assert!(system_prompt.contains(&final_output_tool_system_prompt));
         Ok(())
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_audit_records_before_tool_call_hooks() -> Result<()> {
+        use crate::audit::AuditQuery;
+        use crate::hooks::HookConfig;
+
+        let dir = tempfile::tempdir()?;
+        let agent = Agent::new();
+        agent
+            .configure_audit_log(Some(AuditLog::new(dir.path().join("audit.jsonl"))))
+            .await;
+        let hook = |matcher: &str, command: &str| HookConfig {
+            event: HookEvent::BeforeToolCall,
+            matcher: Some(matcher.to_string()),
+            command: Some(command.to_string()),
+            tool: None,
+            timeout: None,
+        };
+        agent
+            .configure_hooks(Hooks::new(vec![
+                hook(
+                    "test__changed",
+                    r#"echo '{"arguments": {"value": "hook"}}'"#,
+                ),
+                hook("test__blocked", "echo nope >&2; exit 2"),
+            ]))
+            .await;
+
+        for name in ["test__changed", "test__blocked"] {
+            let call = mcp_core::tool::ToolCall::new(name, serde_json::json!({"value": "model"}));
+            if let (_, Ok(result)) = agent.dispatch_tool_call(call, name.to_string(), None).await {
+                let _ = result.result.await;
+            }
+        }
+
+        let entries = AuditLog::new(dir.path().join("audit.jsonl")).query(&AuditQuery::default())?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].arguments, serde_json::json!({"value": "hook"}));
+        assert_eq!(entries[0].decided_by, DecisionSource::Caller);
+        assert_eq!(entries[1].decision, PermissionDecision::Denied);
+        assert_eq!(entries[1].decided_by, DecisionSource::Hook);
+        assert_eq!(entries[1].status, ToolStatus::NotRun);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_permission_hook_arguments_are_asked_about() -> Result<()> {
+        use crate::audit::AuditQuery;
+        use crate::config::PermissionManager;
+        use crate::hooks::HookConfig;
+        use crate::message::MessageContent;
+        use crate::permission::permission_confirmation::PrincipalType;
+        use crate::permission::Permission;
+        use futures::TryStreamExt;
+
+        let dir = tempfile::tempdir()?;
+        let agent = Agent::new();
+        agent
+            .configure_audit_log(Some(AuditLog::new(dir.path().join("audit.jsonl"))))
+            .await;
+        agent
+            .configure_hooks(Hooks::new(vec![HookConfig {
+                event: HookEvent::PermissionRequest,
+                matcher: None,
+                command: Some(r#"echo '{"arguments": {"value": "hook"}}'"#.to_string()),
+                tool: None,
+                timeout: None,
+            }]))
+            .await;
+
+        let requests = vec![ToolRequest {
+            id: "1".to_string(),
+            tool_call: Ok(mcp_core::tool::ToolCall::new(
+                "test__changed",
+                serde_json::json!({"value": "model"}),
+            )),
+        }];
+        let mut permission_manager = PermissionManager::new(dir.path().join("permission.yaml"));
+        let mut stream = agent.handle_approval_tool_requests(
+            &requests,
+            Arc::new(Mutex::new(Vec::new())),
+            &mut permission_manager,
+            Arc::new(Mutex::new(Message::user())),
+            None,
+            None,
+        );
+
+        // The hook did not allow the call, so the user is asked about the changed one
+        let Some(AgentEvent::Message(message)) = stream.try_next().await? else {
+            panic!("Expected a confirmation request");
+        };
+        let Some(MessageContent::ToolConfirmationRequest(confirmation)) = message.content.first()
+        else {
+            panic!("Expected a confirmation request");
+        };
+        assert_eq!(confirmation.arguments, serde_json::json!({"value": "hook"}));
+
+        agent
+            .handle_confirmation(
+                "1".to_string(),
+                PermissionConfirmation {
+                    principal_type: PrincipalType::Tool,
+                    permission: Permission::AllowOnce,
+                    arguments: None,
+                },
+            )
+            .await;
+        while stream.try_next().await?.is_some() {}
+        drop(stream);
+
+        let entries = AuditLog::new(dir.path().join("audit.jsonl")).query(&AuditQuery::default())?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].arguments, serde_json::json!({"value": "hook"}));
+        assert_eq!(entries[0].decided_by, DecisionSource::User);
+        Ok(())
+    }
 }
@@ -0,0 +1,392 @@
+//! Previews of the file changes the developer extension's text editor is asked to make, so they
+//! can be reviewed as a diff before the tool runs. A change that is only accepted in part, or that
+//! the user edited, is applied by calling the tool with a `write` of the resulting content.
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use similar::{ChangeTag, DiffOp, TextDiff};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+pub const TEXT_EDITOR_TOOL_NAME: &str = "developer__text_editor";
+
+/// Lines of unchanged context around each hunk
+const CONTEXT_LINES: usize = 3;
+
+/// Files larger than this are not read to preview a change
+const MAX_PREVIEW_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// A reviewable diff of the change a tool call would make to a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EditPreview {
+    pub path: String,
+    /// True when the change creates the file
+    pub new_file: bool,
+    /// Unified diff of the file on disk against the file after the change
+    pub diff: String,
+    /// The hunks of the diff in order, each can be accepted on its own
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    /// The `@@ -start,count +start,count @@` line
+    pub header: String,
+    /// The lines of the hunk, each starting with ` `, `+` or `-`
+    pub lines: Vec<String>,
+}
+
+/// The content a text editor call would leave in a file
+#[derive(Debug, Clone)]
+pub struct ProposedEdit {
+    pub path: PathBuf,
+    /// The file as it is on disk, None when it does not exist yet
+    pub original: Option<String>,
+    pub proposed: String,
+}
+
+impl ProposedEdit {
+    /// Works out the result of a `write`, `str_replace` or `insert` the same way the developer
+    /// extension does. Returns None for other tools and commands, and for calls the tool would
+    /// reject.
+    pub fn from_tool_call(tool_name: &str, arguments: &Value) -> Option<Self> {
+        if tool_name != TEXT_EDITOR_TOOL_NAME {
+            return None;
+        }
+        let path = resolve_path(arguments.get("path")?.as_str()?)?;
+        let original = match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.len() > MAX_PREVIEW_FILE_SIZE => return None,
+            Ok(_) => Some(std::fs::read_to_string(&path).ok()?),
+            Err(_) => None,
+        };
+
+        let proposed = match arguments.get("command")?.as_str()? {
+            "write" => with_final_newline(arguments.get("file_text")?.as_str()?.to_string()),
+            "str_replace" | "edit_file" => {
+                let content = original.as_deref()?;
+                let old_str = arguments.get("old_str")?.as_str()?;
+                let new_str = arguments.get("new_str")?.as_str()?;
+                if content.matches(old_str).count() != 1 {
+                    return None;
+                }
+                content.replace(old_str, new_str)
+            }
+            "insert" => {
+                let content = original.as_deref()?;
+                let insert_line = arguments.get("insert_line")?.as_u64()? as usize;
+                let new_str = arguments.get("new_str")?.as_str()?;
+                let mut lines: Vec<&str> = content.lines().collect();
+                if insert_line > lines.len() {
+                    return None;
+                }
+                lines.insert(insert_line, new_str);
+                with_final_newline(lines.join("\n"))
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            path,
+            original,
+            proposed,
+        })
+    }
+
+    /// The diff of the change, None when it would not change the file
+    pub fn preview(&self) -> Option<EditPreview> {
+        let original = self.original.as_deref().unwrap_or_default();
+        if self.original.is_some() && original == self.proposed {
+            return None;
+        }
+        let diff = TextDiff::from_lines(original, &self.proposed);
+
+        let hunks: Vec<DiffHunk> = diff
+            .grouped_ops(CONTEXT_LINES)
+            .iter()
+            .map(|ops| {
+                let mut lines = Vec::new();
+                for op in ops {
+                    for change in diff.iter_changes(op) {
+                        let sign = match change.tag() {
+                            ChangeTag::Equal => ' ',
+                            ChangeTag::Insert => '+',
+                            ChangeTag::Delete => '-',
+                        };
+                        let value = change.value();
+                        lines.push(format!("{}{}", sign, value.trim_end_matches(['\n', '\r'])));
+                        if !value.ends_with('\n') {
+                            lines.push("\\ No newline at end of file".to_string());
+                        }
+                    }
+                }
+                DiffHunk {
+                    header: hunk_header(ops),
+                    lines,
+                }
+            })
+            .collect();
+
+        let path = self.path.display().to_string();
+        let mut text = format!(
+            "--- {}\n+++ {}\n",
+            if self.original.is_some() {
+                path.as_str()
+            } else {
+                "/dev/null"
+            },
+            path
+        );
+        for hunk in &hunks {
+            text.push_str(&hunk.header);
+            text.push('\n');
+            for line in &hunk.lines {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+
+        Some(EditPreview {
+            path,
+            new_file: self.original.is_none(),
+            diff: text,
+            hunks,
+        })
+    }
+
+    /// The file with only the hunks at the given indexes of the preview applied
+    pub fn apply_hunks(&self, accepted: &[usize]) -> String {
+        let original = self.original.as_deref().unwrap_or_default();
+        let diff = TextDiff::from_lines(original, &self.proposed);
+        let old_lines = diff.old_slices();
+        let new_lines = diff.new_slices();
+
+        let mut result = String::new();
+        let mut position = 0;
+        for (index, ops) in diff.grouped_ops(CONTEXT_LINES).iter().enumerate() {
+            let (old_range, new_range) = hunk_ranges(ops);
+            result.extend(old_lines[position..old_range.start].iter().copied());
+            if accepted.contains(&index) {
+                result.extend(new_lines[new_range].iter().copied());
+            } else {
+                result.extend(old_lines[old_range.clone()].iter().copied());
+            }
+            position = old_range.end;
+        }
+        result.extend(old_lines[position..].iter().copied());
+        result
+    }
+
+    /// Text editor arguments that write the given content to the file
+    pub fn write_arguments(&self, file_text: &str) -> Value {
+        json!({
+            "command": "write",
+            "path": self.path.display().to_string(),
+            "file_text": file_text,
+        })
+    }
+}
+
+/// Whether the user may run a tool call with `replacement` instead of its `original` arguments.
+/// Only a text editor change that had a preview can be changed, and only into the same command
+/// or a `write` of the result, on the same file.
+pub fn is_allowed_override(tool_name: &str, original: &Value, replacement: &Value) -> bool {
+    let Some(edit) = ProposedEdit::from_tool_call(tool_name, original) else {
+        return false;
+    };
+    if edit.preview().is_none() {
+        return false;
+    }
+    let command = replacement.get("command").and_then(Value::as_str);
+    let same_command =
+        command == Some("write") || command == original.get("command").and_then(Value::as_str);
+    let same_path = replacement
+        .get("path")
+        .and_then(Value::as_str)
+        .and_then(resolve_path)
+        .is_some_and(|path| path == edit.path);
+    same_command && same_path
+}
+
+fn resolve_path(path: &str) -> Option<PathBuf> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()?.join(rest),
+        None => PathBuf::from(path),
+    };
+    // The tool only accepts absolute paths
+    Path::is_absolute(&path).then_some(path)
+}
+
+fn with_final_newline(mut text: String) -> String {
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+fn hunk_ranges(ops: &[DiffOp]) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+    match (ops.first(), ops.last()) {
+        (Some(first), Some(last)) => (
+            first.old_range().start..last.old_range().end,
+            first.new_range().start..last.new_range().end,
+        ),
+        _ => (0..0, 0..0),
+    }
+}
+
+/// Unified diff line numbers start at 1, an empty range is numbered by the line before it
+fn hunk_header(ops: &[DiffOp]) -> String {
+    let (old, new) = hunk_ranges(ops);
+    let start = |range: &std::ops::Range<usize>| {
+        if range.is_empty() {
+            range.start
+        } else {
+            range.start + 1
+        }
+    };
+    format!(
+        "@@ -{},{} +{},{} @@",
+        start(&old),
+        old.len(),
+        start(&new),
+        new.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn numbered_lines(count: usize) -> String {
+        (1..=count).map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn test_str_replace_preview() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "alpha\nbeta\ngamma\n").unwrap();
+
+        let edit = ProposedEdit::from_tool_call(
+            TEXT_EDITOR_TOOL_NAME,
+            &json!({"command": "str_replace", "path": path, "old_str": "beta", "new_str": "BETA"}),
+        )
+        .unwrap();
+        let preview = edit.preview().unwrap();
+
+        assert!(!preview.new_file);
+        assert_eq!(preview.hunks.len(), 1);
+        assert_eq!(preview.hunks[0].header, "@@ -1,3 +1,3 @@");
+        assert_eq!(
+            preview.hunks[0].lines,
+            vec![" alpha", "-beta", "+BETA", " gamma"]
+        );
+        assert!(preview
+            .diff
+            .ends_with("@@ -1,3 +1,3 @@\n alpha\n-beta\n+BETA\n gamma\n"));
+    }
+
+    #[test]
+    fn test_write_preview_of_a_new_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("new.txt");
+
+        let edit = ProposedEdit::from_tool_call(
+            TEXT_EDITOR_TOOL_NAME,
+            &json!({"command": "write", "path": path, "file_text": "hello"}),
+        )
+        .unwrap();
+        let preview = edit.preview().unwrap();
+
+        assert!(preview.new_file);
+        assert!(preview.diff.starts_with("--- /dev/null\n"));
+        assert_eq!(preview.hunks[0].header, "@@ -0,0 +1,1 @@");
+        assert_eq!(preview.hunks[0].lines, vec!["+hello"]);
+    }
+
+    #[test]
+    fn test_apply_some_hunks() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("long.txt");
+        let original = numbered_lines(20);
+        std::fs::write(&path, &original).unwrap();
+        let proposed = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "line eighteen\n");
+
+        let edit = ProposedEdit::from_tool_call(
+            TEXT_EDITOR_TOOL_NAME,
+            &json!({"command": "write", "path": path, "file_text": proposed}),
+        )
+        .unwrap();
+        assert_eq!(edit.preview().unwrap().hunks.len(), 2);
+
+        assert_eq!(
+            edit.apply_hunks(&[1]),
+            original.replace("line 18\n", "line eighteen\n")
+        );
+        assert_eq!(edit.apply_hunks(&[0, 1]), proposed);
+        assert_eq!(edit.apply_hunks(&[]), original);
+    }
+
+    #[test]
+    fn test_no_preview_for_calls_the_tool_would_reject() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "same\nsame\n").unwrap();
+
+        let ambiguous =
+            json!({"command": "str_replace", "path": path, "old_str": "same", "new_str": "x"});
+        assert!(ProposedEdit::from_tool_call(TEXT_EDITOR_TOOL_NAME, &ambiguous).is_none());
+        let relative = json!({"command": "write", "path": "notes.txt", "file_text": "x"});
+        assert!(ProposedEdit::from_tool_call(TEXT_EDITOR_TOOL_NAME, &relative).is_none());
+        let view = json!({"command": "view", "path": path});
+        assert!(ProposedEdit::from_tool_call(TEXT_EDITOR_TOOL_NAME, &view).is_none());
+        assert!(ProposedEdit::from_tool_call("developer__shell", &ambiguous).is_none());
+    }
+
+    #[test]
+    fn test_only_previewed_edits_can_be_overridden() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "alpha\nbeta\n").unwrap();
+        let other = dir.path().join("other.txt");
+        let original =
+            json!({"command": "str_replace", "path": path, "old_str": "beta", "new_str": "BETA"});
+        let edit = ProposedEdit::from_tool_call(TEXT_EDITOR_TOOL_NAME, &original).unwrap();
+
+        let partial = edit.write_arguments("alpha\nBeta\n");
+        assert!(is_allowed_override(
+            TEXT_EDITOR_TOOL_NAME,
+            &original,
+            &partial
+        ));
+        let same_command =
+            json!({"command": "str_replace", "path": path, "old_str": "beta", "new_str": "Beta"});
+        assert!(is_allowed_override(
+            TEXT_EDITOR_TOOL_NAME,
+            &original,
+            &same_command
+        ));
+
+        let other_file = json!({"command": "write", "path": other, "file_text": "x"});
+        assert!(!is_allowed_override(
+            TEXT_EDITOR_TOOL_NAME,
+            &original,
+            &other_file
+        ));
+        let other_command = json!({"command": "view", "path": path});
+        assert!(!is_allowed_override(
+            TEXT_EDITOR_TOOL_NAME,
+            &original,
+            &other_command
+        ));
+        let shell = json!({"command": "rm -rf /"});
+        assert!(!is_allowed_override(
+            "developer__shell",
+            &json!({"command": "ls"}),
+            &shell
+        ));
+    }
+}
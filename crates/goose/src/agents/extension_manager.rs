@@ -2,7 +2,7 @@ use anyhow::Result;
 use chrono::{DateTime, TimeZone, Utc};
 use futures::stream::{FuturesUnordered, StreamExt};
 use futures::{future, FutureExt};
-use rmcp::model::GetPromptResult;
+use rmcp::model::{ArgumentInfo, GetPromptResult, PromptReference, Reference};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::LazyLock;
@@ -617,25 +617,34 @@ impl ExtensionManager {
         })?;
 
         let client_guard = client.lock().await;
-        client_guard
-            .list_resources(None)
+        let resources = client_guard.list_resources(None).await.map_err(|e| {
+            ToolError::ExecutionError(format!(
+                "Unable to list resources for {}, {:?}",
+                extension_name, e
+            ))
+        })?;
+
+        // Templates are optional, an extension that fails to list them still has its resources
+        let templates = client_guard
+            .list_resource_templates(None)
             .await
-            .map_err(|e| {
-                ToolError::ExecutionError(format!(
-                    "Unable to list resources for {}, {:?}",
-                    extension_name, e
-                ))
-            })
-            .map(|lr| {
-                let resource_list = lr
-                    .resources
-                    .into_iter()
-                    .map(|r| format!("{} - {}, uri: ({})", extension_name, r.name, r.uri))
-                    .collect::<Vec<String>>()
-                    .join("\n");
+            .map(|lt| lt.resource_templates)
+            .unwrap_or_default();
+
+        let resource_list = resources
+            .resources
+            .into_iter()
+            .map(|r| format!("{} - {}, uri: ({})", extension_name, r.name, r.uri))
+            .chain(templates.into_iter().map(|t| {
+                format!(
+                    "{} - {}, uri template: ({})",
+                    extension_name, t.name, t.uri_template
+                )
+            }))
+            .collect::<Vec<String>>()
+            .join("\n");
 
-                vec![Content::text(resource_list)]
-            })
+        Ok(vec![Content::text(resource_list)])
     }
 
     pub async fn list_resources(&self, params: Value) -> Result<Vec<Content>, ToolError> {
@@ -802,6 +811,35 @@ impl ExtensionManager {
             .map_err(|e| anyhow::anyhow!("Failed to get prompt: {}", e))
     }
 
+    /// Ask an extension for completions of a prompt argument given its partial value
+    pub async fn complete_prompt_argument(
+        &self,
+        extension_name: &str,
+        prompt_name: &str,
+        argument_name: &str,
+        value: &str,
+    ) -> Result<Vec<String>> {
+        let client = self
+            .clients
+            .get(extension_name)
+            .ok_or_else(|| anyhow::anyhow!("Extension {} not found", extension_name))?;
+
+        let client_guard = client.lock().await;
+        client_guard
+            .complete(
+                Reference::Prompt(PromptReference {
+                    name: prompt_name.to_string(),
+                }),
+                ArgumentInfo {
+                    name: argument_name.to_string(),
+                    value: value.to_string(),
+                },
+            )
+            .await
+            .map(|result| result.completion.values)
+            .map_err(|e| anyhow::anyhow!("Failed to complete prompt argument: {}", e))
+    }
+
     pub async fn search_available_extensions(&self) -> Result<Vec<Content>, ToolError> {
         let mut output_parts = vec![];
 
@@ -889,7 +927,10 @@ mod tests {
         CallToolResult, InitializeResult, ListPromptsResult, ListResourcesResult, ListToolsResult,
         ReadResourceResult,
     };
-    use rmcp::model::{GetPromptResult, ServerNotification};
+    use rmcp::model::{
+        ArgumentInfo, CompleteResult, GetPromptResult, ListResourceTemplatesResult, Reference,
+        ServerNotification,
+    };
     use serde_json::json;
     use tokio::sync::mpsc;
 
@@ -916,6 +957,13 @@ mod tests {
             Err(Error::NotInitialized)
         }
 
+        async fn list_resource_templates(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListResourceTemplatesResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
         async fn list_tools(&self, _next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
             Err(Error::NotInitialized)
         }
@@ -945,6 +993,14 @@ mod tests {
             Err(Error::NotInitialized)
         }
 
+        async fn complete(
+            &self,
+            _reference: Reference,
+            _argument: ArgumentInfo,
+        ) -> Result<CompleteResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
         async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
             mpsc::channel(1).1
         }
@@ -8,6 +8,8 @@ use serde_json::Value;
 pub const FINAL_OUTPUT_TOOL_NAME: &str = "recipe__final_output";
 pub const FINAL_OUTPUT_CONTINUATION_MESSAGE: &str =
     "You MUST call the `final_output` tool NOW with the final output for the user.";
+pub const STRUCTURED_OUTPUT_REQUEST_MESSAGE: &str =
+    "Respond NOW with the final output for the user as JSON matching the expected schema.";
 
 pub struct FinalOutputTool {
     pub response: Response,
@@ -78,6 +80,10 @@ impl FinalOutputTool {
         })
     }
 
+    pub fn schema(&self) -> &Value {
+        self.response.json_schema.as_ref().unwrap()
+    }
+
     pub fn system_prompt(&self) -> String {
         formatdoc! {r#"
             # Final Output Instructions
@@ -137,6 +143,16 @@ impl FinalOutputTool {
         }
     }
 
+    /// Collect final output that a provider produced natively as JSON text, validating it
+    /// against the schema the same way as a call to the tool
+    pub async fn collect_structured_output(&mut self, text: &str) -> Result<(), String> {
+        let output: Value = serde_json::from_str(text.trim())
+            .map_err(|e| format!("Structured output is not valid JSON: {}", e))?;
+        let parsed_value = self.validate_json_output(&output).await?;
+        self.final_output = Some(Self::parsed_final_output_string(parsed_value));
+        Ok(())
+    }
+
     // Formats the parsed JSON as a single line string so its easy to extract from the output
     fn parsed_final_output_string(parsed_json: Value) -> String {
         serde_json::to_string(&parsed_json).unwrap()
@@ -262,4 +278,28 @@ mod tests {
         assert!(serde_json::from_str::<Value>(&final_output).is_ok());
         assert!(!final_output.contains('\n'));
     }
+
+    #[tokio::test]
+    async fn test_collect_structured_output() {
+        let response = Response {
+            json_schema: Some(create_complex_test_schema()),
+        };
+        let mut tool = FinalOutputTool::new(response);
+
+        let invalid = tool.collect_structured_output("not json").await;
+        assert!(invalid.unwrap_err().contains("not valid JSON"));
+
+        let mismatched = tool.collect_structured_output(r#"{"tags": []}"#).await;
+        assert!(mismatched.unwrap_err().contains("Validation failed"));
+        assert!(tool.final_output.is_none());
+
+        let text = "{\n  \"user\": {\"name\": \"Ada\", \"age\": 36},\n  \"tags\": []\n}\n";
+        tool.collect_structured_output(text).await.unwrap();
+        let final_output = tool.final_output.unwrap();
+        assert!(!final_output.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<Value>(&final_output).unwrap(),
+            json!({"user": {"name": "Ada", "age": 36}, "tags": []})
+        );
+    }
 }
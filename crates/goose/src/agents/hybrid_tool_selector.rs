@@ -0,0 +1,878 @@
+//! Hybrid tool selection for the router
+//!
+//! Ranks tools with a BM25 index over their names, descriptions and parameter names, which
+//! runs in memory and answers in well under a millisecond for a few hundred tools. When an
+//! embedding provider is configured with `GOOSE_EMBEDDING_MODEL_PROVIDER`, cosine similarity
+//! between the query and tool embeddings is blended into the score. Finally, a small prior
+//! learned from past sessions favours tools that were actually invoked after being selected,
+//! and is persisted to `tool_usage.json` in the goose data directory.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use etcetera::{choose_app_strategy, AppStrategy};
+use fs2::FileExt;
+use mcp_core::ToolError;
+use rmcp::model::{Content, Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::env;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+
+use crate::agents::router_tool_selector::{
+    embedding_provider_from_env, RouterToolSelectionStrategy, RouterToolSelector,
+};
+use crate::config::APP_STRATEGY;
+use crate::providers::base::Provider;
+
+const USAGE_FILE_NAME: &str = "tool_usage.json";
+
+/// BM25 term frequency saturation
+const BM25_K1: f32 = 1.2;
+/// BM25 document length normalization
+const BM25_B: f32 = 0.75;
+
+/// Weight of the lexical score when vector scores are available
+const LEXICAL_WEIGHT: f32 = 0.6;
+/// Weight of the vector score when vector scores are available
+const VECTOR_WEIGHT: f32 = 0.4;
+/// Weight of the usage prior, a tool never seen before scores zero here
+const USAGE_WEIGHT: f32 = 0.2;
+
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on",
+    "or", "that", "the", "this", "to", "with",
+];
+
+/// The score breakdown for one tool
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ToolSelectionScore {
+    pub tool_name: String,
+    pub extension_name: String,
+    /// The combined score tools are ranked by
+    pub score: f32,
+    /// BM25 score relative to the best lexical match, between 0 and 1
+    pub lexical: f32,
+    /// Cosine similarity to the query, when an embedding provider is configured
+    pub vector: Option<f32>,
+    /// Contribution of past usage, positive for tools that were usually invoked once selected
+    pub usage: f32,
+    /// Query terms found in the tool's name, description or parameters
+    pub matched_terms: Vec<String>,
+}
+
+/// Why the router would select the tools it does for a query
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ToolSelectionExplanation {
+    pub strategy: String,
+    pub query: String,
+    pub extension_name: Option<String>,
+    /// The normalized terms the query was matched with
+    pub query_terms: Vec<String>,
+    pub vector_enabled: bool,
+    pub elapsed_micros: u64,
+    pub tools: Vec<ToolSelectionScore>,
+}
+
+/// How often a tool was offered by the router, and how often it was then called
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+struct ToolUsage {
+    selected: u32,
+    invoked: u32,
+}
+
+impl ToolUsage {
+    fn add(&mut self, other: ToolUsage) {
+        self.selected += other.selected;
+        self.invoked += other.invoked;
+    }
+
+    /// Laplace smoothed invocation rate centered on zero, so unseen tools are neutral
+    fn prior(&self) -> f32 {
+        (self.invoked as f32 + 1.0) / (self.selected as f32 + 2.0) - 0.5
+    }
+}
+
+struct IndexedTool {
+    tool_name: String,
+    extension_name: String,
+    description: String,
+    schema: String,
+    term_freqs: HashMap<String, u32>,
+    length: usize,
+    embedding: Option<Vec<f32>>,
+}
+
+#[derive(Default)]
+struct LexicalIndex {
+    tools: HashMap<String, IndexedTool>,
+    doc_freqs: HashMap<String, u32>,
+    total_length: usize,
+}
+
+impl LexicalIndex {
+    fn insert(&mut self, tool: IndexedTool) {
+        self.remove(&tool.tool_name);
+        for term in tool.term_freqs.keys() {
+            *self.doc_freqs.entry(term.clone()).or_default() += 1;
+        }
+        self.total_length += tool.length;
+        self.tools.insert(tool.tool_name.clone(), tool);
+    }
+
+    fn remove(&mut self, tool_name: &str) {
+        let Some(tool) = self.tools.remove(tool_name) else {
+            return;
+        };
+        for term in tool.term_freqs.keys() {
+            if let Some(count) = self.doc_freqs.get_mut(term) {
+                *count -= 1;
+                if *count == 0 {
+                    self.doc_freqs.remove(term);
+                }
+            }
+        }
+        self.total_length -= tool.length;
+    }
+
+    /// BM25 score of a tool for the query terms, and the terms that matched
+    fn bm25(&self, tool: &IndexedTool, query_terms: &[String]) -> (f32, Vec<String>) {
+        let doc_count = self.tools.len() as f32;
+        let avg_length = (self.total_length as f32 / doc_count.max(1.0)).max(1.0);
+
+        let mut score = 0.0;
+        let mut matched = Vec::new();
+        for term in query_terms {
+            let Some(&freq) = tool.term_freqs.get(term) else {
+                continue;
+            };
+            let doc_freq = self.doc_freqs.get(term).copied().unwrap_or(0) as f32;
+            let idf = ((doc_count - doc_freq + 0.5) / (doc_freq + 0.5) + 1.0).ln();
+            let freq = freq as f32;
+            let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * tool.length as f32 / avg_length);
+            score += idf * freq * (BM25_K1 + 1.0) / (freq + norm);
+            matched.push(term.clone());
+        }
+        (score, matched)
+    }
+}
+
+/// Lowercased terms with camelCase and snake_case split, stop words dropped and plurals trimmed
+fn tokenize(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev_lower && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_lowercase() || c.is_numeric();
+            current.extend(c.to_lowercase());
+        } else {
+            prev_lower = false;
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+        .into_iter()
+        .filter(|w| w.len() > 1 && !STOP_WORDS.contains(&w.as_str()))
+        .map(|w| {
+            if w.len() > 3 && w.ends_with('s') && !w.ends_with("ss") {
+                w[..w.len() - 1].to_string()
+            } else {
+                w
+            }
+        })
+        .collect()
+}
+
+/// The text a tool is matched on: its name without the extension prefix (counted twice so
+/// names outweigh long descriptions), its description and its parameter names
+fn tool_terms(tool: &Tool) -> Vec<String> {
+    let short_name = tool
+        .name
+        .split_once("__")
+        .map(|(_, name)| name)
+        .unwrap_or(&tool.name);
+    let name_terms = tokenize(short_name);
+
+    let mut terms = name_terms.clone();
+    terms.extend(name_terms);
+    if let Some(description) = &tool.description {
+        terms.extend(tokenize(description));
+    }
+    if let Some(Value::Object(properties)) = tool.input_schema.get("properties") {
+        for name in properties.keys() {
+            terms.extend(tokenize(name));
+        }
+    }
+    terms
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+fn default_usage_path() -> Option<PathBuf> {
+    choose_app_strategy(APP_STRATEGY.clone())
+        .ok()
+        .map(|strategy| strategy.data_dir().join(USAGE_FILE_NAME))
+}
+
+/// Add counts to the usage file and return the merged totals. The file is updated under an
+/// exclusive lock and replaced atomically, so concurrent sessions add to each other's counts
+/// instead of overwriting them.
+fn merge_usage_file(
+    path: &Path,
+    delta: &HashMap<String, ToolUsage>,
+) -> std::io::Result<HashMap<String, ToolUsage>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("json.lock"))?;
+    lock_file.lock_exclusive()?;
+
+    let mut usage: HashMap<String, ToolUsage> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    for (tool_name, counts) in delta {
+        usage.entry(tool_name.clone()).or_default().add(*counts);
+    }
+
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, serde_json::to_string_pretty(&usage)?)?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(usage)
+}
+
+pub struct HybridToolSelector {
+    index: Arc<RwLock<LexicalIndex>>,
+    embedding_provider: Option<Arc<dyn Provider>>,
+    usage: Arc<RwLock<HashMap<String, ToolUsage>>>,
+    usage_path: Option<PathBuf>,
+    /// Tools returned by the latest search that have not been called yet
+    pending_selections: Arc<RwLock<HashSet<String>>>,
+    recent_tool_calls: Arc<RwLock<VecDeque<String>>>,
+}
+
+impl HybridToolSelector {
+    pub async fn new(provider: Arc<dyn Provider>) -> Result<Self> {
+        // Vector scores are opt in, so the default is fully local and fast
+        let embedding_provider = if env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").is_ok() {
+            match embedding_provider_from_env(provider) {
+                Ok(p) if p.supports_embeddings() => Some(p),
+                Ok(_) => {
+                    tracing::warn!("Embedding provider does not support embeddings, hybrid tool selection will use lexical scores only");
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("{}, hybrid tool selection will use lexical scores only", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Self::with_options(embedding_provider, default_usage_path()))
+    }
+
+    pub fn with_options(
+        embedding_provider: Option<Arc<dyn Provider>>,
+        usage_path: Option<PathBuf>,
+    ) -> Self {
+        let usage = usage_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            index: Arc::new(RwLock::new(LexicalIndex::default())),
+            embedding_provider,
+            usage: Arc::new(RwLock::new(usage)),
+            usage_path,
+            pending_selections: Arc::new(RwLock::new(HashSet::new())),
+            recent_tool_calls: Arc::new(RwLock::new(VecDeque::with_capacity(100))),
+        }
+    }
+
+    /// Scores every candidate tool for the query, best first
+    pub async fn explain(
+        &self,
+        query: &str,
+        k: usize,
+        extension_name: Option<&str>,
+    ) -> ToolSelectionExplanation {
+        let start = std::time::Instant::now();
+        let mut seen = HashSet::new();
+        let query_terms: Vec<String> = tokenize(query)
+            .into_iter()
+            .filter(|term| seen.insert(term.clone()))
+            .collect();
+
+        // Embed the query before taking the index lock, a failure drops back to lexical scores
+        let query_embedding = match &self.embedding_provider {
+            Some(provider) => match provider.create_embeddings(vec![query.to_string()]).await {
+                Ok(embeddings) => embeddings.into_iter().next(),
+                Err(e) => {
+                    tracing::warn!("Failed to embed tool search query: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let index = self.index.read().await;
+        let usage_stats = self.usage.read().await;
+
+        let candidates: Vec<(&IndexedTool, f32, Vec<String>, Option<f32>)> = index
+            .tools
+            .values()
+            .filter(|tool| extension_name.is_none_or(|ext| tool.extension_name == ext))
+            .map(|tool| {
+                let (bm25, matched) = index.bm25(tool, &query_terms);
+                let vector = match (&query_embedding, &tool.embedding) {
+                    (Some(q), Some(t)) => Some(cosine_similarity(q, t).max(0.0)),
+                    _ => None,
+                };
+                (tool, bm25, matched, vector)
+            })
+            .collect();
+
+        let max_bm25 = candidates
+            .iter()
+            .map(|(_, bm25, _, _)| *bm25)
+            .fold(0.0_f32, f32::max);
+
+        let mut tools: Vec<ToolSelectionScore> = candidates
+            .into_iter()
+            .filter_map(|(tool, bm25, matched_terms, vector)| {
+                let lexical = if max_bm25 > 0.0 { bm25 / max_bm25 } else { 0.0 };
+                let relevance = match vector {
+                    Some(vector) => LEXICAL_WEIGHT * lexical + VECTOR_WEIGHT * vector,
+                    None => lexical,
+                };
+                // Usage only reorders relevant tools, it never selects a tool on its own
+                if relevance <= 0.0 {
+                    return None;
+                }
+                let usage = USAGE_WEIGHT
+                    * usage_stats
+                        .get(&tool.tool_name)
+                        .map(ToolUsage::prior)
+                        .unwrap_or(0.0);
+                Some(ToolSelectionScore {
+                    tool_name: tool.tool_name.clone(),
+                    extension_name: tool.extension_name.clone(),
+                    score: relevance + usage,
+                    lexical,
+                    vector,
+                    usage,
+                    matched_terms,
+                })
+            })
+            .collect();
+
+        tools.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.tool_name.cmp(&b.tool_name))
+        });
+        tools.truncate(k);
+
+        ToolSelectionExplanation {
+            strategy: "hybrid".to_string(),
+            query: query.to_string(),
+            extension_name: extension_name.map(str::to_string),
+            query_terms,
+            vector_enabled: query_embedding.is_some(),
+            elapsed_micros: start.elapsed().as_micros() as u64,
+            tools,
+        }
+    }
+
+    /// Add counts to the usage in memory and in the usage file
+    async fn record_usage(&self, delta: HashMap<String, ToolUsage>) {
+        {
+            let mut usage = self.usage.write().await;
+            for (tool_name, counts) in &delta {
+                usage.entry(tool_name.clone()).or_default().add(*counts);
+            }
+        }
+
+        let Some(path) = self.usage_path.clone() else {
+            return;
+        };
+        let merged = tokio::task::spawn_blocking(move || {
+            merge_usage_file(&path, &delta).map_err(|e| (path, e))
+        })
+        .await;
+        match merged {
+            // Picks up counts other sessions saved in the meantime
+            Ok(Ok(merged)) => *self.usage.write().await = merged,
+            Ok(Err((path, e))) => {
+                tracing::warn!("Failed to save tool usage to {}: {}", path.display(), e)
+            }
+            Err(e) => tracing::warn!("Failed to save tool usage: {}", e),
+        }
+    }
+}
+
+fn parse_search_params(params: &Value) -> Result<(&str, usize, Option<&str>), ToolError> {
+    let query = params
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ToolError::InvalidParameters("Missing 'query' parameter".to_string()))?;
+    let k = params.get("k").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
+    let extension_name = params.get("extension_name").and_then(|v| v.as_str());
+    Ok((query, k, extension_name))
+}
+
+#[async_trait]
+impl RouterToolSelector for HybridToolSelector {
+    async fn select_tools(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let (query, k, extension_name) = parse_search_params(&params)?;
+        let explanation = self.explain(query, k, extension_name).await;
+
+        let mut delta = HashMap::new();
+        let selected_tools = {
+            let index = self.index.read().await;
+            let mut pending = self.pending_selections.write().await;
+            // Only a call to a tool from the latest search counts towards it
+            pending.clear();
+
+            explanation
+                .tools
+                .iter()
+                .filter_map(|score| index.tools.get(&score.tool_name))
+                .map(|tool| {
+                    delta.insert(
+                        tool.tool_name.clone(),
+                        ToolUsage {
+                            selected: 1,
+                            invoked: 0,
+                        },
+                    );
+                    pending.insert(tool.tool_name.clone());
+                    Content::text(format!(
+                        "Tool: {}\nDescription: {}\nSchema: {}",
+                        tool.tool_name, tool.description, tool.schema
+                    ))
+                })
+                .collect()
+        };
+        self.record_usage(delta).await;
+
+        Ok(selected_tools)
+    }
+
+    async fn index_tools(&self, tools: &[Tool], extension_name: &str) -> Result<(), ToolError> {
+        let embeddings = match &self.embedding_provider {
+            Some(provider) => {
+                let texts = tools
+                    .iter()
+                    .map(|tool| {
+                        format!(
+                            "{} {}",
+                            tool.name,
+                            tool.description
+                                .as_ref()
+                                .map(|d| d.as_ref())
+                                .unwrap_or_default()
+                        )
+                    })
+                    .collect();
+                match provider.create_embeddings(texts).await {
+                    Ok(embeddings) if embeddings.len() == tools.len() => {
+                        embeddings.into_iter().map(Some).collect()
+                    }
+                    Ok(_) => vec![None; tools.len()],
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to embed tools for {}, using lexical scores only: {}",
+                            extension_name,
+                            e
+                        );
+                        vec![None; tools.len()]
+                    }
+                }
+            }
+            None => vec![None; tools.len()],
+        };
+
+        let mut index = self.index.write().await;
+        for (tool, embedding) in tools.iter().zip(embeddings) {
+            let terms = tool_terms(tool);
+            let mut term_freqs = HashMap::new();
+            for term in &terms {
+                *term_freqs.entry(term.clone()).or_default() += 1;
+            }
+            index.insert(IndexedTool {
+                tool_name: tool.name.to_string(),
+                extension_name: extension_name.to_string(),
+                description: tool
+                    .description
+                    .as_ref()
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+                schema: serde_json::to_string_pretty(&tool.input_schema)
+                    .unwrap_or_else(|_| "{}".to_string()),
+                term_freqs,
+                length: terms.len(),
+                embedding,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn remove_tool(&self, tool_name: &str) -> Result<(), ToolError> {
+        self.index.write().await.remove(tool_name);
+        Ok(())
+    }
+
+    async fn record_tool_call(&self, tool_name: &str) -> Result<(), ToolError> {
+        {
+            let mut recent_calls = self.recent_tool_calls.write().await;
+            if recent_calls.len() >= 100 {
+                recent_calls.pop_front();
+            }
+            recent_calls.push_back(tool_name.to_string());
+        }
+
+        // Only a call following a selection counts as the selection being useful
+        if self.pending_selections.write().await.remove(tool_name) {
+            let counts = ToolUsage {
+                selected: 0,
+                invoked: 1,
+            };
+            self.record_usage(HashMap::from([(tool_name.to_string(), counts)]))
+                .await;
+        }
+        Ok(())
+    }
+
+    async fn get_recent_tool_calls(&self, limit: usize) -> Result<Vec<String>, ToolError> {
+        let recent_calls = self.recent_tool_calls.read().await;
+        Ok(recent_calls.iter().rev().take(limit).cloned().collect())
+    }
+
+    async fn explain_selection(
+        &self,
+        params: Value,
+    ) -> Result<Option<ToolSelectionExplanation>, ToolError> {
+        let (query, k, extension_name) = parse_search_params(&params)?;
+        Ok(Some(self.explain(query, k, extension_name).await))
+    }
+
+    fn selector_type(&self) -> RouterToolSelectionStrategy {
+        RouterToolSelectionStrategy::Hybrid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use crate::model::ModelConfig;
+    use crate::providers::base::{ProviderMetadata, ProviderUsage};
+    use crate::providers::errors::ProviderError;
+    use rmcp::object;
+    use serde_json::json;
+
+    fn tool(name: &str, description: &str, params: &[&str]) -> Tool {
+        let properties: serde_json::Map<String, Value> = params
+            .iter()
+            .map(|p| (p.to_string(), json!({"type": "string"})))
+            .collect();
+        Tool::new(
+            name.to_string(),
+            description.to_string(),
+            object!({"type": "object", "properties": properties}),
+        )
+    }
+
+    fn developer_tools() -> Vec<Tool> {
+        vec![
+            tool(
+                "developer__shell",
+                "Execute a command in the shell and return its output",
+                &["command"],
+            ),
+            tool(
+                "developer__text_editor",
+                "View, create and edit text files",
+                &["path", "command", "file_text"],
+            ),
+            tool(
+                "developer__list_windows",
+                "List the open windows on the screen",
+                &[],
+            ),
+            tool(
+                "developer__screen_capture",
+                "Capture a screenshot of a display or window",
+                &["display", "window_title"],
+            ),
+        ]
+    }
+
+    async fn indexed_selector(usage_path: Option<PathBuf>) -> HybridToolSelector {
+        let selector = HybridToolSelector::with_options(None, usage_path);
+        selector
+            .index_tools(&developer_tools(), "developer")
+            .await
+            .unwrap();
+        selector
+            .index_tools(
+                &[tool(
+                    "github__list_issues",
+                    "List the issues in a GitHub repository",
+                    &["owner", "repo"],
+                )],
+                "github",
+            )
+            .await
+            .unwrap();
+        selector
+    }
+
+    fn names(explanation: &ToolSelectionExplanation) -> Vec<&str> {
+        explanation
+            .tools
+            .iter()
+            .map(|t| t.tool_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("readFile and list_open_windows for the Files"),
+            vec!["read", "file", "list", "open", "window", "file"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lexical_ranking() {
+        let selector = indexed_selector(None).await;
+
+        let explanation = selector.explain("edit a text file", 5, None).await;
+        assert_eq!(names(&explanation)[0], "developer__text_editor");
+        assert!(!explanation.vector_enabled);
+        assert_eq!(explanation.tools[0].lexical, 1.0);
+        assert!(explanation.tools[0]
+            .matched_terms
+            .contains(&"file".to_string()));
+
+        let explanation = selector.explain("take a screenshot", 5, None).await;
+        assert_eq!(names(&explanation), vec!["developer__screen_capture"]);
+
+        // Tools with no matching terms are never selected
+        let explanation = selector.explain("weather forecast", 5, None).await;
+        assert!(explanation.tools.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_extension_filter_and_removal() {
+        let selector = indexed_selector(None).await;
+
+        let explanation = selector.explain("list", 5, Some("github")).await;
+        assert_eq!(names(&explanation), vec!["github__list_issues"]);
+
+        let explanation = selector.explain("list", 5, None).await;
+        assert_eq!(explanation.tools.len(), 2);
+
+        selector.remove_tool("github__list_issues").await.unwrap();
+        let explanation = selector.explain("list", 5, None).await;
+        assert_eq!(names(&explanation), vec!["developer__list_windows"]);
+    }
+
+    #[tokio::test]
+    async fn test_usage_learning_persists() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let usage_path = temp_dir.path().join(USAGE_FILE_NAME);
+
+        let selector = indexed_selector(Some(usage_path.clone())).await;
+        let before = selector.explain("list", 5, None).await;
+        assert!(before.tools.iter().all(|t| t.usage == 0.0));
+
+        // The github tool gets picked whenever it is offered, the developer one never
+        for _ in 0..3 {
+            selector
+                .select_tools(json!({"query": "list", "k": 5}))
+                .await
+                .unwrap();
+            selector
+                .record_tool_call("github__list_issues")
+                .await
+                .unwrap();
+        }
+
+        // A new session reads the usage back from disk
+        let selector = indexed_selector(Some(usage_path)).await;
+        let after = selector.explain("list", 5, None).await;
+        let score = |name: &str| after.tools.iter().find(|t| t.tool_name == name).unwrap();
+        assert!(score("github__list_issues").usage > 0.0);
+        assert!(score("developer__list_windows").usage < 0.0);
+        assert_eq!(names(&after)[0], "github__list_issues");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sessions_merge_usage() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let usage_path = temp_dir.path().join(USAGE_FILE_NAME);
+
+        // Both sessions load the empty file before either saves
+        let first = indexed_selector(Some(usage_path.clone())).await;
+        let second = indexed_selector(Some(usage_path.clone())).await;
+        for selector in [&first, &second] {
+            selector
+                .select_tools(json!({"query": "issues", "k": 1}))
+                .await
+                .unwrap();
+            selector
+                .record_tool_call("github__list_issues")
+                .await
+                .unwrap();
+        }
+
+        let saved: HashMap<String, ToolUsage> =
+            serde_json::from_str(&std::fs::read_to_string(&usage_path).unwrap()).unwrap();
+        assert_eq!(
+            saved["github__list_issues"],
+            ToolUsage {
+                selected: 2,
+                invoked: 2
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_search_replaces_pending_selections() {
+        let selector = indexed_selector(None).await;
+        selector
+            .select_tools(json!({"query": "issues", "k": 1}))
+            .await
+            .unwrap();
+        selector
+            .select_tools(json!({"query": "windows", "k": 1}))
+            .await
+            .unwrap();
+
+        let pending = selector.pending_selections.read().await.clone();
+        assert_eq!(
+            pending,
+            HashSet::from(["developer__list_windows".to_string()])
+        );
+        selector
+            .record_tool_call("github__list_issues")
+            .await
+            .unwrap();
+        assert_eq!(
+            selector.usage.read().await["github__list_issues"].invoked,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unselected_calls_do_not_count() {
+        let selector = HybridToolSelector::with_options(None, None);
+        selector
+            .index_tools(&developer_tools(), "developer")
+            .await
+            .unwrap();
+        selector.record_tool_call("developer__shell").await.unwrap();
+
+        assert!(selector.usage.read().await.is_empty());
+        assert_eq!(
+            selector.get_recent_tool_calls(5).await.unwrap(),
+            vec!["developer__shell"]
+        );
+    }
+
+    struct KeywordEmbeddingProvider;
+
+    #[async_trait]
+    impl Provider for KeywordEmbeddingProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("mock".to_string())
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            unimplemented!()
+        }
+
+        fn supports_embeddings(&self) -> bool {
+            true
+        }
+
+        // Puts anything about pictures close together, whatever the wording
+        async fn create_embeddings(
+            &self,
+            texts: Vec<String>,
+        ) -> Result<Vec<Vec<f32>>, ProviderError> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let t = t.to_lowercase();
+                    if t.contains("screenshot") || t.contains("picture") {
+                        vec![1.0, 0.0]
+                    } else {
+                        vec![0.0, 1.0]
+                    }
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vector_scores_blend_in() {
+        let selector =
+            HybridToolSelector::with_options(Some(Arc::new(KeywordEmbeddingProvider)), None);
+        selector
+            .index_tools(&developer_tools(), "developer")
+            .await
+            .unwrap();
+
+        // No lexical match at all, the embedding finds the tool
+        let explanation = selector.explain("grab a picture", 1, None).await;
+        assert!(explanation.vector_enabled);
+        assert_eq!(names(&explanation), vec!["developer__screen_capture"]);
+        assert_eq!(explanation.tools[0].lexical, 0.0);
+        assert_eq!(explanation.tools[0].vector, Some(1.0));
+    }
+}
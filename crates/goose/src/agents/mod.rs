@@ -1,8 +1,10 @@
 mod agent;
 mod context;
+pub mod edit_preview;
 pub mod extension;
 pub mod extension_manager;
 pub mod final_output_tool;
+mod hybrid_tool_selector;
 mod large_response_handler;
 pub mod platform_tools;
 pub mod prompt_manager;
@@ -18,6 +20,7 @@ pub mod subagent_execution_tool;
 pub mod subagent_handler;
 mod subagent_task_config;
 mod tool_execution;
+pub mod tool_result_cache;
 mod tool_router_index_manager;
 pub(crate) mod tool_vectordb;
 pub mod types;
@@ -25,7 +28,9 @@ pub mod types;
 pub use agent::{Agent, AgentEvent};
 pub use extension::ExtensionConfig;
 pub use extension_manager::ExtensionManager;
+pub use hybrid_tool_selector::{ToolSelectionExplanation, ToolSelectionScore};
 pub use prompt_manager::PromptManager;
 pub use subagent::{SubAgent, SubAgentProgress, SubAgentStatus};
 pub use subagent_task_config::TaskConfig;
+pub use tool_result_cache::ToolCacheStats;
 pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck};
@@ -4,7 +4,9 @@ use std::collections::HashMap;
 
 use crate::agents::extension::ExtensionInfo;
 use crate::agents::router_tool_selector::RouterToolSelectionStrategy;
-use crate::agents::router_tools::{llm_search_tool_prompt, vector_search_tool_prompt};
+use crate::agents::router_tools::{
+    hybrid_search_tool_prompt, llm_search_tool_prompt, vector_search_tool_prompt,
+};
 use crate::providers::base::get_current_model;
 use crate::system_prompts::SystemPromptManager;
 use crate::{config::Config, prompt_template};
@@ -139,6 +141,12 @@ impl PromptManager {
                     Value::String(llm_search_tool_prompt()),
                 );
             }
+            Some(RouterToolSelectionStrategy::Hybrid) => {
+                context.insert(
+                    "tool_selection_strategy",
+                    Value::String(hybrid_search_tool_prompt()),
+                );
+            }
             None => {}
         }
 
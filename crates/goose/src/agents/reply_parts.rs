@@ -6,13 +6,14 @@ use async_stream::try_stream;
 use futures::stream::StreamExt;
 
 use crate::agents::router_tool_selector::RouterToolSelectionStrategy;
+use crate::agents::tool_result_cache::ToolCacheStats;
 use crate::config::Config;
 use crate::message::{Message, MessageContent, ToolRequest};
 use crate::providers::base::{stream_from_single_message, MessageStream, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
 use crate::providers::toolshim::{
-    augment_message_with_tool_calls, convert_tool_messages_to_text,
-    modify_system_prompt_for_tool_json, OllamaInterpreter,
+    augment_message_with_tool_calls, convert_tool_messages_to_text, create_interpreter,
+    modify_system_prompt_for_tool_json,
 };
 use crate::session;
 use rmcp::model::Tool;
@@ -23,11 +24,11 @@ async fn toolshim_postprocess(
     response: Message,
     toolshim_tools: &[Tool],
 ) -> Result<Message, ProviderError> {
-    let interpreter = OllamaInterpreter::new().map_err(|e| {
-        ProviderError::ExecutionError(format!("Failed to create OllamaInterpreter: {}", e))
+    let interpreter = create_interpreter().map_err(|e| {
+        ProviderError::ExecutionError(format!("Failed to create tool interpreter: {}", e))
     })?;
 
-    augment_message_with_tool_calls(&interpreter, response, toolshim_tools)
+    augment_message_with_tool_calls(interpreter.as_ref(), response, toolshim_tools)
         .await
         .map_err(|e| ProviderError::ExecutionError(format!("Failed to augment message: {}", e)))
 }
@@ -41,11 +42,8 @@ impl Agent {
             .get_param("GOOSE_ROUTER_TOOL_SELECTION_STRATEGY")
             .unwrap_or_else(|_| "default".to_string());
 
-        let tool_selection_strategy = match router_tool_selection_strategy.to_lowercase().as_str() {
-            "vector" => Some(RouterToolSelectionStrategy::Vector),
-            "llm" => Some(RouterToolSelectionStrategy::Llm),
-            _ => None,
-        };
+        let tool_selection_strategy =
+            RouterToolSelectionStrategy::from_config_value(&router_tool_selection_strategy);
 
         // Get tools from extension manager
         let mut tools = match tool_selection_strategy {
@@ -57,6 +55,10 @@ impl Agent {
                 self.list_tools_for_router(Some(RouterToolSelectionStrategy::Llm))
                     .await
             }
+            Some(RouterToolSelectionStrategy::Hybrid) => {
+                self.list_tools_for_router(Some(RouterToolSelectionStrategy::Hybrid))
+                    .await
+            }
             _ => self.list_tools(None).await,
         };
         // Add frontend tools
@@ -66,6 +68,10 @@ impl Agent {
         }
 
         // Prepare system prompt
+        if let Some(cache) = self.tool_result_cache.lock().await.as_mut() {
+            cache.update_tools(&tools);
+        }
+
         let extension_manager = self.extension_manager.read().await;
         let extensions_info = extension_manager.get_extensions_info().await;
 
@@ -277,6 +283,7 @@ impl Agent {
         session_config: &crate::agents::types::SessionConfig,
         usage: &ProviderUsage,
         messages_length: usize,
+        tool_cache_stats: Option<ToolCacheStats>,
     ) -> Result<()> {
         let session_file_path = match session::storage::get_path(session_config.id.clone()) {
             Ok(path) => path,
@@ -309,6 +316,13 @@ impl Agent {
             usage.usage.output_tokens,
         );
 
+        if let Some(stats) = tool_cache_stats {
+            metadata
+                .tool_cache
+                .get_or_insert_with(Default::default)
+                .merge(stats);
+        }
+
         session::storage::update_metadata(&session_file_path, &metadata).await?;
 
         Ok(())
@@ -11,7 +11,8 @@ use std::env;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::agents::tool_vectordb::ToolVectorDB;
+use crate::agents::hybrid_tool_selector::{HybridToolSelector, ToolSelectionExplanation};
+use crate::agents::tool_vectordb::{ToolVectorDB, DEFAULT_EMBEDDING_DIMENSION};
 use crate::message::Message;
 use crate::model::ModelConfig;
 use crate::providers::{self, base::Provider};
@@ -20,6 +21,19 @@ use crate::providers::{self, base::Provider};
 pub enum RouterToolSelectionStrategy {
     Vector,
     Llm,
+    Hybrid,
+}
+
+impl RouterToolSelectionStrategy {
+    /// Parses a GOOSE_ROUTER_TOOL_SELECTION_STRATEGY value, `None` disables the router
+    pub fn from_config_value(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "vector" => Some(Self::Vector),
+            "llm" => Some(Self::Llm),
+            "hybrid" => Some(Self::Hybrid),
+            _ => None,
+        }
+    }
 }
 
 #[async_trait]
@@ -29,9 +43,40 @@ pub trait RouterToolSelector: Send + Sync {
     async fn remove_tool(&self, tool_name: &str) -> Result<(), ToolError>;
     async fn record_tool_call(&self, tool_name: &str) -> Result<(), ToolError>;
     async fn get_recent_tool_calls(&self, limit: usize) -> Result<Vec<String>, ToolError>;
+    /// Scores behind a selection for the same params as `select_tools`, if the selector can explain them
+    async fn explain_selection(
+        &self,
+        _params: Value,
+    ) -> Result<Option<ToolSelectionExplanation>, ToolError> {
+        Ok(None)
+    }
     fn selector_type(&self) -> RouterToolSelectionStrategy;
 }
 
+/// The provider named by GOOSE_EMBEDDING_MODEL_PROVIDER, or the goose provider when it is unset
+pub(crate) fn embedding_provider_from_env(
+    provider: Arc<dyn Provider>,
+) -> Result<Arc<dyn Provider>> {
+    if env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").is_ok() {
+        // If env var is set, create a new provider for embeddings
+        // Get embedding model and provider from environment variables
+        let embedding_model = env::var("GOOSE_EMBEDDING_MODEL")
+            .unwrap_or_else(|_| "text-embedding-3-small".to_string());
+        let embedding_provider_name =
+            env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").unwrap_or_else(|_| "openai".to_string());
+
+        // Create the provider using the factory
+        let model_config = ModelConfig::new(embedding_model);
+        providers::create(&embedding_provider_name, model_config).context(format!(
+            "Failed to create {} provider for embeddings. If using OpenAI, make sure OPENAI_API_KEY env var is set or that you have configured the OpenAI provider via Goose before.",
+            embedding_provider_name
+        ))
+    } else {
+        // Otherwise fall back to using the same provider instance as used for base goose model
+        Ok(provider)
+    }
+}
+
 pub struct VectorToolSelector {
     vector_db: Arc<RwLock<ToolVectorDB>>,
     embedding_provider: Arc<dyn Provider>,
@@ -40,26 +85,12 @@ pub struct VectorToolSelector {
 
 impl VectorToolSelector {
     pub async fn new(provider: Arc<dyn Provider>, table_name: String) -> Result<Self> {
-        let vector_db = ToolVectorDB::new(Some(table_name)).await?;
-
-        let embedding_provider = if env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").is_ok() {
-            // If env var is set, create a new provider for embeddings
-            // Get embedding model and provider from environment variables
-            let embedding_model = env::var("GOOSE_EMBEDDING_MODEL")
-                .unwrap_or_else(|_| "text-embedding-3-small".to_string());
-            let embedding_provider_name =
-                env::var("GOOSE_EMBEDDING_MODEL_PROVIDER").unwrap_or_else(|_| "openai".to_string());
-
-            // Create the provider using the factory
-            let model_config = ModelConfig::new(embedding_model);
-            providers::create(&embedding_provider_name, model_config).context(format!(
-                "Failed to create {} provider for embeddings. If using OpenAI, make sure OPENAI_API_KEY env var is set or that you have configured the OpenAI provider via Goose before.",
-                embedding_provider_name
-            ))?
-        } else {
-            // Otherwise fall back to using the same provider instance as used for base goose model
-            provider.clone()
-        };
+        let embedding_provider = embedding_provider_from_env(provider)?;
+
+        let dimension = embedding_provider
+            .embedding_dimension()
+            .unwrap_or(DEFAULT_EMBEDDING_DIMENSION);
+        let vector_db = ToolVectorDB::new_with_dimension(Some(table_name), dimension).await?;
 
         Ok(Self {
             vector_db: Arc::new(RwLock::new(vector_db)),
@@ -381,6 +412,10 @@ pub async fn create_tool_selector(
             let selector = LLMToolSelector::new(provider).await?;
             Ok(Box::new(selector))
         }
+        Some(RouterToolSelectionStrategy::Hybrid) => {
+            let selector = HybridToolSelector::new(provider).await?;
+            Ok(Box::new(selector))
+        }
         None => {
             let selector = LLMToolSelector::new(provider).await?;
             Ok(Box::new(selector))
@@ -8,6 +8,7 @@ use rmcp::object;
 
 pub const ROUTER_VECTOR_SEARCH_TOOL_NAME: &str = "router__vector_search";
 pub const ROUTER_LLM_SEARCH_TOOL_NAME: &str = "router__llm_search";
+pub const ROUTER_HYBRID_SEARCH_TOOL_NAME: &str = "router__hybrid_search";
 
 pub fn vector_search_tool() -> Tool {
     Tool::new(
@@ -120,3 +121,57 @@ pub fn llm_search_tool_prompt() -> String {
         PLATFORM_LIST_RESOURCES_TOOL_NAME
     )
 }
+
+pub fn hybrid_search_tool() -> Tool {
+    Tool::new(
+        ROUTER_HYBRID_SEARCH_TOOL_NAME.to_string(),
+        indoc! {r#"
+            Searches for relevant tools based on the user's messages.
+            Tools are matched on the words in their names, descriptions and parameters, so format a query made of keywords
+            describing the action and the object it applies to, rather than passing the user's message directly.
+            This tool should be invoked when the user's messages suggest they are asking for a tool to be run.
+            Use the extension_name parameter to filter tools by the appropriate extension when you know which one applies.
+            Example: {"User": "list the files in the current directory", "Query": "list files directory", "Extension Name": "developer", "k": 5}
+            The returned result will be a list of tool names, descriptions, and schemas from which you, the agent can select the most relevant tool to invoke.
+        "#}
+        .to_string(),
+        object!({
+            "type": "object",
+            "required": ["query"],
+            "properties": {
+                "query": {"type": "string", "description": "Keywords describing the tool to search for"},
+                "k": {"type": "integer", "description": "The number of tools to retrieve (defaults to 5)", "default": 5},
+                "extension_name": {"type": "string", "description": "Name of the extension to filter tools by"}
+            }
+        })
+    ).annotate(ToolAnnotations {
+        title: Some("Hybrid search for relevant tools".to_string()),
+        read_only_hint: Some(true),
+        destructive_hint: Some(false),
+        idempotent_hint: Some(false),
+        open_world_hint: Some(false),
+    })
+}
+
+pub fn hybrid_search_tool_prompt() -> String {
+    format!(
+        r#"# Tool Selection Instructions
+    Important: the user has opted to dynamically enable tools, so although an extension could be enabled, \
+    please invoke the hybrid search tool to actually retrieve the most relevant tools to use according to the user's messages.
+    For example, if the user has 3 extensions enabled, but they are asking for a tool to read a pdf file, \
+    you would invoke the hybrid_search tool with a query like "read pdf file" to find the most relevant tool.
+    By dynamically enabling tools, you (Goose) as the agent save context window space and allow the user to dynamically retrieve the most relevant tools.
+    Be sure to format a query of keywords naming the action and its object, matching is done on the words in tool names and descriptions.
+    In addition to the extension names available to you, you also have platform extension tools available to you.
+    The platform extension contains the following tools:
+    - {}
+    - {}
+    - {}
+    - {}
+    "#,
+        PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME,
+        PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME,
+        PLATFORM_READ_RESOURCE_TOOL_NAME,
+        PLATFORM_LIST_RESOURCES_TOOL_NAME
+    )
+}
@@ -3,15 +3,20 @@ use std::sync::Arc;
 
 use async_stream::try_stream;
 use futures::stream::{self, BoxStream};
-use futures::{Stream, StreamExt};
+use futures::{FutureExt, Stream, StreamExt};
 use rmcp::model::ServerNotification;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
+use crate::agents::edit_preview::{is_allowed_override, ProposedEdit};
+use crate::audit::{DecisionSource, PermissionDecision};
 use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
-use crate::message::{Message, ToolRequest};
-use crate::permission::Permission;
+use crate::hooks::{HookEvent, HookInput};
+use crate::message::{Message, MessageContent, ToolConfirmationRequest, ToolRequest};
+use crate::permission::permission_confirmation::PrincipalType;
+use crate::permission::{Permission, PermissionConfirmation};
+use mcp_core::tool::ToolCall;
 use mcp_core::ToolResult;
 use rmcp::model::Content;
 
@@ -32,12 +37,23 @@ impl From<ToolResult<Vec<Content>>> for ToolCallResult {
 }
 
 use super::agent::{tool_stream, ToolStream};
-use crate::agents::Agent;
+use crate::agents::{Agent, AgentEvent};
 
 pub const DECLINED_RESPONSE: &str = "The user has declined to run this tool. \
     DO NOT attempt to call this tool again. \
     If there are no alternative methods to proceed, clearly explain the situation and STOP.";
 
+pub const CHANGED_BY_USER_RESPONSE: &str = "The user changed the arguments of this tool call \
+    before allowing it, so its effect differs from what was requested. \
+    Check the result, for example by viewing the file again, before relying on it.";
+
+pub const CHANGED_BY_HOOK_RESPONSE: &str = "A permission_request hook changed the arguments of \
+    this tool call, so its effect differs from what was requested. \
+    Check the result, for example by viewing the file again, before relying on it.";
+
+pub const OVERRIDE_REJECTED_RESPONSE: &str = "The user approved this tool call with different \
+    arguments, which is not supported for this tool, so it was not run.";
+
 pub const CHAT_MODE_TOOL_SKIPPED_RESPONSE: &str = "Let the user know the tool call was skipped in Goose chat mode. \
                                         DO NOT apologize for skipping the tool call. DO NOT say sorry. \
                                         Provide an explanation of what the tool call would do, structured as a \
@@ -55,48 +71,137 @@ impl Agent {
         permission_manager: &'a mut PermissionManager,
         message_tool_response: Arc<Mutex<Message>>,
         cancellation_token: Option<CancellationToken>,
-    ) -> BoxStream<'a, anyhow::Result<Message>> {
+        session_id: Option<String>,
+    ) -> BoxStream<'a, anyhow::Result<AgentEvent>> {
         try_stream! {
             for request in tool_requests {
                 if let Ok(tool_call) = request.tool_call.clone() {
-                    let confirmation = Message::user().with_tool_confirmation_request(
-                        request.id.clone(),
-                        tool_call.name.clone(),
-                        tool_call.arguments.clone(),
-                        Some("Goose would like to call the above tool. Allow? (y/n):".to_string()),
-                    );
-                    yield confirmation;
-
-                    let mut rx = self.confirmation_rx.lock().await;
-                    while let Some((req_id, confirmation)) = rx.recv().await {
-                        if req_id == request.id {
-                            if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
-                                let (req_id, tool_result) = self.dispatch_tool_call(tool_call.clone(), request.id.clone(), cancellation_token.clone()).await;
-                                let mut futures = tool_futures.lock().await;
-
-                                futures.push((req_id, match tool_result {
-                                    Ok(result) => tool_stream(
-                                        result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
-                                        result.result,
-                                    ),
-                                    Err(e) => tool_stream(
-                                        Box::new(stream::empty()),
-                                        futures::future::ready(Err(e)),
-                                    ),
-                                }));
-
-                                if confirmation.permission == Permission::AlwaysAllow {
-                                    permission_manager.update_user_permission(&tool_call.name, PermissionLevel::AlwaysAllow);
-                                }
+                    // A permission_request hook can answer before the user is asked
+                    let hook_outcome = self.run_hooks(
+                        HookInput::new(HookEvent::PermissionRequest, session_id.clone())
+                            .with_tool(&tool_call.name, &tool_call.arguments),
+                    ).await;
+                    if let Some(reason) = hook_outcome.blocked {
+                        self.audit_not_run(request, session_id.clone(), PermissionDecision::Denied, DecisionSource::Hook).await;
+                        {
+                            let mut response = message_tool_response.lock().await;
+                            *response = response.clone().with_tool_response(
+                                request.id.clone(),
+                                Ok(vec![Content::text(format!(
+                                    "A permission_request hook denied this tool call: {}",
+                                    reason
+                                ))]),
+                            );
+                        }
+                        yield AgentEvent::ToolDenied { id: request.id.clone(), tool_name: tool_call.name.clone() };
+                        continue;
+                    }
+
+                    // Arguments a hook changed are what gets approved, whether the hook allows
+                    // the call or the user is asked about it
+                    let (tool_call, changed_by_hook) = match hook_outcome.arguments {
+                        Some(arguments) => (ToolCall::new(&tool_call.name, arguments), true),
+                        None => (tool_call, false),
+                    };
+
+                    let (confirmation, decided_by) = if hook_outcome.allowed {
+                        let confirmation = PermissionConfirmation {
+                            principal_type: PrincipalType::Tool,
+                            permission: Permission::AllowOnce,
+                            arguments: None,
+                        };
+                        (Some(confirmation), DecisionSource::Hook)
+                    } else {
+                        let edit_preview = ProposedEdit::from_tool_call(&tool_call.name, &tool_call.arguments)
+                            .and_then(|edit| edit.preview());
+                        let confirmation = Message::user().with_content(MessageContent::ToolConfirmationRequest(
+                            ToolConfirmationRequest {
+                                id: request.id.clone(),
+                                tool_name: tool_call.name.clone(),
+                                arguments: tool_call.arguments.clone(),
+                                prompt: Some("Goose would like to call the above tool. Allow? (y/n):".to_string()),
+                                edit_preview,
+                            },
+                        ));
+                        yield AgentEvent::Message(confirmation);
+
+                        let mut answer = None;
+                        let mut rx = self.confirmation_rx.lock().await;
+                        while let Some((req_id, confirmation)) = rx.recv().await {
+                            // Skip answers to other requests until this one is answered
+                            if req_id == request.id {
+                                answer = Some(confirmation);
+                                break;
+                            }
+                        }
+                        (answer, DecisionSource::User)
+                    };
+
+                    if let Some(confirmation) = confirmation {
+                        // Users can only change the file edits they were shown a preview of,
+                        // hooks are configured by the user and may change any call
+                        let rejected_override = decided_by == DecisionSource::User
+                            && confirmation.permission != Permission::DenyOnce
+                            && confirmation.arguments.as_ref().is_some_and(|arguments| {
+                                !is_allowed_override(&tool_call.name, &tool_call.arguments, arguments)
+                            });
+                        if rejected_override {
+                            tracing::warn!("Rejected changed arguments for {}", tool_call.name);
+                            self.audit_not_run(request, session_id.clone(), PermissionDecision::Denied, decided_by).await;
+                            let mut response = message_tool_response.lock().await;
+                            *response = response.clone().with_tool_response(
+                                request.id.clone(),
+                                Ok(vec![Content::text(OVERRIDE_REJECTED_RESPONSE)]),
+                            );
+                            continue;
+                        }
+
+                        if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
+                            let changed_note = if confirmation.arguments.is_some() {
+                                Some(CHANGED_BY_USER_RESPONSE)
                             } else {
-                                // User declined - add declined response
+                                changed_by_hook.then_some(CHANGED_BY_HOOK_RESPONSE)
+                            };
+                            let approved_call = match confirmation.arguments {
+                                Some(arguments) => ToolCall::new(&tool_call.name, arguments),
+                                None => tool_call.clone(),
+                            };
+                            let (req_id, tool_result) = self.dispatch_approved_tool_call(approved_call, request.id.clone(), cancellation_token.clone(), session_id.clone(), decided_by).await;
+                            let mut futures = tool_futures.lock().await;
+
+                            futures.push((req_id, match tool_result {
+                                // The model should not assume its own arguments were used
+                                Ok(result) if changed_note.is_some() => tool_stream(
+                                    result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
+                                    result.result.map(move |output| output.map(|mut contents| {
+                                        contents.extend(changed_note.map(Content::text));
+                                        contents
+                                    })),
+                                ),
+                                Ok(result) => tool_stream(
+                                    result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
+                                    result.result,
+                                ),
+                                Err(e) => tool_stream(
+                                    Box::new(stream::empty()),
+                                    futures::future::ready(Err(e)),
+                                ),
+                            }));
+
+                            if confirmation.permission == Permission::AlwaysAllow {
+                                permission_manager.update_user_permission(&tool_call.name, PermissionLevel::AlwaysAllow);
+                            }
+                        } else {
+                            // User declined - add declined response
+                            self.audit_not_run(request, session_id.clone(), PermissionDecision::Denied, decided_by).await;
+                            {
                                 let mut response = message_tool_response.lock().await;
                                 *response = response.clone().with_tool_response(
                                     request.id.clone(),
                                     Ok(vec![Content::text(DECLINED_RESPONSE)]),
                                 );
                             }
-                            break; // Exit the loop once the matching `req_id` is found
+                            yield AgentEvent::ToolDenied { id: request.id.clone(), tool_name: tool_call.name.clone() };
                         }
                     }
                 }
@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+
+use rmcp::model::{Content, Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Hit and miss counters for the tool result cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ToolCacheStats {
+    /// Number of tool calls answered from the cache
+    pub hits: u64,
+    /// Number of cacheable tool calls that had to be dispatched
+    pub misses: u64,
+}
+
+impl ToolCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+
+    pub fn merge(&mut self, other: ToolCacheStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+    }
+}
+
+/// Caches results of read-only, idempotent tool calls so that repeated reads within a
+/// session are answered without dispatching to the extension again.
+///
+/// A tool is cacheable only if its annotations declare both `read_only_hint` and
+/// `idempotent_hint`. Any call to a tool that is not read-only clears the cache, since
+/// it may have changed whatever the cached reads observed. Reads that were already in
+/// flight when the cache was cleared are not stored, since they may predate the change.
+#[derive(Debug, Default)]
+pub struct ToolResultCache {
+    cacheable_tools: HashSet<String>,
+    read_only_tools: HashSet<String>,
+    entries: HashMap<String, Vec<Content>>,
+    generation: u64,
+    stats: ToolCacheStats,
+    unreported: ToolCacheStats,
+}
+
+impl ToolResultCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refresh which tools are cacheable from the annotations of the current tool list
+    pub fn update_tools(&mut self, tools: &[Tool]) {
+        self.cacheable_tools.clear();
+        self.read_only_tools.clear();
+        for tool in tools {
+            let Some(annotations) = &tool.annotations else {
+                continue;
+            };
+            if annotations.read_only_hint.unwrap_or(false) {
+                self.read_only_tools.insert(tool.name.to_string());
+                if annotations.idempotent_hint.unwrap_or(false) {
+                    self.cacheable_tools.insert(tool.name.to_string());
+                }
+            }
+        }
+    }
+
+    pub fn is_cacheable(&self, tool_name: &str) -> bool {
+        self.cacheable_tools.contains(tool_name)
+    }
+
+    /// Whether a call to this tool may modify state and so must invalidate cached results.
+    ///
+    /// `text_editor` is annotated as a write tool as a whole, but its `view` command is a
+    /// plain read and leaves the cache intact.
+    pub fn invalidates(&self, tool_name: &str, arguments: &Value) -> bool {
+        if is_text_editor(tool_name) {
+            return arguments.get("command").and_then(|v| v.as_str()) != Some("view");
+        }
+        !self.read_only_tools.contains(tool_name)
+    }
+
+    /// Look up a cached result, recording a hit or miss
+    pub fn get(&mut self, tool_name: &str, arguments: &Value) -> Option<Vec<Content>> {
+        let key = cache_key(tool_name, arguments);
+        match self.entries.get(&key) {
+            Some(result) => {
+                self.stats.hits += 1;
+                self.unreported.hits += 1;
+                Some(result.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                self.unreported.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// The current generation, to pass to `insert` once a dispatched read completes
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Store a result read during `generation`, dropping it if the cache was invalidated since
+    pub fn insert(
+        &mut self,
+        tool_name: &str,
+        arguments: &Value,
+        result: Vec<Content>,
+        generation: u64,
+    ) {
+        if generation == self.generation {
+            self.entries.insert(cache_key(tool_name, arguments), result);
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.entries.clear();
+        self.generation += 1;
+    }
+
+    pub fn stats(&self) -> ToolCacheStats {
+        self.stats
+    }
+
+    /// Return the hits and misses recorded since the last call, for accumulating into
+    /// session metadata
+    pub fn take_unreported_stats(&mut self) -> ToolCacheStats {
+        std::mem::take(&mut self.unreported)
+    }
+}
+
+fn is_text_editor(tool_name: &str) -> bool {
+    tool_name == "text_editor" || tool_name.ends_with("__text_editor")
+}
+
+/// Build a cache key from the tool name and its arguments with object keys sorted, so
+/// that `{"a":1,"b":2}` and `{"b":2,"a":1}` share an entry
+fn cache_key(tool_name: &str, arguments: &Value) -> String {
+    format!("{}:{}", tool_name, canonicalize(arguments))
+}
+
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let mut sorted = serde_json::Map::new();
+            for key in keys {
+                sorted.insert(key.clone(), canonicalize(&map[key]));
+            }
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::ToolAnnotations;
+    use rmcp::object;
+    use serde_json::json;
+
+    fn tool(name: &str, read_only: bool, idempotent: bool) -> Tool {
+        Tool::new(
+            name.to_string(),
+            "test tool".to_string(),
+            object!({"type": "object"}),
+        )
+        .annotate(ToolAnnotations {
+            title: None,
+            read_only_hint: Some(read_only),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(idempotent),
+            open_world_hint: Some(false),
+        })
+    }
+
+    fn cache() -> ToolResultCache {
+        let mut cache = ToolResultCache::new();
+        cache.update_tools(&[
+            tool("dev__grep", true, true),
+            tool("memory__retrieve", true, false),
+            tool("dev__shell", false, false),
+        ]);
+        cache
+    }
+
+    #[test]
+    fn test_only_read_only_idempotent_tools_are_cacheable() {
+        let cache = cache();
+        assert!(cache.is_cacheable("dev__grep"));
+        assert!(!cache.is_cacheable("memory__retrieve"));
+        assert!(!cache.is_cacheable("dev__shell"));
+        assert!(!cache.is_cacheable("unknown__tool"));
+    }
+
+    #[test]
+    fn test_hit_with_reordered_arguments() {
+        let mut cache = cache();
+        let args = json!({"pattern": "foo", "path": "src"});
+        assert!(cache.get("dev__grep", &args).is_none());
+        let generation = cache.generation();
+        cache.insert("dev__grep", &args, vec![Content::text("match")], generation);
+
+        let reordered = json!({"path": "src", "pattern": "foo"});
+        let hit = cache.get("dev__grep", &reordered).unwrap();
+        assert_eq!(hit[0].as_text().unwrap().text, "match");
+        assert_eq!(cache.stats(), ToolCacheStats { hits: 1, misses: 1 });
+        assert_eq!(cache.stats().hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn test_invalidation() {
+        let cache = cache();
+        assert!(cache.invalidates("dev__shell", &json!({"command": "ls"})));
+        assert!(cache.invalidates("unknown__tool", &json!({})));
+        assert!(!cache.invalidates("dev__grep", &json!({})));
+        assert!(!cache.invalidates("memory__retrieve", &json!({})));
+        assert!(!cache.invalidates(
+            "developer__text_editor",
+            &json!({"command": "view", "path": "/tmp/a"})
+        ));
+        assert!(cache.invalidates(
+            "developer__text_editor",
+            &json!({"command": "write", "path": "/tmp/a", "file_text": ""})
+        ));
+    }
+
+    #[test]
+    fn test_stale_insert_after_invalidation_is_dropped() {
+        let mut cache = cache();
+        let args = json!({"pattern": "foo"});
+        assert!(cache.get("dev__grep", &args).is_none());
+        let generation = cache.generation();
+
+        // A write lands while the read is still in flight
+        cache.invalidate();
+        cache.insert("dev__grep", &args, vec![Content::text("stale")], generation);
+        assert!(cache.get("dev__grep", &args).is_none());
+
+        let generation = cache.generation();
+        cache.insert("dev__grep", &args, vec![Content::text("fresh")], generation);
+        let hit = cache.get("dev__grep", &args).unwrap();
+        assert_eq!(hit[0].as_text().unwrap().text, "fresh");
+    }
+
+    #[test]
+    fn test_take_unreported_stats() {
+        let mut cache = cache();
+        let args = json!({});
+        cache.get("dev__grep", &args);
+        assert_eq!(
+            cache.take_unreported_stats(),
+            ToolCacheStats { hits: 0, misses: 1 }
+        );
+        assert_eq!(cache.take_unreported_stats(), ToolCacheStats::default());
+        assert_eq!(cache.stats().misses, 1);
+    }
+}
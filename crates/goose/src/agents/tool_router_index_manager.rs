@@ -102,10 +102,14 @@ impl ToolRouterIndexManager {
         Ok(())
     }
 
-    /// Helper to check if vector or llm tool router is enabled
+    /// Helper to check if vector, llm or hybrid tool router is enabled
     pub fn is_tool_router_enabled(selector: &Option<Arc<Box<dyn RouterToolSelector>>>) -> bool {
         selector.is_some()
-            && (selector.as_ref().unwrap().selector_type() == RouterToolSelectionStrategy::Vector
-                || selector.as_ref().unwrap().selector_type() == RouterToolSelectionStrategy::Llm)
+            && matches!(
+                selector.as_ref().unwrap().selector_type(),
+                RouterToolSelectionStrategy::Vector
+                    | RouterToolSelectionStrategy::Llm
+                    | RouterToolSelectionStrategy::Hybrid
+            )
     }
 }
@@ -14,6 +14,9 @@ use tokio::sync::RwLock;
 
 use crate::config::Config;
 
+/// Length of OpenAI's text-embedding-3-small vectors, used unless the provider reports another
+pub const DEFAULT_EMBEDDING_DIMENSION: usize = 1536;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolRecord {
     pub tool_name: String,
@@ -26,10 +29,16 @@ pub struct ToolRecord {
 pub struct ToolVectorDB {
     connection: Arc<RwLock<Connection>>,
     table_name: String,
+    dimension: i32,
 }
 
 impl ToolVectorDB {
     pub async fn new(table_name: Option<String>) -> Result<Self> {
+        Self::new_with_dimension(table_name, DEFAULT_EMBEDDING_DIMENSION).await
+    }
+
+    /// Create the database for embedding vectors of the given length
+    pub async fn new_with_dimension(table_name: Option<String>, dimension: usize) -> Result<Self> {
         let db_path = Self::get_db_path()?;
 
         // Ensure the directory exists
@@ -47,6 +56,7 @@ impl ToolVectorDB {
         let tool_db = Self {
             connection: Arc::new(RwLock::new(connection)),
             table_name: table_name.unwrap_or_else(|| "tools".to_string()),
+            dimension: dimension as i32,
         };
 
         // Initialize the table if it doesn't exist
@@ -101,7 +111,7 @@ impl ToolVectorDB {
                     "vector",
                     DataType::FixedSizeList(
                         Arc::new(Field::new("item", DataType::Float32, true)),
-                        1536, // OpenAI embedding dimension
+                        self.dimension,
                     ),
                     false,
                 ),
@@ -116,7 +126,7 @@ impl ToolVectorDB {
 
             // Create empty fixed size list array for vectors
             let mut vectors_builder =
-                FixedSizeListBuilder::new(arrow::array::Float32Builder::new(), 1536);
+                FixedSizeListBuilder::new(arrow::array::Float32Builder::new(), self.dimension);
             let vectors = vectors_builder.finish();
 
             let batch = arrow::record_batch::RecordBatch::try_new(
@@ -202,7 +212,7 @@ impl ToolVectorDB {
                 "vector",
                 DataType::FixedSizeList(
                     Arc::new(Field::new("item", DataType::Float32, true)),
-                    1536,
+                    self.dimension,
                 ),
                 false,
             ),
@@ -215,7 +225,7 @@ impl ToolVectorDB {
         let extension_names_array = StringArray::from(extension_names);
         // Build vectors array
         let mut vectors_builder =
-            FixedSizeListBuilder::new(arrow::array::Float32Builder::new(), 1536);
+            FixedSizeListBuilder::new(arrow::array::Float32Builder::new(), self.dimension);
         for vector_opt in vectors_data {
             if let Some(vector) = vector_opt {
                 let values = vectors_builder.values();
@@ -515,6 +525,33 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_tool_vectordb_custom_dimension() -> Result<()> {
+        // Local sentence models produce shorter vectors than OpenAI
+        let db = ToolVectorDB::new_with_dimension(
+            Some("test_tool_vectordb_custom_dimension".to_string()),
+            384,
+        )
+        .await?;
+        db.clear_tools().await?;
+
+        db.index_tools(vec![ToolRecord {
+            tool_name: "small_tool".to_string(),
+            description: "A tool indexed with a small embedding".to_string(),
+            schema: "{}".to_string(),
+            vector: vec![0.1; 384],
+            extension_name: "test_extension".to_string(),
+        }])
+        .await?;
+
+        let results = db.search_tools(vec![0.1; 384], 1, None).await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].tool_name, "small_tool");
+
+        Ok(())
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_custom_db_path_override() -> Result<()> {
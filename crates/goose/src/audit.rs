@@ -0,0 +1,656 @@
+//! Append-only, hash-chained audit log of tool requests.
+//!
+//! Each entry records what the agent asked to run, how the permission decision was made
+//! and how the call went. Entries are chained by including the previous entry's hash in
+//! the hash of the next one, so editing, reordering or deleting a line breaks the chain
+//! from that point on and is reported by [`AuditLog::verify`].
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use etcetera::{choose_app_strategy, AppStrategy};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::config::APP_STRATEGY;
+
+/// Hash used as the predecessor of the first entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// What happened to a tool request at the permission stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionDecision {
+    Allowed,
+    Denied,
+    /// The request was not run because the agent is in chat mode
+    Skipped,
+}
+
+/// Who or what made the permission decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionSource {
+    /// The user answered a confirmation prompt
+    User,
+    /// An argument rule in permission.yaml
+    Rule,
+    /// A stored tool-level permission in permission.yaml
+    PermissionStore,
+    /// Smart approve, from tool annotations, a remembered verdict or the LLM judge
+    SmartApprove,
+    /// The configured GOOSE_MODE, e.g. `auto` or `chat`
+    Mode,
+    /// The tool was dispatched directly, without going through a permission check
+    Caller,
+    /// A permission_request hook answered instead of the user, or a before_tool_call hook
+    /// blocked the call
+    Hook,
+}
+
+/// Outcome of a tool request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolStatus {
+    Success,
+    Error,
+    /// The request never ran because permission was denied or it was skipped
+    NotRun,
+}
+
+/// The fields of an audit entry supplied by the caller
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    pub session_id: Option<String>,
+    pub request_id: String,
+    pub tool: String,
+    pub arguments: Value,
+    pub decision: PermissionDecision,
+    pub decided_by: DecisionSource,
+    pub status: ToolStatus,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// A single line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntry {
+    /// Position in the log, starting at 1
+    pub seq: u64,
+    #[schema(value_type = String)]
+    pub timestamp: DateTime<Utc>,
+    pub session_id: Option<String>,
+    pub request_id: String,
+    pub tool: String,
+    #[schema(value_type = Object)]
+    pub arguments: Value,
+    pub decision: PermissionDecision,
+    pub decided_by: DecisionSource,
+    pub status: ToolStatus,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+    /// Hash of the previous entry
+    pub prev_hash: String,
+    /// SHA-256 over the previous hash and this entry's other fields
+    pub hash: String,
+}
+
+impl AuditEntry {
+    fn compute_hash(&self) -> String {
+        let content = serde_json::json!({
+            "seq": self.seq,
+            "timestamp": self.timestamp,
+            "session_id": self.session_id,
+            "request_id": self.request_id,
+            "tool": self.tool,
+            "arguments": self.arguments,
+            "decision": self.decision,
+            "decided_by": self.decided_by,
+            "status": self.status,
+            "duration_ms": self.duration_ms,
+            "error": self.error,
+        });
+        let mut hasher = Sha256::new();
+        hasher.update(self.prev_hash.as_bytes());
+        hasher.update(content.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Filters for [`AuditLog::query`]
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct AuditQuery {
+    pub session_id: Option<String>,
+    /// Only entries for this tool; `*` matches any characters
+    pub tool: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub since: Option<DateTime<Utc>>,
+    pub decision: Option<PermissionDecision>,
+    /// Return at most this many of the most recent matching entries
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn tool_regex(&self) -> Option<regex::Regex> {
+        let tool = self.tool.as_ref()?;
+        regex::Regex::new(&format!("^{}$", regex::escape(tool).replace(r"\*", ".*"))).ok()
+    }
+
+    /// Whether the entry matches, given the tool pattern compiled by [`Self::tool_regex`]
+    fn matches(&self, entry: &AuditEntry, tool_regex: Option<&regex::Regex>) -> bool {
+        if let Some(session_id) = &self.session_id {
+            if entry.session_id.as_ref() != Some(session_id) {
+                return false;
+            }
+        }
+        if self.tool.is_some() && !tool_regex.is_some_and(|re| re.is_match(&entry.tool)) {
+            return false;
+        }
+        if let Some(since) = self.since {
+            if entry.timestamp < since {
+                return false;
+            }
+        }
+        if let Some(decision) = self.decision {
+            if entry.decision != decision {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Result of checking the hash chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct AuditVerification {
+    /// Number of entries checked
+    pub entries: u64,
+    /// Line number of the first entry that breaks the chain, if any
+    pub first_invalid_line: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl AuditVerification {
+    pub fn is_valid(&self) -> bool {
+        self.first_invalid_line.is_none()
+    }
+}
+
+/// Where the chain ended after this process last appended to the log
+#[derive(Debug, Clone)]
+struct ChainTail {
+    seq: u64,
+    hash: String,
+    /// Length of the file after that append. Any other length means another process has
+    /// written since, and the tail must be read back from the file.
+    len: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    tail: Arc<Mutex<Option<ChainTail>>>,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        let data_dir = choose_app_strategy(APP_STRATEGY.clone())
+            .expect("goose requires a home dir")
+            .data_dir();
+        Self::new(data_dir.join("audit").join("audit.jsonl"))
+    }
+}
+
+impl AuditLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            tail: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record, chaining it to the last entry in the log.
+    ///
+    /// The file is locked for the duration of the append so that concurrent goose
+    /// processes writing to the same log cannot fork the chain. The last hash is kept in
+    /// memory, so the log is only read back when another process has appended since.
+    pub fn append(&self, record: AuditRecord) -> Result<AuditEntry> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log {}", self.path.display()))?;
+        file.lock_exclusive()?;
+
+        let result = (|| {
+            let len = file.metadata()?.len();
+            let mut tail = self.tail.lock().unwrap();
+            let (seq, prev_hash) = match tail.as_ref().filter(|tail| tail.len == len) {
+                Some(tail) => (tail.seq + 1, tail.hash.clone()),
+                None => match last_entry(&file)? {
+                    Some(last) => (last.seq + 1, last.hash),
+                    None => (1, GENESIS_HASH.to_string()),
+                },
+            };
+            let mut entry = AuditEntry {
+                seq,
+                timestamp: Utc::now(),
+                session_id: record.session_id,
+                request_id: record.request_id,
+                tool: record.tool,
+                arguments: record.arguments,
+                decision: record.decision,
+                decided_by: record.decided_by,
+                status: record.status,
+                duration_ms: record.duration_ms,
+                error: record.error,
+                prev_hash,
+                hash: String::new(),
+            };
+            entry.hash = entry.compute_hash();
+
+            let mut line = serde_json::to_string(&entry)?;
+            line.push('\n');
+            file.write_all(line.as_bytes())?;
+            file.flush()?;
+            *tail = Some(ChainTail {
+                seq: entry.seq,
+                hash: entry.hash.clone(),
+                len: len + line.len() as u64,
+            });
+            Ok(entry)
+        })();
+
+        FileExt::unlock(&file)?;
+        result
+    }
+
+    /// Append a record from async code without blocking the runtime on file IO
+    pub async fn append_async(&self, record: AuditRecord) -> Result<AuditEntry> {
+        let log = self.clone();
+        tokio::task::spawn_blocking(move || log.append(record)).await?
+    }
+
+    /// Return entries matching the query, oldest first
+    pub fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let reader = BufReader::new(File::open(&self.path)?);
+        let tool_regex = query.tool_regex();
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: AuditEntry = serde_json::from_str(&line)?;
+            if query.matches(&entry, tool_regex.as_ref()) {
+                entries.push(entry);
+            }
+        }
+        if let Some(limit) = query.limit {
+            let skip = entries.len().saturating_sub(limit);
+            entries.drain(..skip);
+        }
+        Ok(entries)
+    }
+
+    /// Recompute every hash and check that each entry links to the one before it
+    pub fn verify(&self) -> Result<AuditVerification> {
+        let mut verification = AuditVerification {
+            entries: 0,
+            first_invalid_line: None,
+            error: None,
+        };
+        if !self.path.exists() {
+            return Ok(verification);
+        }
+
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut prev_hash = GENESIS_HASH.to_string();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            // Blank lines are skipped, the same as when querying
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line_number = index as u64 + 1;
+            verification.entries += 1;
+            let expected_seq = verification.entries;
+
+            let problem = match serde_json::from_str::<AuditEntry>(&line) {
+                Err(e) => Some(format!("unparseable entry: {}", e)),
+                Ok(entry) if entry.seq != expected_seq => Some(format!(
+                    "expected seq {}, found {}",
+                    expected_seq, entry.seq
+                )),
+                Ok(entry) if entry.prev_hash != prev_hash => {
+                    Some("previous hash does not match the preceding entry".to_string())
+                }
+                Ok(entry) if entry.compute_hash() != entry.hash => {
+                    Some("entry hash does not match its contents".to_string())
+                }
+                Ok(entry) => {
+                    prev_hash = entry.hash;
+                    None
+                }
+            };
+            if let Some(problem) = problem {
+                verification.first_invalid_line = Some(line_number);
+                verification.error = Some(problem);
+                break;
+            }
+        }
+        Ok(verification)
+    }
+}
+
+/// The entry of a tool call that is running. It is written when the call finishes, or when the
+/// call is dropped before then, so cancelled calls are recorded too.
+pub struct PendingAudit {
+    audit_log: Arc<AuditLog>,
+    record: Option<AuditRecord>,
+    started: Instant,
+}
+
+impl PendingAudit {
+    pub fn new(audit_log: Arc<AuditLog>, record: AuditRecord) -> Self {
+        Self {
+            audit_log,
+            record: Some(record),
+            started: Instant::now(),
+        }
+    }
+
+    fn complete(&mut self, outcome: Result<(), String>) -> Option<AuditRecord> {
+        let mut record = self.record.take()?;
+        record.duration_ms = Some(self.started.elapsed().as_millis() as u64);
+        if let Err(e) = outcome {
+            record.status = ToolStatus::Error;
+            record.error = Some(e);
+        }
+        Some(record)
+    }
+
+    /// Write the entry with how the call went
+    pub async fn finish(mut self, outcome: Result<(), String>) {
+        if let Some(record) = self.complete(outcome) {
+            if let Err(e) = self.audit_log.append_async(record).await {
+                tracing::error!("Failed to write audit log entry: {}", e);
+            }
+        }
+    }
+}
+
+impl Drop for PendingAudit {
+    fn drop(&mut self) {
+        let Some(record) = self.complete(Err("Cancelled before it finished".to_string())) else {
+            return;
+        };
+        let audit_log = self.audit_log.clone();
+        let write = move || {
+            if let Err(e) = audit_log.append(record) {
+                tracing::error!("Failed to write audit log entry: {}", e);
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(write);
+            }
+            Err(_) => write(),
+        }
+    }
+}
+
+fn last_entry(file: &File) -> Result<Option<AuditEntry>> {
+    let reader = BufReader::new(file);
+    let mut last = None;
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            last = Some(line);
+        }
+    }
+    last.map(|line| serde_json::from_str(&line).context("Audit log has a corrupt last entry"))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn record(session: &str, tool: &str, decision: PermissionDecision) -> AuditRecord {
+        AuditRecord {
+            session_id: Some(session.to_string()),
+            request_id: "req".to_string(),
+            tool: tool.to_string(),
+            arguments: json!({"command": "ls"}),
+            decision,
+            decided_by: DecisionSource::Rule,
+            status: ToolStatus::Success,
+            duration_ms: Some(5),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_append_chains_and_verifies() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("audit").join("audit.jsonl"));
+
+        let first = log
+            .append(record(
+                "s1",
+                "developer__shell",
+                PermissionDecision::Allowed,
+            ))
+            .unwrap();
+        let second = log
+            .append(record("s2", "memory__remember", PermissionDecision::Denied))
+            .unwrap();
+
+        assert_eq!(first.seq, 1);
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert_eq!(second.seq, 2);
+        assert_eq!(second.prev_hash, first.hash);
+
+        let verification = log.verify().unwrap();
+        assert!(verification.is_valid());
+        assert_eq!(verification.entries, 2);
+    }
+
+    #[test]
+    fn test_interleaved_writers_keep_the_chain() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        // Separate instances stand in for separate goose processes
+        let first = AuditLog::new(&path);
+        let second = AuditLog::new(&path);
+
+        let a = first
+            .append(record(
+                "s1",
+                "developer__shell",
+                PermissionDecision::Allowed,
+            ))
+            .unwrap();
+        let b = second
+            .append(record(
+                "s2",
+                "developer__shell",
+                PermissionDecision::Allowed,
+            ))
+            .unwrap();
+        let c = first
+            .append(record("s1", "developer__shell", PermissionDecision::Denied))
+            .unwrap();
+
+        assert_eq!((a.seq, b.seq, c.seq), (1, 2, 3));
+        assert_eq!(c.prev_hash, b.hash);
+        assert!(first.verify().unwrap().is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_append_async() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        let entry = log
+            .append_async(record(
+                "s1",
+                "developer__shell",
+                PermissionDecision::Allowed,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(entry.seq, 1);
+        assert!(log.verify().unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        for _ in 0..3 {
+            log.append(record(
+                "s1",
+                "developer__shell",
+                PermissionDecision::Allowed,
+            ))
+            .unwrap();
+        }
+
+        let contents = fs::read_to_string(log.path()).unwrap();
+        let tampered: Vec<String> = contents
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                if i == 1 {
+                    line.replace("\"allowed\"", "\"denied\"")
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        fs::write(log.path(), tampered.join("\n") + "\n").unwrap();
+
+        let verification = log.verify().unwrap();
+        assert_eq!(verification.first_invalid_line, Some(2));
+
+        // Dropping a line breaks the chain at the entry that followed it
+        let lines: Vec<&str> = contents.lines().collect();
+        fs::write(log.path(), format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert_eq!(log.verify().unwrap().first_invalid_line, Some(2));
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        log.append(record(
+            "s1",
+            "developer__shell",
+            PermissionDecision::Allowed,
+        ))
+        .unwrap();
+        log.append(record(
+            "s1",
+            "developer__text_editor",
+            PermissionDecision::Denied,
+        ))
+        .unwrap();
+        log.append(record(
+            "s2",
+            "memory__remember",
+            PermissionDecision::Allowed,
+        ))
+        .unwrap();
+
+        let by_session = log
+            .query(&AuditQuery {
+                session_id: Some("s1".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(by_session.len(), 2);
+
+        let by_tool = log
+            .query(&AuditQuery {
+                tool: Some("developer__*".to_string()),
+                decision: Some(PermissionDecision::Denied),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(by_tool.len(), 1);
+        assert_eq!(by_tool[0].tool, "developer__text_editor");
+
+        let latest = log
+            .query(&AuditQuery {
+                limit: Some(1),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].seq, 3);
+    }
+
+    #[test]
+    fn test_verify_skips_blank_lines() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::new(&path);
+        log.append(record(
+            "s1",
+            "developer__shell",
+            PermissionDecision::Allowed,
+        ))
+        .unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\n").unwrap();
+        log.append(record(
+            "s1",
+            "developer__shell",
+            PermissionDecision::Allowed,
+        ))
+        .unwrap();
+
+        let verification = log.verify().unwrap();
+        assert!(verification.is_valid(), "{:?}", verification.error);
+        assert_eq!(verification.entries, 2);
+        assert_eq!(log.query(&AuditQuery::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dropped_pending_audit_is_recorded() {
+        let dir = TempDir::new().unwrap();
+        let log = Arc::new(AuditLog::new(dir.path().join("audit.jsonl")));
+
+        drop(PendingAudit::new(
+            log.clone(),
+            record("s1", "developer__shell", PermissionDecision::Allowed),
+        ));
+
+        let entries = log.query(&AuditQuery::default()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].status, ToolStatus::Error);
+        assert!(entries[0].error.is_some());
+    }
+}
@@ -1,9 +1,13 @@
 use super::APP_STRATEGY;
 use etcetera::{choose_app_strategy, AppStrategy};
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
 use utoipa::ToSchema;
 
 /// Enum representing the possible permission levels for a tool.
@@ -21,6 +25,184 @@ pub struct PermissionConfig {
     pub always_allow: Vec<String>, // List of tools that are always allowed
     pub ask_before: Vec<String>,   // List of tools that require user consent
     pub never_allow: Vec<String>,  // List of tools that are never allowed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<PermissionRule>, // Argument-level rules, evaluated in order
+}
+
+/// A rule that decides the permission for calls to a tool based on their arguments.
+///
+/// ```yaml
+/// user:
+///   rules:
+///     - tool: developer__shell
+///       when:
+///         command: { matches: '^(git status|cargo test)$' }
+///       permission: always_allow
+///     - tool: developer__text_editor
+///       when:
+///         command: { matches: "^(write|str_replace|insert)$" }
+///         path: { outside: "{working_dir}" }
+///       permission: never_allow
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PermissionRule {
+    /// Tool name the rule applies to, where `*` matches any sequence of characters
+    pub tool: String,
+    /// Conditions on top-level arguments, all of which must hold for the rule to apply
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub when: BTreeMap<String, ArgumentCondition>,
+    /// Permission applied to calls matching the rule
+    pub permission: PermissionLevel,
+    /// Optional explanation shown when the rule matches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Conditions on a single argument value. Every condition that is set must hold.
+///
+/// Directories in `within` and `outside` may use `~` and `{working_dir}`; relative
+/// paths, in the condition or the argument, are resolved against the working directory.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ArgumentCondition {
+    /// Regex the value must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matches: Option<String>,
+    /// Regex the value must not match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_matches: Option<String>,
+    /// Directory the value, read as a path, must be inside
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub within: Option<String>,
+    /// Directory the value, read as a path, must be outside of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outside: Option<String>,
+}
+
+impl PermissionRule {
+    /// Checks that the tool pattern and all regexes compile.
+    pub fn validate(&self) -> Result<(), regex::Error> {
+        glob_to_regex(&self.tool)?;
+        for condition in self.when.values() {
+            for pattern in [&condition.matches, &condition.not_matches]
+                .into_iter()
+                .flatten()
+            {
+                compile(pattern)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether this rule applies to a call of `tool_name` with `arguments`.
+    /// Rules that fail to compile never match.
+    pub fn matches(&self, tool_name: &str, arguments: &Value, working_dir: &Path) -> bool {
+        match self.try_matches(tool_name, arguments, working_dir) {
+            Ok(matched) => matched,
+            Err(e) => {
+                tracing::warn!("Ignoring invalid permission rule for {}: {}", self.tool, e);
+                false
+            }
+        }
+    }
+
+    fn try_matches(
+        &self,
+        tool_name: &str,
+        arguments: &Value,
+        working_dir: &Path,
+    ) -> Result<bool, regex::Error> {
+        if !glob_to_regex(&self.tool)?.is_match(tool_name) {
+            return Ok(false);
+        }
+        for (argument, condition) in &self.when {
+            let value = match arguments.get(argument) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => return Ok(false),
+                Some(other) => other.to_string(),
+            };
+            if !condition.try_matches(&value, working_dir)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl ArgumentCondition {
+    fn try_matches(&self, value: &str, working_dir: &Path) -> Result<bool, regex::Error> {
+        if let Some(pattern) = &self.matches {
+            if !compile(pattern)?.is_match(value) {
+                return Ok(false);
+            }
+        }
+        if let Some(pattern) = &self.not_matches {
+            if compile(pattern)?.is_match(value) {
+                return Ok(false);
+            }
+        }
+        if self.within.is_some() || self.outside.is_some() {
+            let path = resolve_path(value, working_dir);
+            if let Some(dir) = &self.within {
+                if !path.starts_with(resolve_path(dir, working_dir)) {
+                    return Ok(false);
+                }
+            }
+            if let Some(dir) = &self.outside {
+                if path.starts_with(resolve_path(dir, working_dir)) {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Rules are evaluated on every tool call, so each pattern is compiled only once
+static COMPILED_PATTERNS: Lazy<Mutex<HashMap<String, Regex>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut compiled = COMPILED_PATTERNS.lock().unwrap();
+    if let Some(regex) = compiled.get(pattern) {
+        return Ok(regex.clone());
+    }
+    let regex = Regex::new(pattern)?;
+    compiled.insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
+fn glob_to_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let escaped = regex::escape(pattern).replace(r"\*", ".*");
+    compile(&format!("^{}$", escaped))
+}
+
+/// Expands `~` and `{working_dir}` and lexically normalizes the result, so that `..`
+/// cannot be used to step out of a directory without the rule noticing.
+fn resolve_path(value: &str, working_dir: &Path) -> PathBuf {
+    let expanded = value.replace("{working_dir}", &working_dir.to_string_lossy());
+    let path = match expanded.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(&expanded),
+    };
+    let path = if path.is_absolute() {
+        path
+    } else {
+        working_dir.join(path)
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
 }
 
 /// PermissionManager manages permission configurations for various tools.
@@ -124,6 +306,37 @@ impl PermissionManager {
         None // Return None if no matching permission level is found
     }
 
+    /// Returns the user's argument rules in evaluation order.
+    pub fn get_rules(&self) -> &[PermissionRule] {
+        self.permission_map
+            .get(USER_PERMISSION)
+            .map(|config| config.rules.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Finds the first user rule that applies to a tool call, along with its index.
+    pub fn evaluate_rules(
+        &self,
+        tool_name: &str,
+        arguments: &Value,
+        working_dir: &Path,
+    ) -> Option<(usize, &PermissionRule)> {
+        self.get_rules()
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(tool_name, arguments, working_dir))
+    }
+
+    /// Appends an argument rule to the user permissions.
+    pub fn add_rule(&mut self, rule: PermissionRule) {
+        self.permission_map
+            .entry(USER_PERMISSION.to_string())
+            .or_default()
+            .rules
+            .push(rule);
+        self.save();
+    }
+
     /// Updates the user permission level for a specific tool.
     pub fn update_user_permission(&mut self, principal_name: &str, level: PermissionLevel) {
         self.update_permission(USER_PERMISSION, principal_name, level)
@@ -165,10 +378,7 @@ impl PermissionManager {
                 .push(principal_name.to_string()),
         }
 
-        // Serialize the updated permission map and write it back to the config file
-        let yaml_content = serde_yaml::to_string(&self.permission_map)
-            .expect("Failed to serialize permission config");
-        fs::write(&self.config_path, yaml_content).expect("Failed to write to permission.yaml");
+        self.save();
     }
 
     /// Removes all entries where the principal name starts with the given extension name.
@@ -183,8 +393,16 @@ impl PermissionManager {
             permission_config
                 .never_allow
                 .retain(|p| !p.starts_with(extension_name));
+            permission_config
+                .rules
+                .retain(|rule| !rule.tool.starts_with(extension_name));
         }
 
+        self.save();
+    }
+
+    /// Serializes the permission map and writes it back to the config file.
+    fn save(&self) {
         let yaml_content = serde_yaml::to_string(&self.permission_map)
             .expect("Failed to serialize permission config");
         fs::write(&self.config_path, yaml_content).expect("Failed to write to permission.yaml");
@@ -194,6 +412,7 @@ impl PermissionManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
     use tempfile::NamedTempFile;
 
     // Helper function to create a test instance of PermissionManager with a temp dir
@@ -305,4 +524,142 @@ mod tests {
             .always_allow
             .contains(&"nonprefix__tool2".to_string()));
     }
+
+    fn rule(
+        tool: &str,
+        when: &[(&str, ArgumentCondition)],
+        level: PermissionLevel,
+    ) -> PermissionRule {
+        PermissionRule {
+            tool: tool.to_string(),
+            when: when
+                .iter()
+                .map(|(arg, cond)| (arg.to_string(), cond.clone()))
+                .collect(),
+            permission: level,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_rules_match_arguments_in_order() {
+        let mut manager = create_test_permission_manager();
+        manager.add_rule(rule(
+            "developer__shell",
+            &[(
+                "command",
+                ArgumentCondition {
+                    matches: Some("^(git status|cargo test)$".to_string()),
+                    ..Default::default()
+                },
+            )],
+            PermissionLevel::AlwaysAllow,
+        ));
+        manager.add_rule(rule("developer__*", &[], PermissionLevel::AskBefore));
+
+        let cwd = Path::new("/work");
+        let (index, matched) = manager
+            .evaluate_rules("developer__shell", &json!({"command": "cargo test"}), cwd)
+            .unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(matched.permission, PermissionLevel::AlwaysAllow);
+
+        // The end anchor keeps chained commands from riding on an allowed prefix
+        let (index, _) = manager
+            .evaluate_rules(
+                "developer__shell",
+                &json!({"command": "cargo test && rm -rf /"}),
+                cwd,
+            )
+            .unwrap();
+        assert_eq!(index, 1);
+
+        let (index, _) = manager
+            .evaluate_rules("developer__shell", &json!({"command": "rm -rf /"}), cwd)
+            .unwrap();
+        assert_eq!(index, 1);
+
+        assert!(manager
+            .evaluate_rules("memory__remember", &json!({}), cwd)
+            .is_none());
+    }
+
+    #[test]
+    fn test_rule_path_conditions() {
+        let deny_outside = rule(
+            "developer__text_editor",
+            &[
+                (
+                    "command",
+                    ArgumentCondition {
+                        matches: Some("^(write|str_replace|insert)$".to_string()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "path",
+                    ArgumentCondition {
+                        outside: Some("{working_dir}".to_string()),
+                        ..Default::default()
+                    },
+                ),
+            ],
+            PermissionLevel::NeverAllow,
+        );
+        let cwd = Path::new("/work/project");
+        let call = |command: &str, path: &str| json!({"command": command, "path": path});
+
+        assert!(deny_outside.matches("developer__text_editor", &call("write", "/etc/passwd"), cwd));
+        assert!(deny_outside.matches(
+            "developer__text_editor",
+            &call("write", "src/../../other/file.rs"),
+            cwd
+        ));
+        assert!(!deny_outside.matches(
+            "developer__text_editor",
+            &call("write", "src/main.rs"),
+            cwd
+        ));
+        assert!(!deny_outside.matches("developer__text_editor", &call("view", "/etc/passwd"), cwd));
+        assert!(!deny_outside.matches("developer__text_editor", &json!({"command": "write"}), cwd));
+    }
+
+    #[test]
+    fn test_rules_round_trip_and_validate() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let yaml = r#"
+user:
+  always_allow: []
+  ask_before: []
+  never_allow: []
+  rules:
+    - tool: computercontroller__web_scrape
+      when:
+        url: { not_matches: "^https://docs\.rs/" }
+      permission: ask_before
+    - tool: developer__shell
+      when:
+        command: { matches: "(" }
+      permission: always_allow
+"#;
+        fs::write(temp_file.path(), yaml).unwrap();
+        let manager = PermissionManager::new(temp_file.path());
+        let rules = manager.get_rules();
+        assert_eq!(rules.len(), 2);
+        assert!(rules[0].validate().is_ok());
+        assert!(rules[1].validate().is_err());
+
+        let cwd = Path::new("/work");
+        let scrape = "computercontroller__web_scrape";
+        assert!(manager
+            .evaluate_rules(scrape, &json!({"url": "https://docs.rs/serde"}), cwd)
+            .is_none());
+        assert!(manager
+            .evaluate_rules(scrape, &json!({"url": "https://example.com"}), cwd)
+            .is_some());
+        // An invalid rule never matches
+        assert!(manager
+            .evaluate_rules("developer__shell", &json!({"command": "("}), cwd)
+            .is_none());
+    }
 }
@@ -0,0 +1,554 @@
+//! Lifecycle hooks: shell commands or MCP tools the user configures to run when a session starts
+//! or ends, before and after each tool call, before each provider call and when a tool call needs
+//! permission.
+//!
+//! Hooks are listed under `hooks` in the config file. Each one gets the event as JSON on stdin, or
+//! as the arguments of its tool. A command that exits with 0 lets the action go ahead and can
+//! change it by printing a [`HookResponse`] as JSON, while any other output is passed to the model
+//! as context. Exiting with 2 blocks the action, with stderr as the reason. Other failures are
+//! logged and ignored so a broken hook never stops goose.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use mcp_core::tool::ToolCall;
+use mcp_core::{ToolError, ToolResult};
+use regex::Regex;
+use rmcp::model::Content;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::agents::extension_manager::ExtensionManager;
+use crate::config::{Config, ConfigError};
+
+/// Seconds a hook may run before it is given up on
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// The exit code a command hook uses to block the action
+const BLOCK_EXIT_CODE: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    SessionStart,
+    SessionEnd,
+    BeforeToolCall,
+    AfterToolCall,
+    BeforeProviderCall,
+    PermissionRequest,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::SessionStart => "session_start",
+            HookEvent::SessionEnd => "session_end",
+            HookEvent::BeforeToolCall => "before_tool_call",
+            HookEvent::AfterToolCall => "after_tool_call",
+            HookEvent::BeforeProviderCall => "before_provider_call",
+            HookEvent::PermissionRequest => "permission_request",
+        }
+    }
+}
+
+/// A hook as it is written in the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookConfig {
+    pub event: HookEvent,
+    /// Only run for tools whose prefixed name matches; `*` matches any characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+    /// Shell command to run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Prefixed name of an MCP tool to call instead of running a command, e.g. `secrets__scan`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// Seconds to wait for the hook, 60 when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+/// What a hook is told about the event, sent as JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct HookInput {
+    pub event: HookEvent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub cwd: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
+    /// The text the tool returned, or its error, for after_tool_call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+    /// The text of the latest message in the conversation, for before_provider_call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// How many messages the provider is sent, for before_provider_call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_count: Option<usize>,
+}
+
+impl HookInput {
+    pub fn new(event: HookEvent, session_id: Option<String>) -> Self {
+        Self {
+            event,
+            session_id,
+            cwd: std::env::current_dir().unwrap_or_default(),
+            tool_name: None,
+            arguments: None,
+            output: None,
+            is_error: None,
+            message: None,
+            message_count: None,
+        }
+    }
+
+    pub fn with_cwd(mut self, cwd: PathBuf) -> Self {
+        self.cwd = cwd;
+        self
+    }
+
+    pub fn with_tool(mut self, name: &str, arguments: &Value) -> Self {
+        self.tool_name = Some(name.to_string());
+        self.arguments = Some(arguments.clone());
+        self
+    }
+
+    pub fn with_output(mut self, output: &ToolResult<Vec<Content>>) -> Self {
+        let (text, is_error) = match output {
+            Ok(contents) => (
+                contents
+                    .iter()
+                    .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                false,
+            ),
+            Err(e) => (e.to_string(), true),
+        };
+        self.output = Some(text);
+        self.is_error = Some(is_error);
+        self
+    }
+
+    pub fn with_messages(mut self, message: String, message_count: usize) -> Self {
+        self.message = Some(message);
+        self.message_count = Some(message_count);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookDecision {
+    Allow,
+    Block,
+}
+
+/// What a hook can print as JSON to change the action
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct HookResponse {
+    /// `block` stops the action. `allow` answers a permission request without asking the user.
+    #[serde(default)]
+    pub decision: Option<HookDecision>,
+    /// Why the action was blocked, shown to the user and the model
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Arguments to call the tool with instead, for before_tool_call and permission_request
+    #[serde(default)]
+    pub arguments: Option<Value>,
+    /// Text for the model. It is added to the tool result after a tool call, and to the system
+    /// prompt at session start and before a provider call.
+    #[serde(default)]
+    pub context: Option<String>,
+}
+
+impl HookResponse {
+    /// Reads a JSON response, taking any other output as context
+    fn parse(stdout: &str) -> Self {
+        let stdout = stdout.trim();
+        if stdout.is_empty() {
+            return Self::default();
+        }
+        serde_json::from_str(stdout).unwrap_or_else(|_| Self {
+            context: Some(stdout.to_string()),
+            ..Default::default()
+        })
+    }
+}
+
+/// The combined responses of every hook that ran for an event
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HookOutcome {
+    /// Why a hook blocked the action. Hooks after it do not run.
+    pub blocked: Option<String>,
+    /// A hook allowed the action
+    pub allowed: bool,
+    /// The arguments the last hook to change them asked for
+    pub arguments: Option<Value>,
+    pub context: Vec<String>,
+}
+
+impl HookOutcome {
+    pub fn context(&self) -> Option<String> {
+        (!self.context.is_empty()).then(|| self.context.join("\n\n"))
+    }
+
+    /// The tool output once after_tool_call hooks had their say: an error when one blocked it,
+    /// otherwise with their context added
+    pub fn apply_to_output(&self, output: ToolResult<Vec<Content>>) -> ToolResult<Vec<Content>> {
+        if let Some(reason) = &self.blocked {
+            return Err(ToolError::ExecutionError(format!(
+                "An after_tool_call hook rejected the result: {}",
+                reason
+            )));
+        }
+        output.map(|mut contents| {
+            if let Some(context) = self.context() {
+                contents.push(Content::text(context));
+            }
+            contents
+        })
+    }
+}
+
+/// A configured hook with its matcher compiled
+#[derive(Debug, Clone)]
+struct Hook {
+    config: HookConfig,
+    matcher: Option<Regex>,
+}
+
+/// The configured hooks
+#[derive(Debug, Clone, Default)]
+pub struct Hooks {
+    hooks: Vec<Hook>,
+}
+
+impl Hooks {
+    /// Keeps the hooks that name exactly one of a command and a tool, logging the others
+    pub fn new(hooks: Vec<HookConfig>) -> Self {
+        let hooks = hooks
+            .into_iter()
+            .filter_map(|config| {
+                if config.command.is_some() == config.tool.is_some() {
+                    warn!(
+                        "Ignoring a {} hook, it needs either a command or a tool",
+                        config.event.as_str()
+                    );
+                    return None;
+                }
+                let matcher = match config.matcher.as_deref().map(matcher_regex).transpose() {
+                    Ok(matcher) => matcher,
+                    Err(e) => {
+                        warn!(
+                            "Ignoring a {} hook, its matcher is invalid: {}",
+                            config.event.as_str(),
+                            e
+                        );
+                        return None;
+                    }
+                };
+                Some(Hook { config, matcher })
+            })
+            .collect();
+        Self { hooks }
+    }
+
+    /// The hooks under `hooks` in the config file
+    pub fn from_config() -> Self {
+        match Config::global().get_param::<Vec<HookConfig>>("hooks") {
+            Ok(hooks) => Self::new(hooks),
+            Err(ConfigError::NotFound(_)) => Self::default(),
+            Err(e) => {
+                warn!("Failed to read hooks from the config: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether any hook runs for the event, and for tool events, the tool
+    pub fn has(&self, event: HookEvent, tool_name: Option<&str>) -> bool {
+        self.matching(event, tool_name).next().is_some()
+    }
+
+    fn matching<'a>(
+        &'a self,
+        event: HookEvent,
+        tool_name: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a HookConfig> + 'a {
+        self.hooks
+            .iter()
+            .filter(move |hook| {
+                hook.config.event == event
+                    && match (&hook.matcher, tool_name) {
+                        (None, _) => true,
+                        (Some(matcher), Some(name)) => matcher.is_match(name),
+                        (Some(_), None) => false,
+                    }
+            })
+            .map(|hook| &hook.config)
+    }
+
+    /// Runs the hooks for the event in the order they are configured. Arguments a hook changes
+    /// are what the next hook sees, and a hook that blocks the action stops the rest.
+    pub async fn run(
+        &self,
+        mut input: HookInput,
+        extensions: &RwLock<ExtensionManager>,
+    ) -> HookOutcome {
+        let mut outcome = HookOutcome::default();
+        let hooks: Vec<&HookConfig> = self
+            .matching(input.event, input.tool_name.as_deref())
+            .collect();
+        for hook in hooks {
+            let response = match hook.execute(&input, extensions).await {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("A {} hook failed: {:#}", input.event.as_str(), e);
+                    continue;
+                }
+            };
+            debug!(
+                "{} hook responded with {:?}",
+                input.event.as_str(),
+                response
+            );
+
+            outcome.context.extend(response.context);
+            if let Some(arguments) = response.arguments {
+                input.arguments = Some(arguments.clone());
+                outcome.arguments = Some(arguments);
+            }
+            match response.decision {
+                Some(HookDecision::Block) => {
+                    outcome.blocked = Some(
+                        response
+                            .reason
+                            .unwrap_or_else(|| "no reason given".to_string()),
+                    );
+                    break;
+                }
+                Some(HookDecision::Allow) => outcome.allowed = true,
+                None => {}
+            }
+        }
+        outcome
+    }
+}
+
+impl HookConfig {
+    async fn execute(
+        &self,
+        input: &HookInput,
+        extensions: &RwLock<ExtensionManager>,
+    ) -> Result<HookResponse> {
+        let timeout = Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS));
+        match (&self.command, &self.tool) {
+            (Some(command), _) => run_command(command, input, timeout).await,
+            (None, Some(tool)) => call_tool(tool, input, extensions, timeout).await,
+            (None, None) => bail!("the hook has no command or tool"),
+        }
+    }
+}
+
+/// The whole tool name has to match, with `*` matching any characters
+fn matcher_regex(matcher: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!(
+        "^{}$",
+        regex::escape(matcher).replace(r"\*", ".*")
+    ))
+}
+
+async fn run_command(command: &str, input: &HookInput, timeout: Duration) -> Result<HookResponse> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    let mut child = cmd
+        .current_dir(&input.cwd)
+        .env("GOOSE_HOOK_EVENT", input.event.as_str())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // Written separately so a hook that never reads its input cannot hold up the wait
+    let payload = serde_json::to_vec(input)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        tokio::spawn(async move {
+            let _ = stdin.write_all(&payload).await;
+        });
+    }
+
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| anyhow!("`{}` timed out after {:?}", command, timeout))??;
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    match output.status.code() {
+        Some(0) => Ok(HookResponse::parse(&String::from_utf8_lossy(
+            &output.stdout,
+        ))),
+        Some(BLOCK_EXIT_CODE) => Ok(HookResponse {
+            decision: Some(HookDecision::Block),
+            reason: Some(stderr).filter(|s| !s.is_empty()),
+            ..Default::default()
+        }),
+        _ => bail!("`{}` exited with {}: {}", command, output.status, stderr),
+    }
+}
+
+async fn call_tool(
+    tool: &str,
+    input: &HookInput,
+    extensions: &RwLock<ExtensionManager>,
+    timeout: Duration,
+) -> Result<HookResponse> {
+    let call = ToolCall::new(tool, serde_json::to_value(input)?);
+    // Dispatching can wait on the extension too, so it counts against the timeout
+    let contents = tokio::time::timeout(timeout, async {
+        let result = extensions.read().await.dispatch_tool_call(call).await?;
+        Ok::<_, anyhow::Error>(result.result.await?)
+    })
+    .await
+    .map_err(|_| anyhow!("{} timed out after {:?}", tool, timeout))??;
+    let text = contents
+        .iter()
+        .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(HookResponse::parse(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn command_hook(event: HookEvent, matcher: Option<&str>, command: &str) -> HookConfig {
+        HookConfig {
+            event,
+            matcher: matcher.map(String::from),
+            command: Some(command.to_string()),
+            tool: None,
+            timeout: None,
+        }
+    }
+
+    async fn run(hooks: Vec<HookConfig>, input: HookInput) -> HookOutcome {
+        let extensions = RwLock::new(ExtensionManager::new());
+        Hooks::new(hooks).run(input, &extensions).await
+    }
+
+    #[test]
+    fn test_matching() {
+        let hooks = Hooks::new(vec![
+            command_hook(HookEvent::AfterToolCall, Some("developer__*"), "true"),
+            command_hook(HookEvent::SessionStart, None, "true"),
+            HookConfig {
+                tool: Some("secrets__scan".to_string()),
+                ..command_hook(HookEvent::BeforeToolCall, None, "true")
+            },
+        ]);
+
+        assert!(hooks.has(HookEvent::AfterToolCall, Some("developer__shell")));
+        assert!(!hooks.has(HookEvent::AfterToolCall, Some("memory__remember")));
+        assert!(hooks.has(HookEvent::SessionStart, None));
+        // A hook with both a command and a tool is left out
+        assert!(!hooks.has(HookEvent::BeforeToolCall, Some("developer__shell")));
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(HookResponse::parse("  \n"), HookResponse::default());
+        assert_eq!(
+            HookResponse::parse(r#"{"decision": "block", "reason": "secret found"}"#),
+            HookResponse {
+                decision: Some(HookDecision::Block),
+                reason: Some("secret found".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            HookResponse::parse("Formatted 1 file\n").context.as_deref(),
+            Some("Formatted 1 file")
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_hooks() {
+        let input = HookInput::new(HookEvent::BeforeToolCall, None)
+            .with_tool("developer__shell", &json!({"command": "ls"}));
+
+        // Hooks see the input on stdin, and the arguments earlier hooks changed
+        let outcome = run(
+            vec![
+                command_hook(
+                    HookEvent::BeforeToolCall,
+                    None,
+                    r#"echo '{"arguments": {"command": "ls -a"}}'"#,
+                ),
+                command_hook(
+                    HookEvent::BeforeToolCall,
+                    None,
+                    r#"grep -q '"ls -a"' && echo seen"#,
+                ),
+            ],
+            input.clone(),
+        )
+        .await;
+        assert_eq!(outcome.arguments, Some(json!({"command": "ls -a"})));
+        assert_eq!(outcome.context(), Some("seen".to_string()));
+
+        // Exit code 2 blocks, and failing hooks are skipped
+        let outcome = run(
+            vec![
+                command_hook(HookEvent::BeforeToolCall, None, "exit 1"),
+                command_hook(HookEvent::BeforeToolCall, None, "echo nope >&2; exit 2"),
+                command_hook(HookEvent::BeforeToolCall, None, "echo never"),
+            ],
+            input,
+        )
+        .await;
+        assert_eq!(outcome.blocked.as_deref(), Some("nope"));
+        assert!(outcome.context.is_empty());
+    }
+
+    #[test]
+    fn test_apply_to_output() {
+        let outcome = HookOutcome {
+            context: vec!["formatted".to_string()],
+            ..Default::default()
+        };
+        let output = outcome
+            .apply_to_output(Ok(vec![Content::text("done")]))
+            .unwrap();
+        assert_eq!(output.len(), 2);
+
+        let blocked = HookOutcome {
+            blocked: Some("leaked key".to_string()),
+            ..Default::default()
+        };
+        assert!(blocked
+            .apply_to_output(Ok(vec![Content::text("done")]))
+            .is_err());
+    }
+}
@@ -1,7 +1,9 @@
 pub mod agents;
+pub mod audit;
 pub mod config;
 pub mod context_mgmt;
 mod conversation_fixer;
+pub mod hooks;
 pub mod message;
 pub mod model;
 pub mod permission;
@@ -5,6 +5,7 @@
 ///
 /// The content of the messages uses MCP types to avoid additional conversions
 /// when interacting with MCP servers.
+use crate::agents::edit_preview::EditPreview;
 use chrono::Utc;
 use mcp_core::handler::ToolResult;
 use mcp_core::tool::ToolCall;
@@ -66,6 +67,9 @@ pub struct ToolConfirmationRequest {
     pub tool_name: String,
     pub arguments: Value,
     pub prompt: Option<String>,
+    /// The diff of a file change the tool would make, for tools whose changes can be previewed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit_preview: Option<EditPreview>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -189,6 +193,7 @@ impl MessageContent {
             tool_name,
             arguments,
             prompt,
+            edit_preview: None,
         })
     }
 
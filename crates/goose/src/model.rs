@@ -1,9 +1,16 @@
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use utoipa::ToSchema;
 
 const DEFAULT_CONTEXT_LIMIT: usize = 128_000;
 
+/// Thinking budget used by the legacy `CLAUDE_THINKING_ENABLED` setting when
+/// `CLAUDE_THINKING_BUDGET` is not set
+const LEGACY_THINKING_BUDGET: i32 = 16_000;
+
 // Define the model limits as a static HashMap for reuse
 static MODEL_SPECIFIC_LIMITS: Lazy<HashMap<&'static str, usize>> = Lazy::new(|| {
     let mut map = HashMap::new();
@@ -36,6 +43,67 @@ static MODEL_SPECIFIC_LIMITS: Lazy<HashMap<&'static str, usize>> = Lazy::new(||
     map
 });
 
+/// How much reasoning a model should do before answering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    /// Thinking budget in tokens for providers that only accept a budget
+    pub fn thinking_budget(&self) -> i32 {
+        match self {
+            ReasoningEffort::Low => 1_024,
+            ReasoningEffort::Medium => 8_192,
+            ReasoningEffort::High => 24_576,
+        }
+    }
+
+    /// Closest effort level for a thinking budget, for providers that only accept an effort
+    pub fn from_thinking_budget(budget: i32) -> Self {
+        if budget <= ReasoningEffort::Low.thinking_budget() {
+            ReasoningEffort::Low
+        } else if budget <= ReasoningEffort::Medium.thinking_budget() {
+            ReasoningEffort::Medium
+        } else {
+            ReasoningEffort::High
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for ReasoningEffort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ReasoningEffort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(ReasoningEffort::Low),
+            "medium" => Ok(ReasoningEffort::Medium),
+            "high" => Ok(ReasoningEffort::High),
+            _ => Err(format!(
+                "Invalid reasoning effort '{}': expected low, medium or high",
+                s
+            )),
+        }
+    }
+}
+
 /// Configuration for model-specific settings and limits
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
@@ -51,6 +119,12 @@ pub struct ModelConfig {
     pub toolshim: bool,
     /// Model to use for toolshim (optional as a default exists)
     pub toolshim_model: Option<String>,
+    /// Optional reasoning effort for models that reason before answering
+    #[serde(default)]
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Optional token budget for extended thinking
+    #[serde(default)]
+    pub thinking_budget: Option<i32>,
 }
 
 /// Struct to represent model pattern matches and their limits
@@ -89,6 +163,12 @@ impl ModelConfig {
             .ok()
             .and_then(|val| val.parse::<f32>().ok());
 
+        let reasoning_effort = std::env::var("GOOSE_REASONING_EFFORT")
+            .ok()
+            .and_then(|val| val.parse::<ReasoningEffort>().ok());
+
+        let thinking_budget = Self::get_thinking_budget_from_env(&model_name);
+
         Self {
             model_name,
             context_limit,
@@ -96,11 +176,38 @@ impl ModelConfig {
             max_tokens: None,
             toolshim,
             toolshim_model,
+            reasoning_effort,
+            thinking_budget,
         }
     }
 
+    /// Read the thinking budget from GOOSE_THINKING_BUDGET, falling back to the legacy
+    /// CLAUDE_THINKING_ENABLED and CLAUDE_THINKING_BUDGET for the Claude Sonnet models
+    /// they applied to
+    fn get_thinking_budget_from_env(model_name: &str) -> Option<i32> {
+        if let Some(budget) = std::env::var("GOOSE_THINKING_BUDGET")
+            .ok()
+            .and_then(|val| val.parse::<i32>().ok())
+        {
+            return Some(budget);
+        }
+
+        let is_legacy_thinking_model =
+            model_name.contains("claude-3-7-sonnet") || model_name.contains("claude-4-sonnet");
+        if is_legacy_thinking_model && std::env::var("CLAUDE_THINKING_ENABLED").is_ok() {
+            return Some(
+                std::env::var("CLAUDE_THINKING_BUDGET")
+                    .ok()
+                    .and_then(|val| val.parse::<i32>().ok())
+                    .unwrap_or(LEGACY_THINKING_BUDGET),
+            );
+        }
+
+        None
+    }
+
     /// Get model-specific context limit based on model name
-    fn get_model_specific_limit(model_name: &str) -> Option<usize> {
+    pub(crate) fn get_model_specific_limit(model_name: &str) -> Option<usize> {
         for (pattern, &limit) in MODEL_SPECIFIC_LIMITS.iter() {
             if model_name.contains(pattern) {
                 return Some(limit);
@@ -155,6 +262,36 @@ impl ModelConfig {
         self
     }
 
+    /// Set the reasoning effort, keeping the current one if None
+    pub fn with_reasoning_effort(mut self, effort: Option<ReasoningEffort>) -> Self {
+        if effort.is_some() {
+            self.reasoning_effort = effort;
+        }
+        self
+    }
+
+    /// Set the thinking budget in tokens, keeping the current one if None
+    pub fn with_thinking_budget(mut self, budget: Option<i32>) -> Self {
+        if budget.is_some() {
+            self.thinking_budget = budget;
+        }
+        self
+    }
+
+    /// The reasoning effort to request, derived from the thinking budget if only that is set
+    pub fn effective_reasoning_effort(&self) -> Option<ReasoningEffort> {
+        self.reasoning_effort.or_else(|| {
+            self.thinking_budget
+                .map(ReasoningEffort::from_thinking_budget)
+        })
+    }
+
+    /// The thinking budget to request, derived from the reasoning effort if only that is set
+    pub fn effective_thinking_budget(&self) -> Option<i32> {
+        self.thinking_budget
+            .or_else(|| self.reasoning_effort.map(|e| e.thinking_budget()))
+    }
+
     /// Get the context_limit for the current model
     /// If none are defined, use the DEFAULT_CONTEXT_LIMIT
     pub fn context_limit(&self) -> usize {
@@ -267,6 +404,66 @@ mod tests {
         assert_eq!(config.temperature, None);
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_model_config_reasoning_env_vars() {
+        use temp_env::with_vars;
+
+        with_vars(
+            [
+                ("GOOSE_REASONING_EFFORT", Some("HIGH")),
+                ("GOOSE_THINKING_BUDGET", Some("4000")),
+            ],
+            || {
+                let config = ModelConfig::new("test-model".to_string());
+                assert_eq!(config.reasoning_effort, Some(ReasoningEffort::High));
+                assert_eq!(config.thinking_budget, Some(4000));
+            },
+        );
+
+        with_vars(
+            [
+                ("CLAUDE_THINKING_ENABLED", Some("1")),
+                ("CLAUDE_THINKING_BUDGET", None),
+                ("GOOSE_THINKING_BUDGET", None),
+            ],
+            || {
+                let config = ModelConfig::new("claude-3-7-sonnet-latest".to_string());
+                assert_eq!(config.thinking_budget, Some(LEGACY_THINKING_BUDGET));
+
+                let config = ModelConfig::new("claude-3-5-haiku-latest".to_string());
+                assert_eq!(config.thinking_budget, None);
+            },
+        );
+
+        with_vars([("GOOSE_REASONING_EFFORT", Some("extreme"))], || {
+            let config = ModelConfig::new("test-model".to_string());
+            assert_eq!(config.reasoning_effort, None);
+        });
+    }
+
+    #[test]
+    fn test_effective_reasoning_settings() {
+        let config = ModelConfig::new("test-model".to_string())
+            .with_reasoning_effort(Some(ReasoningEffort::Low));
+        assert_eq!(config.effective_thinking_budget(), Some(1_024));
+
+        let config = ModelConfig::new("test-model".to_string()).with_thinking_budget(Some(20_000));
+        assert_eq!(
+            config.effective_reasoning_effort(),
+            Some(ReasoningEffort::High)
+        );
+
+        let config = ModelConfig::new("test-model".to_string())
+            .with_reasoning_effort(Some(ReasoningEffort::Medium))
+            .with_thinking_budget(Some(2_000));
+        assert_eq!(
+            config.effective_reasoning_effort(),
+            Some(ReasoningEffort::Medium)
+        );
+        assert_eq!(config.effective_thinking_budget(), Some(2_000));
+    }
+
     #[test]
     fn test_get_all_model_limits() {
         let limits = ModelConfig::get_all_model_limits();
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -19,4 +20,8 @@ pub enum PrincipalType {
 pub struct PermissionConfirmation {
     pub principal_type: PrincipalType,
     pub permission: Permission,
+    /// Arguments to run an allowed tool with instead of the ones it was called with, for when the
+    /// user changed what the tool would do, such as editing a proposed file change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
 }
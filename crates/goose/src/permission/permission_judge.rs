@@ -1,4 +1,5 @@
 use crate::agents::platform_tools::PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME;
+use crate::audit::DecisionSource;
 use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
 use crate::message::{Message, MessageContent, ToolRequest};
@@ -9,7 +10,8 @@ use rmcp::model::{Tool, ToolAnnotations};
 use rmcp::object;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 
 /// Creates the tool definition for checking read-only permissions.
@@ -154,11 +156,18 @@ pub struct PermissionCheckResult {
     pub approved: Vec<ToolRequest>,
     pub needs_approval: Vec<ToolRequest>,
     pub denied: Vec<ToolRequest>,
+    /// What decided each request, keyed by request id. Requests that need approval are
+    /// ultimately decided by the user.
+    #[serde(default)]
+    pub decided_by: HashMap<String, DecisionSource>,
 }
 
+/// Sort tool requests by permission. Argument rules resolve `{working_dir}` and relative paths
+/// against the session's working directory.
 pub async fn check_tool_permissions(
     candidate_requests: &[ToolRequest],
     mode: &str,
+    working_dir: &Path,
     tools_with_readonly_annotation: HashSet<String>,
     tools_without_annotation: HashSet<String>,
     permission_manager: &mut PermissionManager,
@@ -169,20 +178,45 @@ pub async fn check_tool_permissions(
     let mut denied = vec![];
     let mut llm_detect_candidates = vec![];
     let mut extension_request_ids = vec![];
+    let mut decided_by = HashMap::new();
 
     for request in candidate_requests {
         if let Ok(tool_call) = request.tool_call.clone() {
+            let rule_level = permission_manager
+                .evaluate_rules(&tool_call.name, &tool_call.arguments, working_dir)
+                .map(|(_, rule)| rule.permission.clone());
+
+            // never_allow rules hold in every mode, so auto and chat cannot bypass them
+            if rule_level == Some(PermissionLevel::NeverAllow) {
+                denied.push(request.clone());
+                decided_by.insert(request.id.clone(), DecisionSource::Rule);
+                continue;
+            }
+
             if mode == "chat" {
                 continue;
             } else if mode == "auto" {
                 approved.push(request.clone());
+                decided_by.insert(request.id.clone(), DecisionSource::Mode);
             } else {
                 if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
                     extension_request_ids.push(request.id.clone());
                 }
 
-                // 1. Check user-defined permission
+                // 1. Check user-defined argument rules
+                if let Some(level) = rule_level {
+                    decided_by.insert(request.id.clone(), DecisionSource::Rule);
+                    match level {
+                        PermissionLevel::AlwaysAllow => approved.push(request.clone()),
+                        PermissionLevel::AskBefore => needs_approval.push(request.clone()),
+                        PermissionLevel::NeverAllow => denied.push(request.clone()),
+                    }
+                    continue;
+                }
+
+                // 2. Check user-defined tool permission
                 if let Some(level) = permission_manager.get_user_permission(&tool_call.name) {
+                    decided_by.insert(request.id.clone(), DecisionSource::PermissionStore);
                     match level {
                         PermissionLevel::AlwaysAllow => approved.push(request.clone()),
                         PermissionLevel::AskBefore => needs_approval.push(request.clone()),
@@ -191,15 +225,17 @@ pub async fn check_tool_permissions(
                     continue;
                 }
 
-                // 2. Fallback based on mode
+                // 3. Fallback based on mode
                 match mode {
                     "approve" => {
                         needs_approval.push(request.clone());
+                        decided_by.insert(request.id.clone(), DecisionSource::Mode);
                     }
                     "smart_approve" => {
                         if let Some(level) =
                             permission_manager.get_smart_approve_permission(&tool_call.name)
                         {
+                            decided_by.insert(request.id.clone(), DecisionSource::SmartApprove);
                             match level {
                                 PermissionLevel::AlwaysAllow => approved.push(request.clone()),
                                 PermissionLevel::AskBefore => needs_approval.push(request.clone()),
@@ -208,6 +244,7 @@ pub async fn check_tool_permissions(
                             continue;
                         }
 
+                        decided_by.insert(request.id.clone(), DecisionSource::SmartApprove);
                         if tools_with_readonly_annotation.contains(&tool_call.name) {
                             approved.push(request.clone());
                         } else if tools_without_annotation.contains(&tool_call.name) {
@@ -218,13 +255,14 @@ pub async fn check_tool_permissions(
                     }
                     _ => {
                         needs_approval.push(request.clone());
+                        decided_by.insert(request.id.clone(), DecisionSource::Mode);
                     }
                 }
             }
         }
     }
 
-    // 3. LLM detect
+    // 4. LLM detect
     if !llm_detect_candidates.is_empty() && mode == "smart_approve" {
         let detected_readonly_tools =
             detect_read_only_tools(provider, llm_detect_candidates.iter().collect()).await;
@@ -252,6 +290,7 @@ pub async fn check_tool_permissions(
             approved,
             needs_approval,
             denied,
+            decided_by,
         },
         extension_request_ids,
     )
@@ -260,6 +299,7 @@ pub async fn check_tool_permissions(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::permission::{ArgumentCondition, PermissionRule};
     use crate::message::{Message, MessageContent, ToolRequest};
     use crate::model::ModelConfig;
     use crate::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
@@ -443,6 +483,7 @@ mod tests {
         let (result, enable_extension_request_ids) = check_tool_permissions(
             &candidate_requests,
             "smart_approve",
+            Path::new("/work"),
             tools_with_readonly_annotation,
             tools_without_annotation,
             &mut permission_manager,
@@ -502,6 +543,7 @@ mod tests {
         let (result, _) = check_tool_permissions(
             &candidate_requests,
             "auto",
+            Path::new("/work"),
             tools_with_readonly_annotation,
             tools_without_annotation,
             &mut permission_manager,
@@ -514,4 +556,136 @@ mod tests {
         assert_eq!(result.needs_approval.len(), 0); // data_fetcher should need approval
         assert_eq!(result.denied.len(), 0); // No tool should be denied in this test
     }
+
+    #[tokio::test]
+    async fn test_check_tool_permissions_argument_rules() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut permission_manager = PermissionManager::new(temp_file.path());
+        let provider = create_mock_provider();
+
+        permission_manager.update_user_permission("developer__shell", PermissionLevel::AskBefore);
+        permission_manager.add_rule(PermissionRule {
+            tool: "developer__shell".to_string(),
+            when: [(
+                "command".to_string(),
+                ArgumentCondition {
+                    matches: Some("^git status".to_string()),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            permission: PermissionLevel::AlwaysAllow,
+            description: None,
+        });
+        permission_manager.add_rule(PermissionRule {
+            tool: "developer__shell".to_string(),
+            when: [(
+                "command".to_string(),
+                ArgumentCondition {
+                    matches: Some("rm -rf".to_string()),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            permission: PermissionLevel::NeverAllow,
+            description: None,
+        });
+
+        let shell = |id: &str, command: &str| ToolRequest {
+            id: id.to_string(),
+            tool_call: ToolResult::Ok(ToolCall {
+                name: "developer__shell".to_string(),
+                arguments: json!({ "command": command }),
+            }),
+        };
+        let candidate_requests = vec![
+            shell("allowed", "git status"),
+            shell("denied", "rm -rf /"),
+            shell("fallback", "cargo build"),
+        ];
+
+        let (result, _) = check_tool_permissions(
+            &candidate_requests,
+            "smart_approve",
+            Path::new("/work"),
+            HashSet::new(),
+            vec!["developer__shell".to_string()].into_iter().collect(),
+            &mut permission_manager,
+            provider,
+        )
+        .await;
+
+        assert_eq!(result.approved.len(), 1);
+        assert_eq!(result.approved[0].id, "allowed");
+        assert_eq!(result.denied.len(), 1);
+        assert_eq!(result.denied[0].id, "denied");
+        // Calls no rule matches fall back to the tool-level permission
+        assert_eq!(result.needs_approval.len(), 1);
+        assert_eq!(result.needs_approval[0].id, "fallback");
+        assert_eq!(result.decided_by["allowed"], DecisionSource::Rule);
+        assert_eq!(
+            result.decided_by["fallback"],
+            DecisionSource::PermissionStore
+        );
+
+        // never_allow rules still deny in modes that otherwise skip permission checks
+        for mode in ["auto", "chat"] {
+            let (result, _) = check_tool_permissions(
+                &candidate_requests,
+                mode,
+                Path::new("/work"),
+                HashSet::new(),
+                HashSet::new(),
+                &mut permission_manager,
+                create_mock_provider(),
+            )
+            .await;
+            assert_eq!(result.denied.len(), 1, "{mode}");
+            assert_eq!(result.denied[0].id, "denied");
+            assert_eq!(result.decided_by["denied"], DecisionSource::Rule);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_tool_permissions_uses_session_working_dir() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut permission_manager = PermissionManager::new(temp_file.path());
+        permission_manager.add_rule(PermissionRule {
+            tool: "developer__text_editor".to_string(),
+            when: [(
+                "path".to_string(),
+                ArgumentCondition {
+                    outside: Some("{working_dir}".to_string()),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            permission: PermissionLevel::NeverAllow,
+            description: None,
+        });
+
+        let request = ToolRequest {
+            id: "write".to_string(),
+            tool_call: ToolResult::Ok(ToolCall {
+                name: "developer__text_editor".to_string(),
+                arguments: json!({"command": "write", "path": "/work/project/src/main.rs"}),
+            }),
+        };
+        for (working_dir, denied) in [("/work/project", false), ("/srv/goosed", true)] {
+            let (result, _) = check_tool_permissions(
+                std::slice::from_ref(&request),
+                "auto",
+                Path::new(working_dir),
+                HashSet::new(),
+                HashSet::new(),
+                &mut permission_manager,
+                create_mock_provider(),
+            )
+            .await;
+            assert_eq!(result.denied.len() == 1, denied, "{working_dir}");
+        }
+    }
 }
@@ -14,7 +14,8 @@ use tokio_util::io::StreamReader;
 use super::base::{ConfigKey, MessageStream, ModelInfo, Provider, ProviderMetadata, ProviderUsage};
 use super::errors::ProviderError;
 use super::formats::anthropic::{
-    create_request, get_usage, response_to_message, response_to_streaming_message,
+    create_request, create_structured_request, get_usage, response_to_message,
+    response_to_streaming_message, structured_response_to_message, thinking_budget,
 };
 use super::utils::{emit_debug_trace, get_model};
 use crate::message::Message;
@@ -73,6 +74,28 @@ impl AnthropicProvider {
         })
     }
 
+    fn request_headers(&self) -> HeaderMap {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-api-key", self.api_key.parse().unwrap());
+        headers.insert("anthropic-version", ANTHROPIC_API_VERSION.parse().unwrap());
+
+        let is_thinking_enabled = thinking_budget(&self.model).is_some();
+        if self.model.model_name.starts_with("claude-3-7-sonnet-") && is_thinking_enabled {
+            // https://docs.anthropic.com/en/docs/build-with-claude/extended-thinking#extended-output-capabilities-beta
+            headers.insert("anthropic-beta", "output-128k-2025-02-19".parse().unwrap());
+        }
+
+        if self.model.model_name.starts_with("claude-3-7-sonnet-") {
+            // https://docs.anthropic.com/en/docs/build-with-claude/tool-use/token-efficient-tool-use
+            headers.insert(
+                "anthropic-beta",
+                "token-efficient-tools-2025-02-19".parse().unwrap(),
+            );
+        }
+
+        headers
+    }
+
     async fn post(&self, headers: HeaderMap, payload: &Value) -> Result<Value, ProviderError> {
         let base_url = url::Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
@@ -179,26 +202,8 @@ impl Provider for AnthropicProvider {
     ) -> Result<(Message, ProviderUsage), ProviderError> {
         let payload = create_request(&self.model, system, messages, tools)?;
 
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("x-api-key", self.api_key.parse().unwrap());
-        headers.insert("anthropic-version", ANTHROPIC_API_VERSION.parse().unwrap());
-
-        let is_thinking_enabled = std::env::var("CLAUDE_THINKING_ENABLED").is_ok();
-        if self.model.model_name.starts_with("claude-3-7-sonnet-") && is_thinking_enabled {
-            // https://docs.anthropic.com/en/docs/build-with-claude/extended-thinking#extended-output-capabilities-beta
-            headers.insert("anthropic-beta", "output-128k-2025-02-19".parse().unwrap());
-        }
-
-        if self.model.model_name.starts_with("claude-3-7-sonnet-") {
-            // https://docs.anthropic.com/en/docs/build-with-claude/tool-use/token-efficient-tool-use
-            headers.insert(
-                "anthropic-beta",
-                "token-efficient-tools-2025-02-19".parse().unwrap(),
-            );
-        }
-
         // Make request
-        let response = self.post(headers, &payload).await?;
+        let response = self.post(self.request_headers(), &payload).await?;
 
         // Parse response
         let message = response_to_message(&response)?;
@@ -262,23 +267,7 @@ impl Provider for AnthropicProvider {
             .unwrap()
             .insert("stream".to_string(), Value::Bool(true));
 
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert("x-api-key", self.api_key.parse().unwrap());
-        headers.insert("anthropic-version", ANTHROPIC_API_VERSION.parse().unwrap());
-
-        let is_thinking_enabled = std::env::var("CLAUDE_THINKING_ENABLED").is_ok();
-        if self.model.model_name.starts_with("claude-3-7-sonnet-") && is_thinking_enabled {
-            // https://docs.anthropic.com/en/docs/build-with-claude/extended-thinking#extended-output-capabilities-beta
-            headers.insert("anthropic-beta", "output-128k-2025-02-19".parse().unwrap());
-        }
-
-        if self.model.model_name.starts_with("claude-3-7-sonnet-") {
-            // https://docs.anthropic.com/en/docs/build-with-claude/tool-use/token-efficient-tool-use
-            headers.insert(
-                "anthropic-beta",
-                "token-efficient-tools-2025-02-19".parse().unwrap(),
-            );
-        }
+        let headers = self.request_headers();
 
         let base_url = url::Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
@@ -322,6 +311,26 @@ impl Provider for AnthropicProvider {
         }))
     }
 
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    async fn complete_structured(
+        &self,
+        system: &str,
+        messages: &[Message],
+        schema: &Value,
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let payload = create_structured_request(&self.model, system, messages, schema)?;
+        let response = self.post(self.request_headers(), &payload).await?;
+
+        let message = structured_response_to_message(&response)?;
+        let usage = get_usage(&response)?;
+        let model = get_model(&response);
+        emit_debug_trace(&self.model, &payload, &response, &usage);
+        Ok((message, ProviderUsage::new(model, usage)))
+    }
+
     fn supports_streaming(&self) -> bool {
         true
     }
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use futures::Stream;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use super::errors::ProviderError;
 use crate::message::Message;
@@ -296,6 +297,11 @@ pub trait Provider: Send + Sync {
         false
     }
 
+    /// Length of the vectors returned by `create_embeddings`, when known without a request
+    fn embedding_dimension(&self) -> Option<usize> {
+        None
+    }
+
     /// Check if this provider supports cache control
     fn supports_cache_control(&self) -> bool {
         false
@@ -308,6 +314,25 @@ pub trait Provider: Send + Sync {
         ))
     }
 
+    /// Check if this provider can constrain a response to a JSON schema natively
+    fn supports_structured_output(&self) -> bool {
+        false
+    }
+
+    /// Generate a response whose text is a JSON value matching `schema`, without tools.
+    /// Only called when `supports_structured_output` returns true; providers without native
+    /// support are asked for structured output through the final output tool instead.
+    async fn complete_structured(
+        &self,
+        _system: &str,
+        _messages: &[Message],
+        _schema: &Value,
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        Err(ProviderError::NotImplemented(
+            "structured output not implemented".to_string(),
+        ))
+    }
+
     /// Check if this provider is a LeadWorkerProvider
     /// This is used for logging model information at startup
     fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
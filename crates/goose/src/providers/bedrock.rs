@@ -2,15 +2,17 @@ use std::collections::HashMap;
 use std::time::Duration;
 
 use anyhow::Result;
+use async_stream::try_stream;
 use async_trait::async_trait;
 use aws_sdk_bedrockruntime::config::ProvideCredentials;
 use aws_sdk_bedrockruntime::operation::converse::ConverseError;
+use aws_sdk_bedrockruntime::operation::converse_stream::ConverseStreamError;
 use aws_sdk_bedrockruntime::{types as bedrock, Client};
-use rmcp::model::Tool;
+use rmcp::model::{Role, Tool};
 use serde_json::Value;
 use tokio::time::sleep;
 
-use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage};
+use super::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage};
 use super::errors::ProviderError;
 use crate::message::Message;
 use crate::model::ModelConfig;
@@ -18,7 +20,8 @@ use crate::providers::utils::emit_debug_trace;
 
 // Import the migrated helper functions from providers/formats/bedrock.rs
 use super::formats::bedrock::{
-    from_bedrock_message, from_bedrock_usage, to_bedrock_message, to_bedrock_tool_config,
+    from_bedrock_message, from_bedrock_usage, to_bedrock_message, to_bedrock_thinking_config,
+    to_bedrock_tool_config, ConverseStreamState,
 };
 
 pub const BEDROCK_DOC_LINK: &str =
@@ -30,6 +33,11 @@ pub const BEDROCK_KNOWN_MODELS: &[&str] = &[
     "anthropic.claude-3-5-sonnet-20241022-v2:0",
 ];
 
+// Retry configuration for throttled requests
+const MAX_RETRIES: u32 = 10;
+const INITIAL_BACKOFF_MS: u64 = 20_000; // 20 seconds
+const MAX_BACKOFF_MS: u64 = 120_000; // 120 seconds (2 minutes)
+
 #[derive(Debug, serde::Serialize)]
 pub struct BedrockProvider {
     #[serde(skip)]
@@ -123,10 +131,11 @@ impl Provider for BedrockProvider {
             request = request.tool_config(to_bedrock_tool_config(tools)?);
         }
 
-        // Retry configuration
-        const MAX_RETRIES: u32 = 10;
-        const INITIAL_BACKOFF_MS: u64 = 20_000; // 20 seconds
-        const MAX_BACKOFF_MS: u64 = 120_000; // 120 seconds (2 minutes)
+        if let Some((inference_config, fields)) = to_bedrock_thinking_config(&self.model) {
+            request = request
+                .inference_config(inference_config)
+                .additional_model_request_fields(fields);
+        }
 
         let mut attempts = 0;
         let mut backoff_ms = INITIAL_BACKOFF_MS;
@@ -235,4 +244,122 @@ impl Provider for BedrockProvider {
             }
         }
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let model_name = self.model.model_name.clone();
+
+        let mut request = self
+            .client
+            .converse_stream()
+            .system(bedrock::SystemContentBlock::Text(system.to_string()))
+            .model_id(model_name.clone())
+            .set_messages(Some(
+                messages
+                    .iter()
+                    .map(to_bedrock_message)
+                    .collect::<Result<_>>()?,
+            ));
+
+        if !tools.is_empty() {
+            request = request.tool_config(to_bedrock_tool_config(tools)?);
+        }
+
+        if let Some((inference_config, fields)) = to_bedrock_thinking_config(&self.model) {
+            request = request
+                .inference_config(inference_config)
+                .additional_model_request_fields(fields);
+        }
+
+        let mut attempts = 0;
+        let mut backoff_ms = INITIAL_BACKOFF_MS;
+
+        let output = loop {
+            attempts += 1;
+
+            match request.clone().send().await {
+                Ok(output) => break output,
+                Err(err) => match err.into_service_error() {
+                    ConverseStreamError::ThrottlingException(throttle_err)
+                        if attempts <= MAX_RETRIES =>
+                    {
+                        tracing::warn!(
+                            "Bedrock throttling error (attempt {}/{}), retrying in {} ms: {:?}",
+                            attempts,
+                            MAX_RETRIES,
+                            backoff_ms,
+                            throttle_err
+                        );
+                        sleep(Duration::from_millis(backoff_ms)).await;
+                        backoff_ms = (backoff_ms * 2).min(MAX_BACKOFF_MS);
+                    }
+                    err => return Err(from_converse_stream_error(err)),
+                },
+            }
+        };
+
+        let model_config = self.model.clone();
+        let debug_payload = serde_json::json!({
+            "system": system,
+            "messages": messages,
+            "tools": tools
+        });
+
+        Ok(Box::pin(try_stream! {
+            let mut receiver = output.stream;
+            let mut state = ConverseStreamState::default();
+            // Partial messages of one response share an id so they can be joined back together
+            let message_id = uuid::Uuid::new_v4().to_string();
+
+            while let Some(event) = receiver.recv().await.map_err(|e| {
+                ProviderError::RequestFailed(format!("Bedrock stream failed: {:?}", e))
+            })? {
+                if let Some(content) = state.handle_event(&event) {
+                    let mut message = Message::new(
+                        Role::Assistant,
+                        chrono::Utc::now().timestamp(),
+                        vec![content],
+                    );
+                    message.id = Some(message_id.clone());
+                    yield (Some(message), None);
+                }
+            }
+
+            let usage = state.usage().unwrap_or_default();
+            emit_debug_trace(&model_config, &debug_payload, &Value::Null, &usage);
+            yield (None, Some(ProviderUsage::new(model_name, usage)));
+        }))
+    }
+}
+
+fn from_converse_stream_error(err: ConverseStreamError) -> ProviderError {
+    match err {
+        ConverseStreamError::ThrottlingException(err) => ProviderError::RateLimitExceeded(format!(
+            "Failed to call Bedrock after {MAX_RETRIES} retries: {:?}",
+            err
+        )),
+        ConverseStreamError::AccessDeniedException(err) => {
+            ProviderError::Authentication(format!("Failed to call Bedrock: {:?}", err))
+        }
+        ConverseStreamError::ValidationException(err)
+            if err
+                .message()
+                .unwrap_or_default()
+                .contains("Input is too long for requested model.") =>
+        {
+            ProviderError::ContextLengthExceeded(format!("Failed to call Bedrock: {:?}", err))
+        }
+        ConverseStreamError::ModelErrorException(err) => {
+            ProviderError::ExecutionError(format!("Failed to call Bedrock: {:?}", err))
+        }
+        err => ProviderError::ServerError(format!("Failed to call Bedrock: {:?}", err)),
+    }
 }
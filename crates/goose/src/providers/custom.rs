@@ -0,0 +1,503 @@
+//! Custom providers declared in the config file
+//!
+//! Any gateway that speaks the OpenAI chat completions API (vLLM, LM Studio, llama.cpp,
+//! internal proxies) can be added without code changes by declaring it under
+//! `custom_providers` in config.yaml:
+//!
+//! ```yaml
+//! custom_providers:
+//!   vllm:
+//!     display_name: vLLM
+//!     base_url: http://localhost:8000/v1
+//!     auth: none
+//!     models:
+//!       - name: Qwen/Qwen2.5-Coder-32B-Instruct
+//!         context_limit: 32768
+//!     supports_tools: false
+//! ```
+//!
+//! Declared providers are listed alongside the built-in ones in `providers()` and are
+//! created by name in `create`. Requests use the OpenAI format from `formats::openai`.
+
+use super::base::{
+    ConfigKey, MessageStream, ModelInfo, Provider, ProviderMetadata, ProviderUsage, Usage,
+};
+use super::errors::ProviderError;
+use super::utils::{
+    decoded_message_stream, emit_debug_trace, get_model, handle_response_openai_compat,
+    handle_status_openai_compat, sse_lines, ImageFormat,
+};
+use crate::config::{Config, ConfigError};
+use crate::message::Message;
+use crate::model::ModelConfig;
+use crate::providers::formats::openai::{
+    create_request, get_usage, response_to_message, response_to_streaming_message,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder, Response};
+use rmcp::model::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use url::Url;
+
+/// Config key holding the custom provider declarations
+pub const CUSTOM_PROVIDERS_CONFIG_KEY: &str = "custom_providers";
+pub const CUSTOM_PROVIDER_TIMEOUT: u64 = 600; // seconds
+
+/// How requests to a custom provider are authenticated
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomProviderAuth {
+    /// `Authorization: Bearer <key>`
+    #[default]
+    Bearer,
+    /// The key is sent as-is in the named header, e.g. `{header: api-key}`
+    Header(String),
+    /// No authentication
+    None,
+}
+
+/// A model offered by a custom provider
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomModel {
+    pub name: String,
+    #[serde(default)]
+    pub context_limit: Option<usize>,
+}
+
+/// A custom OpenAI-compatible provider declared in the config file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomProviderConfig {
+    /// The provider name, taken from its key under `custom_providers`
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Base URL of the API, `chat/completions` and `models` are resolved against it
+    pub base_url: String,
+    #[serde(default)]
+    pub auth: CustomProviderAuth,
+    /// Config or secret key holding the API key (default: `<NAME>_API_KEY`)
+    #[serde(default)]
+    pub api_key_name: Option<String>,
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub models: Vec<CustomModel>,
+    /// Default model, the first declared model when not set
+    #[serde(default)]
+    pub default_model: Option<String>,
+    #[serde(default = "default_true")]
+    pub supports_streaming: bool,
+    /// Whether the models support native tool calling, the tool shim is used when they do not
+    #[serde(default = "default_true")]
+    pub supports_tools: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl CustomProviderConfig {
+    pub fn api_key_name(&self) -> String {
+        self.api_key_name.clone().unwrap_or_else(|| {
+            format!(
+                "{}_API_KEY",
+                self.name.to_uppercase().replace(['-', '.'], "_")
+            )
+        })
+    }
+
+    pub fn default_model(&self) -> String {
+        self.default_model
+            .clone()
+            .or_else(|| self.models.first().map(|m| m.name.clone()))
+            .unwrap_or_default()
+    }
+
+    fn context_limit(&self, model_name: &str) -> Option<usize> {
+        self.models
+            .iter()
+            .find(|m| m.name == model_name)
+            .and_then(|m| m.context_limit)
+    }
+
+    pub fn metadata(&self) -> ProviderMetadata {
+        let models = self
+            .models
+            .iter()
+            .map(|m| {
+                let context_limit = m
+                    .context_limit
+                    .unwrap_or_else(|| ModelConfig::new(m.name.clone()).context_limit());
+                ModelInfo::new(m.name.clone(), context_limit)
+            })
+            .collect();
+
+        let config_keys = match self.auth {
+            CustomProviderAuth::None => vec![],
+            _ => vec![ConfigKey::new(&self.api_key_name(), true, true, None)],
+        };
+
+        ProviderMetadata::with_models(
+            &self.name,
+            self.display_name.as_deref().unwrap_or(&self.name),
+            self.description
+                .as_deref()
+                .unwrap_or("Custom OpenAI-compatible provider"),
+            &self.default_model(),
+            models,
+            &self.base_url,
+            config_keys,
+        )
+    }
+}
+
+/// Load the custom providers declared in the config
+pub fn custom_providers() -> Vec<CustomProviderConfig> {
+    let declared: BTreeMap<String, CustomProviderConfig> =
+        match Config::global().get_param(CUSTOM_PROVIDERS_CONFIG_KEY) {
+            Ok(declared) => declared,
+            Err(ConfigError::NotFound(_)) => BTreeMap::new(),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid {}: {}", CUSTOM_PROVIDERS_CONFIG_KEY, e);
+                BTreeMap::new()
+            }
+        };
+
+    declared
+        .into_iter()
+        .map(|(name, mut definition)| {
+            definition.name = name;
+            definition
+        })
+        .collect()
+}
+
+/// Find a declared custom provider by name
+pub fn find_custom_provider(name: &str) -> Option<CustomProviderConfig> {
+    custom_providers().into_iter().find(|p| p.name == name)
+}
+
+#[derive(serde::Serialize)]
+pub struct CustomProvider {
+    #[serde(skip)]
+    client: Client,
+    definition: CustomProviderConfig,
+    #[serde(skip)]
+    api_key: Option<String>,
+    model: ModelConfig,
+}
+
+impl CustomProvider {
+    pub fn from_config(definition: CustomProviderConfig, model: ModelConfig) -> Result<Self> {
+        let config = Config::global();
+        let api_key = match definition.auth {
+            CustomProviderAuth::None => None,
+            _ => Some(config.get_secret(&definition.api_key_name())?),
+        };
+
+        let mut model = model;
+        // A declared limit replaces the built-in default for the model name, but not an override
+        if let Some(limit) = definition.context_limit(&model.model_name) {
+            if model.context_limit == ModelConfig::get_model_specific_limit(&model.model_name) {
+                model = model.with_context_limit(Some(limit));
+            }
+        }
+        if !definition.supports_tools {
+            model = model.with_toolshim(true);
+        }
+
+        let timeout = Duration::from_secs(
+            config
+                .get_param("CUSTOM_PROVIDER_TIMEOUT")
+                .unwrap_or(CUSTOM_PROVIDER_TIMEOUT),
+        );
+        let client = Client::builder().timeout(timeout).build()?;
+
+        Ok(Self {
+            client,
+            definition,
+            api_key,
+            model,
+        })
+    }
+
+    fn url(&self, path: &str) -> Result<Url, ProviderError> {
+        // Ensure the base URL ends with a slash so the path is appended rather than replacing
+        // the last segment
+        let base_url = if self.definition.base_url.ends_with('/') {
+            self.definition.base_url.clone()
+        } else {
+            format!("{}/", self.definition.base_url)
+        };
+        Url::parse(&base_url)
+            .and_then(|url| url.join(path))
+            .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))
+    }
+
+    fn with_headers(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(api_key) = &self.api_key {
+            request = match &self.definition.auth {
+                CustomProviderAuth::Bearer => request.bearer_auth(api_key),
+                CustomProviderAuth::Header(name) => request.header(name, api_key),
+                CustomProviderAuth::None => request,
+            };
+        }
+        for (key, value) in &self.definition.headers {
+            request = request.header(key, value);
+        }
+        request
+    }
+
+    async fn send(&self, payload: &Value) -> Result<Response, ProviderError> {
+        let url = self.url("chat/completions")?;
+        tracing::debug!("{} API URL: {}", self.definition.name, url);
+
+        Ok(self
+            .with_headers(self.client.post(url))
+            .json(payload)
+            .send()
+            .await?)
+    }
+
+    fn create_request(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<Value, ProviderError> {
+        // Tool definitions are left out for models without native tool calling,
+        // the tool shim describes them in the system prompt instead
+        let tools = if self.definition.supports_tools {
+            tools
+        } else {
+            &[]
+        };
+        Ok(create_request(
+            &self.model,
+            system,
+            messages,
+            tools,
+            &ImageFormat::OpenAi,
+        )?)
+    }
+}
+
+#[async_trait]
+impl Provider for CustomProvider {
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata::new(
+            "custom",
+            "Custom OpenAI-compatible",
+            "OpenAI-compatible providers declared under custom_providers in the config file",
+            "",
+            vec![],
+            "",
+            vec![],
+        )
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.model.clone()
+    }
+
+    #[tracing::instrument(
+        skip(self, system, messages, tools),
+        fields(model_config, input, output, input_tokens, output_tokens, total_tokens)
+    )]
+    async fn complete(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let payload = self.create_request(system, messages, tools)?;
+
+        let response = handle_response_openai_compat(self.send(&payload).await?).await?;
+
+        let message = response_to_message(&response)?;
+        let usage = response.get("usage").map(get_usage).unwrap_or_else(|| {
+            tracing::debug!("Failed to get usage data");
+            Usage::default()
+        });
+        let model = get_model(&response);
+        emit_debug_trace(&self.model, &payload, &response, &usage);
+        Ok((message, ProviderUsage::new(model, usage)))
+    }
+
+    async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
+        if !self.definition.models.is_empty() {
+            return Ok(Some(
+                self.definition
+                    .models
+                    .iter()
+                    .map(|m| m.name.clone())
+                    .collect(),
+            ));
+        }
+
+        let response = self
+            .with_headers(self.client.get(self.url("models")?))
+            .send()
+            .await?;
+        let json = handle_response_openai_compat(response).await?;
+        let mut models: Vec<String> = json
+            .get("data")
+            .and_then(|v| v.as_array())
+            .map(|data| {
+                data.iter()
+                    .filter_map(|m| m.get("id").and_then(|v| v.as_str()).map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        models.sort();
+        Ok(Some(models))
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.definition.supports_streaming
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let mut payload = self.create_request(system, messages, tools)?;
+        payload["stream"] = Value::Bool(true);
+        payload["stream_options"] = json!({
+            "include_usage": true,
+        });
+
+        let response = handle_status_openai_compat(self.send(&payload).await?).await?;
+
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response)),
+            self.model.clone(),
+            payload,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    const DECLARATIONS: &str = r#"{
+        "local-vllm": {
+            "display_name": "vLLM",
+            "base_url": "http://localhost:8000/v1",
+            "auth": "none",
+            "models": [
+                {"name": "qwen-coder", "context_limit": 32768},
+                {"name": "llama-3"}
+            ],
+            "supports_tools": false
+        },
+        "gateway": {
+            "base_url": "https://llm.internal.example.com/openai/",
+            "auth": {"header": "api-key"},
+            "headers": {"X-Team": "goose"},
+            "default_model": "gpt-4o"
+        }
+    }"#;
+
+    #[test]
+    #[serial]
+    fn test_custom_providers_from_config() {
+        temp_env::with_var("CUSTOM_PROVIDERS", Some(DECLARATIONS), || {
+            let providers = custom_providers();
+            assert_eq!(providers.len(), 2);
+
+            let gateway = &providers[0];
+            assert_eq!(gateway.name, "gateway");
+            assert_eq!(
+                gateway.auth,
+                CustomProviderAuth::Header("api-key".to_string())
+            );
+            assert_eq!(gateway.api_key_name(), "GATEWAY_API_KEY");
+            assert_eq!(gateway.default_model(), "gpt-4o");
+            assert!(gateway.supports_streaming);
+            assert!(gateway.supports_tools);
+
+            let vllm = find_custom_provider("local-vllm").unwrap();
+            assert_eq!(vllm.auth, CustomProviderAuth::None);
+            assert_eq!(vllm.api_key_name(), "LOCAL_VLLM_API_KEY");
+            assert_eq!(vllm.default_model(), "qwen-coder");
+            assert!(!vllm.supports_tools);
+
+            assert!(find_custom_provider("missing").is_none());
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn test_custom_provider_metadata() {
+        temp_env::with_var("CUSTOM_PROVIDERS", Some(DECLARATIONS), || {
+            let metadata = find_custom_provider("local-vllm").unwrap().metadata();
+            assert_eq!(metadata.name, "local-vllm");
+            assert_eq!(metadata.display_name, "vLLM");
+            assert_eq!(metadata.default_model, "qwen-coder");
+            assert_eq!(metadata.known_models.len(), 2);
+            assert_eq!(metadata.known_models[0].context_limit, 32768);
+            assert!(metadata.config_keys.is_empty());
+
+            let metadata = find_custom_provider("gateway").unwrap().metadata();
+            assert_eq!(metadata.display_name, "gateway");
+            assert_eq!(metadata.config_keys.len(), 1);
+            assert_eq!(metadata.config_keys[0].name, "GATEWAY_API_KEY");
+            assert!(metadata.config_keys[0].secret);
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn test_custom_provider_model_config() {
+        temp_env::with_vars(
+            [
+                ("CUSTOM_PROVIDERS", Some(DECLARATIONS)),
+                ("GOOSE_TOOLSHIM", None),
+                ("GOOSE_CONTEXT_LIMIT", None),
+            ],
+            || {
+                let definition = find_custom_provider("local-vllm").unwrap();
+                let provider = CustomProvider::from_config(
+                    definition,
+                    ModelConfig::new("qwen-coder".to_string()),
+                )
+                .unwrap();
+
+                let model = provider.get_model_config();
+                assert_eq!(model.context_limit(), 32768);
+                assert!(model.toolshim);
+                assert!(provider.supports_streaming());
+                assert_eq!(
+                    provider.url("chat/completions").unwrap().as_str(),
+                    "http://localhost:8000/v1/chat/completions"
+                );
+
+                let payload = provider
+                    .create_request(
+                        "system",
+                        &[Message::user().with_text("hi")],
+                        &[Tool::new(
+                            "developer__shell".to_string(),
+                            "shell".to_string(),
+                            rmcp::object!({"type": "object"}),
+                        )],
+                    )
+                    .unwrap();
+                assert!(payload.get("tools").is_none());
+            },
+        );
+    }
+}
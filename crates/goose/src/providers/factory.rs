@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
+#[cfg(feature = "local-embeddings")]
+use super::local_embedding::LocalEmbeddingProvider;
 use super::{
     anthropic::AnthropicProvider,
     azure::AzureProvider,
     base::{Provider, ProviderMetadata},
     bedrock::BedrockProvider,
     claude_code::ClaudeCodeProvider,
+    custom::{custom_providers, find_custom_provider, CustomProvider},
     databricks::DatabricksProvider,
     gcpvertexai::GcpVertexAIProvider,
     gemini_cli::GeminiCliProvider,
@@ -40,6 +43,24 @@ fn default_fallback_turns() -> usize {
 }
 
 pub fn providers() -> Vec<ProviderMetadata> {
+    let mut providers = builtin_providers();
+
+    // Custom providers declared in the config cannot shadow the built-in ones
+    for custom in custom_providers() {
+        if providers.iter().any(|p| p.name == custom.name) {
+            tracing::warn!(
+                "Ignoring custom provider {}, a built-in provider has the same name",
+                custom.name
+            );
+            continue;
+        }
+        providers.push(custom.metadata());
+    }
+
+    providers
+}
+
+fn builtin_providers() -> Vec<ProviderMetadata> {
     vec![
         AnthropicProvider::metadata(),
         AzureProvider::metadata(),
@@ -161,6 +182,8 @@ fn create_provider(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>>
         "google" => Ok(Arc::new(GoogleProvider::from_env(model)?)),
         "groq" => Ok(Arc::new(GroqProvider::from_env(model)?)),
         "litellm" => Ok(Arc::new(LiteLLMProvider::from_env(model)?)),
+        #[cfg(feature = "local-embeddings")]
+        "local" => Ok(Arc::new(LocalEmbeddingProvider::from_env(model)?)),
         "ollama" => Ok(Arc::new(OllamaProvider::from_env(model)?)),
         "openai" => Ok(Arc::new(OpenAiProvider::from_env(model)?)),
         "openrouter" => Ok(Arc::new(OpenRouterProvider::from_env(model)?)),
@@ -168,7 +191,10 @@ fn create_provider(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>>
         "snowflake" => Ok(Arc::new(SnowflakeProvider::from_env(model)?)),
         "venice" => Ok(Arc::new(VeniceProvider::from_env(model)?)),
         "xai" => Ok(Arc::new(XaiProvider::from_env(model)?)),
-        _ => Err(anyhow::anyhow!("Unknown provider: {}", name)),
+        _ => match find_custom_provider(name) {
+            Some(definition) => Ok(Arc::new(CustomProvider::from_config(definition, model)?)),
+            None => Err(anyhow::anyhow!("Unknown provider: {}", name)),
+        },
     }
 }
 
@@ -28,6 +28,9 @@ const IS_ERROR_FIELD: &str = "is_error";
 const SIGNATURE_FIELD: &str = "signature";
 const DATA_FIELD: &str = "data";
 
+/// Name of the tool the model is forced to call to return structured output
+pub const STRUCTURED_OUTPUT_TOOL_NAME: &str = "structured_output";
+
 /// Convert internal Message format to Anthropic's API message specification
 pub fn format_messages(messages: &[Message]) -> Vec<Value> {
     let mut anthropic_messages = Vec::new();
@@ -401,10 +404,12 @@ pub fn create_request(
             .insert("tools".to_string(), json!(tool_specs));
     }
 
+    let thinking_budget = thinking_budget(model_config);
+
     // Add temperature if specified and not using extended thinking model
     if let Some(temp) = model_config.temperature {
-        // Claude 3.7 models with thinking enabled don't support temperature
-        if !model_config.model_name.starts_with("claude-3-7-sonnet-") {
+        // Claude 3.7 models and models with thinking enabled don't support temperature
+        if !model_config.model_name.starts_with("claude-3-7-sonnet-") && thinking_budget.is_none() {
             payload
                 .as_object_mut()
                 .unwrap()
@@ -412,15 +417,8 @@ pub fn create_request(
         }
     }
 
-    // Add thinking parameters for claude-3-7-sonnet model
-    let is_thinking_enabled = std::env::var("CLAUDE_THINKING_ENABLED").is_ok();
-    if model_config.model_name.starts_with("claude-3-7-sonnet-") && is_thinking_enabled {
-        // Minimum budget_tokens is 1024
-        let budget_tokens = std::env::var("CLAUDE_THINKING_BUDGET")
-            .unwrap_or_else(|_| "16000".to_string())
-            .parse()
-            .unwrap_or(16000);
-
+    // Add thinking parameters for models that support extended thinking
+    if let Some(budget_tokens) = thinking_budget {
         payload
             .as_object_mut()
             .unwrap()
@@ -438,6 +436,73 @@ pub fn create_request(
     Ok(payload)
 }
 
+/// Create a request that forces a call to a single tool whose input schema is the given
+/// JSON schema, so the tool input is the structured output.
+///
+/// Extended thinking is left off since it cannot be combined with a forced tool choice.
+pub fn create_structured_request(
+    model_config: &ModelConfig,
+    system: &str,
+    messages: &[Message],
+    schema: &Value,
+) -> Result<Value> {
+    let mut payload = create_request(model_config, system, messages, &[])?;
+    let payload_map = payload.as_object_mut().unwrap();
+    payload_map.remove("thinking");
+    payload_map.insert(
+        "max_tokens".to_string(),
+        json!(model_config.max_tokens.unwrap_or(8192)),
+    );
+    payload_map.insert(
+        "tools".to_string(),
+        json!([{
+            NAME_FIELD: STRUCTURED_OUTPUT_TOOL_NAME,
+            "description": "Return the final output as JSON matching the input schema",
+            "input_schema": schema
+        }]),
+    );
+    payload_map.insert(
+        "tool_choice".to_string(),
+        json!({ TYPE_FIELD: "tool", NAME_FIELD: STRUCTURED_OUTPUT_TOOL_NAME }),
+    );
+    Ok(payload)
+}
+
+/// Convert a response to a structured request into a text message holding the JSON
+/// input of the forced tool call
+pub fn structured_response_to_message(response: &Value) -> Result<Message> {
+    let input = response
+        .get(CONTENT_FIELD)
+        .and_then(|c| c.as_array())
+        .and_then(|blocks| {
+            blocks.iter().find(|block| {
+                block.get(TYPE_FIELD).and_then(|t| t.as_str()) == Some(TOOL_USE_TYPE)
+                    && block.get(NAME_FIELD).and_then(|n| n.as_str())
+                        == Some(STRUCTURED_OUTPUT_TOOL_NAME)
+            })
+        })
+        .and_then(|block| block.get(INPUT_FIELD))
+        .ok_or_else(|| anyhow!("Response is missing the structured output tool call"))?;
+
+    Ok(Message::assistant().with_text(input.to_string()))
+}
+
+/// Whether a Claude model supports extended thinking
+pub fn supports_extended_thinking(model_name: &str) -> bool {
+    ["claude-3-7-sonnet", "claude-sonnet-4", "claude-opus-4"]
+        .iter()
+        .any(|prefix| model_name.contains(prefix))
+}
+
+/// The thinking budget to request, if thinking is configured and the model supports it.
+/// Anthropic requires a budget of at least 1024 tokens.
+pub fn thinking_budget(model_config: &ModelConfig) -> Option<i32> {
+    model_config
+        .effective_thinking_budget()
+        .filter(|_| supports_extended_thinking(&model_config.model_name))
+        .map(|budget| budget.max(1024))
+}
+
 /// Process streaming response from Anthropic's API
 pub fn response_to_streaming_message<S>(
     mut stream: S,
@@ -466,6 +531,8 @@ where
         let mut accumulated_text = String::new();
         let mut accumulated_tool_calls: std::collections::HashMap<String, (String, String)> = std::collections::HashMap::new();
         let mut current_tool_id: Option<String> = None;
+        // Thinking text and signature of the thinking block being streamed
+        let mut current_thinking: Option<(String, String)> = None;
         let mut final_usage: Option<crate::providers::base::ProviderUsage> = None;
         let mut message_id: Option<String> = None;
 
@@ -520,7 +587,20 @@ where
                 "content_block_start" => {
                     // A new content block started
                     if let Some(content_block) = event.data.get("content_block") {
-                        if content_block.get("type") == Some(&json!("tool_use")) {
+                        if content_block.get("type") == Some(&json!(THINKING_TYPE)) {
+                            current_thinking = Some((String::new(), String::new()));
+                        } else if content_block.get("type") == Some(&json!(REDACTED_THINKING_TYPE)) {
+                            // Redacted thinking arrives whole and must be replayed as is
+                            if let Some(data) = content_block.get(DATA_FIELD).and_then(|v| v.as_str()) {
+                                let mut message = Message::new(
+                                    Role::Assistant,
+                                    chrono::Utc::now().timestamp(),
+                                    vec![MessageContent::redacted_thinking(data)],
+                                );
+                                message.id = message_id.clone();
+                                yield (Some(message), None);
+                            }
+                        } else if content_block.get("type") == Some(&json!("tool_use")) {
                             if let Some(id) = content_block.get("id").and_then(|v| v.as_str()) {
                                 current_tool_id = Some(id.to_string());
                                 if let Some(name) = content_block.get("name").and_then(|v| v.as_str()) {
@@ -547,6 +627,14 @@ where
                                 message.id = message_id.clone();
                                 yield (Some(message), None);
                             }
+                        } else if delta.get("type") == Some(&json!("thinking_delta")) {
+                            if let (Some((thinking, _)), Some(text)) = (current_thinking.as_mut(), delta.get(THINKING_TYPE).and_then(|v| v.as_str())) {
+                                thinking.push_str(text);
+                            }
+                        } else if delta.get("type") == Some(&json!("signature_delta")) {
+                            if let (Some((_, signature)), Some(text)) = (current_thinking.as_mut(), delta.get(SIGNATURE_FIELD).and_then(|v| v.as_str())) {
+                                signature.push_str(text);
+                            }
                         } else if delta.get("type") == Some(&json!("input_json_delta")) {
                             // Tool input delta
                             if let Some(tool_id) = &current_tool_id {
@@ -562,7 +650,16 @@ where
                 }
                 "content_block_stop" => {
                     // Content block finished
-                    if let Some(tool_id) = current_tool_id.take() {
+                    if let Some((thinking, signature)) = current_thinking.take() {
+                        // Thinking is yielded whole so that it can be replayed with its signature
+                        let mut message = Message::new(
+                            Role::Assistant,
+                            chrono::Utc::now().timestamp(),
+                            vec![MessageContent::thinking(thinking, signature)],
+                        );
+                        message.id = message_id.clone();
+                        yield (Some(message), None);
+                    } else if let Some(tool_id) = current_tool_id.take() {
                         // Tool call finished, yield complete tool call
                         if let Some((name, args)) = accumulated_tool_calls.remove(&tool_id) {
                             let parsed_args = if args.is_empty() {
@@ -952,6 +1049,115 @@ mod tests {
         result
     }
 
+    #[tokio::test]
+    async fn test_streamed_thinking_is_yielded_whole() -> Result<()> {
+        use futures::StreamExt;
+
+        let events = [
+            json!({"type": "message_start", "message": {"id": "msg_1", "model": "claude-sonnet-4", "usage": {"input_tokens": 10, "output_tokens": 1}}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "Let me "}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "think."}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "signature_delta", "signature": "sig"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "Done"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "message_stop"}),
+        ];
+        let lines: Vec<Result<String>> = events
+            .iter()
+            .map(|event| Ok(format!("data: {}", event)))
+            .collect();
+
+        let stream = response_to_streaming_message(futures::stream::iter(lines));
+        tokio::pin!(stream);
+        let mut contents = Vec::new();
+        while let Some(item) = stream.next().await {
+            if let (Some(message), _) = item? {
+                assert_eq!(message.id.as_deref(), Some("msg_1"));
+                contents.extend(message.content);
+            }
+        }
+
+        assert_eq!(contents.len(), 2);
+        match &contents[0] {
+            MessageContent::Thinking(thinking) => {
+                assert_eq!(thinking.thinking, "Let me think.");
+                assert_eq!(thinking.signature, "sig");
+            }
+            other => panic!("Expected thinking content, got {:?}", other),
+        }
+        assert_eq!(contents[1].as_text(), Some("Done"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_request_with_thinking_budget() -> Result<()> {
+        let messages = vec![Message::user().with_text("Hello")];
+
+        let model_config = ModelConfig::new("claude-sonnet-4-20250514".to_string())
+            .with_temperature(Some(0.5))
+            .with_max_tokens(Some(4000))
+            .with_thinking_budget(Some(500));
+        let payload = create_request(&model_config, "system", &messages, &[])?;
+        assert_eq!(payload["thinking"]["budget_tokens"], 1024);
+        assert_eq!(payload["max_tokens"], 5024);
+        assert!(payload.get("temperature").is_none());
+
+        let model_config = ModelConfig::new("claude-3-5-haiku-latest".to_string())
+            .with_temperature(Some(0.5))
+            .with_reasoning_effort(Some(crate::model::ReasoningEffort::High));
+        let payload = create_request(&model_config, "system", &messages, &[])?;
+        assert!(payload.get("thinking").is_none());
+        assert_eq!(payload["temperature"], 0.5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_structured_request() -> Result<()> {
+        let model_config = ModelConfig::new("claude-sonnet-4-20250514".to_string());
+        let messages = vec![Message::user().with_text("How many files?")];
+        let schema = json!({
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"]
+        });
+
+        let payload = create_structured_request(&model_config, "system", &messages, &schema)?;
+        assert!(payload.get("thinking").is_none());
+        assert_eq!(payload["tools"].as_array().unwrap().len(), 1);
+        assert_eq!(payload["tools"][0]["name"], STRUCTURED_OUTPUT_TOOL_NAME);
+        assert_eq!(payload["tools"][0]["input_schema"], schema);
+        assert_eq!(payload["tool_choice"]["type"], "tool");
+        assert_eq!(payload["tool_choice"]["name"], STRUCTURED_OUTPUT_TOOL_NAME);
+        Ok(())
+    }
+
+    #[test]
+    fn test_structured_response_to_message() -> Result<()> {
+        let response = json!({
+            "id": "msg_123",
+            "type": "message",
+            "role": "assistant",
+            "content": [{
+                "type": "tool_use",
+                "id": "tool_1",
+                "name": STRUCTURED_OUTPUT_TOOL_NAME,
+                "input": {"count": 3}
+            }],
+            "usage": {"input_tokens": 10, "output_tokens": 5}
+        });
+
+        let message = structured_response_to_message(&response)?;
+        let output: Value = serde_json::from_str(&message.as_concat_text())?;
+        assert_eq!(output, json!({"count": 3}));
+
+        let missing = json!({"content": [{"type": "text", "text": "3"}]});
+        assert!(structured_response_to_message(&missing).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_cache_pricing_calculation() -> Result<()> {
         // Test realistic cache scenario: small fresh input, large cached content
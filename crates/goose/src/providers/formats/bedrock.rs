@@ -3,15 +3,17 @@ use std::path::Path;
 
 use anyhow::{anyhow, bail, Result};
 use aws_sdk_bedrockruntime::types as bedrock;
-use aws_smithy_types::{Document, Number};
+use aws_smithy_types::{Blob, Document, Number};
 use base64::Engine;
 use chrono::Utc;
 use mcp_core::{ToolCall, ToolError, ToolResult};
 use rmcp::model::{Content, RawContent, ResourceContents, Role, Tool};
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use super::super::base::Usage;
+use super::anthropic::thinking_budget;
 use crate::message::{Message, MessageContent};
+use crate::model::ModelConfig;
 
 pub fn to_bedrock_message(message: &Message) -> Result<bedrock::Message> {
     bedrock::Message::builder()
@@ -36,13 +38,22 @@ pub fn to_bedrock_message_content(content: &MessageContent) -> Result<bedrock::C
         MessageContent::Image(image) => {
             bedrock::ContentBlock::Image(to_bedrock_image(&image.data, &image.mime_type)?)
         }
-        MessageContent::Thinking(_) => {
-            // Thinking blocks are not supported in Bedrock - skip
-            bedrock::ContentBlock::Text("".to_string())
+        MessageContent::Thinking(thinking) => {
+            bedrock::ContentBlock::ReasoningContent(bedrock::ReasoningContentBlock::ReasoningText(
+                bedrock::ReasoningTextBlock::builder()
+                    .text(thinking.thinking.to_string())
+                    .signature(thinking.signature.to_string())
+                    .build()?,
+            ))
         }
-        MessageContent::RedactedThinking(_) => {
-            // Redacted thinking blocks are not supported in Bedrock - skip
-            bedrock::ContentBlock::Text("".to_string())
+        MessageContent::RedactedThinking(redacted) => {
+            // Redacted content is kept base64 encoded, since it is opaque bytes
+            let data = base64::prelude::BASE64_STANDARD
+                .decode(&redacted.data)
+                .map_err(|e| anyhow!("Failed to decode redacted thinking: {}", e))?;
+            bedrock::ContentBlock::ReasoningContent(
+                bedrock::ReasoningContentBlock::RedactedContent(Blob::new(data)),
+            )
         }
         MessageContent::ContextLengthExceeded(_) => {
             bail!("ContextLengthExceeded should not get passed to the provider")
@@ -297,10 +308,42 @@ pub fn from_bedrock_content_block(block: &bedrock::ContentBlock) -> Result<Messa
                     .collect::<ToolResult<Vec<_>>>()
             },
         ),
+        bedrock::ContentBlock::ReasoningContent(reasoning) => match reasoning {
+            bedrock::ReasoningContentBlock::ReasoningText(text) => {
+                MessageContent::thinking(text.text(), text.signature().unwrap_or_default())
+            }
+            bedrock::ReasoningContentBlock::RedactedContent(data) => {
+                MessageContent::redacted_thinking(
+                    base64::prelude::BASE64_STANDARD.encode(data.as_ref()),
+                )
+            }
+            _ => bail!("Unsupported reasoning content block type from Bedrock"),
+        },
         _ => bail!("Unsupported content block type from Bedrock"),
     })
 }
 
+/// Inference configuration and additional request fields that enable extended thinking,
+/// if it is configured and the model is a Claude model that supports it
+pub fn to_bedrock_thinking_config(
+    model_config: &ModelConfig,
+) -> Option<(bedrock::InferenceConfiguration, Document)> {
+    let budget_tokens = thinking_budget(model_config)?;
+    // max_tokens must leave room for the answer on top of the thinking budget
+    let max_tokens = model_config.max_tokens.unwrap_or(8192) + budget_tokens;
+    Some((
+        bedrock::InferenceConfiguration::builder()
+            .max_tokens(max_tokens)
+            .build(),
+        to_bedrock_json(&json!({
+            "thinking": {
+                "type": "enabled",
+                "budget_tokens": budget_tokens
+            }
+        })),
+    ))
+}
+
 pub fn from_bedrock_tool_result_content_block(
     content: &bedrock::ToolResultContentBlock,
 ) -> ToolResult<Content> {
@@ -330,6 +373,116 @@ pub fn from_bedrock_usage(usage: &bedrock::TokenUsage) -> Usage {
     }
 }
 
+/// Assembles `ConverseStream` events into message content.
+///
+/// Text deltas are returned as they arrive. Tool use input arrives as partial JSON, so a
+/// tool request is returned only once its content block stops. Reasoning is likewise
+/// returned whole, since it is only valid to replay along with its signature.
+#[derive(Debug, Default)]
+pub struct ConverseStreamState {
+    /// Tool use id, name and accumulated input, by content block index
+    tool_uses: HashMap<i32, (String, String, String)>,
+    /// Accumulated reasoning text and signature, by content block index
+    reasoning: HashMap<i32, (String, String)>,
+    usage: Option<Usage>,
+}
+
+impl ConverseStreamState {
+    pub fn handle_event(
+        &mut self,
+        event: &bedrock::ConverseStreamOutput,
+    ) -> Option<MessageContent> {
+        match event {
+            bedrock::ConverseStreamOutput::ContentBlockStart(event) => {
+                if let Some(bedrock::ContentBlockStart::ToolUse(tool_use)) = event.start() {
+                    self.tool_uses.insert(
+                        event.content_block_index(),
+                        (
+                            tool_use.tool_use_id().to_string(),
+                            tool_use.name().to_string(),
+                            String::new(),
+                        ),
+                    );
+                }
+                None
+            }
+            bedrock::ConverseStreamOutput::ContentBlockDelta(event) => match event.delta() {
+                Some(bedrock::ContentBlockDelta::Text(text)) if !text.is_empty() => {
+                    Some(MessageContent::text(text))
+                }
+                Some(bedrock::ContentBlockDelta::ReasoningContent(delta)) => {
+                    let (text, signature) = self
+                        .reasoning
+                        .entry(event.content_block_index())
+                        .or_default();
+                    match delta {
+                        bedrock::ReasoningContentBlockDelta::Text(delta) => text.push_str(delta),
+                        bedrock::ReasoningContentBlockDelta::Signature(delta) => {
+                            signature.push_str(delta)
+                        }
+                        bedrock::ReasoningContentBlockDelta::RedactedContent(data) => {
+                            self.reasoning.remove(&event.content_block_index());
+                            return Some(MessageContent::redacted_thinking(
+                                base64::prelude::BASE64_STANDARD.encode(data.as_ref()),
+                            ));
+                        }
+                        _ => {}
+                    }
+                    None
+                }
+                Some(bedrock::ContentBlockDelta::ToolUse(delta)) => {
+                    if let Some((_, _, input)) =
+                        self.tool_uses.get_mut(&event.content_block_index())
+                    {
+                        input.push_str(delta.input());
+                    }
+                    None
+                }
+                _ => None,
+            },
+            bedrock::ConverseStreamOutput::ContentBlockStop(event) => {
+                let index = event.content_block_index();
+                if let Some((text, signature)) = self.reasoning.remove(&index) {
+                    return Some(MessageContent::thinking(text, signature));
+                }
+                self.tool_uses
+                    .remove(&index)
+                    .map(|(id, name, input)| streamed_tool_request(id, name, &input))
+            }
+            bedrock::ConverseStreamOutput::Metadata(event) => {
+                if let Some(usage) = event.usage() {
+                    self.usage = Some(from_bedrock_usage(usage));
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Token usage, available once the trailing metadata event has been handled
+    pub fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+}
+
+fn streamed_tool_request(id: String, name: String, input: &str) -> MessageContent {
+    let arguments = if input.trim().is_empty() {
+        Ok(Value::Object(Default::default()))
+    } else {
+        serde_json::from_str::<Value>(input)
+    };
+    match arguments {
+        Ok(arguments) => MessageContent::tool_request(id, Ok(ToolCall::new(name, arguments))),
+        Err(e) => {
+            let error = ToolError::InvalidParameters(format!(
+                "Could not interpret tool use parameters for id {}: {}",
+                id, e
+            ));
+            MessageContent::tool_request(id, Err(error))
+        }
+    }
+}
+
 pub fn from_bedrock_json(document: &Document) -> Result<Value> {
     Ok(match document {
         Document::Null => Value::Null,
@@ -358,6 +511,7 @@ mod tests {
     use super::*;
     use anyhow::Result;
     use rmcp::model::{AnnotateAble, RawImageContent};
+    use serde_json::json;
 
     // Base64 encoded 1x1 PNG image for testing
     const TEST_IMAGE_BASE64: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP8/5+hHgAHggJ/PchI7wAAAABJRU5ErkJggg==";
@@ -442,4 +596,144 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_converse_stream_state_assembles_tool_use() -> Result<()> {
+        let events = vec![
+            bedrock::ConverseStreamOutput::ContentBlockDelta(
+                bedrock::ContentBlockDeltaEvent::builder()
+                    .content_block_index(0)
+                    .delta(bedrock::ContentBlockDelta::Text("Listing".to_string()))
+                    .build()?,
+            ),
+            bedrock::ConverseStreamOutput::ContentBlockStop(
+                bedrock::ContentBlockStopEvent::builder()
+                    .content_block_index(0)
+                    .build()?,
+            ),
+            bedrock::ConverseStreamOutput::ContentBlockStart(
+                bedrock::ContentBlockStartEvent::builder()
+                    .content_block_index(1)
+                    .start(bedrock::ContentBlockStart::ToolUse(
+                        bedrock::ToolUseBlockStart::builder()
+                            .tool_use_id("tooluse_1")
+                            .name("developer__shell")
+                            .build()?,
+                    ))
+                    .build()?,
+            ),
+            bedrock::ConverseStreamOutput::ContentBlockDelta(
+                bedrock::ContentBlockDeltaEvent::builder()
+                    .content_block_index(1)
+                    .delta(bedrock::ContentBlockDelta::ToolUse(
+                        bedrock::ToolUseBlockDelta::builder()
+                            .input("{\"command\": ")
+                            .build()?,
+                    ))
+                    .build()?,
+            ),
+            bedrock::ConverseStreamOutput::ContentBlockDelta(
+                bedrock::ContentBlockDeltaEvent::builder()
+                    .content_block_index(1)
+                    .delta(bedrock::ContentBlockDelta::ToolUse(
+                        bedrock::ToolUseBlockDelta::builder()
+                            .input("\"ls\"}")
+                            .build()?,
+                    ))
+                    .build()?,
+            ),
+            bedrock::ConverseStreamOutput::ContentBlockStop(
+                bedrock::ContentBlockStopEvent::builder()
+                    .content_block_index(1)
+                    .build()?,
+            ),
+        ];
+
+        let mut state = ConverseStreamState::default();
+        let contents: Vec<MessageContent> = events
+            .iter()
+            .filter_map(|event| state.handle_event(event))
+            .collect();
+
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents[0].as_text(), Some("Listing"));
+        let MessageContent::ToolRequest(request) = &contents[1] else {
+            panic!("Expected a tool request");
+        };
+        assert_eq!(request.id, "tooluse_1");
+        let tool_call = request.tool_call.as_ref().unwrap();
+        assert_eq!(tool_call.name, "developer__shell");
+        assert_eq!(tool_call.arguments, json!({"command": "ls"}));
+        Ok(())
+    }
+
+    #[test]
+    fn test_thinking_round_trips_through_reasoning_content() -> Result<()> {
+        for content in [
+            MessageContent::thinking("Considering options", "sig"),
+            MessageContent::redacted_thinking(base64::prelude::BASE64_STANDARD.encode(b"opaque")),
+        ] {
+            let block = to_bedrock_message_content(&content)?;
+            assert!(matches!(block, bedrock::ContentBlock::ReasoningContent(_)));
+            assert_eq!(from_bedrock_content_block(&block)?, content);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_converse_stream_state_assembles_reasoning() -> Result<()> {
+        let delta = |delta: bedrock::ReasoningContentBlockDelta| {
+            bedrock::ContentBlockDeltaEvent::builder()
+                .content_block_index(0)
+                .delta(bedrock::ContentBlockDelta::ReasoningContent(delta))
+                .build()
+                .map(bedrock::ConverseStreamOutput::ContentBlockDelta)
+        };
+        let events = vec![
+            delta(bedrock::ReasoningContentBlockDelta::Text(
+                "Let me ".to_string(),
+            ))?,
+            delta(bedrock::ReasoningContentBlockDelta::Text(
+                "think.".to_string(),
+            ))?,
+            delta(bedrock::ReasoningContentBlockDelta::Signature(
+                "sig".to_string(),
+            ))?,
+            bedrock::ConverseStreamOutput::ContentBlockStop(
+                bedrock::ContentBlockStopEvent::builder()
+                    .content_block_index(0)
+                    .build()?,
+            ),
+        ];
+
+        let mut state = ConverseStreamState::default();
+        let contents: Vec<MessageContent> = events
+            .iter()
+            .filter_map(|event| state.handle_event(event))
+            .collect();
+
+        assert_eq!(
+            contents,
+            vec![MessageContent::thinking("Let me think.", "sig")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_bedrock_thinking_config() {
+        let model_config =
+            ModelConfig::new("us.anthropic.claude-sonnet-4-20250514-v1:0".to_string())
+                .with_max_tokens(Some(1000))
+                .with_thinking_budget(Some(2000));
+        let (inference_config, fields) = to_bedrock_thinking_config(&model_config).unwrap();
+        assert_eq!(inference_config.max_tokens(), Some(3000));
+        assert_eq!(
+            from_bedrock_json(&fields).unwrap(),
+            json!({"thinking": {"type": "enabled", "budget_tokens": 2000}})
+        );
+
+        let model_config =
+            ModelConfig::new("amazon.nova-pro-v1:0".to_string()).with_thinking_budget(Some(2000));
+        assert!(to_bedrock_thinking_config(&model_config).is_none());
+    }
 }
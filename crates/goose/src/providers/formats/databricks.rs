@@ -1,5 +1,5 @@
 use crate::message::{Message, MessageContent};
-use crate::model::ModelConfig;
+use crate::model::{ModelConfig, ReasoningEffort};
 use crate::providers::utils::{
     convert_image, detect_image_path, is_valid_function_name, load_image_file, safely_parse_json,
     sanitize_function_name, ImageFormat,
@@ -486,7 +486,12 @@ pub fn create_request(
             }
             _ => (
                 model_config.model_name.to_string(),
-                Some("medium".to_string()),
+                Some(
+                    model_config
+                        .effective_reasoning_effort()
+                        .unwrap_or(ReasoningEffort::Medium)
+                        .to_string(),
+                ),
             ),
         }
     } else {
@@ -531,14 +536,13 @@ pub fn create_request(
             .insert("tools".to_string(), json!(tools_spec));
     }
 
-    // Add thinking parameters for Claude 3.7 Sonnet model when requested
-    let is_thinking_enabled = std::env::var("CLAUDE_THINKING_ENABLED").is_ok();
-    if is_claude_sonnet && is_thinking_enabled {
+    // Add thinking parameters for Claude Sonnet models when requested
+    let thinking_budget = model_config
+        .effective_thinking_budget()
+        .filter(|_| is_claude_sonnet);
+    if let Some(budget_tokens) = thinking_budget {
         // Minimum budget_tokens is 1024
-        let budget_tokens = std::env::var("CLAUDE_THINKING_BUDGET")
-            .unwrap_or_else(|_| "16000".to_string())
-            .parse()
-            .unwrap_or(16000);
+        let budget_tokens = budget_tokens.max(1024);
 
         // For Claude models with thinking enabled, we need to add max_tokens + budget_tokens
         // Default to 8192 (Claude max output) + budget if not specified
@@ -994,6 +998,8 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1025,6 +1031,8 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1057,6 +1065,8 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1079,6 +1089,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_create_request_claude_thinking_budget() -> anyhow::Result<()> {
+        let model_config = ModelConfig {
+            model_name: "databricks-claude-3-7-sonnet".to_string(),
+            context_limit: Some(4096),
+            temperature: Some(0.5),
+            max_tokens: Some(1000),
+            toolshim: false,
+            toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: Some(4000),
+        };
+        let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
+        assert_eq!(request["thinking"]["budget_tokens"], 4000);
+        assert_eq!(request["max_tokens"], 5000);
+        assert_eq!(request["temperature"], 2);
+
+        // Models without extended thinking ignore the budget
+        let model_config = ModelConfig {
+            model_name: "databricks-meta-llama-3-3-70b-instruct".to_string(),
+            ..model_config
+        };
+        let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
+        assert!(request.get("thinking").is_none());
+        assert_eq!(request["max_tokens"], 1000);
+
+        Ok(())
+    }
+
     #[test]
     fn test_response_to_message_claude_thinking() -> anyhow::Result<()> {
         let response = json!({
@@ -1,12 +1,14 @@
 use super::{anthropic, google};
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::base::Usage;
+use crate::providers::base::{ProviderUsage, Usage};
 use anyhow::{Context, Result};
+use futures::Stream;
 use rmcp::model::Tool;
 use serde_json::Value;
 
 use std::fmt;
+use std::pin::Pin;
 
 /// Sensible default values of Google Cloud Platform (GCP) locations for model deployment.
 ///
@@ -337,6 +339,32 @@ pub fn response_to_message(response: Value, request_context: RequestContext) ->
     }
 }
 
+/// Converts a streaming provider response to a stream of messages.
+///
+/// # Arguments
+/// * `stream` - Lines of the server-sent events response
+/// * `request_context` - Context information about the request
+/// * `model_name` - Model name to report usage under if the response has none
+///
+/// # Returns
+/// * A stream of partial messages and, at the end, usage statistics
+pub fn response_to_streaming_message<S>(
+    stream: S,
+    request_context: &RequestContext,
+    model_name: &str,
+) -> Pin<Box<dyn Stream<Item = Result<(Option<Message>, Option<ProviderUsage>)>> + Send>>
+where
+    S: Stream<Item = Result<String>> + Unpin + Send + 'static,
+{
+    match request_context.provider() {
+        ModelProvider::Anthropic => Box::pin(anthropic::response_to_streaming_message(stream)),
+        ModelProvider::Google => Box::pin(google::response_to_streaming_message(
+            stream,
+            model_name.to_string(),
+        )),
+    }
+}
+
 /// Extracts token usage information from the response data.
 ///
 /// # Arguments
@@ -1,9 +1,13 @@
 use crate::message::{Message, MessageContent};
 use crate::model::ModelConfig;
-use crate::providers::base::Usage;
+use crate::providers::base::{ProviderUsage, Usage};
 use crate::providers::errors::ProviderError;
-use crate::providers::utils::{is_valid_function_name, sanitize_function_name};
-use anyhow::Result;
+use crate::providers::utils::{
+    is_valid_function_name, sanitize_function_name, unescape_json_values,
+};
+use anyhow::{anyhow, Result};
+use async_stream::try_stream;
+use futures::{Stream, StreamExt};
 use mcp_core::tool::ToolCall;
 use rand::{distributions::Alphanumeric, Rng};
 use rmcp::model::{AnnotateAble, RawContent, Role, Tool};
@@ -116,7 +120,8 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                             }
                         }
                     }
-
+                    // Gemini keeps its reasoning server side, so thoughts are not sent back
+                    MessageContent::Thinking(_) | MessageContent::RedactedThinking(_) => {}
                     _ => {}
                 }
             }
@@ -241,8 +246,17 @@ pub fn response_to_message(response: Value) -> Result<Message> {
         .unwrap_or(&binding);
 
     for part in parts {
+        let is_thought = part.get("thought").and_then(|v| v.as_bool()) == Some(true);
         if let Some(text) = part.get("text").and_then(|v| v.as_str()) {
-            content.push(MessageContent::text(text.to_string()));
+            if is_thought {
+                let signature = part
+                    .get("thoughtSignature")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default();
+                content.push(MessageContent::thinking(text, signature));
+            } else {
+                content.push(MessageContent::text(text.to_string()));
+            }
         } else if let Some(function_call) = part.get("functionCall") {
             let id: String = rand::thread_rng()
                 .sample_iter(&Alphanumeric)
@@ -299,6 +313,81 @@ pub fn get_usage(data: &Value) -> Result<Usage> {
     }
 }
 
+/// Convert a `streamGenerateContent?alt=sse` response into streamed messages.
+///
+/// Gemini sends function calls whole rather than as partial arguments, so each chunk is
+/// converted like a complete response: text is yielded as it arrives and tool requests as
+/// soon as their part is seen. Usage metadata is cumulative and is reported once, at the
+/// end of the stream.
+pub fn response_to_streaming_message<S>(
+    mut stream: S,
+    model_name: String,
+) -> impl Stream<Item = Result<(Option<Message>, Option<ProviderUsage>)>> + 'static
+where
+    S: Stream<Item = Result<String>> + Unpin + Send + 'static,
+{
+    try_stream! {
+        let mut message_id: Option<String> = None;
+        let mut usage_chunk: Option<Value> = None;
+
+        while let Some(line) = stream.next().await {
+            let line = line?;
+            let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                continue;
+            };
+            if data.is_empty() {
+                continue;
+            }
+
+            let chunk: Value = serde_json::from_str(data)
+                .map_err(|e| anyhow!("Failed to parse streaming chunk: {}: {:?}", e, data))?;
+            if let Some(error) = chunk.get("error") {
+                let message = error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("Unknown error");
+                Err::<(), _>(anyhow!("Stream failed: {}", message))?;
+            }
+
+            // Chunks of one response share an id so the text can be joined back together
+            if message_id.is_none() {
+                message_id = Some(
+                    chunk
+                        .get("responseId")
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                );
+            }
+
+            let mut message = response_to_message(unescape_json_values(&chunk))?;
+            if !message.content.is_empty() {
+                message.id = message_id.clone();
+                yield (Some(message), None);
+            }
+
+            if chunk.get("usageMetadata").is_some() {
+                usage_chunk = Some(chunk);
+            }
+        }
+
+        if let Some(chunk) = usage_chunk {
+            let usage = get_usage(&chunk)?;
+            let model = chunk
+                .get("modelVersion")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+                .unwrap_or(model_name);
+            yield (None, Some(ProviderUsage::new(model, usage)));
+        }
+    }
+}
+
+/// Whether a Gemini model accepts a thinking budget
+fn supports_thinking_config(model_name: &str) -> bool {
+    model_name.contains("gemini-2.5") || model_name.contains("gemini-2-5")
+}
+
 /// Create a complete request payload for Google's API
 pub fn create_request(
     model_config: &ModelConfig,
@@ -325,6 +414,15 @@ pub fn create_request(
     if let Some(tokens) = model_config.max_tokens {
         generation_config.insert("maxOutputTokens".to_string(), json!(tokens));
     }
+    if let Some(budget) = model_config
+        .effective_thinking_budget()
+        .filter(|_| supports_thinking_config(&model_config.model_name))
+    {
+        generation_config.insert(
+            "thinkingConfig".to_string(),
+            json!({"thinkingBudget": budget, "includeThoughts": true}),
+        );
+    }
     if !generation_config.is_empty() {
         payload.insert("generationConfig".to_string(), json!(generation_config));
     }
@@ -332,6 +430,27 @@ pub fn create_request(
     Ok(json!(payload))
 }
 
+/// Create a request whose response is constrained to the given JSON schema through
+/// `responseSchema`, for collecting a recipe's final output without function calls
+pub fn create_structured_request(
+    model_config: &ModelConfig,
+    system: &str,
+    messages: &[Message],
+    schema: &Value,
+) -> Result<Value> {
+    let mut payload = create_request(model_config, system, messages, &[])?;
+    let schema = schema
+        .as_object()
+        .ok_or_else(|| anyhow!("Response schema must be a JSON object"))?;
+    let payload_map = payload.as_object_mut().unwrap();
+    let generation_config = payload_map
+        .entry("generationConfig")
+        .or_insert_with(|| json!({}));
+    generation_config["responseMimeType"] = json!("application/json");
+    generation_config["responseSchema"] = process_map(schema, None);
+    Ok(payload)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -780,6 +899,50 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_streamed_text_and_function_call() -> Result<()> {
+        let response_lines = r#"
+data: {"candidates": [{"content": {"parts": [{"text": "Let me "}],"role": "model"},"index": 0}],"usageMetadata": {"promptTokenCount": 10,"totalTokenCount": 10},"modelVersion": "gemini-2.5-flash","responseId": "abc123"}
+
+data: {"candidates": [{"content": {"parts": [{"text": "check."}],"role": "model"},"index": 0}],"usageMetadata": {"promptTokenCount": 10,"totalTokenCount": 12},"modelVersion": "gemini-2.5-flash","responseId": "abc123"}
+
+data: {"candidates": [{"content": {"parts": [{"functionCall": {"name": "developer__shell","args": {"command": "ls"}}}],"role": "model"},"finishReason": "STOP","index": 0}],"usageMetadata": {"promptTokenCount": 10,"candidatesTokenCount": 7,"totalTokenCount": 17},"modelVersion": "gemini-2.5-flash","responseId": "abc123"}
+"#;
+        let lines = futures::stream::iter(response_lines.lines().map(|l| Ok(l.to_string())));
+        let stream = response_to_streaming_message(lines, "fallback".to_string());
+        tokio::pin!(stream);
+
+        let mut messages = Vec::new();
+        let mut final_usage = None;
+        while let Some(item) = stream.next().await {
+            let (message, usage) = item?;
+            if let Some(message) = message {
+                assert_eq!(message.id.as_deref(), Some("abc123"));
+                messages.push(message);
+            }
+            if usage.is_some() {
+                assert!(final_usage.is_none(), "usage should be reported once");
+                final_usage = usage;
+            }
+        }
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].as_concat_text(), "Let me ");
+        assert_eq!(messages[1].as_concat_text(), "check.");
+        let MessageContent::ToolRequest(request) = &messages[2].content[0] else {
+            panic!("Expected a tool request");
+        };
+        let tool_call = request.tool_call.as_ref().unwrap();
+        assert_eq!(tool_call.name, "developer__shell");
+        assert_eq!(tool_call.arguments, json!({"command": "ls"}));
+
+        let final_usage = final_usage.unwrap();
+        assert_eq!(final_usage.model, "gemini-2.5-flash");
+        assert_eq!(final_usage.usage.output_tokens, Some(7));
+        assert_eq!(final_usage.usage.total_tokens, Some(17));
+        Ok(())
+    }
+
     #[test]
     fn test_response_to_message_with_empty_content() {
         let tool_result: Vec<Content> = Vec::new();
@@ -805,4 +968,76 @@ mod tests {
 
         assert_eq!(payload, expected_payload);
     }
+
+    #[test]
+    fn test_thinking_budget_and_thought_parts() -> Result<()> {
+        let messages = vec![set_up_text_message("Hello", Role::User)];
+        let model_config = ModelConfig::new("gemini-2.5-pro".to_string())
+            .with_reasoning_effort(Some(crate::model::ReasoningEffort::Low));
+        let request = create_request(&model_config, "system", &messages, &[])?;
+        assert_eq!(
+            request["generationConfig"]["thinkingConfig"],
+            json!({"thinkingBudget": 1024, "includeThoughts": true})
+        );
+
+        let model_config =
+            ModelConfig::new("gemini-2.0-flash".to_string()).with_thinking_budget(Some(2048));
+        let request = create_request(&model_config, "system", &messages, &[])?;
+        assert!(request.get("generationConfig").is_none());
+
+        let response = json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"text": "Weighing options", "thought": true},
+                        {"text": "Hello!"}
+                    ]
+                }
+            }]
+        });
+        let message = response_to_message(response)?;
+        assert_eq!(
+            message.content,
+            vec![
+                MessageContent::thinking("Weighing options", ""),
+                MessageContent::text("Hello!")
+            ]
+        );
+
+        // Thoughts are not replayed to Gemini
+        assert_eq!(
+            format_messages(&[message])[0]["parts"],
+            json!([{"text": "Hello!"}])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_structured_request() -> Result<()> {
+        let model_config =
+            ModelConfig::new("gemini-2.5-flash".to_string()).with_max_tokens(Some(256));
+        let schema = json!({
+            "type": "object",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "properties": {"summary": {"type": "string", "format": "text"}},
+            "required": ["summary"]
+        });
+        let messages = vec![set_up_text_message("Summarize", Role::User)];
+        let request = create_structured_request(&model_config, "system", &messages, &schema)?;
+
+        let generation_config = &request["generationConfig"];
+        assert_eq!(generation_config["maxOutputTokens"], 256);
+        assert_eq!(generation_config["responseMimeType"], "application/json");
+        assert_eq!(
+            generation_config["responseSchema"],
+            json!({
+                "type": "object",
+                "properties": {"summary": {"type": "string"}},
+                "required": ["summary"]
+            })
+        );
+        assert!(request.get("tools").is_none());
+        Ok(())
+    }
 }
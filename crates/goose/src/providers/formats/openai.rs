@@ -1,5 +1,5 @@
 use crate::message::{Message, MessageContent};
-use crate::model::ModelConfig;
+use crate::model::{ModelConfig, ReasoningEffort};
 use crate::providers::base::{ProviderUsage, Usage};
 use crate::providers::utils::{
     convert_image, detect_image_path, is_valid_function_name, load_image_file, safely_parse_json,
@@ -448,18 +448,37 @@ where
                     }
                 }
 
+                let mut tool_usage = usage;
                 let mut done = false;
                 while !done {
                     if let Some(response_chunk) = stream.next().await {
                         if response_chunk.as_ref().is_ok_and(|s| s == "data: [DONE]") {
-                            break 'outer;
+                            // Still emit the calls assembled so far
+                            break;
                         }
                         let response_str = response_chunk?;
                         if let Some(line) = strip_data_prefix(&response_str) {
+                            if line.is_empty() {
+                                continue;
+                            }
                             let tool_chunk: StreamingChunk = serde_json::from_str(line)
                                 .map_err(|e| anyhow!("Failed to parse streaming chunk: {}: {:?}", e, &line))?;
 
-                            if let Some(delta_tool_calls) = &tool_chunk.choices[0].delta.tool_calls {
+                            // Usage is cumulative, so keep the latest one seen while assembling
+                            if let Some(u) = tool_chunk.usage.as_ref() {
+                                tool_usage = Some(ProviderUsage {
+                                    usage: get_usage(u),
+                                    model: tool_chunk.model.clone(),
+                                });
+                            }
+
+                            // Some providers send usage in a trailing chunk without choices
+                            let Some(choice) = tool_chunk.choices.first() else {
+                                done = true;
+                                continue;
+                            };
+
+                            if let Some(delta_tool_calls) = &choice.delta.tool_calls {
                                 for delta_call in delta_tool_calls {
                                     if let Some(index) = delta_call.index {
                                         if let Some((_, _, ref mut args)) = tool_call_data.get_mut(&index) {
@@ -473,7 +492,7 @@ where
                                 done = true;
                             }
 
-                            if tool_chunk.choices[0].finish_reason == Some("tool_calls".to_string()) {
+                            if choice.finish_reason == Some("tool_calls".to_string()) {
                                 done = true;
                             }
                         }
@@ -518,7 +537,7 @@ where
                         created: chrono::Utc::now().timestamp(),
                         content: contents,
                     }),
-                    usage,
+                    tool_usage,
                 )
             } else if let Some(text) = &chunk.choices[0].delta.content {
                 yield (
@@ -566,7 +585,12 @@ pub fn create_request(
             }
             _ => (
                 model_config.model_name.to_string(),
-                Some("medium".to_string()),
+                Some(
+                    model_config
+                        .effective_reasoning_effort()
+                        .unwrap_or(ReasoningEffort::Medium)
+                        .to_string(),
+                ),
             ),
         }
     } else {
@@ -635,6 +659,30 @@ pub fn create_request(
     Ok(payload)
 }
 
+/// Create a request whose response is constrained to the given JSON schema through
+/// `response_format`, for collecting a recipe's final output without tool calls
+pub fn create_structured_request(
+    model_config: &ModelConfig,
+    system: &str,
+    messages: &[Message],
+    schema: &Value,
+    image_format: &ImageFormat,
+) -> anyhow::Result<Value, Error> {
+    let mut payload = create_request(model_config, system, messages, &[], image_format)?;
+    payload.as_object_mut().unwrap().insert(
+        "response_format".to_string(),
+        json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "final_output",
+                "schema": schema,
+                "strict": true
+            }
+        }),
+    );
+    Ok(payload)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1042,6 +1090,8 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1073,6 +1123,8 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1105,6 +1157,8 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1127,6 +1181,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_create_structured_request() -> anyhow::Result<()> {
+        let model_config = ModelConfig {
+            model_name: "gpt-4o".to_string(),
+            context_limit: Some(4096),
+            temperature: None,
+            max_tokens: None,
+            toolshim: false,
+            toolshim_model: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+        };
+        let schema = json!({
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"]
+        });
+        let request = create_structured_request(
+            &model_config,
+            "system",
+            &[Message::user().with_text("How many?")],
+            &schema,
+            &ImageFormat::OpenAi,
+        )?;
+
+        assert_eq!(request["response_format"]["type"], "json_schema");
+        assert_eq!(request["response_format"]["json_schema"]["schema"], schema);
+        assert_eq!(request["response_format"]["json_schema"]["strict"], true);
+        assert!(request.get("tools").is_none());
+        assert_eq!(request["messages"].as_array().unwrap().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_request_o3_configured_reasoning_effort() -> anyhow::Result<()> {
+        let model_config = ModelConfig {
+            model_name: "o3".to_string(),
+            context_limit: Some(4096),
+            temperature: None,
+            max_tokens: None,
+            toolshim: false,
+            toolshim_model: None,
+            reasoning_effort: Some(ReasoningEffort::Low),
+            thinking_budget: None,
+        };
+        let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
+        assert_eq!(request["model"], "o3");
+        assert_eq!(request["reasoning_effort"], "low");
+
+        // A suffix on the model name takes precedence over the configured effort
+        let model_config = ModelConfig {
+            model_name: "o3-high".to_string(),
+            ..model_config
+        };
+        let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
+        assert_eq!(request["model"], "o3");
+        assert_eq!(request["reasoning_effort"], "high");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_streamed_multi_tool_response_to_messages() -> anyhow::Result<()> {
         let response_lines = r#"
@@ -1176,4 +1292,42 @@ data: [DONE]
 
         panic!("Expected tool call message with two calls, but did not see it");
     }
+
+    #[tokio::test]
+    async fn test_streamed_tool_call_with_trailing_usage_chunk() -> anyhow::Result<()> {
+        // Ollama and Groq send each tool call whole, then usage in a chunk without choices
+        let response_lines = r#"
+data: {"id":"chatcmpl-1","model":"qwen2.5","choices":[{"index":0,"delta":{"role":"assistant","content":"","tool_calls":[{"id":"call_1","index":0,"type":"function","function":{"name":"developer__shell","arguments":"{\"command\":\"ls\"}"}}]},"finish_reason":null}]}
+data: {"id":"chatcmpl-1","model":"qwen2.5","choices":[{"index":0,"delta":{"role":"assistant","content":""},"finish_reason":"tool_calls"}]}
+data: {"id":"chatcmpl-1","model":"qwen2.5","choices":[],"usage":{"prompt_tokens":10,"completion_tokens":5,"total_tokens":15}}
+data: [DONE]
+"#;
+
+        let response_stream =
+            tokio_stream::iter(response_lines.lines().map(|line| Ok(line.to_string())));
+        let messages = response_to_streaming_message(response_stream);
+        pin!(messages);
+
+        let mut tool_calls = Vec::new();
+        let mut final_usage = None;
+        while let Some(item) = messages.next().await {
+            let (message, usage) = item?;
+            if let Some(msg) = message {
+                for content in msg.content {
+                    if let MessageContent::ToolRequest(req) = content {
+                        tool_calls.push(req.tool_call.unwrap());
+                    }
+                }
+            }
+            if usage.is_some() {
+                final_usage = usage;
+            }
+        }
+
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].name, "developer__shell");
+        assert_eq!(tool_calls[0].arguments, json!({"command": "ls"}));
+        assert_eq!(final_usage.unwrap().usage.total_tokens, Some(15));
+        Ok(())
+    }
 }
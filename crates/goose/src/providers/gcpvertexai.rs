@@ -3,24 +3,24 @@ use std::time::Duration;
 use anyhow::Result;
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
-use reqwest::{Client, StatusCode};
+use reqwest::{Client, Response, StatusCode};
 use serde_json::Value;
 use tokio::time::sleep;
 use url::Url;
 
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage};
+use crate::providers::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage};
 
 use crate::providers::errors::ProviderError;
 use crate::providers::formats::gcpvertexai::{
-    create_request, get_usage, response_to_message, ClaudeVersion, GcpVertexAIModel, GeminiVersion,
-    ModelProvider, RequestContext,
+    create_request, get_usage, response_to_message, response_to_streaming_message, ClaudeVersion,
+    GcpVertexAIModel, GeminiVersion, ModelProvider, RequestContext,
 };
 
 use crate::providers::formats::gcpvertexai::GcpLocation::Iowa;
 use crate::providers::gcpauth::GcpAuth;
-use crate::providers::utils::emit_debug_trace;
+use crate::providers::utils::{decoded_message_stream, emit_debug_trace, sse_lines};
 use rmcp::model::Tool;
 
 /// Base URL for GCP Vertex AI documentation
@@ -274,10 +274,12 @@ impl GcpVertexAIProvider {
     /// # Arguments
     /// * `provider` - The model provider (Anthropic or Google)
     /// * `location` - The GCP location for model deployment
+    /// * `stream` - Whether the response should be streamed as server-sent events
     fn build_request_url(
         &self,
         provider: ModelProvider,
         location: &str,
+        stream: bool,
     ) -> Result<Url, GcpVertexAIError> {
         // Create host URL for the specified location
         let host_url = if self.location == location {
@@ -291,9 +293,10 @@ impl GcpVertexAIProvider {
             Url::parse(host_url).map_err(|e| GcpVertexAIError::InvalidUrl(e.to_string()))?;
 
         // Determine endpoint based on provider type
-        let endpoint = match provider {
-            ModelProvider::Anthropic => "streamRawPredict",
-            ModelProvider::Google => "generateContent",
+        let endpoint = match (provider, stream) {
+            (ModelProvider::Anthropic, _) => "streamRawPredict",
+            (ModelProvider::Google, false) => "generateContent",
+            (ModelProvider::Google, true) => "streamGenerateContent?alt=sse",
         };
 
         // Construct path for URL
@@ -318,14 +321,16 @@ impl GcpVertexAIProvider {
     /// * `payload` - The request payload to send
     /// * `context` - Request context containing model information
     /// * `location` - The GCP location for the request
-    async fn post_with_location(
+    /// * `stream` - Whether to call the streaming endpoint
+    async fn send_with_location(
         &self,
         payload: &Value,
         context: &RequestContext,
         location: &str,
-    ) -> Result<Value, ProviderError> {
+        stream: bool,
+    ) -> Result<Response, ProviderError> {
         let url = self
-            .build_request_url(context.provider(), location)
+            .build_request_url(context.provider(), location, stream)
             .map_err(|e| ProviderError::RequestFailed(e.to_string()))?;
 
         // Initialize separate counters for different error types
@@ -435,6 +440,7 @@ impl GcpVertexAIProvider {
                     );
                     sleep(delay).await;
                 }
+                StatusCode::OK => return Ok(response),
                 // For any other status codes, process normally
                 _ => {
                     let response_json = response.json::<Value>().await.map_err(|e| {
@@ -442,7 +448,6 @@ impl GcpVertexAIProvider {
                     })?;
 
                     return match status {
-                        StatusCode::OK => Ok(response_json),
                         StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
                             tracing::debug!(
                                 "Authentication failed. Status: {status}, Payload: {payload:?}"
@@ -470,14 +475,16 @@ impl GcpVertexAIProvider {
     /// # Arguments
     /// * `payload` - The request payload to send
     /// * `context` - Request context containing model information
-    async fn post(
+    /// * `stream` - Whether to call the streaming endpoint
+    async fn send(
         &self,
         payload: &Value,
         context: &RequestContext,
-    ) -> Result<Value, ProviderError> {
+        stream: bool,
+    ) -> Result<Response, ProviderError> {
         // Try with user-specified location first
         let result = self
-            .post_with_location(payload, context, &self.location)
+            .send_with_location(payload, context, &self.location, stream)
             .await;
 
         // If location is already the known location for the model or request succeeded, return result
@@ -496,13 +503,30 @@ impl GcpVertexAIProvider {
                     "Trying known location {known_location} for {model_name} instead of {configured_location}: {msg}"
                 );
 
-                self.post_with_location(payload, context, &known_location)
+                self.send_with_location(payload, context, &known_location, stream)
                     .await
             }
             // For any other error, return the original result
             _ => result,
         }
     }
+
+    /// Makes a non-streaming request and parses the JSON response.
+    ///
+    /// # Arguments
+    /// * `payload` - The request payload to send
+    /// * `context` - Request context containing model information
+    async fn post(
+        &self,
+        payload: &Value,
+        context: &RequestContext,
+    ) -> Result<Value, ProviderError> {
+        self.send(payload, context, false)
+            .await?
+            .json::<Value>()
+            .await
+            .map_err(|e| ProviderError::RequestFailed(format!("Failed to parse response: {e}")))
+    }
 }
 
 impl Default for GcpVertexAIProvider {
@@ -629,6 +653,32 @@ impl Provider for GcpVertexAIProvider {
     fn get_model_config(&self) -> ModelConfig {
         self.model.clone()
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    /// Streams a model interaction, using the Anthropic or Gemini streaming format
+    /// depending on the model.
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let (mut request, context) = create_request(&self.model, system, messages, tools)?;
+        if context.provider() == ModelProvider::Anthropic {
+            request["stream"] = Value::Bool(true);
+        }
+
+        let response = self.send(&request, &context, true).await?;
+
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response), &context, &self.model.model_name),
+            self.model.clone(),
+            request,
+        ))
+    }
 }
 
 #[cfg(test)]
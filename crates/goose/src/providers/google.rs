@@ -1,15 +1,19 @@
 use super::errors::ProviderError;
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage};
-use crate::providers::formats::google::{create_request, get_usage, response_to_message};
+use crate::providers::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage};
+use crate::providers::formats::google::{
+    create_request, create_structured_request, get_usage, response_to_message,
+    response_to_streaming_message,
+};
 use crate::providers::utils::{
-    emit_debug_trace, handle_response_google_compat, unescape_json_values,
+    decoded_message_stream, emit_debug_trace, handle_response_google_compat, sse_lines,
+    unescape_json_values,
 };
 use anyhow::Result;
 use async_trait::async_trait;
 use axum::http::HeaderMap;
-use reqwest::Client;
+use reqwest::{Client, Response};
 use rmcp::model::Tool;
 use serde_json::Value;
 use std::time::Duration;
@@ -86,22 +90,50 @@ impl GoogleProvider {
         })
     }
 
-    async fn post(&self, payload: &Value) -> Result<Value, ProviderError> {
+    fn endpoint(&self, method: &str) -> Result<Url, ProviderError> {
         let base_url = Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
 
-        let url = base_url
+        base_url
             .join(&format!(
-                "v1beta/models/{}:generateContent",
-                self.model.model_name
+                "v1beta/models/{}:{}",
+                self.model.model_name, method
             ))
             .map_err(|e| {
                 ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
-            })?;
+            })
+    }
+
+    /// Send a request and parse its response. Structured responses are left as they are,
+    /// since unescaping would change the strings inside the requested JSON.
+    async fn complete_payload(
+        &self,
+        payload: &Value,
+        structured: bool,
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        // Make request
+        let response = self.post(payload).await?;
+
+        // Parse response
+        let message = if structured {
+            response_to_message(response.clone())?
+        } else {
+            response_to_message(unescape_json_values(&response))?
+        };
+        let usage = get_usage(&response)?;
+        let model = match response.get("modelVersion") {
+            Some(model_version) => model_version.as_str().unwrap_or_default().to_string(),
+            None => self.model.model_name.clone(),
+        };
+        emit_debug_trace(&self.model, payload, &response, &usage);
+        let provider_usage = ProviderUsage::new(model, usage);
+        Ok((message, provider_usage))
+    }
+
+    async fn post(&self, payload: &Value) -> Result<Value, ProviderError> {
+        let url = self.endpoint("generateContent")?;
 
-        let max_retries = 3;
         let mut retries = 0;
-        let base_delay = Duration::from_secs(2);
 
         loop {
             let response = self
@@ -117,17 +149,7 @@ impl GoogleProvider {
                         Ok(result) => return Ok(result),
                         Err(ProviderError::RateLimitExceeded(_)) => {
                             retries += 1;
-                            if retries > max_retries {
-                                return Err(ProviderError::RateLimitExceeded(
-                                    "Max retries exceeded for rate limit error".to_string(),
-                                ));
-                            }
-
-                            let delay = 2u64.pow(retries);
-                            let total_delay = Duration::from_secs(delay) + base_delay;
-
-                            println!("Rate limit hit. Retrying in {:?}", total_delay);
-                            tokio::time::sleep(total_delay).await;
+                            wait_for_rate_limit(retries).await?;
                             continue;
                         }
                         Err(err) => return Err(err), // Other errors
@@ -142,6 +164,60 @@ impl GoogleProvider {
             }
         }
     }
+
+    /// Start a streaming request, returning the response once it has a success status
+    async fn post_stream(&self, payload: &Value) -> Result<Response, ProviderError> {
+        let url = self.endpoint("streamGenerateContent?alt=sse")?;
+
+        let mut retries = 0;
+
+        loop {
+            let response = self
+                .client
+                .post(url.clone())
+                .json(&payload)
+                .send()
+                .await
+                .map_err(|e| ProviderError::RequestFailed(format!("Request failed: {}", e)))?;
+
+            if response.status().is_success() {
+                return Ok(response);
+            }
+
+            match handle_response_google_compat(response).await {
+                Err(ProviderError::RateLimitExceeded(_)) => {
+                    retries += 1;
+                    wait_for_rate_limit(retries).await?;
+                }
+                Err(err) => return Err(err),
+                Ok(payload) => {
+                    return Err(ProviderError::RequestFailed(format!(
+                        "Unexpected response: {}",
+                        payload
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Back off before retrying a rate limited request, or fail once retries are exhausted
+async fn wait_for_rate_limit(retries: u32) -> Result<(), ProviderError> {
+    let max_retries = 3;
+    let base_delay = Duration::from_secs(2);
+
+    if retries > max_retries {
+        return Err(ProviderError::RateLimitExceeded(
+            "Max retries exceeded for rate limit error".to_string(),
+        ));
+    }
+
+    let delay = 2u64.pow(retries);
+    let total_delay = Duration::from_secs(delay) + base_delay;
+
+    println!("Rate limit hit. Retrying in {:?}", total_delay);
+    tokio::time::sleep(total_delay).await;
+    Ok(())
 }
 
 #[async_trait]
@@ -176,20 +252,21 @@ impl Provider for GoogleProvider {
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
         let payload = create_request(&self.model, system, messages, tools)?;
+        self.complete_payload(&payload, false).await
+    }
 
-        // Make request
-        let response = self.post(&payload).await?;
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
 
-        // Parse response
-        let message = response_to_message(unescape_json_values(&response))?;
-        let usage = get_usage(&response)?;
-        let model = match response.get("modelVersion") {
-            Some(model_version) => model_version.as_str().unwrap_or_default().to_string(),
-            None => self.model.model_name.clone(),
-        };
-        emit_debug_trace(&self.model, &payload, &response, &usage);
-        let provider_usage = ProviderUsage::new(model, usage);
-        Ok((message, provider_usage))
+    async fn complete_structured(
+        &self,
+        system: &str,
+        messages: &[Message],
+        schema: &Value,
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let payload = create_structured_request(&self.model, system, messages, schema)?;
+        self.complete_payload(&payload, true).await
     }
 
     /// Fetch supported models from Google Generative Language API; returns Err on failure, Ok(None) if not present
@@ -211,4 +288,25 @@ impl Provider for GoogleProvider {
         models.sort();
         Ok(Some(models))
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let payload = create_request(&self.model, system, messages, tools)?;
+
+        let response = self.post_stream(&payload).await?;
+
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response), self.model.model_name.clone()),
+            self.model.clone(),
+            payload,
+        ))
+    }
 }
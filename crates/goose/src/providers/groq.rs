@@ -1,14 +1,20 @@
 use super::errors::ProviderError;
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
-use crate::providers::formats::openai::{create_request, get_usage, response_to_message};
-use crate::providers::utils::get_model;
+use crate::providers::base::{
+    ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage,
+};
+use crate::providers::formats::openai::{
+    create_request, get_usage, response_to_message, response_to_streaming_message,
+};
+use crate::providers::utils::{
+    decoded_message_stream, get_model, handle_status_openai_compat, sse_lines,
+};
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::{Client, Response, StatusCode};
 use rmcp::model::Tool;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::time::Duration;
 use url::Url;
 
@@ -54,20 +60,24 @@ impl GroqProvider {
         })
     }
 
-    async fn post(&self, payload: &Value) -> anyhow::Result<Value, ProviderError> {
+    async fn send(&self, payload: &Value) -> anyhow::Result<Response, ProviderError> {
         let base_url = Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
         let url = base_url.join("openai/v1/chat/completions").map_err(|e| {
             ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
         })?;
 
-        let response = self
+        Ok(self
             .client
             .post(url)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .json(payload)
             .send()
-            .await?;
+            .await?)
+    }
+
+    async fn post(&self, payload: &Value) -> anyhow::Result<Value, ProviderError> {
+        let response = self.send(payload).await?;
 
         let status = response.status();
         let response_payload: Option<Value> = response.json().await.ok();
@@ -148,6 +158,37 @@ impl Provider for GroqProvider {
         Ok((message, ProviderUsage::new(model, usage)))
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let mut payload = create_request(
+            &self.model,
+            system,
+            messages,
+            tools,
+            &super::utils::ImageFormat::OpenAi,
+        )?;
+        payload["stream"] = Value::Bool(true);
+        payload["stream_options"] = json!({
+            "include_usage": true,
+        });
+
+        let response = handle_status_openai_compat(self.send(&payload).await?).await?;
+
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response)),
+            self.model.clone(),
+            payload,
+        ))
+    }
+
     /// Fetch supported models from Groq; returns Err on failure, Ok(None) if no models found
     async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
         // Construct the Groq models endpoint
@@ -10,6 +10,7 @@ use crate::message::{Message, MessageContent};
 use crate::model::ModelConfig;
 use rmcp::model::Tool;
 use rmcp::model::{Content, RawContent};
+use serde_json::Value;
 
 /// A provider that switches between a lead model and a worker model based on turn count
 /// and can fallback to lead model on consecutive failures
@@ -432,6 +433,14 @@ impl Provider for LeadWorkerProvider {
         self.lead_provider.supports_embeddings() || self.worker_provider.supports_embeddings()
     }
 
+    fn embedding_dimension(&self) -> Option<usize> {
+        if self.lead_provider.supports_embeddings() {
+            self.lead_provider.embedding_dimension()
+        } else {
+            self.worker_provider.embedding_dimension()
+        }
+    }
+
     async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
         // Use the lead provider for embeddings if it supports them, otherwise use worker
         if self.lead_provider.supports_embeddings() {
@@ -445,6 +454,22 @@ impl Provider for LeadWorkerProvider {
         }
     }
 
+    fn supports_structured_output(&self) -> bool {
+        self.lead_provider.supports_structured_output()
+    }
+
+    async fn complete_structured(
+        &self,
+        system: &str,
+        messages: &[Message],
+        schema: &Value,
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        // Structured output is a final answer, so it always comes from the lead model
+        self.lead_provider
+            .complete_structured(system, messages, schema)
+            .await
+    }
+
     /// Check if this provider is a LeadWorkerProvider
     fn as_lead_worker(&self) -> Option<&dyn LeadWorkerProviderTrait> {
         Some(self)
@@ -0,0 +1,272 @@
+//! In-process embeddings from a local sentence-transformer model
+//!
+//! Runs a BERT-family sentence embedding model (e.g. `all-MiniLM-L6-v2` or `bge-small-en`)
+//! on the CPU with candle, so the vector tool routing strategy works offline. The model is a
+//! directory downloaded from Hugging Face containing `config.json`, `tokenizer.json` and
+//! `model.safetensors`, selected with:
+//!
+//! ```sh
+//! GOOSE_EMBEDDING_MODEL_PROVIDER=local
+//! GOOSE_EMBEDDING_MODEL=/path/to/all-MiniLM-L6-v2
+//! ```
+//!
+//! A model name that is not an existing path is looked up in `<goose data dir>/models/`.
+//!
+//! Only built with the `local-embeddings` feature, which pulls in candle and tokenizers.
+
+use super::base::{Provider, ProviderMetadata, ProviderUsage};
+use super::embedding::EmbeddingCapable;
+use super::errors::ProviderError;
+use crate::config::APP_STRATEGY;
+use crate::message::Message;
+use crate::model::ModelConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config as BertConfig, DTYPE};
+use etcetera::{choose_app_strategy, AppStrategy};
+use rmcp::model::Tool;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+pub const LOCAL_EMBEDDING_DEFAULT_MODEL: &str = "all-MiniLM-L6-v2";
+pub const LOCAL_EMBEDDING_DOC_URL: &str =
+    "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2";
+/// Longest input in tokens, longer texts are truncated
+const MAX_SEQUENCE_LENGTH: usize = 512;
+/// Texts embedded per forward pass
+const BATCH_SIZE: usize = 32;
+
+struct LocalEmbeddingModel {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+    dimension: usize,
+}
+
+impl LocalEmbeddingModel {
+    fn load(model_dir: &Path) -> Result<Self> {
+        let device = Device::Cpu;
+
+        let config = std::fs::read_to_string(model_dir.join("config.json"))
+            .with_context(|| format!("Failed to read config.json in {}", model_dir.display()))?;
+        let config: BertConfig = serde_json::from_str(&config)
+            .context("config.json is not a supported BERT model config")?;
+
+        let mut tokenizer = Tokenizer::from_file(model_dir.join("tokenizer.json"))
+            .map_err(|e| anyhow::anyhow!("Failed to load tokenizer.json: {}", e))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_SEQUENCE_LENGTH.min(config.max_position_embeddings),
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow::anyhow!("Failed to configure tokenizer: {}", e))?;
+
+        let weights = model_dir.join("model.safetensors");
+        // Safety: the weights file is memory mapped and must not change while goose runs
+        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[&weights], DTYPE, &device) }
+            .with_context(|| format!("Failed to load {}", weights.display()))?;
+        let model = BertModel::load(vb, &config).context("Failed to load BERT model")?;
+
+        Ok(Self {
+            model,
+            tokenizer,
+            device,
+            dimension: config.hidden_size,
+        })
+    }
+
+    /// Mean-pooled, L2-normalized sentence embeddings
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts.to_vec(), true)
+            .map_err(|e| anyhow::anyhow!("Failed to tokenize input: {}", e))?;
+
+        let token_ids = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_ids(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+        let attention_mask = encodings
+            .iter()
+            .map(|e| Tensor::new(e.get_attention_mask(), &self.device))
+            .collect::<candle_core::Result<Vec<_>>>()?;
+
+        let token_ids = Tensor::stack(&token_ids, 0)?;
+        let attention_mask = Tensor::stack(&attention_mask, 0)?;
+        let token_type_ids = token_ids.zeros_like()?;
+
+        // (batch, tokens, hidden)
+        let hidden = self
+            .model
+            .forward(&token_ids, &token_type_ids, Some(&attention_mask))?;
+
+        // Average over the real tokens only, padding is masked out
+        let mask = attention_mask.to_dtype(DType::F32)?.unsqueeze(2)?;
+        let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+        let counts = mask.sum(1)?;
+        let pooled = summed.broadcast_div(&counts)?;
+
+        let norms = pooled.sqr()?.sum_keepdim(1)?.sqrt()?;
+        let normalized = pooled.broadcast_div(&norms)?;
+
+        Ok(normalized.to_vec2::<f32>()?)
+    }
+}
+
+pub struct LocalEmbeddingProvider {
+    model: Arc<LocalEmbeddingModel>,
+    model_config: ModelConfig,
+}
+
+impl LocalEmbeddingProvider {
+    pub fn from_env(model_config: ModelConfig) -> Result<Self> {
+        let model_dir = Self::resolve_model_dir(&model_config.model_name)?;
+        let model = LocalEmbeddingModel::load(&model_dir)?;
+
+        Ok(Self {
+            model: Arc::new(model),
+            model_config,
+        })
+    }
+
+    /// The model name is a path to the model directory, or the name of a directory
+    /// under `<goose data dir>/models/`
+    fn resolve_model_dir(model_name: &str) -> Result<PathBuf> {
+        let path = PathBuf::from(model_name);
+        if path.is_dir() {
+            return Ok(path);
+        }
+
+        let data_dir = choose_app_strategy(APP_STRATEGY.clone())
+            .context("Failed to determine data directory")?
+            .data_dir();
+        let path = data_dir.join("models").join(model_name);
+        if path.is_dir() {
+            return Ok(path);
+        }
+
+        Err(anyhow::anyhow!(
+            "Local embedding model '{}' not found. Set GOOSE_EMBEDDING_MODEL to a directory with \
+            config.json, tokenizer.json and model.safetensors, or download the model to {}",
+            model_name,
+            path.display()
+        ))
+    }
+}
+
+#[async_trait]
+impl Provider for LocalEmbeddingProvider {
+    fn metadata() -> ProviderMetadata {
+        ProviderMetadata::new(
+            "local",
+            "Local Embeddings",
+            "Sentence embeddings computed on this machine, for tool routing without network access",
+            LOCAL_EMBEDDING_DEFAULT_MODEL,
+            vec![LOCAL_EMBEDDING_DEFAULT_MODEL],
+            LOCAL_EMBEDDING_DOC_URL,
+            vec![],
+        )
+    }
+
+    fn get_model_config(&self) -> ModelConfig {
+        self.model_config.clone()
+    }
+
+    async fn complete(
+        &self,
+        _system: &str,
+        _messages: &[Message],
+        _tools: &[Tool],
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        Err(ProviderError::NotImplemented(
+            "The local provider only creates embeddings, use it as GOOSE_EMBEDDING_MODEL_PROVIDER"
+                .to_string(),
+        ))
+    }
+
+    fn supports_embeddings(&self) -> bool {
+        true
+    }
+
+    fn embedding_dimension(&self) -> Option<usize> {
+        Some(self.model.dimension)
+    }
+
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, ProviderError> {
+        EmbeddingCapable::create_embeddings(self, texts)
+            .await
+            .map_err(|e| ProviderError::ExecutionError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl EmbeddingCapable for LocalEmbeddingProvider {
+    async fn create_embeddings(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Inference is CPU bound, keep it off the async runtime
+        let model = Arc::clone(&self.model);
+        tokio::task::spawn_blocking(move || {
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for batch in texts.chunks(BATCH_SIZE) {
+                embeddings.extend(model.embed(batch)?);
+            }
+            Ok::<_, anyhow::Error>(embeddings)
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_model_dir() {
+        let result = LocalEmbeddingProvider::from_env(ModelConfig::new(
+            "/nonexistent/goose-embedding-model".to_string(),
+        ));
+        let error = result.err().unwrap().to_string();
+        assert!(error.contains("not found"));
+        assert!(error.contains("GOOSE_EMBEDDING_MODEL"));
+    }
+
+    /// Runs against a real model when GOOSE_TEST_LOCAL_EMBEDDING_MODEL points at one
+    #[tokio::test]
+    async fn test_local_embeddings() {
+        let Ok(model_dir) = std::env::var("GOOSE_TEST_LOCAL_EMBEDDING_MODEL") else {
+            return;
+        };
+        let provider = LocalEmbeddingProvider::from_env(ModelConfig::new(model_dir)).unwrap();
+
+        let embeddings = Provider::create_embeddings(
+            &provider,
+            vec![
+                "list the files in a directory".to_string(),
+                "show the contents of a folder".to_string(),
+                "what is the weather tomorrow".to_string(),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let dimension = provider.embedding_dimension().unwrap();
+        assert_eq!(embeddings.len(), 3);
+        assert!(embeddings.iter().all(|e| e.len() == dimension));
+
+        let similarity = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        assert!((similarity(&embeddings[0], &embeddings[0]) - 1.0).abs() < 1e-3);
+        assert!(
+            similarity(&embeddings[0], &embeddings[1]) > similarity(&embeddings[0], &embeddings[2])
+        );
+    }
+}
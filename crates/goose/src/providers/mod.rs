@@ -4,6 +4,7 @@ pub mod azureauth;
 pub mod base;
 pub mod bedrock;
 pub mod claude_code;
+pub mod custom;
 pub mod databricks;
 pub mod embedding;
 pub mod errors;
@@ -17,6 +18,8 @@ pub mod google;
 pub mod groq;
 pub mod lead_worker;
 pub mod litellm;
+#[cfg(feature = "local-embeddings")]
+pub mod local_embedding;
 pub mod oauth;
 pub mod ollama;
 pub mod openai;
@@ -1,14 +1,19 @@
-use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
+use super::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::errors::ProviderError;
-use super::utils::{get_model, handle_response_openai_compat};
+use super::utils::{
+    decoded_message_stream, get_model, handle_response_openai_compat, handle_status_openai_compat,
+    sse_lines,
+};
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::formats::openai::{create_request, get_usage, response_to_message};
+use crate::providers::formats::openai::{
+    create_request, get_usage, response_to_message, response_to_streaming_message,
+};
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, Response};
 use rmcp::model::Tool;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::time::Duration;
 use url::Url;
 
@@ -82,7 +87,7 @@ impl OllamaProvider {
         Ok(base_url)
     }
 
-    async fn post(&self, payload: &Value) -> Result<Value, ProviderError> {
+    async fn send(&self, payload: &Value) -> Result<Response, ProviderError> {
         // TODO: remove this later when the UI handles provider config refresh
         let base_url = self.get_base_url()?;
 
@@ -90,9 +95,31 @@ impl OllamaProvider {
             ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
         })?;
 
-        let response = self.client.post(url).json(payload).send().await?;
+        Ok(self.client.post(url).json(payload).send().await?)
+    }
 
-        handle_response_openai_compat(response).await
+    async fn post(&self, payload: &Value) -> Result<Value, ProviderError> {
+        handle_response_openai_compat(self.send(payload).await?).await
+    }
+
+    /// Build the request, leaving out tools in chat mode
+    fn build_request(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<Value, ProviderError> {
+        let config = crate::config::Config::global();
+        let goose_mode = config.get_param("GOOSE_MODE").unwrap_or("auto".to_string());
+        let filtered_tools = if goose_mode == "chat" { &[] } else { tools };
+
+        Ok(create_request(
+            &self.model,
+            system,
+            messages,
+            filtered_tools,
+            &super::utils::ImageFormat::OpenAi,
+        )?)
     }
 }
 
@@ -132,17 +159,7 @@ impl Provider for OllamaProvider {
         messages: &[Message],
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
-        let config = crate::config::Config::global();
-        let goose_mode = config.get_param("GOOSE_MODE").unwrap_or("auto".to_string());
-        let filtered_tools = if goose_mode == "chat" { &[] } else { tools };
-
-        let payload = create_request(
-            &self.model,
-            system,
-            messages,
-            filtered_tools,
-            &super::utils::ImageFormat::OpenAi,
-        )?;
+        let payload = self.build_request(system, messages, tools)?;
         let response = self.post(&payload).await?;
         let message = response_to_message(&response)?;
 
@@ -154,4 +171,29 @@ impl Provider for OllamaProvider {
         super::utils::emit_debug_trace(&self.model, &payload, &response, &usage);
         Ok((message, ProviderUsage::new(model, usage)))
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let mut payload = self.build_request(system, messages, tools)?;
+        payload["stream"] = Value::Bool(true);
+        payload["stream_options"] = json!({
+            "include_usage": true,
+        });
+
+        let response = handle_status_openai_compat(self.send(&payload).await?).await?;
+
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response)),
+            self.model.clone(),
+            payload,
+        ))
+    }
 }
@@ -1,27 +1,22 @@
 use anyhow::Result;
-use async_stream::try_stream;
 use async_trait::async_trait;
-use futures::TryStreamExt;
 use reqwest::{Client, Response};
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::io;
 use std::time::Duration;
-use tokio::pin;
-use tokio_stream::StreamExt;
-use tokio_util::codec::{FramedRead, LinesCodec};
-use tokio_util::io::StreamReader;
 
 use super::base::{ConfigKey, ModelInfo, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::embedding::{EmbeddingCapable, EmbeddingRequest, EmbeddingResponse};
 use super::errors::ProviderError;
-use super::formats::openai::{create_request, get_usage, response_to_message};
+use super::formats::openai::{
+    create_request, create_structured_request, get_usage, response_to_message,
+};
 use super::utils::{emit_debug_trace, get_model, handle_response_openai_compat, ImageFormat};
 use crate::message::Message;
 use crate::model::ModelConfig;
 use crate::providers::base::MessageStream;
 use crate::providers::formats::openai::response_to_streaming_message;
-use crate::providers::utils::handle_status_openai_compat;
+use crate::providers::utils::{decoded_message_stream, handle_status_openai_compat, sse_lines};
 use rmcp::model::Tool;
 
 pub const OPEN_AI_DEFAULT_MODEL: &str = "gpt-4o";
@@ -113,6 +108,24 @@ impl OpenAiProvider {
         request
     }
 
+    async fn complete_payload(
+        &self,
+        payload: &Value,
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        // Make request
+        let response = handle_response_openai_compat(self.post(payload).await?).await?;
+
+        // Parse response
+        let message = response_to_message(&response)?;
+        let usage = response.get("usage").map(get_usage).unwrap_or_else(|| {
+            tracing::debug!("Failed to get usage data");
+            Usage::default()
+        });
+        let model = get_model(&response);
+        emit_debug_trace(&self.model, payload, &response, &usage);
+        Ok((message, ProviderUsage::new(model, usage)))
+    }
+
     async fn post(&self, payload: &Value) -> Result<Response, ProviderError> {
         let base_url = url::Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
@@ -176,19 +189,22 @@ impl Provider for OpenAiProvider {
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
         let payload = create_request(&self.model, system, messages, tools, &ImageFormat::OpenAi)?;
+        self.complete_payload(&payload).await
+    }
 
-        // Make request
-        let response = handle_response_openai_compat(self.post(&payload).await?).await?;
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
 
-        // Parse response
-        let message = response_to_message(&response)?;
-        let usage = response.get("usage").map(get_usage).unwrap_or_else(|| {
-            tracing::debug!("Failed to get usage data");
-            Usage::default()
-        });
-        let model = get_model(&response);
-        emit_debug_trace(&self.model, &payload, &response, &usage);
-        Ok((message, ProviderUsage::new(model, usage)))
+    async fn complete_structured(
+        &self,
+        system: &str,
+        messages: &[Message],
+        schema: &Value,
+    ) -> Result<(Message, ProviderUsage), ProviderError> {
+        let payload =
+            create_structured_request(&self.model, system, messages, schema, &ImageFormat::OpenAi)?;
+        self.complete_payload(&payload).await
     }
 
     /// Fetch supported models from OpenAI; returns Err on any failure, Ok(None) if no data
@@ -260,22 +276,11 @@ impl Provider for OpenAiProvider {
 
         let response = handle_status_openai_compat(self.post(&payload).await?).await?;
 
-        let stream = response.bytes_stream().map_err(io::Error::other);
-
-        let model_config = self.model.clone();
-        // Wrap in a line decoder and yield lines inside the stream
-        Ok(Box::pin(try_stream! {
-            let stream_reader = StreamReader::new(stream);
-            let framed = FramedRead::new(stream_reader, LinesCodec::new()).map_err(anyhow::Error::from);
-
-            let message_stream = response_to_streaming_message(framed);
-            pin!(message_stream);
-            while let Some(message) = message_stream.next().await {
-                let (message, usage) = message.map_err(|e| ProviderError::RequestFailed(format!("Stream decode error: {}", e)))?;
-                super::utils::emit_debug_trace(&model_config, &payload, &message, &usage.as_ref().map(|f| f.usage).unwrap_or_default());
-                yield (message, usage);
-            }
-        }))
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response)),
+            self.model.clone(),
+            payload,
+        ))
     }
 }
 
@@ -1,18 +1,20 @@
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, Response};
 use serde_json::{json, Value};
 use std::time::Duration;
 
-use super::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
+use super::base::{ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage};
 use super::errors::ProviderError;
 use super::utils::{
-    emit_debug_trace, get_model, handle_response_google_compat, handle_response_openai_compat,
-    is_google_model,
+    decoded_message_stream, emit_debug_trace, get_model, handle_response_google_compat,
+    handle_response_openai_compat, handle_status_openai_compat, is_google_model, sse_lines,
 };
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::formats::openai::{create_request, get_usage, response_to_message};
+use crate::providers::formats::openai::{
+    create_request, get_usage, response_to_message, response_to_streaming_message,
+};
 use rmcp::model::Tool;
 use url::Url;
 
@@ -65,14 +67,14 @@ impl OpenRouterProvider {
         })
     }
 
-    async fn post(&self, payload: &Value) -> Result<Value, ProviderError> {
+    async fn send(&self, payload: &Value) -> Result<Response, ProviderError> {
         let base_url = Url::parse(&self.host)
             .map_err(|e| ProviderError::RequestFailed(format!("Invalid base URL: {e}")))?;
         let url = base_url.join("api/v1/chat/completions").map_err(|e| {
             ProviderError::RequestFailed(format!("Failed to construct endpoint URL: {e}"))
         })?;
 
-        let response = self
+        Ok(self
             .client
             .post(url)
             .header("Content-Type", "application/json")
@@ -81,7 +83,11 @@ impl OpenRouterProvider {
             .header("X-Title", "Goose")
             .json(payload)
             .send()
-            .await?;
+            .await?)
+    }
+
+    async fn post(&self, payload: &Value) -> Result<Value, ProviderError> {
+        let response = self.send(payload).await?;
 
         // Handle Google-compatible model responses differently
         if is_google_model(payload) {
@@ -279,6 +285,33 @@ impl Provider for OpenRouterProvider {
         Ok((message, ProviderUsage::new(model, usage)))
     }
 
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let mut payload = create_request_based_on_model(self, system, messages, tools)?;
+        payload["stream"] = Value::Bool(true);
+        payload["stream_options"] = json!({
+            "include_usage": true,
+        });
+
+        // Errors before the stream starts use the OpenAI error shape for every routed model;
+        // keep-alive comments (": OPENROUTER PROCESSING") are skipped by the decoder
+        let response = handle_status_openai_compat(self.send(&payload).await?).await?;
+
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response)),
+            self.model.clone(),
+            payload,
+        ))
+    }
+
     /// Fetch supported models from OpenRouter API (only models with tool support)
     async fn fetch_supported_models_async(&self) -> Result<Option<Vec<String>>, ProviderError> {
         let base_url = Url::parse(&self.host)
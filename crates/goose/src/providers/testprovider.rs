@@ -1,5 +1,13 @@
+//! A provider that records completions from another provider to a file, or replays them
+//!
+//! Records are keyed by a hash of the input messages, so a replay reproduces a recorded
+//! run exactly as long as the conversation takes the same path. Used by the scenario tests
+//! and by `goose run --record <file>` / `goose run --replay <file>`.
+
 use anyhow::Result;
+use async_stream::try_stream;
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -7,10 +15,13 @@ use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
-use super::base::{Provider, ProviderMetadata, ProviderUsage};
+use super::base::{
+    stream_from_single_message, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage,
+};
 use super::errors::ProviderError;
-use crate::message::Message;
+use crate::message::{push_message, Message};
 use crate::model::ModelConfig;
+use crate::utils::safe_truncate;
 use rmcp::model::Tool;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +35,29 @@ struct TestInput {
 struct TestOutput {
     message: Message,
     usage: ProviderUsage,
+    /// The partial messages of a streamed response, replayed as-is when streaming
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    chunks: Vec<Message>,
+}
+
+impl TestOutput {
+    fn from_chunks(chunks: Vec<Message>, usage: ProviderUsage) -> Self {
+        let mut merged = Vec::new();
+        for chunk in &chunks {
+            push_message(&mut merged, chunk.clone());
+        }
+        let mut merged = merged.into_iter();
+        let mut message = merged.next().unwrap_or_else(Message::assistant);
+        for rest in merged {
+            message.content.extend(rest.content);
+        }
+
+        Self {
+            message,
+            usage,
+            chunks,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +70,8 @@ pub struct TestProvider {
     inner: Option<Arc<dyn Provider>>,
     records: Arc<Mutex<HashMap<String, TestRecord>>>,
     file_path: String,
+    model_config: Option<ModelConfig>,
+    autosave: bool,
 }
 
 impl TestProvider {
@@ -44,6 +80,8 @@ impl TestProvider {
             inner: Some(inner),
             records: Arc::new(Mutex::new(HashMap::new())),
             file_path: file_path.into(),
+            model_config: None,
+            autosave: false,
         }
     }
 
@@ -55,9 +93,27 @@ impl TestProvider {
             inner: None,
             records: Arc::new(Mutex::new(records)),
             file_path,
+            model_config: None,
+            autosave: false,
         })
     }
 
+    /// Report this model config when replaying, instead of a placeholder test model
+    pub fn with_model_config(mut self, model_config: ModelConfig) -> Self {
+        self.model_config = Some(model_config);
+        self
+    }
+
+    /// Write the recording to the file after every response, so it survives an aborted run
+    pub fn with_autosave(mut self) -> Self {
+        self.autosave = true;
+        self
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.inner.is_some()
+    }
+
     pub fn finish_recording(self) -> Result<()> {
         if self.inner.is_some() {
             self.save_records()?;
@@ -87,12 +143,65 @@ impl TestProvider {
     }
 
     pub fn save_records(&self) -> Result<()> {
-        let records = self.records.lock().unwrap();
+        Self::write_records(&self.records, &self.file_path)
+    }
+
+    fn write_records(records: &Mutex<HashMap<String, TestRecord>>, file_path: &str) -> Result<()> {
+        let records = records.lock().unwrap();
         let content = serde_json::to_string_pretty(&*records)?;
-        fs::write(&self.file_path, content)?;
+        fs::write(file_path, content)?;
         Ok(())
     }
 
+    fn insert_record(
+        records: &Mutex<HashMap<String, TestRecord>>,
+        hash: String,
+        record: TestRecord,
+        autosave_path: Option<&str>,
+    ) {
+        records.lock().unwrap().insert(hash, record);
+        if let Some(file_path) = autosave_path {
+            if let Err(e) = Self::write_records(records, file_path) {
+                tracing::warn!("Failed to save recording to {}: {}", file_path, e);
+            }
+        }
+    }
+
+    fn autosave_path(&self) -> Option<String> {
+        self.autosave.then(|| self.file_path.clone())
+    }
+
+    fn replay_record(&self, hash: &str, messages: &[Message]) -> Result<TestOutput, ProviderError> {
+        let records = self.records.lock().unwrap();
+        match records.get(hash) {
+            Some(record) => Ok(record.output.clone()),
+            None => {
+                let last_message = messages
+                    .last()
+                    .map(|m| safe_truncate(&m.as_concat_text(), 100))
+                    .unwrap_or_default();
+                Err(ProviderError::ExecutionError(format!(
+                    "No recorded response found for input hash: {} in {} ({} records). \
+                    The conversation diverged from the recording at message {} ({:?}), \
+                    record the run again to capture it.",
+                    hash,
+                    self.file_path,
+                    records.len(),
+                    messages.len(),
+                    last_message
+                )))
+            }
+        }
+    }
+
+    fn test_input(system: &str, messages: &[Message], tools: &[Tool]) -> TestInput {
+        TestInput {
+            system: system.to_string(),
+            messages: messages.to_vec(),
+            tools: tools.to_vec(),
+        }
+    }
+
     pub fn get_record_count(&self) -> usize {
         self.records.lock().unwrap().len()
     }
@@ -124,38 +233,87 @@ impl Provider for TestProvider {
             let (message, usage) = inner.complete(system, messages, tools).await?;
 
             let record = TestRecord {
-                input: TestInput {
-                    system: system.to_string(),
-                    messages: messages.to_vec(),
-                    tools: tools.to_vec(),
-                },
+                input: Self::test_input(system, messages, tools),
                 output: TestOutput {
                     message: message.clone(),
                     usage: usage.clone(),
+                    chunks: vec![],
                 },
             };
-
-            {
-                let mut records = self.records.lock().unwrap();
-                records.insert(hash, record);
-            }
+            Self::insert_record(&self.records, hash, record, self.autosave_path().as_deref());
 
             Ok((message, usage))
         } else {
-            let records = self.records.lock().unwrap();
-            if let Some(record) = records.get(&hash) {
-                Ok((record.output.message.clone(), record.output.usage.clone()))
-            } else {
-                Err(ProviderError::ExecutionError(format!(
-                    "No recorded response found for input hash: {}",
-                    hash
-                )))
-            }
+            let output = self.replay_record(&hash, messages)?;
+            Ok((output.message, output.usage))
         }
     }
 
     fn get_model_config(&self) -> ModelConfig {
-        ModelConfig::new("test-model".to_string())
+        match (&self.inner, &self.model_config) {
+            (_, Some(model_config)) => model_config.clone(),
+            (Some(inner), None) => inner.get_model_config(),
+            (None, None) => ModelConfig::new("test-model".to_string()),
+        }
+    }
+
+    fn supports_streaming(&self) -> bool {
+        match &self.inner {
+            Some(inner) => inner.supports_streaming(),
+            None => true,
+        }
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let hash = Self::hash_input(messages);
+
+        if let Some(inner) = &self.inner {
+            let mut stream = inner.stream(system, messages, tools).await?;
+            let input = Self::test_input(system, messages, tools);
+            let model_name = inner.get_model_config().model_name;
+            let records = Arc::clone(&self.records);
+            let autosave_path = self.autosave_path();
+
+            Ok(Box::pin(try_stream! {
+                let mut chunks = Vec::new();
+                let mut usage = None;
+                while let Some(item) = stream.next().await {
+                    let (message, item_usage) = item?;
+                    if let Some(message) = &message {
+                        chunks.push(message.clone());
+                    }
+                    if let Some(item_usage) = &item_usage {
+                        usage = Some(item_usage.clone());
+                    }
+                    yield (message, item_usage);
+                }
+
+                let usage = usage.unwrap_or_else(|| ProviderUsage::new(model_name, Usage::default()));
+                let record = TestRecord {
+                    input,
+                    output: TestOutput::from_chunks(chunks, usage),
+                };
+                Self::insert_record(&records, hash, record, autosave_path.as_deref());
+            }))
+        } else {
+            let output = self.replay_record(&hash, messages)?;
+            if output.chunks.is_empty() {
+                return Ok(stream_from_single_message(output.message, output.usage));
+            }
+
+            let usage = output.usage;
+            let items = output
+                .chunks
+                .into_iter()
+                .map(|chunk| Ok((Some(chunk), None)))
+                .chain(std::iter::once(Ok((None, Some(usage)))));
+            Ok(Box::pin(futures::stream::iter(items)))
+        }
     }
 }
 
@@ -212,6 +370,34 @@ mod tests {
         fn get_model_config(&self) -> ModelConfig {
             self.model_config.clone()
         }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        async fn stream(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<MessageStream, ProviderError> {
+            let items: Vec<Result<_, ProviderError>> = self
+                .response
+                .split_inclusive(' ')
+                .map(|word| {
+                    let chunk = Message::assistant().with_id("msg_1").with_text(word);
+                    Ok((Some(chunk), None))
+                })
+                .chain(std::iter::once(Ok((
+                    None,
+                    Some(ProviderUsage::new(
+                        "mock-model".to_string(),
+                        Usage::new(Some(5), Some(2), Some(7)),
+                    )),
+                ))))
+                .collect();
+            Ok(Box::pin(futures::stream::iter(items)))
+        }
     }
 
     #[tokio::test]
@@ -259,6 +445,67 @@ mod tests {
         let _ = fs::remove_file(temp_file);
     }
 
+    #[tokio::test]
+    async fn test_record_and_replay_stream() {
+        let temp_file = format!(
+            "{}/test_stream_records_{}.json",
+            env::temp_dir().display(),
+            std::process::id()
+        );
+
+        let mock = Arc::new(MockProvider {
+            model_config: ModelConfig::new("mock-model".to_string()),
+            response: "Hello, streaming world!".to_string(),
+        });
+        let messages = vec![Message::user().with_text("hi")];
+
+        {
+            let test_provider = TestProvider::new_recording(mock, &temp_file).with_autosave();
+            assert!(test_provider.supports_streaming());
+            assert_eq!(test_provider.get_model_config().model_name, "mock-model");
+
+            let stream = test_provider
+                .stream("system", &messages, &[])
+                .await
+                .unwrap();
+            let items: Vec<_> = stream.collect().await;
+            assert_eq!(items.len(), 4);
+            // Saved without finish_recording
+            assert!(Path::new(&temp_file).exists());
+        }
+
+        let replay_provider = TestProvider::new_replaying(&temp_file)
+            .unwrap()
+            .with_model_config(ModelConfig::new("replayed-model".to_string()));
+        assert_eq!(
+            replay_provider.get_model_config().model_name,
+            "replayed-model"
+        );
+
+        let stream = replay_provider
+            .stream("system", &messages, &[])
+            .await
+            .unwrap();
+        let items: Vec<_> = stream.map(|item| item.unwrap()).collect().await;
+        assert_eq!(items.len(), 4);
+        let text: String = items
+            .iter()
+            .filter_map(|(message, _)| message.as_ref().map(|m| m.as_concat_text()))
+            .collect();
+        assert_eq!(text, "Hello, streaming world!");
+        let usage = items.last().unwrap().1.as_ref().unwrap();
+        assert_eq!(usage.usage.total_tokens, Some(7));
+
+        // A streamed recording can also be replayed as a single completion
+        let (message, _) = replay_provider
+            .complete("system", &messages, &[])
+            .await
+            .unwrap();
+        assert_eq!(message.as_concat_text(), "Hello, streaming world!");
+
+        let _ = fs::remove_file(temp_file);
+    }
+
     #[tokio::test]
     async fn test_replay_missing_record() {
         let temp_file = format!(
@@ -274,10 +521,9 @@ mod tests {
             .await;
 
         assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("No recorded response found"));
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("No recorded response found"));
+        assert!(error.contains(&temp_file));
 
         let _ = fs::remove_file(temp_file);
     }
@@ -21,18 +21,23 @@
 //!
 //! ### Implementations
 //!
-//! The module provides an implementation for Ollama:
+//! The module provides three implementations, selected with `GOOSE_TOOLSHIM_INTERPRETER`:
 //!
-//! - `OllamaInterpreter`: Uses Ollama's structured output API to interpret tool calls
+//! - `OllamaInterpreter` (`ollama`, the default): Uses Ollama's structured output API to interpret tool calls
+//! - `ProviderInterpreter` (`provider`): Uses any configured goose provider, with native structured output where the provider supports it
+//! - `JsonParserInterpreter` (`parser`): Deterministically parses tool calls written as JSON in the text, without a model
 //!
 //! ### Helper Functions
 //!
 //! - `augment_message_with_tool_calls`: A utility function that takes any message, extracts text content, sends it to an interpreter, and adds any detected tool calls back to the message.
+//! - `create_interpreter`: Creates the interpreter selected in the config.
 //!
 
+use super::base::Provider;
 use super::errors::ProviderError;
 use super::ollama::OLLAMA_DEFAULT_PORT;
 use super::ollama::OLLAMA_HOST;
+use crate::config::Config;
 use crate::message::{Message, MessageContent};
 use crate::model::ModelConfig;
 use crate::providers::formats::openai::create_request;
@@ -42,18 +47,48 @@ use reqwest::Client;
 use rmcp::model::{RawContent, Tool};
 use serde_json::{json, Value};
 use std::ops::Deref;
+use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
 
 /// Default model to use for tool interpretation
 pub const DEFAULT_INTERPRETER_MODEL_OLLAMA: &str = "mistral-nemo";
 
+/// Instructions for interpreter models on how to report the tool calls they find
+const TOOL_INTERPRETER_PROMPT: &str = r#"If there is detectable JSON-formatted tool requests, write them into valid JSON tool calls in the following format:
+{
+  "tool_calls": [
+    {
+      "name": "tool_name",
+      "arguments": {
+        "param1": "value1",
+        "param2": "value2"
+      }
+    }
+  ]
+}
+
+Otherwise, if no JSON tool requests are provided, use the no-op tool:
+{
+  "tool_calls": [
+    {
+      "name": "noop",
+      "arguments": {}
+    }
+  ]
+}
+"#;
+
 /// Environment variables that affect behavior:
 /// - GOOSE_TOOLSHIM: When set to "true" or "1", enables using the tool shim in the standard OllamaProvider (default: false)
+/// - GOOSE_TOOLSHIM_INTERPRETER: Which interpreter to use: "ollama", "provider" or "parser" (default: "ollama")
 /// - GOOSE_TOOLSHIM_OLLAMA_MODEL: Ollama model to use as the tool interpreter (default: DEFAULT_INTERPRETER_MODEL)
+/// - GOOSE_TOOLSHIM_PROVIDER / GOOSE_TOOLSHIM_MODEL: Provider and model for the "provider" interpreter
+///   (default: GOOSE_PROVIDER / GOOSE_MODEL)
+///
 /// A trait for models that can interpret text into structured tool call JSON format
 #[async_trait::async_trait]
-pub trait ToolInterpreter {
+pub trait ToolInterpreter: Send + Sync {
     /// Interpret potential tool calls from text and convert them to proper tool call JSON format
     async fn interpret_to_tool_calls(
         &self,
@@ -114,29 +149,7 @@ impl OllamaInterpreter {
     }
 
     fn tool_structured_ouput_format_schema() -> Value {
-        json!({
-            "type": "object",
-            "properties": {
-                "tool_calls": {
-                    "type": "array",
-                    "items": {
-                        "type": "object",
-                        "properties": {
-                            "name": {
-                                "type": "string",
-                                "description": "The name of the tool to call"
-                            },
-                            "arguments": {
-                                "type": "object",
-                                "description": "The arguments to pass to the tool"
-                            }
-                        },
-                        "required": ["name", "arguments"]
-                    }
-                }
-            },
-            "required": ["tool_calls"]
-        })
+        tool_calls_schema()
     }
 
     async fn post_structured(
@@ -205,29 +218,7 @@ impl OllamaInterpreter {
         // Extract tool_calls array from the response
         if response.get("message").is_some() && response["message"].get("content").is_some() {
             let content = response["message"]["content"].as_str().unwrap_or_default();
-
-            // Try to parse the content as JSON
-            if let Ok(content_json) = serde_json::from_str::<Value>(content) {
-                // Check for the format with tool_calls array inside an object
-                if content_json.is_object() && content_json.get("tool_calls").is_some() {
-                    // Process each tool call in the array
-                    if let Some(tool_calls_array) = content_json["tool_calls"].as_array() {
-                        for item in tool_calls_array {
-                            if item.is_object()
-                                && item.get("name").is_some()
-                                && item.get("arguments").is_some()
-                            {
-                                // Create ToolCall directly from the JSON data
-                                let name = item["name"].as_str().unwrap_or_default().to_string();
-                                let arguments = item["arguments"].clone();
-
-                                // Add the tool call to our result vector
-                                tool_calls.push(ToolCall::new(name, arguments));
-                            }
-                        }
-                    }
-                }
-            }
+            tool_calls = parse_tool_calls_json(content).unwrap_or_default();
         }
 
         Ok(tool_calls)
@@ -245,33 +236,11 @@ impl ToolInterpreter for OllamaInterpreter {
             return Ok(vec![]);
         }
 
-        // Create the system prompt
-        let system_prompt = "If there is detectable JSON-formatted tool requests, write them into valid JSON tool calls in the following format:
-{{
-  \"tool_calls\": [
-    {{
-      \"name\": \"tool_name\",
-      \"arguments\": {{
-        \"param1\": \"value1\",
-        \"param2\": \"value2\"
-      }}
-    }}
-  ]
-}}
-
-Otherwise, if no JSON tool requests are provided, use the no-op tool:
-{{
-  \"tool_calls\": [
-    {{
-    \"name\": \"noop\",
-      \"arguments\": {{
-      }}
-    }}]
-}}
-";
-
         // Create enhanced content with instruction to output tool calls as JSON
-        let format_instruction = format!("{}\nRequest: {}\n\n", system_prompt, last_assistant_msg);
+        let format_instruction = format!(
+            "{}\nRequest: {}\n\n",
+            TOOL_INTERPRETER_PROMPT, last_assistant_msg
+        );
 
         // Define the JSON schema for tool call format
         let format_schema = OllamaInterpreter::tool_structured_ouput_format_schema();
@@ -292,6 +261,269 @@ Otherwise, if no JSON tool requests are provided, use the no-op tool:
     }
 }
 
+/// Interprets tool calls with any configured goose provider
+///
+/// Uses the provider's native structured output when it is supported, and otherwise asks for
+/// the JSON in plain text. This makes it possible to pair a model without tool calling (e.g.
+/// one served through the OpenAI-compatible endpoints of llama.cpp or vLLM) with any other model.
+pub struct ProviderInterpreter {
+    provider: Arc<dyn Provider>,
+}
+
+impl ProviderInterpreter {
+    pub fn new(provider: Arc<dyn Provider>) -> Self {
+        Self { provider }
+    }
+
+    /// Create the interpreter from GOOSE_TOOLSHIM_PROVIDER and GOOSE_TOOLSHIM_MODEL,
+    /// falling back to the main GOOSE_PROVIDER and GOOSE_MODEL
+    pub fn from_config() -> Result<Self, ProviderError> {
+        let config = Config::global();
+        let provider_name: String = config
+            .get_param("GOOSE_TOOLSHIM_PROVIDER")
+            .or_else(|_| config.get_param("GOOSE_PROVIDER"))
+            .map_err(|_| {
+                ProviderError::ExecutionError(
+                    "No provider configured for the tool interpreter, set GOOSE_TOOLSHIM_PROVIDER"
+                        .to_string(),
+                )
+            })?;
+        let model_name: String = config
+            .get_param("GOOSE_TOOLSHIM_MODEL")
+            .or_else(|_| config.get_param("GOOSE_MODEL"))
+            .map_err(|_| {
+                ProviderError::ExecutionError(
+                    "No model configured for the tool interpreter, set GOOSE_TOOLSHIM_MODEL"
+                        .to_string(),
+                )
+            })?;
+
+        let model = ModelConfig::new(model_name).with_toolshim(false);
+        let provider = super::create(&provider_name, model).map_err(|e| {
+            ProviderError::ExecutionError(format!(
+                "Failed to create tool interpreter provider {}: {}",
+                provider_name, e
+            ))
+        })?;
+
+        Ok(Self::new(provider))
+    }
+
+    async fn complete_tool_calls(
+        &self,
+        system: &str,
+        messages: &[Message],
+    ) -> Result<Message, ProviderError> {
+        if self.provider.supports_structured_output() {
+            match self
+                .provider
+                .complete_structured(system, messages, &tool_calls_schema())
+                .await
+            {
+                Ok((message, _)) => return Ok(message),
+                Err(e) => tracing::warn!(
+                    "Structured output failed for the tool interpreter, falling back to text: {}",
+                    e
+                ),
+            }
+        }
+
+        let (message, _) = self.provider.complete(system, messages, &[]).await?;
+        Ok(message)
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolInterpreter for ProviderInterpreter {
+    async fn interpret_to_tool_calls(
+        &self,
+        last_assistant_msg: &str,
+        tools: &[Tool],
+    ) -> Result<Vec<ToolCall>, ProviderError> {
+        if tools.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let system = format!(
+            "{}\nAvailable tools:\n\n{}",
+            TOOL_INTERPRETER_PROMPT,
+            format_tool_info(tools)
+        );
+        let messages = vec![Message::user().with_text(format!("Request: {}", last_assistant_msg))];
+
+        let response = self.complete_tool_calls(&system, &messages).await?;
+        let content = response.as_concat_text();
+        tracing::info!("Tool interpreter response is {}", content);
+
+        // Models without structured output often wrap the JSON in prose or a code fence
+        Ok(parse_tool_calls_json(&content)
+            .unwrap_or_else(|| JsonParserInterpreter::parse(&content, tools)))
+    }
+}
+
+/// Deterministically extracts tool calls written as JSON in the assistant's text
+///
+/// Recognizes the formats local models commonly produce, with or without a code fence:
+/// - `{"name": ..., "arguments": {...}}`, also with `parameters`, `args` or `input`
+/// - `{"tool_calls": [...]}` and plain arrays of calls
+/// - OpenAI style `{"type": "function", "function": {"name": ..., "arguments": "..."}}`
+/// - Hermes style `<tool_call>{...}</tool_call>`
+///
+/// Only calls to known tools are returned, so example JSON in the text is ignored.
+pub struct JsonParserInterpreter;
+
+impl JsonParserInterpreter {
+    pub fn parse(text: &str, tools: &[Tool]) -> Vec<ToolCall> {
+        let mut tool_calls = Vec::new();
+        let mut offset = 0;
+
+        while let Some(found) = text[offset..].find(|c| c == '{' || c == '[') {
+            let start = offset + found;
+            let mut values =
+                serde_json::Deserializer::from_str(&text[start..]).into_iter::<Value>();
+            match values.next() {
+                Some(Ok(value)) => {
+                    collect_tool_calls(&value, tools, &mut tool_calls);
+                    offset = start + values.byte_offset();
+                }
+                _ => offset = start + 1,
+            }
+        }
+
+        tool_calls
+    }
+}
+
+#[async_trait::async_trait]
+impl ToolInterpreter for JsonParserInterpreter {
+    async fn interpret_to_tool_calls(
+        &self,
+        last_assistant_msg: &str,
+        tools: &[Tool],
+    ) -> Result<Vec<ToolCall>, ProviderError> {
+        Ok(Self::parse(last_assistant_msg, tools))
+    }
+}
+
+fn collect_tool_calls(value: &Value, tools: &[Tool], tool_calls: &mut Vec<ToolCall>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                collect_tool_calls(item, tools, tool_calls);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(calls) = map.get("tool_calls") {
+                return collect_tool_calls(calls, tools, tool_calls);
+            }
+            if let Some(function) = map.get("function").filter(|f| f.is_object()) {
+                return collect_tool_calls(function, tools, tool_calls);
+            }
+
+            let name = match map.get("name").and_then(Value::as_str) {
+                Some(name) => name,
+                None => return,
+            };
+            let name = match resolve_tool_name(name, tools) {
+                Some(name) => name,
+                None => return,
+            };
+
+            let arguments = ["arguments", "parameters", "args", "input"]
+                .iter()
+                .find_map(|key| map.get(*key))
+                .cloned()
+                .unwrap_or_else(|| json!({}));
+            // OpenAI style calls encode the arguments as a JSON string
+            let arguments = match arguments {
+                Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::String(s)),
+                other => other,
+            };
+
+            if arguments.is_object() {
+                tool_calls.push(ToolCall::new(name, arguments));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Match a tool name exactly, or by a unique suffix after the extension prefix
+/// (e.g. "shell" for "developer__shell")
+fn resolve_tool_name(name: &str, tools: &[Tool]) -> Option<String> {
+    if tools.iter().any(|tool| tool.name.as_ref() == name) {
+        return Some(name.to_string());
+    }
+
+    let suffix = format!("__{}", name);
+    let mut matches = tools.iter().filter(|tool| tool.name.ends_with(&suffix));
+    match (matches.next(), matches.next()) {
+        (Some(tool), None) => Some(tool.name.to_string()),
+        _ => None,
+    }
+}
+
+/// JSON schema of the response expected from interpreter models
+fn tool_calls_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "tool_calls": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "description": "The name of the tool to call"
+                        },
+                        "arguments": {
+                            "type": "object",
+                            "description": "The arguments to pass to the tool"
+                        }
+                    },
+                    "required": ["name", "arguments"]
+                }
+            }
+        },
+        "required": ["tool_calls"]
+    })
+}
+
+/// Parse an interpreter response in the `{"tool_calls": [...]}` format
+fn parse_tool_calls_json(content: &str) -> Option<Vec<ToolCall>> {
+    let value: Value = serde_json::from_str(content.trim()).ok()?;
+    let calls = value.get("tool_calls")?.as_array()?;
+
+    Some(
+        calls
+            .iter()
+            .filter_map(|item| {
+                let name = item.get("name")?.as_str()?;
+                let arguments = item.get("arguments")?.clone();
+                Some(ToolCall::new(name, arguments))
+            })
+            .collect(),
+    )
+}
+
+/// Create the tool interpreter selected by GOOSE_TOOLSHIM_INTERPRETER
+pub fn create_interpreter() -> Result<Box<dyn ToolInterpreter>, ProviderError> {
+    let interpreter: String = Config::global()
+        .get_param("GOOSE_TOOLSHIM_INTERPRETER")
+        .unwrap_or_else(|_| "ollama".to_string());
+
+    match interpreter.to_lowercase().as_str() {
+        "ollama" => Ok(Box::new(OllamaInterpreter::new()?)),
+        "provider" => Ok(Box::new(ProviderInterpreter::from_config()?)),
+        "parser" => Ok(Box::new(JsonParserInterpreter)),
+        other => Err(ProviderError::ExecutionError(format!(
+            "Unknown tool interpreter '{}', expected one of: ollama, provider, parser",
+            other
+        ))),
+    }
+}
+
 /// Creates a string containing formatted tool information
 pub fn format_tool_info(tools: &[Tool]) -> String {
     let mut tool_info = String::new();
@@ -380,7 +612,7 @@ pub fn modify_system_prompt_for_tool_json(system_prompt: &str, tools: &[Tool]) -
 }
 
 /// Helper function to augment a message with tool calls if any are detected
-pub async fn augment_message_with_tool_calls<T: ToolInterpreter>(
+pub async fn augment_message_with_tool_calls<T: ToolInterpreter + ?Sized>(
     interpreter: &T,
     message: Message,
     tools: &[Tool],
@@ -434,3 +666,135 @@ pub async fn augment_message_with_tool_calls<T: ToolInterpreter>(
 
     Ok(final_message)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use rmcp::object;
+
+    fn tools() -> Vec<Tool> {
+        [
+            "developer__shell",
+            "developer__text_editor",
+            "memory__remember",
+        ]
+        .iter()
+        .map(|name| {
+            Tool::new(
+                name.to_string(),
+                "test tool".to_string(),
+                object!({"type": "object"}),
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_parser_fenced_json() {
+        let text = "Let me list the files.\n```json\n{\n  \"name\": \"developer__shell\",\n  \"arguments\": {\"command\": \"ls\"}\n}\n```";
+        let calls = JsonParserInterpreter::parse(text, &tools());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "developer__shell");
+        assert_eq!(calls[0].arguments, json!({"command": "ls"}));
+    }
+
+    #[test]
+    fn test_parser_openai_function_style() {
+        let text = r#"{"tool_calls": [{"id": "call_1", "type": "function", "function": {"name": "developer__shell", "arguments": "{\"command\": \"pwd\"}"}}]}"#;
+        let calls = JsonParserInterpreter::parse(text, &tools());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "developer__shell");
+        assert_eq!(calls[0].arguments, json!({"command": "pwd"}));
+    }
+
+    #[test]
+    fn test_parser_hermes_style() {
+        let text = "<tool_call>\n{\"name\": \"memory__remember\", \"parameters\": {\"text\": \"hi\"}}\n</tool_call>\n<tool_call>\n{\"name\": \"developer__shell\", \"arguments\": {\"command\": \"ls\"}}\n</tool_call>";
+        let calls = JsonParserInterpreter::parse(text, &tools());
+        let names: Vec<_> = calls.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["memory__remember", "developer__shell"]);
+        assert_eq!(calls[0].arguments, json!({"text": "hi"}));
+    }
+
+    #[test]
+    fn test_parser_ignores_unknown_tools_and_resolves_suffix() {
+        let text = r#"For example {"name": "tool_name", "arguments": {}} or [1, 2].
+Now: {"name": "shell", "arguments": {"command": "ls"}} and {"name": "text_editor""#;
+        let calls = JsonParserInterpreter::parse(text, &tools());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "developer__shell");
+    }
+
+    #[test]
+    fn test_parse_tool_calls_json() {
+        let calls = parse_tool_calls_json(
+            r#"{"tool_calls": [{"name": "noop", "arguments": {}}, {"name": "missing_args"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "noop");
+
+        assert!(parse_tool_calls_json("no json here").is_none());
+    }
+
+    struct MockProvider {
+        response: String,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            ModelConfig::new("mock".to_string())
+        }
+
+        async fn complete(
+            &self,
+            system: &str,
+            _messages: &[Message],
+            tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            assert!(system.contains("developer__shell"));
+            assert!(tools.is_empty());
+            Ok((
+                Message::assistant().with_text(&self.response),
+                ProviderUsage::new("mock".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_provider_interpreter() {
+        let interpreter = ProviderInterpreter::new(Arc::new(MockProvider {
+            response:
+                r#"{"tool_calls": [{"name": "developer__shell", "arguments": {"command": "ls"}}]}"#
+                    .to_string(),
+        }));
+        let calls = interpreter
+            .interpret_to_tool_calls("run ls", &tools())
+            .await
+            .unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments, json!({"command": "ls"}));
+
+        // Falls back to parsing JSON out of prose
+        let interpreter = ProviderInterpreter::new(Arc::new(MockProvider {
+            response:
+                "Sure:\n```json\n{\"name\": \"shell\", \"arguments\": {\"command\": \"ls\"}}\n```"
+                    .to_string(),
+        }));
+        let message = augment_message_with_tool_calls(
+            &interpreter,
+            Message::assistant().with_text("run ls"),
+            &tools(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(message.content.len(), 2);
+        assert!(matches!(message.content[1], MessageContent::ToolRequest(_)));
+    }
+}
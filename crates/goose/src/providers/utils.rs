@@ -1,15 +1,21 @@
-use super::base::Usage;
+use super::base::{MessageStream, ProviderUsage, Usage};
 use super::errors::GoogleErrorCode;
+use crate::message::Message;
 use crate::model::ModelConfig;
 use anyhow::Result;
+use async_stream::try_stream;
 use base64::Engine;
+use futures::{Stream, StreamExt, TryStreamExt};
 use regex::Regex;
 use reqwest::{Response, StatusCode};
 use rmcp::model::{AnnotateAble, ImageContent, RawImageContent};
 use serde::{Deserialize, Serialize};
 use serde_json::{from_value, json, Map, Value};
-use std::io::Read;
+use std::io::{self, Read};
 use std::path::Path;
+use std::pin::Pin;
+use tokio_util::codec::{FramedRead, LinesCodec};
+use tokio_util::io::StreamReader;
 
 use crate::providers::errors::{OpenAIError, ProviderError};
 
@@ -98,6 +104,37 @@ pub async fn handle_response_openai_compat(response: Response) -> Result<Value,
     })
 }
 
+/// A streaming response body split into lines
+pub type LineStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Split a streaming (server-sent events) response body into lines for a format decoder
+pub fn sse_lines(response: Response) -> LineStream {
+    let bytes = response.bytes_stream().map_err(io::Error::other);
+    Box::pin(
+        FramedRead::new(StreamReader::new(bytes), LinesCodec::new()).map_err(anyhow::Error::from),
+    )
+}
+
+/// Wrap a decoded provider stream as a `MessageStream`, mapping decode errors and emitting
+/// a debug trace for each item
+pub fn decoded_message_stream<S>(
+    decoded: S,
+    model_config: ModelConfig,
+    payload: Value,
+) -> MessageStream
+where
+    S: Stream<Item = Result<(Option<Message>, Option<ProviderUsage>)>> + Send + 'static,
+{
+    Box::pin(try_stream! {
+        let mut decoded = Box::pin(decoded);
+        while let Some(item) = decoded.next().await {
+            let (message, usage) = item.map_err(|e| ProviderError::RequestFailed(format!("Stream decode error: {}", e)))?;
+            emit_debug_trace(&model_config, &payload, &message, &usage.as_ref().map(|u| u.usage).unwrap_or_default());
+            yield (message, usage);
+        }
+    })
+}
+
 /// Check if the model is a Google model based on the "model" field in the payload.
 ///
 /// ### Arguments
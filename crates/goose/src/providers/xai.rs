@@ -1,14 +1,20 @@
 use super::errors::ProviderError;
 use crate::message::Message;
 use crate::model::ModelConfig;
-use crate::providers::base::{ConfigKey, Provider, ProviderMetadata, ProviderUsage, Usage};
-use crate::providers::formats::openai::{create_request, get_usage, response_to_message};
-use crate::providers::utils::get_model;
+use crate::providers::base::{
+    ConfigKey, MessageStream, Provider, ProviderMetadata, ProviderUsage, Usage,
+};
+use crate::providers::formats::openai::{
+    create_request, get_usage, response_to_message, response_to_streaming_message,
+};
+use crate::providers::utils::{
+    decoded_message_stream, get_model, handle_status_openai_compat, sse_lines,
+};
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::{Client, StatusCode};
+use reqwest::{Client, Response, StatusCode};
 use rmcp::model::Tool;
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::time::Duration;
 use url::Url;
 
@@ -72,7 +78,7 @@ impl XaiProvider {
         })
     }
 
-    async fn post(&self, payload: &Value) -> anyhow::Result<Value, ProviderError> {
+    async fn send(&self, payload: &Value) -> anyhow::Result<Response, ProviderError> {
         // Ensure the host ends with a slash for proper URL joining
         let host = if self.host.ends_with('/') {
             self.host.clone()
@@ -88,13 +94,17 @@ impl XaiProvider {
         tracing::debug!("xAI API URL: {}", url);
         tracing::debug!("xAI request model: {:?}", self.model.model_name);
 
-        let response = self
+        Ok(self
             .client
             .post(url)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .json(&payload)
             .send()
-            .await?;
+            .await?)
+    }
+
+    async fn post(&self, payload: &Value) -> anyhow::Result<Value, ProviderError> {
+        let response = self.send(payload).await?;
 
         let status = response.status();
         let payload: Option<Value> = response.json().await.ok();
@@ -174,4 +184,35 @@ impl Provider for XaiProvider {
         super::utils::emit_debug_trace(&self.model, &payload, &response, &usage);
         Ok((message, ProviderUsage::new(model, usage)))
     }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn stream(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[Tool],
+    ) -> Result<MessageStream, ProviderError> {
+        let mut payload = create_request(
+            &self.model,
+            system,
+            messages,
+            tools,
+            &super::utils::ImageFormat::OpenAi,
+        )?;
+        payload["stream"] = Value::Bool(true);
+        payload["stream_options"] = json!({
+            "include_usage": true,
+        });
+
+        let response = handle_status_openai_compat(self.send(&payload).await?).await?;
+
+        Ok(decoded_message_stream(
+            response_to_streaming_message(sse_lines(response)),
+            self.model.clone(),
+            payload,
+        ))
+    }
 }
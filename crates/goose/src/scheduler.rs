@@ -1228,8 +1228,11 @@ async fn run_scheduled_job_internal(
                         Ok(AgentEvent::McpNotification(_)) => {
                             // Handle notifications if needed
                         }
-                        Ok(AgentEvent::ModelChange { .. }) => {
-                            // Model change events are informational, just continue
+                        Ok(AgentEvent::ModelChange { .. })
+                        | Ok(AgentEvent::Usage(_))
+                        | Ok(AgentEvent::MaxTurnsReached { .. })
+                        | Ok(AgentEvent::ToolDenied { .. }) => {
+                            // Model change and status events are informational, just continue
                         }
 
                         Err(e) => {
@@ -1276,6 +1279,7 @@ async fn run_scheduled_job_internal(
                             accumulated_total_tokens: None,
                             accumulated_input_tokens: None,
                             accumulated_output_tokens: None,
+                            tool_cache: None,
                         };
                         if let Err(e_fb) = crate::session::storage::save_messages_with_metadata(
                             &session_file_path,
@@ -0,0 +1,556 @@
+//! Exports of a session for use outside of goose: a JSON document with a stable, versioned schema
+//! for analytics, and a self-contained HTML transcript. Unlike the session file, whose format
+//! follows the agent's message types, the export schema only changes with its version.
+use crate::message::{Message, MessageContent};
+use crate::session::SessionMetadata;
+use chrono::{DateTime, Utc};
+use rmcp::model::{Content, RawContent, ResourceContents, Role};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+
+/// Bump when a field changes meaning or goes away, adding fields does not need a new version
+pub const SESSION_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SessionExport {
+    pub schema_version: u32,
+    pub session_id: String,
+    /// RFC 3339 timestamp of the export
+    pub exported_at: String,
+    pub description: String,
+    pub working_dir: String,
+    pub schedule_id: Option<String>,
+    pub project_id: Option<String>,
+    pub usage: ExportedUsage,
+    pub messages: Vec<ExportedMessage>,
+}
+
+/// Tokens used over the whole session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExportedUsage {
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExportedMessage {
+    pub index: usize,
+    pub id: Option<String>,
+    /// `user` or `assistant`
+    pub role: String,
+    /// RFC 3339 timestamp of when the message was created
+    pub created_at: String,
+    pub content: Vec<ExportedContent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportedContent {
+    Text {
+        text: String,
+    },
+    Image {
+        mime_type: String,
+        /// Base64 encoded image data
+        data: String,
+    },
+    Resource {
+        uri: String,
+        mime_type: Option<String>,
+        /// The text of text resources, binary resources only have their uri
+        text: Option<String>,
+    },
+    ToolRequest {
+        id: String,
+        name: Option<String>,
+        #[schema(value_type = Object)]
+        arguments: Option<Value>,
+        /// Set when the model asked for a tool call that could not be parsed
+        error: Option<String>,
+    },
+    ToolResponse {
+        id: String,
+        is_error: bool,
+        content: Vec<ExportedContent>,
+        error: Option<String>,
+    },
+    Thinking {
+        text: String,
+    },
+    RedactedThinking,
+    /// Content goose adds to a conversation for itself, such as context limit markers
+    Notice {
+        kind: String,
+        text: String,
+    },
+}
+
+impl SessionExport {
+    pub fn new(session_id: &str, metadata: &SessionMetadata, messages: &[Message]) -> Self {
+        Self {
+            schema_version: SESSION_EXPORT_SCHEMA_VERSION,
+            session_id: session_id.to_string(),
+            exported_at: Utc::now().to_rfc3339(),
+            description: metadata.description.clone(),
+            working_dir: metadata.working_dir.to_string_lossy().to_string(),
+            schedule_id: metadata.schedule_id.clone(),
+            project_id: metadata.project_id.clone(),
+            usage: ExportedUsage {
+                input_tokens: metadata.accumulated_input_tokens.or(metadata.input_tokens),
+                output_tokens: metadata
+                    .accumulated_output_tokens
+                    .or(metadata.output_tokens),
+                total_tokens: metadata.accumulated_total_tokens.or(metadata.total_tokens),
+            },
+            messages: messages
+                .iter()
+                .enumerate()
+                .map(|(index, message)| ExportedMessage::new(index, message))
+                .collect(),
+        }
+    }
+}
+
+impl ExportedMessage {
+    fn new(index: usize, message: &Message) -> Self {
+        Self {
+            index,
+            id: message.id.clone(),
+            role: match message.role {
+                Role::User => "user",
+                Role::Assistant => "assistant",
+            }
+            .to_string(),
+            created_at: DateTime::from_timestamp(message.created, 0)
+                .unwrap_or_default()
+                .to_rfc3339(),
+            content: message
+                .content
+                .iter()
+                .map(ExportedContent::from_message_content)
+                .collect(),
+        }
+    }
+}
+
+impl ExportedContent {
+    fn from_message_content(content: &MessageContent) -> Self {
+        match content {
+            MessageContent::Text(text) => ExportedContent::Text {
+                text: text.text.clone(),
+            },
+            MessageContent::Image(image) => ExportedContent::Image {
+                mime_type: image.mime_type.clone(),
+                data: image.data.clone(),
+            },
+            MessageContent::ToolRequest(request) => {
+                Self::tool_request(&request.id, request.tool_call.as_ref())
+            }
+            MessageContent::FrontendToolRequest(request) => {
+                Self::tool_request(&request.id, request.tool_call.as_ref())
+            }
+            MessageContent::ToolResponse(response) => match &response.tool_result {
+                Ok(contents) => ExportedContent::ToolResponse {
+                    id: response.id.clone(),
+                    is_error: false,
+                    content: contents.iter().map(Self::from_tool_content).collect(),
+                    error: None,
+                },
+                Err(e) => ExportedContent::ToolResponse {
+                    id: response.id.clone(),
+                    is_error: true,
+                    content: Vec::new(),
+                    error: Some(e.to_string()),
+                },
+            },
+            MessageContent::ToolConfirmationRequest(request) => ExportedContent::Notice {
+                kind: "tool_confirmation_request".to_string(),
+                text: request.tool_name.clone(),
+            },
+            MessageContent::Thinking(thinking) => ExportedContent::Thinking {
+                text: thinking.thinking.clone(),
+            },
+            MessageContent::RedactedThinking(_) => ExportedContent::RedactedThinking,
+            MessageContent::ContextLengthExceeded(notice) => ExportedContent::Notice {
+                kind: "context_length_exceeded".to_string(),
+                text: notice.msg.clone(),
+            },
+            MessageContent::SummarizationRequested(notice) => ExportedContent::Notice {
+                kind: "summarization_requested".to_string(),
+                text: notice.msg.clone(),
+            },
+        }
+    }
+
+    fn tool_request(
+        id: &str,
+        tool_call: Result<&mcp_core::tool::ToolCall, &mcp_core::handler::ToolError>,
+    ) -> Self {
+        match tool_call {
+            Ok(call) => ExportedContent::ToolRequest {
+                id: id.to_string(),
+                name: Some(call.name.clone()),
+                arguments: Some(call.arguments.clone()),
+                error: None,
+            },
+            Err(e) => ExportedContent::ToolRequest {
+                id: id.to_string(),
+                name: None,
+                arguments: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    fn from_tool_content(content: &Content) -> Self {
+        match &content.raw {
+            RawContent::Text(text) => ExportedContent::Text {
+                text: text.text.clone(),
+            },
+            RawContent::Image(image) => ExportedContent::Image {
+                mime_type: image.mime_type.clone(),
+                data: image.data.clone(),
+            },
+            RawContent::Resource(resource) => match &resource.resource {
+                ResourceContents::TextResourceContents {
+                    uri,
+                    mime_type,
+                    text,
+                } => ExportedContent::Resource {
+                    uri: uri.clone(),
+                    mime_type: mime_type.clone(),
+                    text: Some(text.clone()),
+                },
+                ResourceContents::BlobResourceContents { uri, mime_type, .. } => {
+                    ExportedContent::Resource {
+                        uri: uri.clone(),
+                        mime_type: mime_type.clone(),
+                        text: None,
+                    }
+                }
+            },
+            RawContent::Audio(_) => ExportedContent::Notice {
+                kind: "audio".to_string(),
+                text: "Audio content is not exported".to_string(),
+            },
+        }
+    }
+}
+
+const HTML_STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; max-width: 860px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; background: #fff; }
+header { border-bottom: 1px solid #d0d7de; margin-bottom: 1.5rem; }
+header p { color: #59636e; font-size: 0.9rem; }
+.message { margin: 1rem 0; padding: 0.75rem 1rem; border-radius: 8px; }
+.message.user { background: #f6f8fa; }
+.message.assistant { border: 1px solid #d0d7de; }
+.role { font-weight: 600; font-size: 0.8rem; text-transform: uppercase; color: #59636e; }
+.role time { font-weight: normal; text-transform: none; margin-left: 0.5rem; }
+.text { white-space: pre-wrap; word-wrap: break-word; }
+details { margin: 0.5rem 0; border: 1px solid #d0d7de; border-radius: 6px; padding: 0.25rem 0.75rem; }
+details summary { cursor: pointer; font-family: ui-monospace, monospace; font-size: 0.9rem; }
+details.error summary { color: #cf222e; }
+details.thinking summary { font-family: inherit; color: #59636e; }
+pre { white-space: pre-wrap; word-wrap: break-word; background: #f6f8fa; padding: 0.5rem; border-radius: 4px; font-size: 0.85rem; }
+img { max-width: 100%; border-radius: 4px; }
+.notice { color: #9a6700; font-style: italic; }
+@media (prefers-color-scheme: dark) {
+  body { color: #e6edf3; background: #0d1117; }
+  .message.user, pre { background: #161b22; }
+  .message.assistant, details, header { border-color: #30363d; }
+}
+"#;
+
+/// Renders the export as a single HTML page with inline styles and images, tool calls are
+/// collapsed together with their results
+pub fn to_html(export: &SessionExport) -> String {
+    let title = if export.description.is_empty() {
+        export.session_id.clone()
+    } else {
+        export.description.clone()
+    };
+
+    // Tool results are shown inside the tool call they answer
+    let responses: HashMap<&str, &ExportedContent> = export
+        .messages
+        .iter()
+        .flat_map(|m| m.content.iter())
+        .filter_map(|c| match c {
+            ExportedContent::ToolResponse { id, .. } => Some((id.as_str(), c)),
+            _ => None,
+        })
+        .collect();
+    let requested: HashSet<&str> = export
+        .messages
+        .iter()
+        .flat_map(|m| m.content.iter())
+        .filter_map(|c| match c {
+            ExportedContent::ToolRequest { id, .. } => Some(id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut body = String::new();
+    for message in &export.messages {
+        let content: String = message
+            .content
+            .iter()
+            .filter(|c| {
+                !matches!(c, ExportedContent::ToolResponse { id, .. } if requested.contains(id.as_str()))
+            })
+            .map(|c| content_to_html(c, &responses))
+            .collect();
+        if content.is_empty() {
+            continue;
+        }
+        body.push_str(&format!(
+            "<section class=\"message {role}\"><div class=\"role\">{role}<time datetime=\"{time}\">{time}</time></div>\n{content}</section>\n",
+            role = escape_html(&message.role),
+            time = escape_html(&message.created_at),
+            content = content,
+        ));
+    }
+
+    let mut details = vec![
+        format!("Session {}", escape_html(&export.session_id)),
+        format!("{} messages", export.messages.len()),
+    ];
+    if !export.working_dir.is_empty() {
+        details.push(escape_html(&export.working_dir));
+    }
+    if let Some(total) = export.usage.total_tokens {
+        details.push(format!("{} tokens", total));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{title}</title>\n<style>{style}</style>\n</head>\n<body>\n<header><h1>{title}</h1><p>{details}</p></header>\n{body}</body>\n</html>\n",
+        title = escape_html(&title),
+        style = HTML_STYLE,
+        details = details.join(" · "),
+        body = body,
+    )
+}
+
+fn content_to_html(
+    content: &ExportedContent,
+    responses: &HashMap<&str, &ExportedContent>,
+) -> String {
+    match content {
+        ExportedContent::Text { text } => {
+            if text.trim().is_empty() {
+                String::new()
+            } else {
+                format!("<div class=\"text\">{}</div>\n", escape_html(text))
+            }
+        }
+        ExportedContent::Image { mime_type, data } => image_to_html(mime_type, data),
+        ExportedContent::Resource {
+            uri,
+            text: Some(text),
+            ..
+        } => format!(
+            "<details><summary>{}</summary><pre>{}</pre></details>\n",
+            escape_html(uri),
+            escape_html(text)
+        ),
+        ExportedContent::Resource { uri, mime_type, .. } => format!(
+            "<p class=\"notice\">Binary resource {} ({})</p>\n",
+            escape_html(uri),
+            escape_html(mime_type.as_deref().unwrap_or("unknown type"))
+        ),
+        ExportedContent::ToolRequest {
+            id,
+            name,
+            arguments,
+            error,
+        } => {
+            let response = responses.get(id.as_str()).copied();
+            let failed = error.is_some()
+                || matches!(response, Some(ExportedContent::ToolResponse { is_error: true, .. }));
+            let mut html = format!(
+                "<details class=\"tool{}\"><summary>{}</summary>\n",
+                if failed { " error" } else { "" },
+                escape_html(name.as_deref().unwrap_or("invalid tool call"))
+            );
+            if let Some(arguments) = arguments {
+                html.push_str(&format!(
+                    "<pre>{}</pre>\n",
+                    escape_html(&serde_json::to_string_pretty(arguments).unwrap_or_default())
+                ));
+            }
+            if let Some(error) = error {
+                html.push_str(&format!("<pre>{}</pre>\n", escape_html(error)));
+            }
+            if let Some(response) = response {
+                html.push_str(&tool_output_to_html(response, responses));
+            }
+            html.push_str("</details>\n");
+            html
+        }
+        ExportedContent::ToolResponse { id, .. } => format!(
+            "<details class=\"tool\"><summary>result of {}</summary>\n{}</details>\n",
+            escape_html(id),
+            tool_output_to_html(content, responses)
+        ),
+        ExportedContent::Thinking { text } => format!(
+            "<details class=\"thinking\"><summary>Thinking</summary><div class=\"text\">{}</div></details>\n",
+            escape_html(text)
+        ),
+        ExportedContent::RedactedThinking => String::new(),
+        ExportedContent::Notice { text, .. } => {
+            format!("<p class=\"notice\">{}</p>\n", escape_html(text))
+        }
+    }
+}
+
+fn tool_output_to_html(
+    response: &ExportedContent,
+    responses: &HashMap<&str, &ExportedContent>,
+) -> String {
+    let ExportedContent::ToolResponse { content, error, .. } = response else {
+        return String::new();
+    };
+    let mut html = String::new();
+    if let Some(error) = error {
+        html.push_str(&format!("<pre>{}</pre>\n", escape_html(error)));
+    }
+    for item in content {
+        html.push_str(&match item {
+            // Tool output is usually code or logs, keep its layout
+            ExportedContent::Text { text } => format!("<pre>{}</pre>\n", escape_html(text)),
+            other => content_to_html(other, responses),
+        });
+    }
+    html
+}
+
+/// Images are embedded as data urls, anything that does not look like base64 image data is left
+/// out rather than put into an attribute
+fn image_to_html(mime_type: &str, data: &str) -> String {
+    let valid_mime = mime_type.strip_prefix("image/").is_some_and(|subtype| {
+        !subtype.is_empty()
+            && subtype
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'))
+    });
+    let valid_data = !data.is_empty()
+        && data
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '\n' | '\r'));
+    if valid_mime && valid_data {
+        format!(
+            "<img src=\"data:{};base64,{}\" alt=\"image\">\n",
+            mime_type,
+            data.replace(['\n', '\r'], "")
+        )
+    } else {
+        format!(
+            "<p class=\"notice\">Image ({}) could not be embedded</p>\n",
+            escape_html(mime_type)
+        )
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn metadata() -> SessionMetadata {
+        SessionMetadata {
+            description: "Fix the <build>".to_string(),
+            accumulated_total_tokens: Some(1200),
+            ..SessionMetadata::new(PathBuf::from("/tmp/project"))
+        }
+    }
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message::user().with_text("Run the tests"),
+            Message::assistant()
+                .with_text("Running them")
+                .with_tool_request(
+                    "call_1",
+                    Ok(ToolCall::new(
+                        "developer__shell",
+                        json!({"command": "cargo test"}),
+                    )),
+                ),
+            Message::user().with_tool_response("call_1", Ok(vec![Content::text("1 failed")])),
+            Message::assistant().with_image("aGVsbG8=", "image/png"),
+        ]
+    }
+
+    #[test]
+    fn test_json_export_schema() {
+        let export = SessionExport::new("20250101_120000", &metadata(), &conversation());
+        let value = serde_json::to_value(&export).unwrap();
+
+        assert_eq!(value["schema_version"], 1);
+        assert_eq!(value["usage"]["total_tokens"], 1200);
+        assert_eq!(value["messages"][1]["role"], "assistant");
+        assert_eq!(
+            value["messages"][1]["content"][1],
+            json!({
+                "type": "tool_request",
+                "id": "call_1",
+                "name": "developer__shell",
+                "arguments": {"command": "cargo test"},
+                "error": null
+            })
+        );
+        assert_eq!(value["messages"][2]["content"][0]["type"], "tool_response");
+        assert_eq!(
+            value["messages"][2]["content"][0]["content"][0],
+            json!({"type": "text", "text": "1 failed"})
+        );
+
+        let round_trip: SessionExport = serde_json::from_value(value).unwrap();
+        assert_eq!(round_trip, export);
+    }
+
+    #[test]
+    fn test_html_export() {
+        let export = SessionExport::new("20250101_120000", &metadata(), &conversation());
+        let html = to_html(&export);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Fix the &lt;build&gt;</title>"));
+        // The result is inside the tool call, and the message that only held it is left out
+        assert_eq!(html.matches("<details class=\"tool\">").count(), 1);
+        assert_eq!(html.matches("<section").count(), 3);
+        assert!(html.contains("<summary>developer__shell</summary>"));
+        assert!(html.contains("<pre>1 failed</pre>"));
+        assert!(html.contains("<img src=\"data:image/png;base64,aGVsbG8=\""));
+    }
+
+    #[test]
+    fn test_html_export_does_not_embed_untrusted_images() {
+        let html = image_to_html("image/png\" onerror=\"alert(1)", "aGVsbG8=");
+        assert!(!html.contains("<img"));
+        assert!(!html.contains("onerror=\""));
+
+        let html = image_to_html("image/png", "aGVs\"><script>");
+        assert!(!html.contains("<script>"));
+    }
+}
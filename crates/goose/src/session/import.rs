@@ -0,0 +1,542 @@
+//! Imports of transcripts from other agents and raw API logs, so a conversation that started
+//! elsewhere can be continued as a goose session. The parser is lenient: it understands both
+//! OpenAI and Anthropic content blocks wherever they appear, and the format only decides how the
+//! messages are laid out in the file.
+use crate::conversation_fixer::ConversationFixer;
+use crate::message::{Message, MessageContent};
+use crate::providers::utils::safely_parse_json;
+use crate::session::storage::{save_messages_with_metadata, SessionMetadata};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use mcp_core::handler::ToolError;
+use mcp_core::tool::ToolCall;
+use rmcp::model::{Content, Role};
+use serde_json::Value;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    /// Chat completions messages, either a request body with `messages` or a bare array
+    OpenAi,
+    /// Messages API messages, either a request body with `messages` and `system` or a bare array
+    Anthropic,
+    /// One record per line, either a message or an object holding one under `message`
+    Jsonl,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportedTranscript {
+    pub messages: Vec<Message>,
+    /// What was skipped or repaired to make the transcript a valid goose conversation
+    pub issues: Vec<String>,
+}
+
+/// Added after the transcript while it is repaired. The fixer prepares conversations for a model
+/// and so drops a final assistant message, which for an import is usually the answer it ended with.
+const END_OF_TRANSCRIPT: &str = "<end of imported transcript>";
+
+/// Converts a transcript into goose messages
+pub fn parse_transcript(format: TranscriptFormat, content: &str) -> Result<ImportedTranscript> {
+    let mut parser = TranscriptParser::default();
+    match format {
+        TranscriptFormat::OpenAi | TranscriptFormat::Anthropic => {
+            let document: Value =
+                serde_json::from_str(content).context("The transcript is not valid JSON")?;
+            let messages = match &document {
+                Value::Array(messages) => messages,
+                Value::Object(fields) => fields
+                    .get("messages")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| anyhow!("The transcript has no messages array"))?,
+                _ => {
+                    return Err(anyhow!(
+                        "The transcript must be an array of messages or an object with messages"
+                    ))
+                }
+            };
+            if document
+                .get("system")
+                .is_some_and(|s| !text_of(s).is_empty())
+            {
+                parser.skip_system_prompt("system");
+            }
+            for message in messages {
+                parser.parse_message(message, None);
+            }
+            // A logged chat completions response carries the answer in its choices
+            if let Some(answer) = document.pointer("/choices/0/message") {
+                parser.parse_message(answer, None);
+            }
+        }
+        TranscriptFormat::Jsonl => {
+            let mut skipped = 0;
+            for (index, line) in content.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Value>(line) {
+                    Ok(record) => {
+                        if !parser.parse_record(&record) {
+                            skipped += 1;
+                        }
+                    }
+                    Err(_) => parser
+                        .issues
+                        .push(format!("Skipped line {} which is not JSON", index + 1)),
+                }
+            }
+            if skipped > 0 {
+                parser
+                    .issues
+                    .push(format!("Skipped {} records that are not messages", skipped));
+            }
+        }
+    }
+
+    if parser.messages.is_empty() {
+        return Err(anyhow!("No messages found in the transcript"));
+    }
+
+    let mut messages = parser.messages;
+    messages.push(Message::user().with_text(END_OF_TRANSCRIPT));
+    let (mut messages, fixes) = ConversationFixer::fix_conversation(messages);
+    if let Some(last) = messages.last_mut() {
+        let is_end = |c: &MessageContent| c.as_text() == Some(END_OF_TRANSCRIPT);
+        if last.content.last().is_some_and(is_end) {
+            last.content.pop();
+            if last.content.is_empty() {
+                messages.pop();
+            }
+        }
+    }
+    if messages.is_empty() {
+        return Err(anyhow!(
+            "No messages left in the transcript after repairing it"
+        ));
+    }
+
+    let mut issues = parser.issues;
+    issues.extend(fixes);
+    Ok(ImportedTranscript { messages, issues })
+}
+
+/// Converts a transcript and writes it as the session at `session_file`
+pub fn import_transcript(
+    format: TranscriptFormat,
+    content: &str,
+    session_file: &Path,
+    mut metadata: SessionMetadata,
+) -> Result<ImportedTranscript> {
+    let transcript = parse_transcript(format, content)?;
+    metadata.message_count = transcript.messages.len();
+    save_messages_with_metadata(session_file, &metadata, &transcript.messages)?;
+    Ok(transcript)
+}
+
+#[derive(Default)]
+struct TranscriptParser {
+    messages: Vec<Message>,
+    issues: Vec<String>,
+    /// Legacy OpenAI `function_call`s by function name, waiting for their `function` result
+    function_calls: Vec<(String, String)>,
+    generated_ids: usize,
+}
+
+impl TranscriptParser {
+    /// Returns false for records that do not hold a message
+    fn parse_record(&mut self, record: &Value) -> bool {
+        // Our own session files, the metadata line is not a message
+        if record.get("created").is_some() {
+            if let Ok(message) = serde_json::from_value::<Message>(record.clone()) {
+                self.messages.push(message);
+                return true;
+            }
+        }
+        if let Some(message) = record.get("message").filter(|m| m.get("role").is_some()) {
+            self.parse_message(message, timestamp_of(record));
+            true
+        } else if record.get("role").is_some() {
+            self.parse_message(record, None);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_message(&mut self, message: &Value, timestamp: Option<i64>) {
+        let created = timestamp
+            .or_else(|| timestamp_of(message))
+            .unwrap_or_else(|| Utc::now().timestamp());
+        let role = message
+            .get("role")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        match role {
+            "system" | "developer" => self.skip_system_prompt(role),
+            "user" | "human" => {
+                let content = self.parse_content(message.get("content"));
+                self.messages
+                    .push(Message::new(Role::User, created, content));
+            }
+            "assistant" => {
+                let mut content = self.parse_content(message.get("content"));
+                for call in message
+                    .get("tool_calls")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    let id = match call.get("id").and_then(Value::as_str) {
+                        Some(id) => id.to_string(),
+                        None => self.generate_id(),
+                    };
+                    content.push(function_call(id, &call["function"]));
+                }
+                if let Some(call) = message.get("function_call") {
+                    let id = self.generate_id();
+                    let name = call["name"].as_str().unwrap_or_default().to_string();
+                    self.function_calls.push((name, id.clone()));
+                    content.push(function_call(id, call));
+                }
+                self.messages
+                    .push(Message::new(Role::Assistant, created, content));
+            }
+            "tool" => {
+                let id = match message.get("tool_call_id").and_then(Value::as_str) {
+                    Some(id) => id.to_string(),
+                    None => self.generate_id(),
+                };
+                let result = self.tool_output(message.get("content"));
+                self.messages.push(Message::new(
+                    Role::User,
+                    created,
+                    vec![MessageContent::tool_response(id, Ok(result))],
+                ));
+            }
+            "function" => {
+                let name = message.get("name").and_then(Value::as_str);
+                let id = match self
+                    .function_calls
+                    .iter()
+                    .position(|(called, _)| Some(called.as_str()) == name)
+                {
+                    Some(index) => self.function_calls.remove(index).1,
+                    None => self.generate_id(),
+                };
+                let result = self.tool_output(message.get("content"));
+                self.messages.push(Message::new(
+                    Role::User,
+                    created,
+                    vec![MessageContent::tool_response(id, Ok(result))],
+                ));
+            }
+            other => self
+                .issues
+                .push(format!("Skipped message with unknown role '{}'", other)),
+        }
+    }
+
+    fn parse_content(&mut self, content: Option<&Value>) -> Vec<MessageContent> {
+        match content {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::String(text)) if text.is_empty() => Vec::new(),
+            Some(Value::String(text)) => vec![MessageContent::text(text)],
+            Some(Value::Array(blocks)) => blocks
+                .iter()
+                .filter_map(|block| self.parse_block(block))
+                .collect(),
+            Some(block) => self.parse_block(block).into_iter().collect(),
+        }
+    }
+
+    fn parse_block(&mut self, block: &Value) -> Option<MessageContent> {
+        if let Some(text) = block.as_str() {
+            return Some(MessageContent::text(text));
+        }
+        let block_type = block
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        match block_type {
+            "text" | "input_text" | "output_text" => block
+                .get("text")
+                .and_then(Value::as_str)
+                .map(MessageContent::text),
+            "image_url" | "input_image" | "image" => Some(image(block)),
+            "tool_use" => {
+                let id = match block.get("id").and_then(Value::as_str) {
+                    Some(id) => id.to_string(),
+                    None => self.generate_id(),
+                };
+                let tool_call = match block.get("name").and_then(Value::as_str) {
+                    Some(name) => Ok(ToolCall::new(
+                        name,
+                        block.get("input").cloned().unwrap_or_default(),
+                    )),
+                    None => Err(ToolError::InvalidParameters(format!(
+                        "Tool call {} has no name",
+                        id
+                    ))),
+                };
+                Some(MessageContent::tool_request(id, tool_call))
+            }
+            "tool_result" => {
+                let id = match block.get("tool_use_id").and_then(Value::as_str) {
+                    Some(id) => id.to_string(),
+                    None => self.generate_id(),
+                };
+                let output = self.tool_output(block.get("content"));
+                let result = if block.get("is_error").and_then(Value::as_bool) == Some(true) {
+                    let message: Vec<String> = output
+                        .iter()
+                        .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+                        .collect();
+                    Err(ToolError::ExecutionError(message.join("\n")))
+                } else {
+                    Ok(output)
+                };
+                Some(MessageContent::tool_response(id, result))
+            }
+            "thinking" => Some(MessageContent::thinking(
+                block
+                    .get("thinking")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default(),
+                block
+                    .get("signature")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default(),
+            )),
+            "redacted_thinking" => Some(MessageContent::redacted_thinking(
+                block
+                    .get("data")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default(),
+            )),
+            other => {
+                self.issues
+                    .push(format!("Skipped unsupported '{}' content", other));
+                None
+            }
+        }
+    }
+
+    fn tool_output(&mut self, content: Option<&Value>) -> Vec<Content> {
+        self.parse_content(content)
+            .into_iter()
+            .filter_map(|content| match content {
+                MessageContent::Text(text) => Some(Content::text(text.text.clone())),
+                MessageContent::Image(image) => {
+                    Some(Content::image(image.data.clone(), image.mime_type.clone()))
+                }
+                _ => {
+                    self.issues
+                        .push("Skipped tool output that is not text or an image".to_string());
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn skip_system_prompt(&mut self, role: &str) {
+        self.issues.push(format!(
+            "Skipped the {} prompt, goose sessions use goose's own",
+            role
+        ));
+    }
+
+    fn generate_id(&mut self) -> String {
+        self.generated_ids += 1;
+        format!("imported_call_{}", self.generated_ids)
+    }
+}
+
+/// A chat completions `function` object, whose arguments are a JSON encoded string
+fn function_call(id: String, function: &Value) -> MessageContent {
+    let Some(name) = function.get("name").and_then(Value::as_str) else {
+        let error = ToolError::InvalidParameters(format!("Tool call {} has no name", id));
+        return MessageContent::tool_request(id, Err(error));
+    };
+    let arguments = match function.get("arguments") {
+        Some(Value::String(arguments)) if !arguments.trim().is_empty() => {
+            safely_parse_json(arguments).map_err(|e| {
+                ToolError::InvalidParameters(format!(
+                    "Could not interpret tool use parameters for id {}: {}. Raw arguments: '{}'",
+                    id, e, arguments
+                ))
+            })
+        }
+        Some(Value::Object(arguments)) => Ok(Value::Object(arguments.clone())),
+        _ => Ok(Value::Object(Default::default())),
+    };
+    MessageContent::tool_request(
+        id,
+        arguments.map(|arguments| ToolCall::new(name, arguments)),
+    )
+}
+
+/// OpenAI image parts carry a url, Anthropic image blocks a source. Images that are not inline
+/// base64 are kept as a link.
+fn image(block: &Value) -> MessageContent {
+    if let Some(source) = block.get("source") {
+        if let (Some(data), Some(mime_type)) = (
+            source.get("data").and_then(Value::as_str),
+            source.get("media_type").and_then(Value::as_str),
+        ) {
+            return MessageContent::image(data, mime_type);
+        }
+    }
+    let url = block
+        .get("image_url")
+        .and_then(|u| u.get("url").or(Some(u)))
+        .or_else(|| block.get("source").and_then(|s| s.get("url")))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    match url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+    {
+        Some((mime_type, data)) => MessageContent::image(data, mime_type),
+        None => MessageContent::text(format!("[Image: {}]", url)),
+    }
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Unix seconds from a `timestamp` or `created_at` field, as RFC 3339 or a number
+fn timestamp_of(value: &Value) -> Option<i64> {
+    let timestamp = value.get("timestamp").or_else(|| value.get("created_at"))?;
+    match timestamp {
+        Value::String(time) => DateTime::parse_from_rfc3339(time)
+            .ok()
+            .map(|t| t.timestamp()),
+        // Milliseconds are common in logs
+        Value::Number(number) => number
+            .as_i64()
+            .map(|t| if t > 10_000_000_000 { t / 1000 } else { t }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_import_openai_chat_completions() {
+        let transcript = json!({
+            "model": "gpt-4o",
+            "messages": [
+                {"role": "system", "content": "You are helpful"},
+                {"role": "user", "content": "What is in main.rs?"},
+                {"role": "assistant", "content": null, "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "read_file", "arguments": "{\"path\": \"main.rs\"}"}
+                }]},
+                {"role": "tool", "tool_call_id": "call_1", "content": "fn main() {}"},
+                {"role": "assistant", "content": "An empty main function."}
+            ]
+        });
+
+        let imported = parse_transcript(TranscriptFormat::OpenAi, &transcript.to_string()).unwrap();
+        let messages = imported.messages;
+
+        assert_eq!(messages.len(), 4);
+        let request = messages[1].content[0].as_tool_request().unwrap();
+        assert_eq!(request.id, "call_1");
+        let call = request.tool_call.as_ref().unwrap();
+        assert_eq!(call.name, "read_file");
+        assert_eq!(call.arguments, json!({"path": "main.rs"}));
+        assert_eq!(
+            messages[2].content[0].as_tool_response_text().as_deref(),
+            Some("fn main() {}")
+        );
+        // The answer the transcript ended with is kept
+        assert_eq!(messages[3].as_concat_text(), "An empty main function.");
+        assert!(imported.issues[0].contains("system prompt"));
+    }
+
+    #[test]
+    fn test_import_anthropic_messages() {
+        let transcript = json!({
+            "system": "Be brief",
+            "messages": [
+                {"role": "user", "content": [
+                    {"type": "text", "text": "Run the tests"},
+                    {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "aGVsbG8="}}
+                ]},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "Use cargo", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": "cargo test"}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "is_error": true, "content": "cargo: not found"}
+                ]}
+            ]
+        });
+
+        let imported =
+            parse_transcript(TranscriptFormat::Anthropic, &transcript.to_string()).unwrap();
+        let messages = imported.messages;
+
+        assert_eq!(messages.len(), 3);
+        assert!(matches!(messages[0].content[1], MessageContent::Image(_)));
+        assert!(matches!(
+            messages[1].content[0],
+            MessageContent::Thinking(_)
+        ));
+        let response = messages[2].content[0].as_tool_response().unwrap();
+        assert!(matches!(
+            &response.tool_result,
+            Err(ToolError::ExecutionError(e)) if e == "cargo: not found"
+        ));
+    }
+
+    #[test]
+    fn test_import_jsonl_skips_records_that_are_not_messages() {
+        let transcript = [
+            json!({"type": "summary", "summary": "Fixing a bug"}).to_string(),
+            json!({"type": "user", "timestamp": "2025-06-01T10:00:00Z", "message": {"role": "user", "content": "Hi"}}).to_string(),
+            "not json".to_string(),
+            json!({"type": "assistant", "message": {"role": "assistant", "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "ls", "input": {}}
+            ]}})
+            .to_string(),
+        ]
+        .join("\n");
+
+        let imported = parse_transcript(TranscriptFormat::Jsonl, &transcript).unwrap();
+
+        // The tool call never got a result, so it is dropped along with its message
+        assert_eq!(imported.messages.len(), 1);
+        assert_eq!(imported.messages[0].as_concat_text(), "Hi");
+        assert_eq!(imported.messages[0].created, 1748772000);
+        assert!(imported
+            .issues
+            .contains(&"Skipped line 3 which is not JSON".to_string()));
+        assert!(imported
+            .issues
+            .contains(&"Skipped 1 records that are not messages".to_string()));
+        assert!(imported
+            .issues
+            .contains(&"Removed orphaned tool request 'toolu_1'".to_string()));
+    }
+
+    #[test]
+    fn test_import_rejects_transcripts_without_messages() {
+        assert!(parse_transcript(TranscriptFormat::OpenAi, "{}").is_err());
+        assert!(parse_transcript(TranscriptFormat::Jsonl, "{\"type\": \"summary\"}").is_err());
+    }
+}
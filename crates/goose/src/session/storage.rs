@@ -5,6 +5,7 @@
 // - Backup creation
 // Additional debug logging can be added if needed for troubleshooting.
 
+use crate::agents::tool_result_cache::ToolCacheStats;
 use crate::message::Message;
 use crate::providers::base::Provider;
 use crate::utils::safe_truncate;
@@ -64,6 +65,9 @@ pub struct SessionMetadata {
     pub accumulated_input_tokens: Option<i32>,
     /// The number of output tokens used in the session. Accumulated across all messages.
     pub accumulated_output_tokens: Option<i32>,
+    /// Tool result cache hits and misses accumulated across the session, if caching is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_cache: Option<ToolCacheStats>,
 }
 
 // Custom deserializer to handle old sessions without working_dir
@@ -84,6 +88,8 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_total_tokens: Option<i32>,
             accumulated_input_tokens: Option<i32>,
             accumulated_output_tokens: Option<i32>,
+            #[serde(default)]
+            tool_cache: Option<ToolCacheStats>,
             working_dir: Option<PathBuf>,
         }
 
@@ -106,6 +112,7 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_total_tokens: helper.accumulated_total_tokens,
             accumulated_input_tokens: helper.accumulated_input_tokens,
             accumulated_output_tokens: helper.accumulated_output_tokens,
+            tool_cache: helper.tool_cache,
             working_dir,
         })
     }
@@ -132,6 +139,7 @@ impl SessionMetadata {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            tool_cache: None,
         }
     }
 }
@@ -1343,6 +1351,20 @@ pub async fn update_metadata(session_file: &Path, metadata: &SessionMetadata) ->
     save_messages_with_metadata(&secure_path, metadata, &messages)
 }
 
+/// Replace the messages in a session file after the user edited the history, keeping the
+/// metadata but updating the message count
+///
+/// Unlike persist_messages, this never regenerates the description, the messages may be fewer
+/// than before.
+pub fn replace_messages(session_file: &Path, messages: &[Message]) -> Result<()> {
+    // Validate the path for security
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+
+    let mut metadata = read_metadata(&secure_path)?;
+    metadata.message_count = messages.len();
+    save_messages_with_metadata(&secure_path, &metadata, messages)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1470,6 +1492,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_replace_messages_keeps_metadata() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("edited.jsonl");
+
+        let metadata = SessionMetadata {
+            description: "Fixing the build".to_string(),
+            message_count: 3,
+            ..SessionMetadata::new(dir.path().to_path_buf())
+        };
+        let messages = vec![
+            Message::user().with_text("Fix the build"),
+            Message::assistant().with_text("Done"),
+            Message::user().with_text("Now the tests"),
+        ];
+        save_messages_with_metadata(&file_path, &metadata, &messages)?;
+
+        replace_messages(&file_path, &messages[..2])?;
+
+        let read_back = read_metadata(&file_path)?;
+        assert_eq!(read_back.description, "Fixing the build");
+        assert_eq!(read_back.message_count, 2);
+        assert_eq!(read_messages(&file_path)?.len(), 2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_generate_session_id() {
         let id = generate_session_id();
@@ -139,8 +139,11 @@ async fn run_truncate_test(
             Ok(AgentEvent::McpNotification(n)) => {
                 println!("MCP Notification: {n:?}");
             }
-            Ok(AgentEvent::ModelChange { .. }) => {
-                // Model change events are informational, just continue
+            Ok(AgentEvent::ModelChange { .. })
+            | Ok(AgentEvent::Usage(_))
+            | Ok(AgentEvent::MaxTurnsReached { .. })
+            | Ok(AgentEvent::ToolDenied { .. }) => {
+                // Model change and status events are informational, just continue
             }
 
             Err(e) => {
@@ -627,6 +630,7 @@ mod final_output_tool_tests {
         tokio::pin!(reply_stream);
 
         let mut responses = Vec::new();
+        let mut max_turns_reached = None;
         while let Some(response_result) = reply_stream.next().await {
             match response_result {
                 Ok(AgentEvent::Message(response)) => responses.push(response),
@@ -1035,13 +1039,18 @@ mod max_turns_tests {
                             goose::permission::PermissionConfirmation {
                                 principal_type: goose::permission::permission_confirmation::PrincipalType::Tool,
                                 permission: goose::permission::Permission::AllowOnce,
+                                arguments: None,
                             }
                         ).await;
                     }
                     responses.push(response);
                 }
+                Ok(AgentEvent::MaxTurnsReached { max_turns }) => {
+                    max_turns_reached = Some(max_turns);
+                }
                 Ok(AgentEvent::McpNotification(_)) => {}
                 Ok(AgentEvent::ModelChange { .. }) => {}
+                Ok(AgentEvent::Usage(_)) | Ok(AgentEvent::ToolDenied { .. }) => {}
                 Err(e) => {
                     return Err(e);
                 }
@@ -1064,6 +1073,7 @@ mod max_turns_tests {
         } else {
             panic!("Expected text content in last message");
         }
+        assert_eq!(max_turns_reached, Some(1));
         Ok(())
     }
 }
@@ -3,8 +3,9 @@ use mcp_core::protocol::{
     ListToolsResult, ReadResourceResult, ServerCapabilities, METHOD_NOT_FOUND,
 };
 use rmcp::model::{
-    GetPromptResult, JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest,
-    JsonRpcResponse, JsonRpcVersion2_0, Notification, NumberOrString, Request, RequestId,
+    ArgumentInfo, CompleteResult, CompletionInfo, GetPromptResult, JsonRpcError, JsonRpcMessage,
+    JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, JsonRpcVersion2_0,
+    ListResourceTemplatesResult, Notification, NumberOrString, Reference, Request, RequestId,
     ServerNotification,
 };
 use serde::{Deserialize, Serialize};
@@ -99,6 +100,11 @@ pub trait McpClientTrait: Send + Sync {
 
     async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, Error>;
 
+    async fn list_resource_templates(
+        &self,
+        next_cursor: Option<String>,
+    ) -> Result<ListResourceTemplatesResult, Error>;
+
     async fn list_tools(&self, next_cursor: Option<String>) -> Result<ListToolsResult, Error>;
 
     async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, Error>;
@@ -107,6 +113,12 @@ pub trait McpClientTrait: Send + Sync {
 
     async fn get_prompt(&self, name: &str, arguments: Value) -> Result<GetPromptResult, Error>;
 
+    async fn complete(
+        &self,
+        reference: Reference,
+        argument: ArgumentInfo,
+    ) -> Result<CompleteResult, Error>;
+
     async fn subscribe(&self) -> mpsc::Receiver<ServerNotification>;
 }
 
@@ -375,6 +387,31 @@ where
         self.send_request("resources/read", params).await
     }
 
+    async fn list_resource_templates(
+        &self,
+        next_cursor: Option<String>,
+    ) -> Result<ListResourceTemplatesResult, Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+        // If resources is not supported, return an empty list
+        if self
+            .server_capabilities
+            .as_ref()
+            .unwrap()
+            .resources
+            .is_none()
+        {
+            return Ok(ListResourceTemplatesResult::default());
+        }
+
+        let payload = next_cursor
+            .map(|cursor| serde_json::json!({"cursor": cursor}))
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        self.send_request("resources/templates/list", payload).await
+    }
+
     async fn list_tools(&self, next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
         if !self.completed_initialization() {
             return Err(Error::NotInitialized);
@@ -451,6 +488,37 @@ where
         self.send_request("prompts/get", params).await
     }
 
+    async fn complete(
+        &self,
+        reference: Reference,
+        argument: ArgumentInfo,
+    ) -> Result<CompleteResult, Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+
+        // If completions are not supported, there is nothing to suggest
+        if self
+            .server_capabilities
+            .as_ref()
+            .unwrap()
+            .completions
+            .is_none()
+        {
+            return Ok(CompleteResult {
+                completion: CompletionInfo {
+                    values: vec![],
+                    total: None,
+                    has_more: None,
+                },
+            });
+        }
+
+        let params = serde_json::json!({ "ref": reference, "argument": argument });
+
+        self.send_request("completion/complete", params).await
+    }
+
     async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
         let (tx, rx) = mpsc::channel(16);
         self.notification_subscribers.lock().await.push(tx);
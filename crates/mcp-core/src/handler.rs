@@ -35,3 +35,13 @@ pub enum PromptError {
     #[error("Prompt not found: {0}")]
     NotFound(String),
 }
+
+#[derive(Error, Debug)]
+pub enum CompletionError {
+    #[error("Invalid parameters: {0}")]
+    InvalidParameters(String),
+    #[error("Internal error: {0}")]
+    InternalError(String),
+    #[error("Reference not found: {0}")]
+    NotFound(String),
+}
@@ -104,3 +104,15 @@ impl From<mcp_core::handler::ResourceError> for RouterError {
         }
     }
 }
+
+impl From<mcp_core::handler::CompletionError> for RouterError {
+    fn from(err: mcp_core::handler::CompletionError) -> Self {
+        match err {
+            mcp_core::handler::CompletionError::InvalidParameters(msg) => {
+                RouterError::InvalidParams(msg)
+            }
+            mcp_core::handler::CompletionError::NotFound(msg) => RouterError::ResourceNotFound(msg),
+            mcp_core::handler::CompletionError::InternalError(msg) => RouterError::Internal(msg),
+        }
+    }
+}
@@ -0,0 +1,835 @@
+//! HTTP transports for serving a [`Router`](crate::Router) to remote clients.
+//!
+//! Two transports are served side by side from the same listener:
+//! - Streamable HTTP (`/mcp`): POST a JSON-RPC message, receive either a JSON response or an
+//!   event stream carrying notifications followed by the response. Sessions are tracked with
+//!   the `Mcp-Session-Id` header and dropped streams can be resumed with `Last-Event-ID`.
+//! - SSE (`/sse` + `/message`): the older transport where a long lived event stream carries
+//!   every server message and requests are POSTed to the advertised endpoint.
+//!
+//! When an auth token is configured every route requires `Authorization: Bearer <token>`.
+//! A server bound to a loopback address rejects requests whose `Host` or `Origin` names another
+//! host, so web pages cannot reach it through DNS rebinding.
+//! Sessions that see no requests and have no open streams for `session_idle_timeout` are dropped.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
+    Json,
+};
+use futures::{future::BoxFuture, stream, Stream, StreamExt};
+use rmcp::model::{
+    ErrorCode, ErrorData, JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse,
+    JsonRpcVersion2_0, RequestId,
+};
+use serde::Deserialize;
+use subtle::ConstantTimeEq;
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
+use tower_service::Service;
+
+use crate::{router::McpRequest, BoxError, RouterError, Server, ServerError, TransportError};
+
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// Stream id used for messages that are not tied to a streamable HTTP POST, i.e. everything
+/// on an SSE transport session
+const SESSION_STREAM: u64 = 0;
+
+/// Configuration for serving a router over HTTP
+#[derive(Debug, Clone)]
+pub struct HttpTransportConfig {
+    /// Address to listen on
+    pub bind_addr: SocketAddr,
+    /// When set, requests must carry `Authorization: Bearer <auth_token>`
+    pub auth_token: Option<String>,
+    /// Number of events kept per session so that dropped streams can be resumed
+    pub max_buffered_events: usize,
+    /// How long a session without requests or open streams is kept before it is dropped
+    pub session_idle_timeout: Duration,
+}
+
+impl HttpTransportConfig {
+    pub fn new(bind_addr: SocketAddr) -> Self {
+        Self {
+            bind_addr,
+            auth_token: None,
+            max_buffered_events: 1024,
+            session_idle_timeout: Duration::from_secs(30 * 60),
+        }
+    }
+
+    pub fn with_auth_token(mut self, auth_token: Option<String>) -> Self {
+        self.auth_token = auth_token;
+        self
+    }
+}
+
+type ServiceFuture = BoxFuture<'static, Result<JsonRpcResponse, BoxError>>;
+type ServiceFn = Arc<dyn Fn(McpRequest) -> ServiceFuture + Send + Sync>;
+
+#[derive(Clone)]
+struct StoredEvent {
+    id: u64,
+    stream_id: u64,
+    message: JsonRpcMessage,
+}
+
+impl StoredEvent {
+    fn to_sse(&self) -> Event {
+        let data = serde_json::to_string(&self.message)
+            .unwrap_or_else(|_| "Failed to serialize message".to_string());
+        Event::default()
+            .id(self.id.to_string())
+            .event("message")
+            .data(data)
+    }
+
+    fn ends_stream(&self) -> bool {
+        matches!(
+            self.message,
+            JsonRpcMessage::Response(_) | JsonRpcMessage::Error(_)
+        )
+    }
+}
+
+/// Per session event log used to resume streams after a disconnect
+struct HttpSession {
+    next_event_id: AtomicU64,
+    next_stream_id: AtomicU64,
+    events: std::sync::Mutex<VecDeque<StoredEvent>>,
+    live: broadcast::Sender<StoredEvent>,
+    max_events: usize,
+    last_active: std::sync::Mutex<Instant>,
+}
+
+impl HttpSession {
+    fn new(max_events: usize) -> Self {
+        let (live, _) = broadcast::channel(256);
+        Self {
+            next_event_id: AtomicU64::new(1),
+            next_stream_id: AtomicU64::new(SESSION_STREAM + 1),
+            events: std::sync::Mutex::new(VecDeque::new()),
+            live,
+            max_events,
+            last_active: std::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) {
+        *self.last_active.lock().unwrap() = Instant::now();
+    }
+
+    /// A session is idle once nobody is listening and no request has used it for `timeout`
+    fn is_idle(&self, timeout: Duration) -> bool {
+        self.live.receiver_count() == 0 && self.last_active.lock().unwrap().elapsed() >= timeout
+    }
+
+    fn new_stream(&self) -> u64 {
+        self.next_stream_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    fn publish(&self, stream_id: u64, message: JsonRpcMessage) {
+        let mut events = self.events.lock().unwrap();
+        let event = StoredEvent {
+            id: self.next_event_id.fetch_add(1, Ordering::SeqCst),
+            stream_id,
+            message,
+        };
+        events.push_back(event.clone());
+        while events.len() > self.max_events {
+            events.pop_front();
+        }
+        // Publishing under the lock keeps the log and the live feed in the same order
+        let _ = self.live.send(event);
+    }
+
+    /// Subscribe to a stream, replaying logged events after `last_event_id` first
+    fn subscribe(
+        &self,
+        stream_id: Option<u64>,
+        last_event_id: Option<u64>,
+    ) -> (Vec<StoredEvent>, broadcast::Receiver<StoredEvent>) {
+        let events = self.events.lock().unwrap();
+        let receiver = self.live.subscribe();
+        let backlog = match last_event_id {
+            Some(last) => events
+                .iter()
+                .filter(|e| e.id > last && stream_id.is_none_or(|s| e.stream_id == s))
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        (backlog, receiver)
+    }
+
+    fn stream_of(&self, event_id: u64) -> Option<u64> {
+        let events = self.events.lock().unwrap();
+        events
+            .iter()
+            .find(|e| e.id == event_id)
+            .map(|e| e.stream_id)
+    }
+}
+
+#[derive(Clone)]
+struct HttpState {
+    service: ServiceFn,
+    sessions: Arc<RwLock<HashMap<String, Arc<HttpSession>>>>,
+    config: Arc<HttpTransportConfig>,
+}
+
+impl HttpState {
+    fn new(service: ServiceFn, config: HttpTransportConfig) -> Self {
+        Self {
+            service,
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            config: Arc::new(config),
+        }
+    }
+
+    async fn create_session(&self) -> (String, Arc<HttpSession>) {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let session = Arc::new(HttpSession::new(self.config.max_buffered_events));
+        self.sessions
+            .write()
+            .await
+            .insert(session_id.clone(), session.clone());
+        (session_id, session)
+    }
+
+    async fn session(&self, session_id: &str) -> Option<Arc<HttpSession>> {
+        let session = self.sessions.read().await.get(session_id).cloned()?;
+        session.touch();
+        Some(session)
+    }
+
+    async fn evict_idle_sessions(&self) {
+        let timeout = self.config.session_idle_timeout;
+        self.sessions.write().await.retain(|session_id, session| {
+            let idle = session.is_idle(timeout);
+            if idle {
+                tracing::debug!(session_id = %session_id, "Dropping idle HTTP session");
+            }
+            !idle
+        });
+    }
+
+    /// Run a request through the service, publishing its notifications and final response
+    async fn process(&self, request: JsonRpcRequest, session: Arc<HttpSession>, stream_id: u64) {
+        let (notify_tx, mut notify_rx) = mpsc::channel(256);
+        let notification_session = session.clone();
+        let forward = tokio::spawn(async move {
+            while let Some(notification) = notify_rx.recv().await {
+                notification_session.publish(stream_id, notification);
+            }
+        });
+
+        let response = self.call(request, notify_tx).await;
+        let _ = forward.await;
+        session.publish(stream_id, response);
+    }
+
+    async fn call(
+        &self,
+        request: JsonRpcRequest,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> JsonRpcMessage {
+        let id = request.id.clone();
+        tracing::info!(method = ?request.request.method, "Received request");
+
+        match (self.service)(McpRequest { request, notifier }).await {
+            Ok(response) => JsonRpcMessage::Response(response),
+            Err(e) => {
+                tracing::error!(error = %e, "Request processing failed");
+                let error = match e.downcast::<RouterError>() {
+                    Ok(router_error) => ErrorData::from(*router_error),
+                    Err(e) => ErrorData {
+                        code: ErrorCode::INTERNAL_ERROR,
+                        message: e.to_string().into(),
+                        data: None,
+                    },
+                };
+                error_message(id, error)
+            }
+        }
+    }
+}
+
+fn error_message(id: RequestId, error: ErrorData) -> JsonRpcMessage {
+    JsonRpcMessage::Error(JsonRpcError {
+        jsonrpc: JsonRpcVersion2_0,
+        id,
+        error,
+    })
+}
+
+fn parse_message(body: &[u8]) -> Result<JsonRpcMessage, Response> {
+    serde_json::from_slice::<JsonRpcMessage>(body).map_err(|e| {
+        let error = error_message(
+            RequestId::Number(0),
+            ErrorData {
+                code: ErrorCode::PARSE_ERROR,
+                message: TransportError::Json(e).to_string().into(),
+                data: None,
+            },
+        );
+        (StatusCode::BAD_REQUEST, Json(error)).into_response()
+    })
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn last_event_id(headers: &HeaderMap) -> Option<u64> {
+    header_str(headers, LAST_EVENT_ID_HEADER).and_then(|v| v.parse().ok())
+}
+
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    header_str(headers, header::ACCEPT.as_str()).is_some_and(|v| v.contains("text/event-stream"))
+}
+
+/// Turn a backlog plus live feed into SSE events for one stream
+fn event_stream(
+    backlog: Vec<StoredEvent>,
+    receiver: broadcast::Receiver<StoredEvent>,
+    stream_id: Option<u64>,
+    end_on_response: bool,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let last_replayed = backlog.last().map(|e| e.id).unwrap_or(0);
+    let finished = end_on_response && backlog.iter().any(StoredEvent::ends_stream);
+
+    let live = stream::unfold(
+        (receiver, finished),
+        move |(mut receiver, finished)| async move {
+            if finished {
+                return None;
+            }
+            loop {
+                match receiver.recv().await {
+                    Ok(event)
+                        if event.id > last_replayed
+                            && stream_id.is_none_or(|s| event.stream_id == s) =>
+                    {
+                        let finished = end_on_response && event.ends_stream();
+                        return Some((event, (receiver, finished)));
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    stream::iter(backlog)
+        .chain(live)
+        .map(|event| Ok(event.to_sse()))
+}
+
+/// The host of a `Host` header or an origin, without scheme or port
+fn host_name(authority: &str) -> &str {
+    let authority = authority
+        .split_once("://")
+        .map_or(authority, |(_, rest)| rest);
+    let authority = authority.split('/').next().unwrap_or(authority);
+    match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(rest),
+        None => authority.split(':').next().unwrap_or(authority),
+    }
+}
+
+fn is_loopback_host(authority: &str) -> bool {
+    let host = host_name(authority);
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+async fn reject_foreign_origin(
+    State(state): State<HttpState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if state.config.bind_addr.ip().is_loopback() {
+        let headers = request.headers();
+        let foreign = [header::HOST, header::ORIGIN]
+            .iter()
+            .filter_map(|name| header_str(headers, name.as_str()))
+            .any(|value| !is_loopback_host(value));
+        if foreign {
+            return (StatusCode::FORBIDDEN, "Host or origin not allowed").into_response();
+        }
+    }
+    next.run(request).await
+}
+
+async fn require_bearer_token(
+    State(state): State<HttpState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(expected) = &state.config.auth_token {
+        let provided = header_str(request.headers(), header::AUTHORIZATION.as_str())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        let valid = provided.is_some_and(|p| bool::from(p.as_bytes().ct_eq(expected.as_bytes())));
+        if !valid {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "Missing or invalid bearer token",
+            )
+                .into_response();
+        }
+    }
+    next.run(request).await
+}
+
+async fn streamable_post(
+    State(state): State<HttpState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let message = match parse_message(&body) {
+        Ok(message) => message,
+        Err(response) => return response,
+    };
+
+    let is_initialize = matches!(
+        &message,
+        JsonRpcMessage::Request(r) if r.request.method == "initialize"
+    );
+
+    let (session_id, session) = if is_initialize {
+        state.create_session().await
+    } else {
+        let Some(session_id) = header_str(&headers, SESSION_ID_HEADER) else {
+            return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+        };
+        match state.session(session_id).await {
+            Some(session) => (session_id.to_string(), session),
+            None => return (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        }
+    };
+
+    let request = match message {
+        JsonRpcMessage::Request(request) => request,
+        JsonRpcMessage::Notification(_)
+        | JsonRpcMessage::Response(_)
+        | JsonRpcMessage::Error(_) => return StatusCode::ACCEPTED.into_response(),
+        JsonRpcMessage::BatchRequest(_) | JsonRpcMessage::BatchResponse(_) => {
+            return (StatusCode::BAD_REQUEST, "Batch messages are not supported").into_response()
+        }
+    };
+
+    let session_header = HeaderValue::from_str(&session_id).expect("uuid is a valid header");
+
+    if !is_initialize && accepts_event_stream(&headers) {
+        let stream_id = session.new_stream();
+        let (backlog, receiver) = session.subscribe(Some(stream_id), None);
+
+        // Processing is detached from the response so that a dropped connection can resume
+        let process_state = state.clone();
+        tokio::spawn(async move { process_state.process(request, session, stream_id).await });
+
+        let stream = event_stream(backlog, receiver, Some(stream_id), true);
+        return (
+            [(SESSION_ID_HEADER, session_header)],
+            Sse::new(stream).keep_alive(KeepAlive::default()),
+        )
+            .into_response();
+    }
+
+    // Plain JSON responses have nowhere to put notifications, so they are dropped
+    let (notify_tx, _notify_rx) = mpsc::channel(256);
+    let response = state.call(request, notify_tx).await;
+    ([(SESSION_ID_HEADER, session_header)], Json(response)).into_response()
+}
+
+async fn streamable_get(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    if !accepts_event_stream(&headers) {
+        return (
+            StatusCode::NOT_ACCEPTABLE,
+            "Expected Accept: text/event-stream",
+        )
+            .into_response();
+    }
+    let Some(session_id) = header_str(&headers, SESSION_ID_HEADER) else {
+        return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+    };
+    let Some(session) = state.session(session_id).await else {
+        return (StatusCode::NOT_FOUND, "Session not found").into_response();
+    };
+
+    // Resuming picks up the stream the last seen event belonged to, otherwise this is the
+    // standalone stream for server initiated messages
+    let last_event_id = last_event_id(&headers);
+    let resumed_stream = last_event_id.and_then(|id| session.stream_of(id));
+    let stream_id = resumed_stream.unwrap_or(SESSION_STREAM);
+    let (backlog, receiver) = session.subscribe(Some(stream_id), last_event_id);
+
+    let stream = event_stream(backlog, receiver, Some(stream_id), resumed_stream.is_some());
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn streamable_delete(State(state): State<HttpState>, headers: HeaderMap) -> Response {
+    let Some(session_id) = header_str(&headers, SESSION_ID_HEADER) else {
+        return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+    };
+    match state.sessions.write().await.remove(session_id) {
+        Some(_) => StatusCode::OK.into_response(),
+        None => (StatusCode::NOT_FOUND, "Session not found").into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct SessionQuery {
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+}
+
+async fn sse_connect(
+    State(state): State<HttpState>,
+    Query(query): Query<SessionQuery>,
+    headers: HeaderMap,
+) -> Response {
+    // Reconnecting clients pass their session back to replay what they missed
+    let (session_id, session) = match query.session_id {
+        Some(session_id) => match state.session(&session_id).await {
+            Some(session) => (session_id, session),
+            None => return (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        },
+        None => state.create_session().await,
+    };
+
+    let (backlog, receiver) = session.subscribe(None, last_event_id(&headers));
+    let endpoint = Event::default()
+        .event("endpoint")
+        .data(format!("/message?sessionId={}", session_id));
+
+    let stream = stream::once(async move { Ok(endpoint) })
+        .chain(event_stream(backlog, receiver, None, false));
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn sse_message(
+    State(state): State<HttpState>,
+    Query(query): Query<SessionQuery>,
+    body: Bytes,
+) -> Response {
+    let Some(session_id) = query.session_id else {
+        return (StatusCode::BAD_REQUEST, "Missing sessionId").into_response();
+    };
+    let Some(session) = state.session(&session_id).await else {
+        return (StatusCode::NOT_FOUND, "Session not found").into_response();
+    };
+
+    match parse_message(&body) {
+        Ok(JsonRpcMessage::Request(request)) => {
+            tokio::spawn(async move { state.process(request, session, SESSION_STREAM).await });
+            StatusCode::ACCEPTED.into_response()
+        }
+        Ok(_) => StatusCode::ACCEPTED.into_response(),
+        Err(response) => response,
+    }
+}
+
+impl<S> Server<S>
+where
+    S: Service<McpRequest, Response = JsonRpcResponse> + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send + 'static,
+{
+    /// Serve the router over Streamable HTTP (`/mcp`) and SSE (`/sse`, `/message`)
+    pub async fn run_http(self, config: HttpTransportConfig) -> Result<(), ServerError> {
+        let service = Arc::new(Mutex::new(self.service));
+        let service: ServiceFn = Arc::new(move |request| -> ServiceFuture {
+            let service = service.clone();
+            Box::pin(async move {
+                // Only hold the lock while creating the future so requests run concurrently
+                let future = service.lock().await.call(request);
+                future.await.map_err(Into::into)
+            })
+        });
+
+        let bind_addr = config.bind_addr;
+        let state = HttpState::new(service, config);
+
+        let listener = tokio::net::TcpListener::bind(bind_addr)
+            .await
+            .map_err(TransportError::Io)?;
+        tracing::info!(addr = %bind_addr, "HTTP server listening");
+
+        let sweep_state = state.clone();
+        let sweeper = tokio::spawn(async move {
+            let period = sweep_state
+                .config
+                .session_idle_timeout
+                .clamp(Duration::from_secs(1), Duration::from_secs(60));
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                sweep_state.evict_idle_sessions().await;
+            }
+        });
+
+        let result = axum::serve(listener, app(state))
+            .await
+            .map_err(|e| ServerError::Transport(TransportError::Io(e)));
+        sweeper.abort();
+        result
+    }
+}
+
+fn app(state: HttpState) -> axum::Router {
+    axum::Router::new()
+        .route(
+            "/mcp",
+            post(streamable_post)
+                .get(streamable_get)
+                .delete(streamable_delete),
+        )
+        .route("/sse", get(sse_connect))
+        .route("/message", post(sse_message))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_bearer_token,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            reject_foreign_origin,
+        ))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http};
+    use serde_json::json;
+
+    const TOKEN: &str = "secret-token";
+
+    /// A service that sends one notification before answering every request
+    fn test_state(config: HttpTransportConfig) -> HttpState {
+        let service: ServiceFn = Arc::new(|McpRequest { request, notifier }| -> ServiceFuture {
+            Box::pin(async move {
+                let notification = serde_json::from_value(json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/message",
+                    "params": {"data": "working"}
+                }))
+                .unwrap();
+                let _ = notifier.send(notification).await;
+                Ok(JsonRpcResponse {
+                    jsonrpc: JsonRpcVersion2_0,
+                    id: request.id,
+                    result: serde_json::Map::new(),
+                })
+            })
+        });
+        HttpState::new(service, config)
+    }
+
+    fn test_app() -> axum::Router {
+        let config = HttpTransportConfig::new(([127, 0, 0, 1], 0).into())
+            .with_auth_token(Some(TOKEN.to_string()));
+        app(test_state(config))
+    }
+
+    fn post_request(method: &str, session_id: Option<&str>, accept: &str) -> Request {
+        let body = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": {}});
+        let mut builder = http::Request::post("/mcp")
+            .header(header::AUTHORIZATION, format!("Bearer {TOKEN}"))
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, accept);
+        if let Some(session_id) = session_id {
+            builder = builder.header(SESSION_ID_HEADER, session_id);
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn send(app: &axum::Router, request: Request) -> Response {
+        app.clone().call(request).await.unwrap()
+    }
+
+    async fn body_text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    fn event_ids(body: &str) -> Vec<u64> {
+        body.lines()
+            .filter_map(|line| line.strip_prefix("id: "))
+            .map(|id| id.parse().unwrap())
+            .collect()
+    }
+
+    async fn initialize(app: &axum::Router) -> String {
+        let response = send(app, post_request("initialize", None, "application/json")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        header_str(response.headers(), SESSION_ID_HEADER)
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_is_rejected() {
+        let app = test_app();
+        let request = http::Request::post("/mcp")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = send(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_token_is_rejected() {
+        let app = test_app();
+        for token in ["wrong-token", "secret-toke", "secret-token-longer"] {
+            let request = http::Request::get("/sse")
+                .header(header::AUTHORIZATION, format!("Bearer {token}"))
+                .body(Body::empty())
+                .unwrap();
+            let response = send(&app, request).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{token}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_foreign_host_and_origin_are_rejected() {
+        let app = test_app();
+        let request = |name: header::HeaderName, value: &str| {
+            let mut request = post_request("initialize", None, "application/json");
+            request
+                .headers_mut()
+                .insert(name, HeaderValue::from_str(value).unwrap());
+            request
+        };
+
+        for (name, value) in [
+            (header::HOST, "evil.example:8080"),
+            (header::ORIGIN, "http://evil.example"),
+            (header::ORIGIN, "null"),
+        ] {
+            let response = send(&app, request(name, value)).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{value}");
+        }
+        for (name, value) in [
+            (header::HOST, "localhost:8080"),
+            (header::HOST, "[::1]:8080"),
+            (header::ORIGIN, "http://127.0.0.1:3000"),
+        ] {
+            let response = send(&app, request(name, value)).await;
+            assert_eq!(response.status(), StatusCode::OK, "{value}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_create_and_delete() {
+        let app = test_app();
+        let session_id = initialize(&app).await;
+
+        let response = send(
+            &app,
+            post_request("ping", Some(&session_id), "application/json"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let delete = |session_id: &str| {
+            http::Request::delete("/mcp")
+                .header(header::AUTHORIZATION, format!("Bearer {TOKEN}"))
+                .header(SESSION_ID_HEADER, session_id)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = send(&app, delete(&session_id)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(&app, delete(&session_id)).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = send(
+            &app,
+            post_request("ping", Some(&session_id), "application/json"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_resume_stream_after_last_event_id() {
+        let app = test_app();
+        let session_id = initialize(&app).await;
+
+        let response = send(
+            &app,
+            post_request("ping", Some(&session_id), "text/event-stream"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_text(response).await;
+        let ids = event_ids(&body);
+        assert_eq!(
+            ids.len(),
+            2,
+            "expected a notification and a response: {body}"
+        );
+
+        // Resuming after the notification replays only the response, then ends the stream
+        let request = http::Request::get("/mcp")
+            .header(header::AUTHORIZATION, format!("Bearer {TOKEN}"))
+            .header(header::ACCEPT, "text/event-stream")
+            .header(SESSION_ID_HEADER, &session_id)
+            .header(LAST_EVENT_ID_HEADER, ids[0].to_string())
+            .body(Body::empty())
+            .unwrap();
+        let response = send(&app, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let resumed = body_text(response).await;
+        assert_eq!(event_ids(&resumed), vec![ids[1]]);
+        assert!(resumed.contains("\"result\""));
+    }
+
+    #[tokio::test]
+    async fn test_idle_sessions_are_evicted() {
+        let mut config = HttpTransportConfig::new(([127, 0, 0, 1], 0).into());
+        config.session_idle_timeout = Duration::ZERO;
+        let state = test_state(config);
+
+        let (session_id, session) = state.create_session().await;
+        let receiver = session.live.subscribe();
+        state.evict_idle_sessions().await;
+        assert!(state.session(&session_id).await.is_some());
+
+        drop(receiver);
+        state.evict_idle_sessions().await;
+        assert!(state.session(&session_id).await.is_none());
+    }
+}
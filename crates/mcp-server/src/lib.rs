@@ -21,6 +21,9 @@ pub use errors::{BoxError, RouterError, ServerError, TransportError};
 pub mod router;
 pub use router::Router;
 
+pub mod http;
+pub use http::HttpTransportConfig;
+
 /// A transport layer that handles JSON-RPC messages over byte
 #[pin_project]
 pub struct ByteTransport<R, W> {
@@ -137,7 +140,6 @@ where
         Self { service }
     }
 
-    // TODO transport trait instead of byte transport if we implement others
     pub async fn run<R, W>(self, mut transport: ByteTransport<R, W>) -> Result<(), ServerError>
     where
         R: AsyncRead + Unpin + Send + 'static,
@@ -1,21 +1,26 @@
 use std::{
+    collections::HashMap,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
 };
 
 type PromptFuture = Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>>;
+type CompletionFuture =
+    Pin<Box<dyn Future<Output = Result<Vec<String>, CompletionError>> + Send + 'static>>;
 use mcp_core::{
-    handler::{PromptError, ResourceError, ToolError},
+    handler::{CompletionError, PromptError, ResourceError, ToolError},
     protocol::{
-        CallToolResult, Implementation, InitializeResult, ListPromptsResult, ListResourcesResult,
-        ListToolsResult, PromptsCapability, ReadResourceResult, ResourcesCapability,
-        ServerCapabilities, ToolsCapability,
+        CallToolResult, CompletionsCapability, Implementation, InitializeResult, ListPromptsResult,
+        ListResourcesResult, ListToolsResult, PromptsCapability, ReadResourceResult,
+        ResourcesCapability, ServerCapabilities, ToolsCapability,
     },
 };
 use rmcp::model::{
-    Content, GetPromptResult, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, JsonRpcVersion2_0,
-    Prompt, PromptMessage, PromptMessageRole, RequestId, Resource, ResourceContents,
+    ArgumentInfo, CompleteRequestParam, CompleteResult, CompletionInfo, Content, GetPromptResult,
+    JsonRpcMessage, JsonRpcRequest, JsonRpcResponse, JsonRpcVersion2_0,
+    ListResourceTemplatesResult, Prompt, PromptMessage, PromptMessageRole, Reference, RequestId,
+    Resource, ResourceContents, ResourceTemplate,
 };
 use serde_json::Value;
 use tokio::sync::mpsc;
@@ -28,6 +33,7 @@ pub struct CapabilitiesBuilder {
     tools: Option<ToolsCapability>,
     prompts: Option<PromptsCapability>,
     resources: Option<ResourcesCapability>,
+    completions: Option<CompletionsCapability>,
 }
 
 impl Default for CapabilitiesBuilder {
@@ -42,6 +48,7 @@ impl CapabilitiesBuilder {
             tools: None,
             prompts: None,
             resources: None,
+            completions: None,
         }
     }
 
@@ -70,6 +77,12 @@ impl CapabilitiesBuilder {
         self
     }
 
+    /// Enable argument completion for prompts and resource templates
+    pub fn with_completions(mut self) -> Self {
+        self.completions = Some(CompletionsCapability {});
+        self
+    }
+
     /// Build the router with automatic capability inference
     pub fn build(self) -> ServerCapabilities {
         // Create capabilities based on what's configured
@@ -77,10 +90,50 @@ impl CapabilitiesBuilder {
             tools: self.tools,
             prompts: self.prompts,
             resources: self.resources,
+            completions: self.completions,
         }
     }
 }
 
+/// Maximum number of values returned in a single completion response, per the MCP spec
+const MAX_COMPLETION_VALUES: usize = 100;
+
+/// Match a URI against a simple (RFC 6570 level 1) URI template such as `memory://{category}`
+///
+/// Returns the captured variables when the URI matches. Each variable captures everything up
+/// to the next literal segment of the template, so the last variable captures the remainder.
+pub fn match_uri_template(template: &str, uri: &str) -> Option<HashMap<String, String>> {
+    let mut captures = HashMap::new();
+    let mut rest_template = template;
+    let mut rest_uri = uri;
+
+    loop {
+        let Some(open) = rest_template.find('{') else {
+            return (rest_template == rest_uri).then_some(captures);
+        };
+
+        let literal = &rest_template[..open];
+        rest_uri = rest_uri.strip_prefix(literal)?;
+
+        let close = rest_template[open..].find('}')? + open;
+        let name = &rest_template[open + 1..close];
+        rest_template = &rest_template[close + 1..];
+
+        let next_literal = &rest_template[..rest_template.find('{').unwrap_or(rest_template.len())];
+        let end = if next_literal.is_empty() {
+            rest_uri.len()
+        } else {
+            rest_uri.find(next_literal)?
+        };
+
+        if end == 0 {
+            return None;
+        }
+        captures.insert(name.to_string(), rest_uri[..end].to_string());
+        rest_uri = &rest_uri[end..];
+    }
+}
+
 pub trait Router: Send + Sync + 'static {
     fn name(&self) -> String;
     // in the protocol, instructions are optional but we make it required
@@ -101,6 +154,16 @@ pub trait Router: Send + Sync + 'static {
     fn list_prompts(&self) -> Vec<Prompt>;
     fn get_prompt(&self, prompt_name: &str) -> PromptFuture;
 
+    /// Templated resource URIs, e.g. `file:///{path}`, resolved through `read_resource`
+    fn list_resource_templates(&self) -> Vec<ResourceTemplate> {
+        Vec::new()
+    }
+
+    /// Suggest values for a prompt or resource template argument given its partial value
+    fn complete(&self, _reference: Reference, _argument: ArgumentInfo) -> CompletionFuture {
+        Box::pin(async move { Ok(Vec::new()) })
+    }
+
     // Helper method to create base response
     fn create_response(&self, id: RequestId) -> JsonRpcResponse {
         JsonRpcResponse {
@@ -243,6 +306,65 @@ pub trait Router: Send + Sync + 'static {
         }
     }
 
+    fn handle_resource_templates_list(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let result =
+                ListResourceTemplatesResult::with_all_items(self.list_resource_templates());
+
+            let mut response = self.create_response(req.id);
+            self.set_result(&mut response, result)?;
+            Ok(response)
+        }
+    }
+
+    fn handle_completion_complete(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let params: CompleteRequestParam =
+                serde_json::from_value(Value::Object(req.request.params.clone())).map_err(|e| {
+                    RouterError::InvalidParams(format!("Invalid completion request: {}", e))
+                })?;
+
+            if let Reference::Prompt(prompt_ref) = &params.r#ref {
+                if !self
+                    .list_prompts()
+                    .iter()
+                    .any(|p| p.name == prompt_ref.name)
+                {
+                    return Err(RouterError::PromptNotFound(format!(
+                        "Prompt '{}' not found",
+                        prompt_ref.name
+                    )));
+                }
+            }
+
+            let mut values = self
+                .complete(params.r#ref, params.argument)
+                .await
+                .map_err(RouterError::from)?;
+
+            let total = values.len();
+            values.truncate(MAX_COMPLETION_VALUES);
+
+            let result = CompleteResult {
+                completion: CompletionInfo {
+                    values,
+                    total: Some(total as u32),
+                    has_more: Some(total > MAX_COMPLETION_VALUES),
+                },
+            };
+
+            let mut response = self.create_response(req.id);
+            self.set_result(&mut response, result)?;
+            Ok(response)
+        }
+    }
+
     fn handle_prompts_list(
         &self,
         req: JsonRpcRequest,
@@ -408,6 +530,10 @@ where
                 "tools/call" => this.handle_tools_call(req.request, req.notifier).await,
                 "resources/list" => this.handle_resources_list(req.request).await,
                 "resources/read" => this.handle_resources_read(req.request).await,
+                "resources/templates/list" => {
+                    this.handle_resource_templates_list(req.request).await
+                }
+                "completion/complete" => this.handle_completion_complete(req.request).await,
                 "prompts/list" => this.handle_prompts_list(req.request).await,
                 "prompts/get" => this.handle_prompts_get(req.request).await,
                 _ => {
@@ -421,3 +547,70 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn captures(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_match_uri_template_single_variable() {
+        assert_eq!(
+            match_uri_template("memory://{category}", "memory://work"),
+            Some(captures(&[("category", "work")]))
+        );
+        // The last variable captures the rest of the URI
+        assert_eq!(
+            match_uri_template("file:///{path}", "file:///home/me/notes.txt"),
+            Some(captures(&[("path", "home/me/notes.txt")]))
+        );
+    }
+
+    #[test]
+    fn test_match_uri_template_multiple_variables() {
+        assert_eq!(
+            match_uri_template("repo://{owner}/{name}/issues", "repo://block/goose/issues"),
+            Some(captures(&[("owner", "block"), ("name", "goose")]))
+        );
+    }
+
+    #[test]
+    fn test_match_uri_template_without_variables() {
+        assert_eq!(
+            match_uri_template("memory://all", "memory://all"),
+            Some(HashMap::new())
+        );
+        assert_eq!(match_uri_template("memory://all", "memory://none"), None);
+    }
+
+    #[test]
+    fn test_match_uri_template_rejects_mismatches() {
+        // Wrong scheme
+        assert_eq!(
+            match_uri_template("memory://{category}", "file://work"),
+            None
+        );
+        // Empty variables do not match
+        assert_eq!(match_uri_template("memory://{category}", "memory://"), None);
+        assert_eq!(
+            match_uri_template("repo://{owner}/{name}", "repo:///goose"),
+            None
+        );
+        // A trailing literal must be present
+        assert_eq!(
+            match_uri_template("repo://{owner}/issues", "repo://block/pulls"),
+            None
+        );
+        // Unclosed variables never match
+        assert_eq!(
+            match_uri_template("memory://{category", "memory://work"),
+            None
+        );
+    }
+}
@@ -1,7 +1,7 @@
 // This file is auto-generated by @hey-api/openapi-ts
 
 import type { Options as ClientOptions, TDataShape, Client } from '@hey-api/client-fetch';
-import type { AddSubRecipesData, AddSubRecipesResponse2, GetToolsData, GetToolsResponse, ReadAllConfigData, ReadAllConfigResponse, BackupConfigData, BackupConfigResponse, GetExtensionsData, GetExtensionsResponse, AddExtensionData, AddExtensionResponse, RemoveExtensionData, RemoveExtensionResponse, InitConfigData, InitConfigResponse, UpsertPermissionsData, UpsertPermissionsResponse, ProvidersData, ProvidersResponse2, ReadConfigData, RecoverConfigData, RecoverConfigResponse, RemoveConfigData, RemoveConfigResponse, UpsertConfigData, UpsertConfigResponse, ValidateConfigData, ValidateConfigResponse, ConfirmPermissionData, ManageContextData, ManageContextResponse, CreateRecipeData, CreateRecipeResponse2, DecodeRecipeData, DecodeRecipeResponse2, EncodeRecipeData, EncodeRecipeResponse2, CreateScheduleData, CreateScheduleResponse, DeleteScheduleData, DeleteScheduleResponse, ListSchedulesData, ListSchedulesResponse2, UpdateScheduleData, UpdateScheduleResponse, InspectRunningJobData, InspectRunningJobResponse, KillRunningJobData, PauseScheduleData, PauseScheduleResponse, RunNowHandlerData, RunNowHandlerResponse, SessionsHandlerData, SessionsHandlerResponse, UnpauseScheduleData, UnpauseScheduleResponse, ListSessionsData, ListSessionsResponse, GetSessionHistoryData, GetSessionHistoryResponse, ListSystemPromptsData, ListSystemPromptsResponse, CreateSystemPromptData, CreateSystemPromptResponse, GetDefaultSystemPromptData, GetDefaultSystemPromptResponse, SearchSystemPromptsData, SearchSystemPromptsResponse, DeleteSystemPromptData, GetSystemPromptData, GetSystemPromptResponse, UpdateSystemPromptData, UpdateSystemPromptResponse, SetDefaultSystemPromptData } from './types.gen';
+import type { AddSubRecipesData, AddSubRecipesResponse2, ExplainToolSelectionData, ExplainToolSelectionResponse, GetToolsData, GetToolsResponse, ListAuditEntriesData, ListAuditEntriesResponse, VerifyAuditLogData, VerifyAuditLogResponse, ReadAllConfigData, ReadAllConfigResponse, BackupConfigData, BackupConfigResponse, GetExtensionsData, GetExtensionsResponse, AddExtensionData, AddExtensionResponse, RemoveExtensionData, RemoveExtensionResponse, InitConfigData, InitConfigResponse, UpsertPermissionsData, UpsertPermissionsResponse, ProvidersData, ProvidersResponse2, ReadConfigData, RecoverConfigData, RecoverConfigResponse, RemoveConfigData, RemoveConfigResponse, UpsertConfigData, UpsertConfigResponse, ValidateConfigData, ValidateConfigResponse, ConfirmPermissionData, ManageContextData, ManageContextResponse, CreateRecipeData, CreateRecipeResponse2, DecodeRecipeData, DecodeRecipeResponse2, EncodeRecipeData, EncodeRecipeResponse2, CreateScheduleData, CreateScheduleResponse, DeleteScheduleData, DeleteScheduleResponse, ListSchedulesData, ListSchedulesResponse2, UpdateScheduleData, UpdateScheduleResponse, InspectRunningJobData, InspectRunningJobResponse, KillRunningJobData, PauseScheduleData, PauseScheduleResponse, RunNowHandlerData, RunNowHandlerResponse, SessionsHandlerData, SessionsHandlerResponse, UnpauseScheduleData, UnpauseScheduleResponse, ListSessionsData, ListSessionsResponse, GetSessionHistoryData, GetSessionHistoryResponse, ExportSessionData, ExportSessionResponse, ListSystemPromptsData, ListSystemPromptsResponse, CreateSystemPromptData, CreateSystemPromptResponse, GetDefaultSystemPromptData, GetDefaultSystemPromptResponse, SearchSystemPromptsData, SearchSystemPromptsResponse, DeleteSystemPromptData, GetSystemPromptData, GetSystemPromptResponse, UpdateSystemPromptData, UpdateSystemPromptResponse, SetDefaultSystemPromptData } from './types.gen';
 import { client as _heyApiClient } from './client.gen';
 
 export type Options<TData extends TDataShape = TDataShape, ThrowOnError extends boolean = boolean> = ClientOptions<TData, ThrowOnError> & {
@@ -29,6 +29,17 @@ export const addSubRecipes = <ThrowOnError extends boolean = false>(options: Opt
     });
 };
 
+export const explainToolSelection = <ThrowOnError extends boolean = false>(options: Options<ExplainToolSelectionData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).post<ExplainToolSelectionResponse, unknown, ThrowOnError>({
+        url: '/agent/router/explain',
+        ...options,
+        headers: {
+            'Content-Type': 'application/json',
+            ...options?.headers
+        }
+    });
+};
+
 export const getTools = <ThrowOnError extends boolean = false>(options?: Options<GetToolsData, ThrowOnError>) => {
     return (options?.client ?? _heyApiClient).get<GetToolsResponse, unknown, ThrowOnError>({
         url: '/agent/tools',
@@ -36,6 +47,20 @@ export const getTools = <ThrowOnError extends boolean = false>(options?: Options
     });
 };
 
+export const listAuditEntries = <ThrowOnError extends boolean = false>(options?: Options<ListAuditEntriesData, ThrowOnError>) => {
+    return (options?.client ?? _heyApiClient).get<ListAuditEntriesResponse, unknown, ThrowOnError>({
+        url: '/audit',
+        ...options
+    });
+};
+
+export const verifyAuditLog = <ThrowOnError extends boolean = false>(options?: Options<VerifyAuditLogData, ThrowOnError>) => {
+    return (options?.client ?? _heyApiClient).get<VerifyAuditLogResponse, unknown, ThrowOnError>({
+        url: '/audit/verify',
+        ...options
+    });
+};
+
 export const readAllConfig = <ThrowOnError extends boolean = false>(options?: Options<ReadAllConfigData, ThrowOnError>) => {
     return (options?.client ?? _heyApiClient).get<ReadAllConfigResponse, unknown, ThrowOnError>({
         url: '/config',
@@ -297,6 +322,13 @@ export const getSessionHistory = <ThrowOnError extends boolean = false>(options:
     });
 };
 
+export const exportSession = <ThrowOnError extends boolean = false>(options: Options<ExportSessionData, ThrowOnError>) => {
+    return (options.client ?? _heyApiClient).get<ExportSessionResponse, unknown, ThrowOnError>({
+        url: '/sessions/{session_id}/export',
+        ...options
+    });
+};
+
 /**
  * Get all system prompts
  */
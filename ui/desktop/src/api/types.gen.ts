@@ -14,6 +14,58 @@ export type Annotations = {
     timestamp?: string;
 };
 
+/**
+ * A single line of the audit log
+ */
+export type AuditEntry = {
+    arguments: {
+        [key: string]: unknown;
+    };
+    decided_by: DecisionSource;
+    decision: PermissionDecision;
+    duration_ms?: number | null;
+    error?: string | null;
+    /**
+     * SHA-256 over the previous hash and this entry's other fields
+     */
+    hash: string;
+    /**
+     * Hash of the previous entry
+     */
+    prev_hash: string;
+    request_id: string;
+    /**
+     * Position in the log, starting at 1
+     */
+    seq: number;
+    session_id?: string | null;
+    status: ToolStatus;
+    timestamp: string;
+    tool: string;
+};
+
+export type AuditListResponse = {
+    /**
+     * Matching audit entries, oldest first
+     */
+    entries: Array<AuditEntry>;
+};
+
+/**
+ * Result of checking the hash chain
+ */
+export type AuditVerification = {
+    /**
+     * Number of entries checked
+     */
+    entries: number;
+    error?: string | null;
+    /**
+     * Line number of the first entry that breaks the chain, if any
+     */
+    first_invalid_line?: number | null;
+};
+
 export type Author = {
     contact?: string | null;
     metadata?: string | null;
@@ -118,6 +170,11 @@ export type CreateSystemPromptRequest = {
     tags?: Array<string> | null;
 };
 
+/**
+ * Who or what made the permission decision
+ */
+export type DecisionSource = 'user' | 'rule' | 'permission_store' | 'smart_approve' | 'mode' | 'caller' | 'hook';
+
 export type DecodeRecipeRequest = {
     deeplink: string;
 };
@@ -126,6 +183,36 @@ export type DecodeRecipeResponse = {
     recipe: Recipe;
 };
 
+export type DiffHunk = {
+    /**
+     * The `@@ -start,count +start,count @@` line
+     */
+    header: string;
+    /**
+     * The lines of the hunk, each starting with ` `, `+` or `-`
+     */
+    lines: Array<string>;
+};
+
+/**
+ * A reviewable diff of the change a tool call would make to a file
+ */
+export type EditPreview = {
+    /**
+     * Unified diff of the file on disk against the file after the change
+     */
+    diff: string;
+    /**
+     * The hunks of the diff in order, each can be accepted on its own
+     */
+    hunks: Array<DiffHunk>;
+    /**
+     * True when the change creates the file
+     */
+    newFile: boolean;
+    path: string;
+};
+
 export type EmbeddedResource = {
     annotations?: Annotations;
     resource: ResourceContents;
@@ -146,6 +233,87 @@ export type Envs = {
 /**
  * Represents the different types of MCP extensions that can be added to the manager
  */
+export type ExplainToolSelectionRequest = {
+    extension_name?: string | null;
+    /**
+     * Number of tools to rank (defaults to 5)
+     */
+    k?: number | null;
+    /**
+     * The search query, as the model would pass it to the router search tool
+     */
+    query: string;
+};
+
+export type ExportedContent = {
+    text: string;
+    type: 'text';
+} | {
+    /**
+     * Base64 encoded image data
+     */
+    data: string;
+    mime_type: string;
+    type: 'image';
+} | {
+    mime_type?: string | null;
+    /**
+     * The text of text resources, binary resources only have their uri
+     */
+    text?: string | null;
+    type: 'resource';
+    uri: string;
+} | {
+    arguments: {
+        [key: string]: unknown;
+    };
+    /**
+     * Set when the model asked for a tool call that could not be parsed
+     */
+    error?: string | null;
+    id: string;
+    name?: string | null;
+    type: 'tool_request';
+} | {
+    content: Array<ExportedContent>;
+    error?: string | null;
+    id: string;
+    is_error: boolean;
+    type: 'tool_response';
+} | {
+    text: string;
+    type: 'thinking';
+} | {
+    type: 'redacted_thinking';
+} | {
+    kind: string;
+    text: string;
+    type: 'notice';
+};
+
+export type ExportedMessage = {
+    content: Array<ExportedContent>;
+    /**
+     * RFC 3339 timestamp of when the message was created
+     */
+    created_at: string;
+    id?: string | null;
+    index: number;
+    /**
+     * `user` or `assistant`
+     */
+    role: string;
+};
+
+/**
+ * Tokens used over the whole session
+ */
+export type ExportedUsage = {
+    input_tokens?: number | null;
+    output_tokens?: number | null;
+    total_tokens?: number | null;
+};
+
 export type ExtensionConfig = {
     /**
      * Whether this extension is bundled with Goose
@@ -360,6 +528,13 @@ export type ModelInfo = {
 
 export type PermissionConfirmationRequest = {
     action: string;
+    /**
+     * Arguments to run the tool with instead of the requested ones, such as a `write` of a
+     * proposed file change that was only accepted in part
+     */
+    arguments?: {
+        [key: string]: unknown;
+    } | null;
     id: string;
     principal_type?: PrincipalType;
 };
@@ -367,6 +542,11 @@ export type PermissionConfirmationRequest = {
 /**
  * Enum representing the possible permission levels for a tool.
  */
+/**
+ * What happened to a tool request at the permission stage
+ */
+export type PermissionDecision = 'allowed' | 'denied' | 'skipped';
+
 export type PermissionLevel = 'always_allow' | 'ask_before' | 'never_allow';
 
 export type PrincipalType = 'Extension' | 'Tool';
@@ -416,6 +596,11 @@ export type ProvidersResponse = {
     providers: Array<ProviderDetails>;
 };
 
+/**
+ * How much reasoning a model should do before answering
+ */
+export type ReasoningEffort = 'low' | 'medium' | 'high';
+
 /**
  * A Recipe represents a personalized, user-generated agent configuration that defines
  * specific behaviors and capabilities within the Goose system.
@@ -580,6 +765,23 @@ export type SessionDisplayInfo = {
     workingDir: string;
 };
 
+export type SessionExport = {
+    description: string;
+    /**
+     * RFC 3339 timestamp of the export
+     */
+    exported_at: string;
+    messages: Array<ExportedMessage>;
+    project_id?: string | null;
+    schedule_id?: string | null;
+    schema_version: number;
+    session_id: string;
+    usage: ExportedUsage;
+    working_dir: string;
+};
+
+export type SessionExportFormat = 'html' | 'json' | 'recipe';
+
 export type SessionHistoryResponse = {
     /**
      * List of messages in the session conversation
@@ -646,6 +848,7 @@ export type SessionMetadata = {
      * ID of the schedule that triggered this session, if any
      */
     schedule_id?: string | null;
+    tool_cache?: ToolCacheStats | null;
     /**
      * The total number of tokens used in the session. Retrieved from the provider's last usage.
      */
@@ -667,8 +870,10 @@ export type SetDefaultRequest = {
 export type Settings = {
     goose_model?: string | null;
     goose_provider?: string | null;
+    reasoning_effort?: ReasoningEffort | null;
     system_prompt_id?: string | null;
     temperature?: number | null;
+    thinking_budget?: number | null;
 };
 
 export type SubRecipe = {
@@ -746,8 +951,23 @@ export type ToolAnnotations = {
     title?: string;
 };
 
+/**
+ * Hit and miss counters for the tool result cache
+ */
+export type ToolCacheStats = {
+    /**
+     * Number of tool calls answered from the cache
+     */
+    hits: number;
+    /**
+     * Number of cacheable tool calls that had to be dispatched
+     */
+    misses: number;
+};
+
 export type ToolConfirmationRequest = {
     arguments: unknown;
+    editPreview?: EditPreview | null;
     id: string;
     prompt?: string | null;
     toolName: string;
@@ -782,6 +1002,55 @@ export type ToolResponse = {
     };
 };
 
+/**
+ * Outcome of a tool request
+ */
+/**
+ * Why the router would select the tools it does for a query
+ */
+export type ToolSelectionExplanation = {
+    elapsed_micros: number;
+    extension_name?: string | null;
+    query: string;
+    /**
+     * The normalized terms the query was matched with
+     */
+    query_terms: Array<string>;
+    strategy: string;
+    tools: Array<ToolSelectionScore>;
+    vector_enabled: boolean;
+};
+
+/**
+ * The score breakdown for one tool
+ */
+export type ToolSelectionScore = {
+    extension_name: string;
+    /**
+     * BM25 score relative to the best lexical match, between 0 and 1
+     */
+    lexical: number;
+    /**
+     * Query terms found in the tool's name, description or parameters
+     */
+    matched_terms: Array<string>;
+    /**
+     * The combined score tools are ranked by
+     */
+    score: number;
+    tool_name: string;
+    /**
+     * Contribution of past usage, positive for tools that were usually invoked once selected
+     */
+    usage: number;
+    /**
+     * Cosine similarity to the query, when an embedding provider is configured
+     */
+    vector?: number | null;
+};
+
+export type ToolStatus = 'success' | 'error' | 'not_run';
+
 export type UpdateScheduleRequest = {
     cron: string;
 };
@@ -827,6 +1096,41 @@ export type AddSubRecipesResponses = {
 
 export type AddSubRecipesResponse2 = AddSubRecipesResponses[keyof AddSubRecipesResponses];
 
+export type ExplainToolSelectionData = {
+    body: ExplainToolSelectionRequest;
+    path?: never;
+    query?: never;
+    url: '/agent/router/explain';
+};
+
+export type ExplainToolSelectionErrors = {
+    /**
+     * Unauthorized - invalid secret key
+     */
+    401: unknown;
+    /**
+     * The tool selection strategy cannot explain its selections
+     */
+    404: unknown;
+    /**
+     * Agent not initialized
+     */
+    424: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type ExplainToolSelectionResponses = {
+    /**
+     * Per-tool scores behind the router's selection
+     */
+    200: ToolSelectionExplanation;
+};
+
+export type ExplainToolSelectionResponse = ExplainToolSelectionResponses[keyof ExplainToolSelectionResponses];
+
 export type GetToolsData = {
     body?: never;
     path?: never;
@@ -863,6 +1167,81 @@ export type GetToolsResponses = {
 
 export type GetToolsResponse = GetToolsResponses[keyof GetToolsResponses];
 
+export type ListAuditEntriesData = {
+    body?: never;
+    path?: never;
+    query?: {
+        /**
+         * Only entries from this session
+         */
+        session_id?: string | null;
+        /**
+         * Only entries for this tool; `*` matches any characters
+         */
+        tool?: string | null;
+        /**
+         * Only entries at or after this RFC 3339 timestamp
+         */
+        since?: string | null;
+        /**
+         * Only entries with this permission decision
+         */
+        decision?: PermissionDecision | null;
+        /**
+         * Return at most this many of the most recent matching entries
+         */
+        limit?: number | null;
+    };
+    url: '/audit';
+};
+
+export type ListAuditEntriesErrors = {
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type ListAuditEntriesResponses = {
+    /**
+     * Audit entries retrieved successfully
+     */
+    200: AuditListResponse;
+};
+
+export type ListAuditEntriesResponse = ListAuditEntriesResponses[keyof ListAuditEntriesResponses];
+
+export type VerifyAuditLogData = {
+    body?: never;
+    path?: never;
+    query?: never;
+    url: '/audit/verify';
+};
+
+export type VerifyAuditLogErrors = {
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type VerifyAuditLogResponses = {
+    /**
+     * Hash chain checked; see first_invalid_line for the result
+     */
+    200: AuditVerification;
+};
+
+export type VerifyAuditLogResponse = VerifyAuditLogResponses[keyof VerifyAuditLogResponses];
+
 export type ReadAllConfigData = {
     body?: never;
     path?: never;
@@ -1660,6 +2039,55 @@ export type GetSessionHistoryResponses = {
 
 export type GetSessionHistoryResponse = GetSessionHistoryResponses[keyof GetSessionHistoryResponses];
 
+export type ExportSessionData = {
+    body?: never;
+    path: {
+        /**
+         * Unique identifier for the session
+         */
+        session_id: string;
+    };
+    query: {
+        /**
+         * Format of the export
+         */
+        format: SessionExportFormat;
+    };
+    url: '/sessions/{session_id}/export';
+};
+
+export type ExportSessionErrors = {
+    /**
+     * Invalid session identifier, or an empty session for a recipe
+     */
+    400: unknown;
+    /**
+     * Unauthorized - Invalid or missing API key
+     */
+    401: unknown;
+    /**
+     * Session not found
+     */
+    404: unknown;
+    /**
+     * Recipe export needs an agent
+     */
+    412: unknown;
+    /**
+     * Internal server error
+     */
+    500: unknown;
+};
+
+export type ExportSessionResponses = {
+    /**
+     * Session exported successfully
+     */
+    200: SessionExport | Recipe;
+};
+
+export type ExportSessionResponse = ExportSessionResponses[keyof ExportSessionResponses];
+
 export type ListSystemPromptsData = {
     body?: never;
     path?: never;